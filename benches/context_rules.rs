@@ -0,0 +1,49 @@
+//! Compares a policy with user-information, service-name and blacklist
+//! rules in one checker, sharing the normalized password across rules,
+//! with the same rules in separate checkers, each normalizing on its own as
+//! every rule did before.
+//!
+//! Run with `cargo bench --bench context_rules`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use passcheck::{Dictionary, MatchMode, PasswordChecker, UserContext, UserInfoCheck};
+
+const WORDS: &str = include_str!("../data/markov-words.txt");
+
+fn rules(checker: PasswordChecker<'static>, family: usize) -> PasswordChecker<'static> {
+    let words: Vec<&str> = WORDS.lines().filter(|w| !w.is_empty()).collect();
+    match family {
+        0 => checker.not_contain_user_info(UserInfoCheck::new().bidirectional(true), None),
+        1 => checker.with_service_names((0..5_000).map(|i| format!("{}{}", words[i % words.len()], i / words.len())), None),
+        _ => checker.blacklist(Dictionary::with_mode(words.iter().take(2_000).copied(), MatchMode::UnicodeCaseFold), None),
+    }
+}
+
+fn main() {
+    const RUNS: u32 = 200;
+    // Twelve tokens: the username, the email's local part and domain, and
+    // nine name parts.
+    let context = UserContext::new()
+        .username("jhamilton")
+        .email("alexander.hamilton@treasury-example.gov")
+        .full_name("Alexander José Hamilton Schuyler de la Vega Ñúñez Whitcombe");
+    let passwords = ["Correct-Horse-Battery-Staple", "Summer2024!", "ÇaVaBienMerci99", "Tr0ub4dor&3", "xkcd936-forever"];
+    let combined = (0..3).fold(PasswordChecker::new(), rules);
+    let separate: Vec<_> = (0..3).map(|family| rules(PasswordChecker::new(), family)).collect();
+    let (mut shared, mut apart) = (Duration::ZERO, Duration::ZERO);
+    for i in 0..RUNS {
+        let password = black_box(passwords[i as usize % passwords.len()]);
+        let started = Instant::now();
+        black_box(combined.check_with_context(password, &context)).ok();
+        shared += started.elapsed();
+        let started = Instant::now();
+        for checker in &separate {
+            black_box(checker.check_with_context(password, &context)).ok();
+        }
+        apart += started.elapsed();
+    }
+    println!("shared forms: {:?} per validation", shared / RUNS);
+    println!("per-rule forms: {:?} per validation", apart / RUNS);
+}
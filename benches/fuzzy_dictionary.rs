@@ -0,0 +1,37 @@
+//! Measures blacklist lookups against 10,000 entries, matched exactly and
+//! within one and two edits.
+//!
+//! Run with `cargo bench --bench fuzzy_dictionary`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use passcheck::Dictionary;
+
+const WORDS: &str = include_str!("../data/markov-words.txt");
+
+/// 10,000 distinct entries shaped like common passwords: a word and a
+/// number.
+fn entries() -> Vec<String> {
+    let words: Vec<&str> = WORDS.lines().filter(|w| !w.is_empty()).collect();
+    (0..10_000).map(|i| format!("{}{}", words[i % words.len()], i / words.len())).collect()
+}
+
+fn main() {
+    const LOOKUPS: u32 = 2_000;
+    let passwords = ["correct-horse-battery", "Summer2024!", "passw0rd", "the7", "xkcd936", "Tr0ub4dor&3"];
+    for distance in [0, 1, 2] {
+        let words = Dictionary::new(entries()).fuzzy(distance);
+        let started = Instant::now();
+        for i in 0..LOOKUPS {
+            black_box(words.matches(black_box(passwords[i as usize % passwords.len()])));
+        }
+        let elapsed = started.elapsed();
+        println!(
+            "distance {}: {:?} per lookup, {:.0} lookups/s",
+            distance,
+            elapsed / LOOKUPS,
+            f64::from(LOOKUPS) / elapsed.as_secs_f64()
+        );
+    }
+}
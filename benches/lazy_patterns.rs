@@ -0,0 +1,37 @@
+//! Compares the first validation of a pattern-heavy policy, which compiles
+//! every pattern, with later validations that reuse the compiled programs.
+//!
+//! Run with `cargo bench --bench lazy_patterns`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use passcheck::{PasswordChecker, Pattern};
+
+fn policy() -> PasswordChecker<'static> {
+    let mut checker = PasswordChecker::new();
+    for year in 1950..2030 {
+        let source = format!("(?:spring|summer|autumn|winter|{})[^A-Za-z]{{0,40}}[a-z]{{2,20}}", year);
+        checker = checker.must_not_match(Pattern::new(&source).unwrap(), None);
+    }
+    checker
+}
+
+fn main() {
+    const RUNS: u32 = 50;
+    let password = "correct-horse-battery-staple";
+    let (mut first, mut second) = (Duration::ZERO, Duration::ZERO);
+    for _ in 0..RUNS {
+        let checker = policy();
+        let started = Instant::now();
+        black_box(checker.check(black_box(password))).ok();
+        first += started.elapsed();
+        let started = Instant::now();
+        black_box(checker.check(black_box(password))).ok();
+        second += started.elapsed();
+    }
+    let (first, second) = (first / RUNS, second / RUNS);
+    println!("first validation:  {:?}", first);
+    println!("second validation: {:?}", second);
+    println!("speedup: {:.1}x", first.as_secs_f64() / second.as_secs_f64());
+}
@@ -0,0 +1,127 @@
+#ifndef PASSCHECK_H
+#define PASSCHECK_H
+
+/* Generated by cbindgen from passcheck-capi. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status codes returned by the C API.
+ */
+typedef enum PasscheckStatus {
+  PASSCHECK_STATUS_OK = 0,
+  PASSCHECK_STATUS_NULL_POINTER = 1,
+  PASSCHECK_STATUS_INVALID_UTF8 = 2,
+  PASSCHECK_STATUS_INVALID_POLICY = 3,
+  PASSCHECK_STATUS_PANIC = 4,
+} PasscheckStatus;
+
+/**
+ * Opaque checker handle.
+ */
+typedef struct PasscheckChecker PasscheckChecker;
+
+/**
+ * Opaque storage for the messages of a [`PasscheckResult`].
+ */
+typedef struct PasscheckMessages PasscheckMessages;
+
+/**
+ * Outcome of `passcheck_validate`.
+ *
+ * Release with `passcheck_free_result`; freeing twice is harmless.
+ */
+typedef struct PasscheckResult {
+  /**
+   * Whether the password passed every rule.
+   */
+  bool valid;
+  /**
+   * Number of messages available through `passcheck_result_message`.
+   */
+  size_t count;
+  /**
+   * Owned by the library; do not touch.
+   */
+  struct PasscheckMessages *messages;
+} PasscheckResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the status of the last `passcheck_new_from_json` or
+ * `passcheck_validate` call on the current thread.
+ */
+enum PasscheckStatus passcheck_last_status(void);
+
+/**
+ * Builds a checker from a JSON policy.
+ *
+ * Returns null on failure; `passcheck_last_status` tells why.
+ *
+ * # Safety
+ *
+ * `json` must be null or point to a NUL-terminated string.
+ */
+struct PasscheckChecker *passcheck_new_from_json(const char *json);
+
+/**
+ * Validates `password` and writes the outcome to `out`.
+ *
+ * `out` is overwritten, so free a previously filled result before reusing it.
+ * On any status other than `PASSCHECK_STATUS_OK`, `out` is left empty.
+ *
+ * # Safety
+ *
+ * `checker` must be null or a live pointer from `passcheck_new_from_json`,
+ * `password` null or a NUL-terminated string, and `out` null or writable.
+ */
+enum PasscheckStatus passcheck_validate(const struct PasscheckChecker *checker,
+                                        const char *password,
+                                        struct PasscheckResult *out);
+
+/**
+ * Returns message `index` of `result`, or null if out of range.
+ *
+ * The string stays valid until the result is freed.
+ *
+ * # Safety
+ *
+ * `result` must be null or point to a result filled by `passcheck_validate`.
+ */
+const char *passcheck_result_message(const struct PasscheckResult *result, size_t index);
+
+/**
+ * Frees the messages held by `result` and resets it to an empty result.
+ *
+ * Safe to call on null or on an already freed result.
+ *
+ * # Safety
+ *
+ * `result` must be null or point to a result filled by `passcheck_validate`.
+ */
+void passcheck_free_result(struct PasscheckResult *result);
+
+/**
+ * Frees the checker pointed to by `checker` and sets `*checker` to null.
+ *
+ * Passing the same handle twice is harmless because it is nulled.
+ *
+ * # Safety
+ *
+ * `checker` must be null or point to a handle that is null or came from
+ * `passcheck_new_from_json`.
+ */
+void passcheck_free_checker(struct PasscheckChecker **checker);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PASSCHECK_H */
@@ -0,0 +1,323 @@
+//! C API for passcheck.
+//!
+//! Every function is panic-safe: a panic inside Rust is caught and reported
+//! as [`PasscheckStatus::Panic`] instead of unwinding into C. Null pointers
+//! and invalid UTF-8 are reported as status codes, never dereferenced.
+//!
+//! The header lives in `include/passcheck.h` and is generated with cbindgen
+//! (see `cbindgen.toml`).
+
+use std::cell::Cell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+use passcheck::PasswordChecker;
+
+/// Status codes returned by the C API.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasscheckStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    InvalidPolicy = 3,
+    Panic = 4,
+}
+
+/// Opaque checker handle.
+pub struct PasscheckChecker(PasswordChecker<'static>);
+
+/// Opaque storage for the messages of a [`PasscheckResult`].
+pub struct PasscheckMessages(Vec<CString>);
+
+/// Outcome of `passcheck_validate`.
+///
+/// Release with `passcheck_free_result`; freeing twice is harmless.
+#[repr(C)]
+pub struct PasscheckResult {
+    /// Whether the password passed every rule.
+    pub valid: bool,
+    /// Number of messages available through `passcheck_result_message`.
+    pub count: usize,
+    /// Owned by the library; do not touch.
+    pub messages: *mut PasscheckMessages,
+}
+
+thread_local! {
+    static LAST_STATUS: Cell<PasscheckStatus> = const { Cell::new(PasscheckStatus::Ok) };
+}
+
+fn set_status(status: PasscheckStatus) -> PasscheckStatus {
+    LAST_STATUS.with(|s| s.set(status));
+    status
+}
+
+/// Reads a C string, distinguishing null from invalid UTF-8.
+///
+/// # Safety
+///
+/// `s` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'s>(s: *const c_char) -> Result<&'s str, PasscheckStatus> {
+    if s.is_null() {
+        return Err(PasscheckStatus::NullPointer);
+    }
+    // SAFETY: guaranteed by the caller.
+    unsafe { CStr::from_ptr(s) }.to_str().map_err(|_| PasscheckStatus::InvalidUtf8)
+}
+
+/// Returns the status of the last `passcheck_new_from_json` or
+/// `passcheck_validate` call on the current thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn passcheck_last_status() -> PasscheckStatus {
+    LAST_STATUS.with(|s| s.get())
+}
+
+/// Builds a checker from a JSON policy.
+///
+/// Returns null on failure; `passcheck_last_status` tells why.
+///
+/// # Safety
+///
+/// `json` must be null or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn passcheck_new_from_json(json: *const c_char) -> *mut PasscheckChecker {
+    let result = catch_unwind(|| {
+        // SAFETY: guaranteed by the caller.
+        let json = unsafe { read_str(json) }?;
+        PasswordChecker::from_json(json).map_err(|_| PasscheckStatus::InvalidPolicy)
+    });
+    match result {
+        Ok(Ok(checker)) => {
+            set_status(PasscheckStatus::Ok);
+            Box::into_raw(Box::new(PasscheckChecker(checker)))
+        }
+        Ok(Err(status)) => {
+            set_status(status);
+            ptr::null_mut()
+        }
+        Err(_) => {
+            set_status(PasscheckStatus::Panic);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Validates `password` and writes the outcome to `out`.
+///
+/// `out` is overwritten, so free a previously filled result before reusing it.
+/// On any status other than `PASSCHECK_STATUS_OK`, `out` is left empty.
+///
+/// # Safety
+///
+/// `checker` must be null or a live pointer from `passcheck_new_from_json`,
+/// `password` null or a NUL-terminated string, and `out` null or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn passcheck_validate(
+    checker: *const PasscheckChecker,
+    password: *const c_char,
+    out: *mut PasscheckResult,
+) -> PasscheckStatus {
+    if out.is_null() {
+        return set_status(PasscheckStatus::NullPointer);
+    }
+    let empty = PasscheckResult { valid: false, count: 0, messages: ptr::null_mut() };
+    // SAFETY: `out` is non-null and writable per the contract.
+    unsafe { out.write(empty) };
+    if checker.is_null() {
+        return set_status(PasscheckStatus::NullPointer);
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: guaranteed by the caller.
+        let password = unsafe { read_str(password) }?;
+        // SAFETY: `checker` is non-null and live per the contract.
+        let checker = unsafe { &(*checker).0 };
+        let messages = checker.validate(password).err().unwrap_or_default();
+        Ok(messages
+            .into_iter()
+            .map(|m| CString::new(m.replace('\0', "")).unwrap_or_default())
+            .collect::<Vec<_>>())
+    }));
+    match result {
+        Ok(Ok(messages)) => {
+            let filled = PasscheckResult {
+                valid: messages.is_empty(),
+                count: messages.len(),
+                messages: Box::into_raw(Box::new(PasscheckMessages(messages))),
+            };
+            // SAFETY: as above.
+            unsafe { out.write(filled) };
+            set_status(PasscheckStatus::Ok)
+        }
+        Ok(Err(status)) => set_status(status),
+        Err(_) => set_status(PasscheckStatus::Panic),
+    }
+}
+
+/// Returns message `index` of `result`, or null if out of range.
+///
+/// The string stays valid until the result is freed.
+///
+/// # Safety
+///
+/// `result` must be null or point to a result filled by `passcheck_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn passcheck_result_message(result: *const PasscheckResult, index: usize) -> *const c_char {
+    catch_unwind(|| {
+        // SAFETY: guaranteed by the caller.
+        let Some(result) = (unsafe { result.as_ref() }) else {
+            return ptr::null();
+        };
+        // SAFETY: non-null `messages` always comes from `passcheck_validate`.
+        match unsafe { result.messages.as_ref() }.and_then(|m| m.0.get(index)) {
+            Some(msg) => msg.as_ptr(),
+            None => ptr::null(),
+        }
+    })
+    .unwrap_or(ptr::null())
+}
+
+/// Frees the messages held by `result` and resets it to an empty result.
+///
+/// Safe to call on null or on an already freed result.
+///
+/// # Safety
+///
+/// `result` must be null or point to a result filled by `passcheck_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn passcheck_free_result(result: *mut PasscheckResult) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: guaranteed by the caller.
+        let Some(result) = (unsafe { result.as_mut() }) else {
+            return;
+        };
+        let messages = std::mem::replace(&mut result.messages, ptr::null_mut());
+        if !messages.is_null() {
+            // SAFETY: the pointer came from `Box::into_raw` and was just detached.
+            drop(unsafe { Box::from_raw(messages) });
+        }
+        result.count = 0;
+        result.valid = false;
+    }));
+}
+
+/// Frees the checker pointed to by `checker` and sets `*checker` to null.
+///
+/// Passing the same handle twice is harmless because it is nulled.
+///
+/// # Safety
+///
+/// `checker` must be null or point to a handle that is null or came from
+/// `passcheck_new_from_json`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn passcheck_free_checker(checker: *mut *mut PasscheckChecker) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        // SAFETY: guaranteed by the caller.
+        let Some(handle) = (unsafe { checker.as_mut() }) else {
+            return;
+        };
+        let raw = std::mem::replace(handle, ptr::null_mut());
+        if !raw.is_null() {
+            // SAFETY: the pointer came from `Box::into_raw` and was just detached.
+            drop(unsafe { Box::from_raw(raw) });
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &CStr = c"{\"rules\": [{\"rule\": \"min_length\", \"length\": 8}, {\"rule\": \"require_number\"}]}";
+
+    fn empty_result() -> PasscheckResult {
+        PasscheckResult { valid: false, count: 0, messages: ptr::null_mut() }
+    }
+
+    fn message(result: &PasscheckResult, index: usize) -> Option<String> {
+        let msg = unsafe { passcheck_result_message(result, index) };
+        (!msg.is_null()).then(|| unsafe { CStr::from_ptr(msg) }.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn validates_through_raw_functions() {
+        unsafe {
+            let mut checker = passcheck_new_from_json(POLICY.as_ptr());
+            assert!(!checker.is_null());
+
+            let mut result = empty_result();
+            assert_eq!(passcheck_validate(checker, c"abc".as_ptr(), &mut result), PasscheckStatus::Ok);
+            assert!(!result.valid);
+            assert_eq!(result.count, 2);
+            assert_eq!(message(&result, 0).unwrap(), "Password must be at least 8 characters long.");
+            assert_eq!(message(&result, 1).unwrap(), "Password must include at least one number.");
+            assert_eq!(message(&result, 2), None);
+            passcheck_free_result(&mut result);
+
+            assert_eq!(passcheck_validate(checker, c"abcdefg1".as_ptr(), &mut result), PasscheckStatus::Ok);
+            assert!(result.valid);
+            assert_eq!(result.count, 0);
+            passcheck_free_result(&mut result);
+
+            passcheck_free_checker(&mut checker);
+            assert!(checker.is_null());
+        }
+    }
+
+    #[test]
+    fn construction_failures_return_null() {
+        unsafe {
+            assert!(passcheck_new_from_json(ptr::null()).is_null());
+            assert_eq!(passcheck_last_status(), PasscheckStatus::NullPointer);
+
+            assert!(passcheck_new_from_json(c"{\"rules\": [".as_ptr()).is_null());
+            assert_eq!(passcheck_last_status(), PasscheckStatus::InvalidPolicy);
+
+            let bad_utf8 = [0xffu8, 0xfe, 0];
+            assert!(passcheck_new_from_json(bad_utf8.as_ptr().cast()).is_null());
+            assert_eq!(passcheck_last_status(), PasscheckStatus::InvalidUtf8);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_bad_arguments() {
+        unsafe {
+            let mut checker = passcheck_new_from_json(POLICY.as_ptr());
+            let mut result = empty_result();
+
+            assert_eq!(passcheck_validate(ptr::null(), c"abc".as_ptr(), &mut result), PasscheckStatus::NullPointer);
+            assert_eq!(passcheck_validate(checker, ptr::null(), &mut result), PasscheckStatus::NullPointer);
+            assert_eq!(passcheck_validate(checker, c"abc".as_ptr(), ptr::null_mut()), PasscheckStatus::NullPointer);
+
+            let bad_utf8 = [b'a', 0xc3, 0];
+            assert_eq!(passcheck_validate(checker, bad_utf8.as_ptr().cast(), &mut result), PasscheckStatus::InvalidUtf8);
+            assert!(!result.valid);
+            assert_eq!(result.count, 0);
+            assert!(result.messages.is_null());
+
+            passcheck_free_checker(&mut checker);
+        }
+    }
+
+    #[test]
+    fn double_free_is_harmless() {
+        unsafe {
+            let mut checker = passcheck_new_from_json(POLICY.as_ptr());
+            let mut result = empty_result();
+            passcheck_validate(checker, c"abc".as_ptr(), &mut result);
+
+            passcheck_free_result(&mut result);
+            passcheck_free_result(&mut result);
+            assert!(result.messages.is_null());
+            assert_eq!(result.count, 0);
+            assert_eq!(message(&result, 0), None);
+
+            passcheck_free_checker(&mut checker);
+            passcheck_free_checker(&mut checker);
+            passcheck_free_checker(ptr::null_mut());
+            passcheck_free_result(ptr::null_mut());
+            assert!(passcheck_result_message(ptr::null(), 0).is_null());
+        }
+    }
+}
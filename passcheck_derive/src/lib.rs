@@ -0,0 +1,303 @@
+//! `#[derive(PasswordPolicy)]` for passcheck.
+//!
+//! Use it through the `derive` feature of `passcheck`, which re-exports the
+//! macro. The generated code refers to the `passcheck` crate by name.
+//!
+//! Written against the bare `proc_macro` API so the crate has no
+//! dependencies.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Derives `password_policy()` and `validate_password(&self)` from a field
+/// marked `#[password(...)]`.
+///
+/// Supported keys, applied in the order written: `min_length = N`,
+/// `max_length = N`, `require_upper_lower`, `require_number` and
+/// `require_special` (or `require_special_char`). Lengths count characters
+/// (Unicode scalar values), not bytes, as `PasswordChecker::min_length`
+/// and `max_length` do.
+#[proc_macro_derive(PasswordPolicy, attributes(password))]
+pub fn derive_password_policy(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(tokens) => tokens,
+        Err(err) => err.into_compile_error(),
+    }
+}
+
+struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        Error { span, message: message.into() }
+    }
+
+    /// Builds `::core::compile_error!("...")` with every token at `span`,
+    /// so the message points at the offending attribute.
+    fn into_compile_error(self) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+        let mut tokens = vec![
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Ident(Ident::new("core", self.span)),
+            TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+            TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+            TokenTree::Ident(Ident::new("compile_error", self.span)),
+            TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+            TokenTree::Group(Group::new(Delimiter::Brace, TokenStream::from(TokenTree::Literal(message)))),
+        ];
+        for token in &mut tokens {
+            token.set_span(self.span);
+        }
+        tokens.into_iter().collect()
+    }
+}
+
+enum Key {
+    MinLength(usize),
+    MaxLength(usize),
+    RequireUpperLower,
+    RequireNumber,
+    RequireSpecialChar,
+}
+
+impl Key {
+    fn builder_call(&self) -> String {
+        match self {
+            Key::MinLength(n) => format!(".min_length({}, ::core::option::Option::None)", n),
+            Key::MaxLength(n) => format!(".max_length({}, ::core::option::Option::None)", n),
+            Key::RequireUpperLower => ".require_upper_lower(::core::option::Option::None)".to_string(),
+            Key::RequireNumber => ".require_number(::core::option::Option::None)".to_string(),
+            Key::RequireSpecialChar => ".require_special_char(::core::option::Option::None)".to_string(),
+        }
+    }
+}
+
+const KNOWN_KEYS: &str = "min_length, max_length, require_upper_lower, require_number, require_special";
+
+fn is_punct(token: Option<&TokenTree>, ch: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == ch)
+}
+
+fn is_ident(token: Option<&TokenTree>, name: &str) -> bool {
+    matches!(token, Some(TokenTree::Ident(i)) if i.to_string() == name)
+}
+
+/// Skips `pub`, `pub(crate)` and similar visibility tokens.
+fn skip_visibility(tokens: &[TokenTree], mut i: usize) -> usize {
+    if is_ident(tokens.get(i), "pub") {
+        i += 1;
+        if matches!(tokens.get(i), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis) {
+            i += 1;
+        }
+    }
+    i
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut i = 0;
+    while is_punct(tokens.get(i), '#') {
+        i += 2;
+    }
+    i = skip_visibility(&tokens, i);
+    match tokens.get(i) {
+        Some(TokenTree::Ident(kw)) if kw.to_string() == "struct" => i += 1,
+        Some(other) => return Err(Error::new(other.span(), "`PasswordPolicy` can only be derived for structs")),
+        None => return Err(Error::new(Span::call_site(), "expected a struct")),
+    }
+    let Some(TokenTree::Ident(name)) = tokens.get(i) else {
+        return Err(Error::new(Span::call_site(), "expected a struct name"));
+    };
+    i += 1;
+    let (params, args) = if is_punct(tokens.get(i), '<') { lifetime_generics(&tokens, &mut i)? } else { Default::default() };
+    // Skip an optional where clause; only the field group matters.
+    while matches!(tokens.get(i), Some(t) if !matches!(t, TokenTree::Group(g) if g.delimiter() == Delimiter::Brace)) {
+        if is_punct(tokens.get(i), ';') || matches!(tokens.get(i), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis) {
+            break;
+        }
+        i += 1;
+    }
+    let fields = match tokens.get(i) {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Brace => g,
+        _ => return Err(Error::new(name.span(), "`PasswordPolicy` requires a struct with named fields")),
+    };
+
+    let (field, keys) = find_password_field(fields, name)?;
+    let calls: String = keys.iter().map(Key::builder_call).collect();
+    let code = format!(
+        "impl{params} {name}{args} {{
+            /// Returns the checker described by the `#[password(...)]` attribute.
+            pub fn password_policy() -> ::passcheck::PasswordChecker<'static> {{
+                ::passcheck::PasswordChecker::new(){calls}
+            }}
+
+            /// Validates the `{field}` field against [`Self::password_policy`].
+            pub fn validate_password(&self) -> ::core::result::Result<(), ::passcheck::Violations> {{
+                Self::password_policy().check(::core::convert::AsRef::<str>::as_ref(&self.{field}))
+            }}
+        }}"
+    );
+    code.parse().map_err(|_| Error::new(name.span(), "failed to generate `PasswordPolicy` impl"))
+}
+
+/// Parses `<'a, 'b: 'a>` starting at `tokens[*i]`, returning the parameter
+/// list for the impl header and the argument list for the type.
+///
+/// Type and const parameters are rejected: the generated impl would need
+/// to repeat their bounds, which this parser does not track.
+fn lifetime_generics(tokens: &[TokenTree], i: &mut usize) -> Result<(String, String), Error> {
+    let start = *i;
+    let mut depth = 0usize;
+    let mut names = Vec::new();
+    let mut expect_param = true;
+    while let Some(token) = tokens.get(*i) {
+        *i += 1;
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '<' => depth += 1,
+            TokenTree::Punct(p) if p.as_char() == '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            TokenTree::Punct(p) if p.as_char() == ',' && depth == 1 => expect_param = true,
+            TokenTree::Punct(p) if p.as_char() == '\'' && expect_param => {
+                if let Some(TokenTree::Ident(name)) = tokens.get(*i) {
+                    names.push(format!("'{}", name));
+                }
+                expect_param = false;
+            }
+            other if expect_param => {
+                return Err(Error::new(other.span(), "`PasswordPolicy` only supports lifetime parameters"));
+            }
+            _ => {}
+        }
+    }
+    let params: TokenStream = tokens[start..*i].iter().cloned().collect();
+    Ok((params.to_string(), format!("<{}>", names.join(", "))))
+}
+
+/// Finds the single field carrying `#[password(...)]` and parses its keys.
+fn find_password_field(fields: &Group, name: &Ident) -> Result<(String, Vec<Key>), Error> {
+    let tokens: Vec<TokenTree> = fields.stream().into_iter().collect();
+    let mut found: Option<(String, Vec<Key>)> = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut attr = None;
+        while is_punct(tokens.get(i), '#') {
+            if let Some(TokenTree::Group(g)) = tokens.get(i + 1) {
+                let inner: Vec<TokenTree> = g.stream().into_iter().collect();
+                if is_ident(inner.first(), "password") {
+                    if found.is_some() || attr.is_some() {
+                        return Err(Error::new(inner[0].span(), "only one field may carry `#[password(...)]`"));
+                    }
+                    attr = Some(parse_keys(&inner)?);
+                }
+            }
+            i += 2;
+        }
+        i = skip_visibility(&tokens, i);
+        let Some(TokenTree::Ident(field)) = tokens.get(i) else {
+            break;
+        };
+        if let Some(keys) = attr {
+            found = Some((field.to_string(), keys));
+        }
+
+        // Skip `: Type` up to the next top-level comma. Commas inside
+        // generic arguments are bare punctuation, so track `<`/`>` depth.
+        let mut depth = 0usize;
+        let mut prev_dash = false;
+        i += 1;
+        while let Some(token) = tokens.get(i) {
+            i += 1;
+            if let TokenTree::Punct(p) = token {
+                match p.as_char() {
+                    ',' if depth == 0 => break,
+                    '<' => depth += 1,
+                    '>' if !prev_dash => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                prev_dash = p.as_char() == '-' && p.spacing() == Spacing::Joint;
+            } else {
+                prev_dash = false;
+            }
+        }
+    }
+    found.ok_or_else(|| Error::new(name.span(), "`#[derive(PasswordPolicy)]` needs a field marked `#[password(...)]`"))
+}
+
+/// Parses the `password(...)` contents of an attribute.
+fn parse_keys(attr: &[TokenTree]) -> Result<Vec<Key>, Error> {
+    let args = match attr.get(1) {
+        Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis && attr.len() == 2 => g,
+        _ => return Err(Error::new(attr[0].span(), "expected `#[password(...)]`")),
+    };
+    let tokens: Vec<TokenTree> = args.stream().into_iter().collect();
+    let mut keys: Vec<Key> = Vec::new();
+    let mut seen: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let TokenTree::Ident(ident) = &tokens[i] else {
+            return Err(Error::new(tokens[i].span(), "expected a rule name"));
+        };
+        let key = ident.to_string();
+        i += 1;
+        let value = if is_punct(tokens.get(i), '=') {
+            let Some(literal) = tokens.get(i + 1) else {
+                return Err(Error::new(tokens[i].span(), format!("expected a value for `{}`", key)));
+            };
+            i += 2;
+            Some(literal)
+        } else {
+            None
+        };
+
+        let parsed = match (key.as_str(), value) {
+            ("min_length", Some(v)) => Key::MinLength(integer(v)?),
+            ("max_length", Some(v)) => Key::MaxLength(integer(v)?),
+            ("min_length" | "max_length", None) => {
+                return Err(Error::new(ident.span(), format!("expected `{} = <integer>`", key)));
+            }
+            ("require_upper_lower" | "require_number" | "require_special" | "require_special_char", Some(v)) => {
+                return Err(Error::new(v.span(), format!("`{}` does not take a value", key)));
+            }
+            ("require_upper_lower", None) => Key::RequireUpperLower,
+            ("require_number", None) => Key::RequireNumber,
+            ("require_special" | "require_special_char", None) => Key::RequireSpecialChar,
+            _ => {
+                return Err(Error::new(
+                    ident.span(),
+                    format!("unknown password attribute `{}`; expected one of {}", key, KNOWN_KEYS),
+                ));
+            }
+        };
+        let canonical = if key == "require_special" { "require_special_char".to_string() } else { key };
+        if seen.contains(&canonical) {
+            return Err(Error::new(ident.span(), format!("duplicate password attribute `{}`", canonical)));
+        }
+        seen.push(canonical);
+        keys.push(parsed);
+
+        match tokens.get(i) {
+            None => {}
+            Some(TokenTree::Punct(p)) if p.as_char() == ',' => i += 1,
+            Some(other) => return Err(Error::new(other.span(), "expected `,`")),
+        }
+    }
+    Ok(keys)
+}
+
+fn integer(token: &TokenTree) -> Result<usize, Error> {
+    let err = || Error::new(token.span(), "expected a non-negative integer literal");
+    let TokenTree::Literal(lit) = token else {
+        return Err(err());
+    };
+    let text = lit.to_string().replace('_', "");
+    let digits = ["usize", "u64", "u32", "u16", "u8"].iter().find_map(|s| text.strip_suffix(s)).unwrap_or(&text);
+    digits.parse().map_err(|_| err())
+}
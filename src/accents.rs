@@ -0,0 +1,322 @@
+//! Removal of diacritics from Latin letters.
+//!
+//! Covers the Latin-1 Supplement and Latin Extended-A and -B blocks with
+//! the mappings of NFKD decomposition followed by dropping combining marks,
+//! plus letters that do not decompose but have a common ASCII spelling,
+//! such as `ø` and `æ`. Combining marks already in the input are dropped.
+//! Other characters are kept as they are.
+
+/// Sorted by source character.
+const BASES: &[(char, &str)] = &[
+    ('\u{00C0}', "A"), // À
+    ('\u{00C1}', "A"), // Á
+    ('\u{00C2}', "A"), // Â
+    ('\u{00C3}', "A"), // Ã
+    ('\u{00C4}', "A"), // Ä
+    ('\u{00C5}', "A"), // Å
+    ('\u{00C6}', "AE"), // Æ
+    ('\u{00C7}', "C"), // Ç
+    ('\u{00C8}', "E"), // È
+    ('\u{00C9}', "E"), // É
+    ('\u{00CA}', "E"), // Ê
+    ('\u{00CB}', "E"), // Ë
+    ('\u{00CC}', "I"), // Ì
+    ('\u{00CD}', "I"), // Í
+    ('\u{00CE}', "I"), // Î
+    ('\u{00CF}', "I"), // Ï
+    ('\u{00D1}', "N"), // Ñ
+    ('\u{00D2}', "O"), // Ò
+    ('\u{00D3}', "O"), // Ó
+    ('\u{00D4}', "O"), // Ô
+    ('\u{00D5}', "O"), // Õ
+    ('\u{00D6}', "O"), // Ö
+    ('\u{00D8}', "O"), // Ø
+    ('\u{00D9}', "U"), // Ù
+    ('\u{00DA}', "U"), // Ú
+    ('\u{00DB}', "U"), // Û
+    ('\u{00DC}', "U"), // Ü
+    ('\u{00DD}', "Y"), // Ý
+    ('\u{00DE}', "TH"), // Þ
+    ('\u{00DF}', "ss"), // ß
+    ('\u{00E0}', "a"), // à
+    ('\u{00E1}', "a"), // á
+    ('\u{00E2}', "a"), // â
+    ('\u{00E3}', "a"), // ã
+    ('\u{00E4}', "a"), // ä
+    ('\u{00E5}', "a"), // å
+    ('\u{00E6}', "ae"), // æ
+    ('\u{00E7}', "c"), // ç
+    ('\u{00E8}', "e"), // è
+    ('\u{00E9}', "e"), // é
+    ('\u{00EA}', "e"), // ê
+    ('\u{00EB}', "e"), // ë
+    ('\u{00EC}', "i"), // ì
+    ('\u{00ED}', "i"), // í
+    ('\u{00EE}', "i"), // î
+    ('\u{00EF}', "i"), // ï
+    ('\u{00F1}', "n"), // ñ
+    ('\u{00F2}', "o"), // ò
+    ('\u{00F3}', "o"), // ó
+    ('\u{00F4}', "o"), // ô
+    ('\u{00F5}', "o"), // õ
+    ('\u{00F6}', "o"), // ö
+    ('\u{00F8}', "o"), // ø
+    ('\u{00F9}', "u"), // ù
+    ('\u{00FA}', "u"), // ú
+    ('\u{00FB}', "u"), // û
+    ('\u{00FC}', "u"), // ü
+    ('\u{00FD}', "y"), // ý
+    ('\u{00FE}', "th"), // þ
+    ('\u{00FF}', "y"), // ÿ
+    ('\u{0100}', "A"), // Ā
+    ('\u{0101}', "a"), // ā
+    ('\u{0102}', "A"), // Ă
+    ('\u{0103}', "a"), // ă
+    ('\u{0104}', "A"), // Ą
+    ('\u{0105}', "a"), // ą
+    ('\u{0106}', "C"), // Ć
+    ('\u{0107}', "c"), // ć
+    ('\u{0108}', "C"), // Ĉ
+    ('\u{0109}', "c"), // ĉ
+    ('\u{010A}', "C"), // Ċ
+    ('\u{010B}', "c"), // ċ
+    ('\u{010C}', "C"), // Č
+    ('\u{010D}', "c"), // č
+    ('\u{010E}', "D"), // Ď
+    ('\u{010F}', "d"), // ď
+    ('\u{0110}', "D"), // Đ
+    ('\u{0111}', "d"), // đ
+    ('\u{0112}', "E"), // Ē
+    ('\u{0113}', "e"), // ē
+    ('\u{0114}', "E"), // Ĕ
+    ('\u{0115}', "e"), // ĕ
+    ('\u{0116}', "E"), // Ė
+    ('\u{0117}', "e"), // ė
+    ('\u{0118}', "E"), // Ę
+    ('\u{0119}', "e"), // ę
+    ('\u{011A}', "E"), // Ě
+    ('\u{011B}', "e"), // ě
+    ('\u{011C}', "G"), // Ĝ
+    ('\u{011D}', "g"), // ĝ
+    ('\u{011E}', "G"), // Ğ
+    ('\u{011F}', "g"), // ğ
+    ('\u{0120}', "G"), // Ġ
+    ('\u{0121}', "g"), // ġ
+    ('\u{0122}', "G"), // Ģ
+    ('\u{0123}', "g"), // ģ
+    ('\u{0124}', "H"), // Ĥ
+    ('\u{0125}', "h"), // ĥ
+    ('\u{0126}', "H"), // Ħ
+    ('\u{0127}', "h"), // ħ
+    ('\u{0128}', "I"), // Ĩ
+    ('\u{0129}', "i"), // ĩ
+    ('\u{012A}', "I"), // Ī
+    ('\u{012B}', "i"), // ī
+    ('\u{012C}', "I"), // Ĭ
+    ('\u{012D}', "i"), // ĭ
+    ('\u{012E}', "I"), // Į
+    ('\u{012F}', "i"), // į
+    ('\u{0130}', "I"), // İ
+    ('\u{0131}', "i"), // ı
+    ('\u{0132}', "IJ"), // Ĳ
+    ('\u{0133}', "ij"), // ĳ
+    ('\u{0134}', "J"), // Ĵ
+    ('\u{0135}', "j"), // ĵ
+    ('\u{0136}', "K"), // Ķ
+    ('\u{0137}', "k"), // ķ
+    ('\u{0139}', "L"), // Ĺ
+    ('\u{013A}', "l"), // ĺ
+    ('\u{013B}', "L"), // Ļ
+    ('\u{013C}', "l"), // ļ
+    ('\u{013D}', "L"), // Ľ
+    ('\u{013E}', "l"), // ľ
+    ('\u{0141}', "L"), // Ł
+    ('\u{0142}', "l"), // ł
+    ('\u{0143}', "N"), // Ń
+    ('\u{0144}', "n"), // ń
+    ('\u{0145}', "N"), // Ņ
+    ('\u{0146}', "n"), // ņ
+    ('\u{0147}', "N"), // Ň
+    ('\u{0148}', "n"), // ň
+    ('\u{014C}', "O"), // Ō
+    ('\u{014D}', "o"), // ō
+    ('\u{014E}', "O"), // Ŏ
+    ('\u{014F}', "o"), // ŏ
+    ('\u{0150}', "O"), // Ő
+    ('\u{0151}', "o"), // ő
+    ('\u{0152}', "OE"), // Œ
+    ('\u{0153}', "oe"), // œ
+    ('\u{0154}', "R"), // Ŕ
+    ('\u{0155}', "r"), // ŕ
+    ('\u{0156}', "R"), // Ŗ
+    ('\u{0157}', "r"), // ŗ
+    ('\u{0158}', "R"), // Ř
+    ('\u{0159}', "r"), // ř
+    ('\u{015A}', "S"), // Ś
+    ('\u{015B}', "s"), // ś
+    ('\u{015C}', "S"), // Ŝ
+    ('\u{015D}', "s"), // ŝ
+    ('\u{015E}', "S"), // Ş
+    ('\u{015F}', "s"), // ş
+    ('\u{0160}', "S"), // Š
+    ('\u{0161}', "s"), // š
+    ('\u{0162}', "T"), // Ţ
+    ('\u{0163}', "t"), // ţ
+    ('\u{0164}', "T"), // Ť
+    ('\u{0165}', "t"), // ť
+    ('\u{0166}', "T"), // Ŧ
+    ('\u{0167}', "t"), // ŧ
+    ('\u{0168}', "U"), // Ũ
+    ('\u{0169}', "u"), // ũ
+    ('\u{016A}', "U"), // Ū
+    ('\u{016B}', "u"), // ū
+    ('\u{016C}', "U"), // Ŭ
+    ('\u{016D}', "u"), // ŭ
+    ('\u{016E}', "U"), // Ů
+    ('\u{016F}', "u"), // ů
+    ('\u{0170}', "U"), // Ű
+    ('\u{0171}', "u"), // ű
+    ('\u{0172}', "U"), // Ų
+    ('\u{0173}', "u"), // ų
+    ('\u{0174}', "W"), // Ŵ
+    ('\u{0175}', "w"), // ŵ
+    ('\u{0176}', "Y"), // Ŷ
+    ('\u{0177}', "y"), // ŷ
+    ('\u{0178}', "Y"), // Ÿ
+    ('\u{0179}', "Z"), // Ź
+    ('\u{017A}', "z"), // ź
+    ('\u{017B}', "Z"), // Ż
+    ('\u{017C}', "z"), // ż
+    ('\u{017D}', "Z"), // Ž
+    ('\u{017E}', "z"), // ž
+    ('\u{017F}', "s"), // ſ
+    ('\u{01A0}', "O"), // Ơ
+    ('\u{01A1}', "o"), // ơ
+    ('\u{01AF}', "U"), // Ư
+    ('\u{01B0}', "u"), // ư
+    ('\u{01C4}', "DZ"), // Ǆ
+    ('\u{01C5}', "Dz"), // ǅ
+    ('\u{01C6}', "dz"), // ǆ
+    ('\u{01C7}', "LJ"), // Ǉ
+    ('\u{01C8}', "Lj"), // ǈ
+    ('\u{01C9}', "lj"), // ǉ
+    ('\u{01CA}', "NJ"), // Ǌ
+    ('\u{01CB}', "Nj"), // ǋ
+    ('\u{01CC}', "nj"), // ǌ
+    ('\u{01CD}', "A"), // Ǎ
+    ('\u{01CE}', "a"), // ǎ
+    ('\u{01CF}', "I"), // Ǐ
+    ('\u{01D0}', "i"), // ǐ
+    ('\u{01D1}', "O"), // Ǒ
+    ('\u{01D2}', "o"), // ǒ
+    ('\u{01D3}', "U"), // Ǔ
+    ('\u{01D4}', "u"), // ǔ
+    ('\u{01D5}', "U"), // Ǖ
+    ('\u{01D6}', "u"), // ǖ
+    ('\u{01D7}', "U"), // Ǘ
+    ('\u{01D8}', "u"), // ǘ
+    ('\u{01D9}', "U"), // Ǚ
+    ('\u{01DA}', "u"), // ǚ
+    ('\u{01DB}', "U"), // Ǜ
+    ('\u{01DC}', "u"), // ǜ
+    ('\u{01DE}', "A"), // Ǟ
+    ('\u{01DF}', "a"), // ǟ
+    ('\u{01E0}', "A"), // Ǡ
+    ('\u{01E1}', "a"), // ǡ
+    ('\u{01E6}', "G"), // Ǧ
+    ('\u{01E7}', "g"), // ǧ
+    ('\u{01E8}', "K"), // Ǩ
+    ('\u{01E9}', "k"), // ǩ
+    ('\u{01EA}', "O"), // Ǫ
+    ('\u{01EB}', "o"), // ǫ
+    ('\u{01EC}', "O"), // Ǭ
+    ('\u{01ED}', "o"), // ǭ
+    ('\u{01F0}', "j"), // ǰ
+    ('\u{01F1}', "DZ"), // Ǳ
+    ('\u{01F2}', "Dz"), // ǲ
+    ('\u{01F3}', "dz"), // ǳ
+    ('\u{01F4}', "G"), // Ǵ
+    ('\u{01F5}', "g"), // ǵ
+    ('\u{01F8}', "N"), // Ǹ
+    ('\u{01F9}', "n"), // ǹ
+    ('\u{01FA}', "A"), // Ǻ
+    ('\u{01FB}', "a"), // ǻ
+    ('\u{0200}', "A"), // Ȁ
+    ('\u{0201}', "a"), // ȁ
+    ('\u{0202}', "A"), // Ȃ
+    ('\u{0203}', "a"), // ȃ
+    ('\u{0204}', "E"), // Ȅ
+    ('\u{0205}', "e"), // ȅ
+    ('\u{0206}', "E"), // Ȇ
+    ('\u{0207}', "e"), // ȇ
+    ('\u{0208}', "I"), // Ȉ
+    ('\u{0209}', "i"), // ȉ
+    ('\u{020A}', "I"), // Ȋ
+    ('\u{020B}', "i"), // ȋ
+    ('\u{020C}', "O"), // Ȍ
+    ('\u{020D}', "o"), // ȍ
+    ('\u{020E}', "O"), // Ȏ
+    ('\u{020F}', "o"), // ȏ
+    ('\u{0210}', "R"), // Ȑ
+    ('\u{0211}', "r"), // ȑ
+    ('\u{0212}', "R"), // Ȓ
+    ('\u{0213}', "r"), // ȓ
+    ('\u{0214}', "U"), // Ȕ
+    ('\u{0215}', "u"), // ȕ
+    ('\u{0216}', "U"), // Ȗ
+    ('\u{0217}', "u"), // ȗ
+    ('\u{0218}', "S"), // Ș
+    ('\u{0219}', "s"), // ș
+    ('\u{021A}', "T"), // Ț
+    ('\u{021B}', "t"), // ț
+    ('\u{021E}', "H"), // Ȟ
+    ('\u{021F}', "h"), // ȟ
+    ('\u{0226}', "A"), // Ȧ
+    ('\u{0227}', "a"), // ȧ
+    ('\u{0228}', "E"), // Ȩ
+    ('\u{0229}', "e"), // ȩ
+    ('\u{022A}', "O"), // Ȫ
+    ('\u{022B}', "o"), // ȫ
+    ('\u{022C}', "O"), // Ȭ
+    ('\u{022D}', "o"), // ȭ
+    ('\u{022E}', "O"), // Ȯ
+    ('\u{022F}', "o"), // ȯ
+    ('\u{0230}', "O"), // Ȱ
+    ('\u{0231}', "o"), // ȱ
+    ('\u{0232}', "Y"), // Ȳ
+    ('\u{0233}', "y"), // ȳ
+];
+
+/// Returns `s` with diacritics removed: `"José García"` becomes
+/// `"Jose Garcia"`.
+pub(crate) fn strip(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if ('\u{0300}'..='\u{036F}').contains(&c) {
+            continue;
+        }
+        match BASES.binary_search_by_key(&c, |&(from, _)| from) {
+            Ok(i) => out.push_str(BASES[i].1),
+            Err(_) => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(BASES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn strips_precomposed_and_combining_marks() {
+        assert_eq!(strip("José García"), "Jose Garcia");
+        assert_eq!(strip("Jose\u{0301}"), "Jose");
+        assert_eq!(strip("Øystein Ærø Łódź"), "Oystein AEro Lodz");
+        assert_eq!(strip("日本 Ωmega"), "日本 Ωmega");
+    }
+}
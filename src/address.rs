@@ -0,0 +1,53 @@
+//! Email addresses and web addresses pasted as passwords.
+
+/// Prefixes of a web address, compared ignoring case.
+const URL_PREFIXES: [&str; 3] = ["http://", "https://", "www."];
+
+/// `"email"` if `password` has the shape `local@domain.tld`, `"url"` if it
+/// starts with a web address prefix, ignoring surrounding whitespace.
+pub(crate) fn detect(password: &str) -> Option<&'static str> {
+    let password = password.trim();
+    if URL_PREFIXES.iter().any(|prefix| password.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix))) {
+        Some("url")
+    } else if is_email(password) {
+        Some("email")
+    } else {
+        None
+    }
+}
+
+/// A local part of letters, digits and `._%+-`, one `@`, and a domain of
+/// two or more labels ending in an alphabetic top-level domain. Passwords
+/// like `p@ssword.123` only share the punctuation.
+fn is_email(password: &str) -> bool {
+    let Some((local, domain)) = password.split_once('@') else {
+        return false;
+    };
+    let labels: Vec<&str> = domain.split('.').collect();
+    let label_ok = |label: &&str| {
+        !label.is_empty() && !label.starts_with('-') && !label.ends_with('-') && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    !local.is_empty() &&
+        local.chars().all(|c| c.is_ascii_alphanumeric() || "._%+-".contains(c)) &&
+        labels.len() >= 2 &&
+        labels.iter().all(label_ok) &&
+        labels.last().is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes() {
+        for email in ["alice@example.com", "j.smith+shop@mail.example-corp.co.uk", " bob@example.org\n"] {
+            assert_eq!(detect(email), Some("email"), "{:?}", email);
+        }
+        for url in ["https://example.com/login?next=/", "http://intranet", "WWW.example.com", "www."] {
+            assert_eq!(detect(url), Some("url"), "{:?}", url);
+        }
+        for password in ["p@ssword.123", "me@home", "@example.com", "a@b@example.com", "x@-bad.com", "sw@g.c0m", "wwwx.com", "my www.site", "https:/x"] {
+            assert_eq!(detect(password), None, "{:?}", password);
+        }
+    }
+}
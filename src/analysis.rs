@@ -0,0 +1,301 @@
+//! Guess-count estimates with per-pattern breakdowns.
+
+use std::cmp::Reverse;
+use std::time::Duration;
+
+use crate::strength::{self, WeakPattern};
+use crate::{Dictionary, Estimator, PolicyFingerprint};
+
+/// Neighbors a keyboard walk can continue to from a typical key.
+const WALK_BRANCHING: f64 = 6.0;
+
+/// Strength analysis of one password, returned by
+/// [`PasswordChecker::analyze`](crate::PasswordChecker::analyze).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    /// Same as [`PasswordChecker::score`](crate::PasswordChecker::score).
+    pub score: u8,
+    /// Estimated number of guesses needed to find the password. Saturates
+    /// at `f64::MAX` instead of overflowing.
+    pub guesses: f64,
+    /// log10 of [`guesses`](Self::guesses), which stays exact for inputs
+    /// where `guesses` saturates.
+    pub guesses_log10: f64,
+    /// Patterns that lowered the estimate, in the order they appear.
+    pub patterns: Vec<PatternContribution>,
+    /// The checker's fingerprint, if it
+    /// [records it](crate::PasswordChecker::record_fingerprint).
+    pub fingerprint: Option<PolicyFingerprint>,
+}
+
+impl Analysis {
+    /// Time to find the password under each [`CrackScenario`] at its
+    /// default rate.
+    pub fn crack_times(&self) -> [(CrackScenario, Duration); 4] {
+        self.crack_times_with(&CrackRates::default())
+    }
+
+    /// Time to find the password under each [`CrackScenario`] at the given
+    /// rates. Durations too long to represent saturate at `Duration::MAX`.
+    pub fn crack_times_with(&self, rates: &CrackRates) -> [(CrackScenario, Duration); 4] {
+        CrackScenario::ALL.map(|scenario| {
+            let seconds = 10f64.powf(self.guesses_log10 - rates.rate(scenario).log10());
+            (scenario, Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX))
+        })
+    }
+}
+
+/// Attacker models for crack-time estimates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrackScenario {
+    /// Guessing through a login form that rate-limits attempts: 100 per hour.
+    OnlineThrottled,
+    /// Guessing through a login form without rate limits: 10 per second.
+    OnlineUnthrottled,
+    /// Cracking a stolen hash made with a slow function such as bcrypt or
+    /// Argon2 on one machine: 10^4 per second.
+    OfflineSlowHash,
+    /// Cracking a stolen unsalted fast hash such as SHA-1 on a cluster of
+    /// GPUs: 10^10 per second.
+    OfflineFastHash,
+}
+
+impl CrackScenario {
+    /// Every scenario, from the slowest attacker to the fastest.
+    pub const ALL: [CrackScenario; 4] = [
+        CrackScenario::OnlineThrottled,
+        CrackScenario::OnlineUnthrottled,
+        CrackScenario::OfflineSlowHash,
+        CrackScenario::OfflineFastHash,
+    ];
+}
+
+/// Guesses per second assumed for each [`CrackScenario`]. The defaults are
+/// the figures documented on the scenarios; override them to match your
+/// own threat model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrackRates {
+    pub online_throttled: f64,
+    pub online_unthrottled: f64,
+    pub offline_slow_hash: f64,
+    pub offline_fast_hash: f64,
+}
+
+impl Default for CrackRates {
+    fn default() -> Self {
+        CrackRates {
+            online_throttled: 100.0 / 3600.0,
+            online_unthrottled: 10.0,
+            offline_slow_hash: 1e4,
+            offline_fast_hash: 1e10,
+        }
+    }
+}
+
+impl CrackRates {
+    /// Guesses per second for `scenario`.
+    pub fn rate(&self, scenario: CrackScenario) -> f64 {
+        match scenario {
+            CrackScenario::OnlineThrottled => self.online_throttled,
+            CrackScenario::OnlineUnthrottled => self.online_unthrottled,
+            CrackScenario::OfflineSlowHash => self.offline_slow_hash,
+            CrackScenario::OfflineFastHash => self.offline_fast_hash,
+        }
+    }
+}
+
+/// Units for [`format_duration_human`], in seconds. A month is a twelfth
+/// of a Gregorian year.
+const UNITS: [(&str, f64); 6] = [
+    ("second", 1.0),
+    ("minute", 60.0),
+    ("hour", 3_600.0),
+    ("day", 86_400.0),
+    ("month", 2_629_746.0),
+    ("year", 31_556_952.0),
+];
+
+/// Formats a crack time as UI copy: `"less than a second"`, `"3 hours"`,
+/// `"2 years"` or, from 100 years on, `"centuries"`.
+///
+/// The count is rounded to the nearest whole unit, moving up a unit when
+/// rounding reaches it, so 59.7 minutes reads `"1 hour"`.
+///
+/// ```
+/// use std::time::Duration;
+/// assert_eq!(passcheck::format_duration_human(Duration::from_secs(3 * 3600)), "3 hours");
+/// ```
+pub fn format_duration_human(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds < 1.0 {
+        return "less than a second".to_string();
+    }
+    let mut unit = UNITS.iter().rposition(|&(_, size)| seconds >= size).unwrap_or(0);
+    let mut count = (seconds / UNITS[unit].1).round();
+    if let Some(&(_, next)) = UNITS.get(unit + 1) && count * UNITS[unit].1 >= next {
+        unit += 1;
+        count = (seconds / next).round();
+    }
+    if unit == UNITS.len() - 1 && count >= 100.0 {
+        return "centuries".to_string();
+    }
+    let (name, _) = UNITS[unit];
+    format!("{} {}{}", count, name, if count == 1.0 { "" } else { "s" })
+}
+
+/// How much one detected pattern lowered a guess estimate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternContribution {
+    pub kind: WeakPattern,
+    /// The part of the password the pattern covers.
+    pub matched: String,
+    /// Orders of magnitude the pattern removed from the search space.
+    pub log10_reduction: f64,
+}
+
+/// Estimates the guesses for `password`: the estimator's bits, less what
+/// each non-overlapping pattern makes predictable, or the size of the
+/// smallest dictionary containing it if that is lower.
+pub(crate) fn analyze<'d>(
+    password: &str,
+    estimator: Estimator,
+    dictionaries: impl IntoIterator<Item = &'d Dictionary>,
+    score: u8,
+) -> Analysis {
+    let log10 = |s: &str| estimator.bits(s) * 2f64.log10();
+    let chars: Vec<char> = password.chars().collect();
+    let mut estimate = log10(password);
+
+    let mut found = strength::find_patterns(password, &chars);
+    // Longest first, so overlapping patterns do not count characters twice.
+    found.sort_by_key(|(_, range)| (Reverse(range.len()), range.start));
+    let mut taken = vec![false; chars.len()];
+    let mut patterns = Vec::new();
+    for (kind, range) in found {
+        if taken[range.clone()].iter().any(|&t| t) {
+            continue;
+        }
+        taken[range.clone()].fill(true);
+        let matched: String = chars[range.clone()].iter().collect();
+        let first = log10(&chars[range.start].to_string());
+        let len = range.len() as f64;
+        let cost = match kind {
+            WeakPattern::Repeat => first + len.log10(),
+            WeakPattern::Sequence => first + 2f64.log10() + len.log10(),
+            _ => first + (len - 1.0) * WALK_BRANCHING.log10(),
+        };
+        let log10_reduction = (log10(&matched) - cost).max(0.0);
+        if log10_reduction > 0.0 {
+            estimate -= log10_reduction;
+            patterns.push((range.start, PatternContribution { kind, matched, log10_reduction }));
+        }
+    }
+    patterns.sort_by_key(|&(start, _)| start);
+    let mut patterns: Vec<_> = patterns.into_iter().map(|(_, p)| p).collect();
+    estimate = estimate.max(0.0);
+
+    let listed = dictionaries
+        .into_iter()
+        .filter(|words| words.contains(password))
+        .map(|words| (words.len().max(1) as f64).log10())
+        .min_by(f64::total_cmp);
+    if let Some(size) = listed.filter(|&size| size < estimate) {
+        patterns = vec![PatternContribution {
+            kind: WeakPattern::Dictionary,
+            matched: password.to_string(),
+            log10_reduction: estimate - size,
+        }];
+        estimate = size;
+    }
+
+    Analysis { score, guesses: 10f64.powf(estimate).min(f64::MAX), guesses_log10: estimate, patterns, fingerprint: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 0.01
+    }
+
+    #[test]
+    fn pins_guess_estimates() {
+        let none: [&Dictionary; 0] = [];
+        let random = analyze("x7kq2vbz", Estimator::Pool, none, 0);
+        assert!(close(random.guesses_log10, 12.45), "{}", random.guesses_log10);
+        assert!(random.patterns.is_empty());
+
+        let walk = analyze("qwer", Estimator::Pool, none, 0);
+        assert!(close(walk.guesses_log10, 3.75), "{}", walk.guesses_log10);
+        assert_eq!(walk.patterns[0].kind, WeakPattern::KeyboardWalk);
+        assert!(close(walk.patterns[0].log10_reduction, 1.91));
+
+        let repeat = analyze("aaaaaaaa", Estimator::Pool, none, 0);
+        assert!(close(repeat.guesses_log10, 2.32), "{}", repeat.guesses_log10);
+        assert!(close(repeat.guesses.log10(), repeat.guesses_log10));
+
+        let mixed = analyze("x7k-1234-zzzz", Estimator::Pool, none, 0);
+        let kinds: Vec<_> = mixed.patterns.iter().map(|p| (p.kind, p.matched.as_str())).collect();
+        assert_eq!(kinds, [(WeakPattern::Sequence, "1234"), (WeakPattern::Repeat, "zzzz")]);
+    }
+
+    #[test]
+    fn dictionary_hits_replace_pattern_estimates() {
+        let words = Dictionary::from(["password", "qwerty"]);
+        let listed = analyze("qwerty", Estimator::Pool, [&words], 0);
+        assert!(close(listed.guesses_log10, 2f64.log10()));
+        assert_eq!(listed.patterns.len(), 1);
+        assert_eq!(listed.patterns[0].kind, WeakPattern::Dictionary);
+    }
+
+    #[test]
+    fn crack_times_follow_the_rates() {
+        let none: [&Dictionary; 0] = [];
+        let analysis = Analysis { guesses: 1e6, guesses_log10: 6.0, ..analyze("", Estimator::Pool, none, 0) };
+        let seconds = |times: [(CrackScenario, Duration); 4]| times.map(|(_, time)| time.as_secs_f64());
+        let expected = [3.6e7, 1e5, 100.0, 1e-4];
+        assert_eq!(analysis.crack_times().map(|(scenario, _)| scenario), CrackScenario::ALL);
+        for (actual, expected) in seconds(analysis.crack_times()).into_iter().zip(expected) {
+            assert!((actual / expected - 1.0).abs() < 1e-9, "{} vs {}", actual, expected);
+        }
+        let rates = CrackRates { offline_fast_hash: 1e3, ..CrackRates::default() };
+        assert!((seconds(analysis.crack_times_with(&rates))[3] - 1000.0).abs() < 1e-6);
+
+        let long = analyze(&"aB3!".repeat(20_000), Estimator::Pool, none, 0);
+        assert_eq!(long.crack_times()[3].1, Duration::MAX);
+    }
+
+    #[test]
+    fn formats_durations_for_humans() {
+        let cases = [
+            (0.0, "less than a second"),
+            (0.999, "less than a second"),
+            (1.0, "1 second"),
+            (42.4, "42 seconds"),
+            (59.6, "1 minute"),
+            (90.0, "2 minutes"),
+            (3.0 * 3_600.0, "3 hours"),
+            (3_600.0 * 23.6, "1 day"),
+            (86_400.0 * 45.0, "1 month"),
+            (31_556_952.0 * 2.0, "2 years"),
+            (31_556_952.0 * 99.4, "99 years"),
+            (31_556_952.0 * 99.6, "centuries"),
+            (f64::MAX, "centuries"),
+        ];
+        for (seconds, expected) in cases {
+            let duration = Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX);
+            assert_eq!(format_duration_human(duration), expected, "{} seconds", seconds);
+        }
+    }
+
+    #[test]
+    fn saturates_for_long_inputs() {
+        let none: [&Dictionary; 0] = [];
+        let long = analyze(&"aB3!".repeat(20_000), Estimator::Pool, none, 0);
+        assert_eq!(long.guesses, f64::MAX);
+        assert!(long.guesses_log10.is_finite() && long.guesses_log10 > 308.0);
+        let empty = analyze("", Estimator::Pool, none, 0);
+        assert_eq!((empty.guesses, empty.guesses_log10), (1.0, 0.0));
+    }
+}
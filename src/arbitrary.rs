@@ -0,0 +1,169 @@
+//! Seeded generators of policies and passwords, for property tests of code
+//! built on a checker, such as "every password the checker accepts, my
+//! service accepts too".
+//!
+//! The crate has no dependencies, so rather than `proptest` strategies this
+//! module offers a small deterministic generator: the same seed always
+//! yields the same policies and passwords, so a failing case is reproduced
+//! from its seed.
+//!
+//! ```
+//! use passcheck::PasswordChecker;
+//! use passcheck::arbitrary::{CharClass, Seeded};
+//!
+//! let mut rng = Seeded::new(7);
+//! for _ in 0..100 {
+//!     let checker = PasswordChecker::from_config(rng.policy(4));
+//!     let password = rng.password(0..24, &CharClass::ALL);
+//!     // Whatever the policy, the checker decides the same way twice.
+//!     assert_eq!(checker.check(&password).is_ok(), checker.validate(&password).is_ok());
+//! }
+//! ```
+
+use std::ops::Range;
+
+use crate::{Dictionary, Estimator, KeyboardWalk, Pattern, PolicyConfig, Rule, SPECIAL_CHARS, SpecialCharPolicy};
+
+/// A character class [`Seeded::password`] draws from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    /// ASCII lowercase letters.
+    Lower,
+    /// ASCII uppercase letters.
+    Upper,
+    /// ASCII digits.
+    Digit,
+    /// The builtin special characters.
+    Special,
+    /// A space and `é`, which none of the composition rules count.
+    Other,
+}
+
+impl CharClass {
+    /// Every class.
+    pub const ALL: [CharClass; 5] = [CharClass::Lower, CharClass::Upper, CharClass::Digit, CharClass::Special, CharClass::Other];
+
+    fn chars(self) -> Vec<char> {
+        match self {
+            CharClass::Lower => ('a'..='z').collect(),
+            CharClass::Upper => ('A'..='Z').collect(),
+            CharClass::Digit => ('0'..='9').collect(),
+            CharClass::Special => SPECIAL_CHARS.to_vec(),
+            CharClass::Other => vec![' ', 'é'],
+        }
+    }
+}
+
+/// A xorshift64 generator; not for anything but tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seeded(u64);
+
+impl Seeded {
+    /// A generator starting from `seed`. A zero seed, which xorshift would
+    /// never leave, is replaced by a fixed one.
+    pub fn new(seed: u64) -> Self {
+        Seeded(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number below `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// A number in `0.0..1.0`, in steps of 1/10000.
+    pub fn fraction(&mut self) -> f64 {
+        (self.next_u64() % 10_000) as f64 / 10_000.0
+    }
+
+    /// A password with a length in `len` over `classes`, with one character
+    /// of each class when it fits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` or `classes` is empty.
+    pub fn password(&mut self, len: Range<usize>, classes: &[CharClass]) -> String {
+        let len = len.start + self.below(len.len());
+        let chosen: Vec<Vec<char>> = classes.iter().map(|class| class.chars()).collect();
+        let all = chosen.concat();
+        let mut chars: Vec<char> = (0..len).map(|_| all[self.below(all.len())]).collect();
+        if len >= chosen.len() {
+            for (i, class) in chosen.iter().enumerate() {
+                let pos = self.below(len / chosen.len()) * chosen.len() + i;
+                chars[pos] = class[self.below(class.len())];
+            }
+        }
+        chars.into_iter().collect()
+    }
+
+    /// One rule with small bounds, counting the builtin special set.
+    pub fn rule(&mut self) -> Rule<'static> {
+        match self.below(10) {
+            0 => Rule::MinLength(self.below(16), None),
+            1 if self.below(2) == 0 => Rule::MaxBytes(self.below(24), None),
+            1 => Rule::MaxLength(self.below(24), None),
+            2 => Rule::RequireUpperLower(None),
+            3 => Rule::RequireNumber(None),
+            4 => Rule::RequireSpecialChar(SpecialCharPolicy::Builtin, None),
+            5 => Rule::Blacklist(Dictionary::from(["abc", "XYZ0"]), None),
+            6 => Rule::MustNotMatch(Pattern::new("\\d{3}").unwrap(), None),
+            7 => Rule::KeyboardWalk(KeyboardWalk::new().min_length(3), None),
+            8 => Rule::MinEntropy(self.below(80) as f64, Estimator::Pool, None),
+            _ => Rule::LengthOrEntropy { min_len_strong: 6, min_entropy_bits: 30.0, min_len_fallback: 12, msg: None },
+        }
+    }
+
+    /// A policy of up to `max_rules` rules from [`rule`](Self::rule), whose
+    /// length limits are never below its minimum lengths.
+    pub fn policy(&mut self, max_rules: usize) -> PolicyConfig {
+        let mut rules: Vec<_> = (0..self.below(max_rules + 1)).map(|_| self.rule()).collect();
+        let min = rules.iter().filter_map(|rule| if let Rule::MinLength(n, _) = rule { Some(*n) } else { None }).max().unwrap_or(0);
+        for rule in &mut rules {
+            if let Rule::MaxLength(n, _) | Rule::MaxBytes(n, _) = rule {
+                *n = (*n).max(min);
+            }
+        }
+        PolicyConfig { rules, length_exemption: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordChecker;
+
+    #[test]
+    fn passwords_have_the_requested_shape() {
+        let mut rng = Seeded::new(1);
+        for _ in 0..200 {
+            let password = rng.password(4..12, &[CharClass::Upper, CharClass::Digit]);
+            assert!((4..12).contains(&password.chars().count()), "{}", password);
+            assert!(password.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()), "{}", password);
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()) && password.chars().any(|c| c.is_ascii_digit()), "{}", password);
+        }
+        assert_eq!(Seeded::new(5).password(0..30, &CharClass::ALL), Seeded::new(5).password(0..30, &CharClass::ALL));
+    }
+
+    #[test]
+    fn policies_keep_their_length_limits_consistent() {
+        let mut rng = Seeded::new(2);
+        for _ in 0..200 {
+            let policy = rng.policy(6);
+            assert!(policy.rules.len() <= 6);
+            let min = policy.rules.iter().filter_map(|rule| if let Rule::MinLength(n, _) = rule { Some(*n) } else { None }).max().unwrap_or(0);
+            assert!(policy.rules.iter().all(|rule| !matches!(rule, Rule::MaxLength(n, _) | Rule::MaxBytes(n, _) if *n < min)), "{:?}", policy);
+            assert_eq!(PolicyConfig::from_json(&policy.to_json()).unwrap(), policy);
+            PasswordChecker::from_config(policy);
+        }
+    }
+}
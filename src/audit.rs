@@ -0,0 +1,203 @@
+//! Aggregate statistics over a corpus of passwords.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::json::Value;
+use crate::{PasswordChecker, Violations};
+
+/// Percentiles of the length distribution shown by [`AuditSummary`]'s
+/// `Display` and [`to_json`](AuditSummary::to_json).
+const PERCENTILES: [u8; 3] = [10, 50, 90];
+
+/// Counts over the passwords given to [`audit`]. No password is kept:
+/// only counts, and lengths in characters.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AuditSummary {
+    pub total: usize,
+    /// Passwords that passed the checker.
+    pub passed: usize,
+    /// Number of passwords failing each rule, by code. With
+    /// [`fail_fast`](PasswordChecker::fail_fast) only first failures count.
+    pub failures: BTreeMap<&'static str, usize>,
+    /// Number of passwords with each
+    /// [`score_zxcvbn_scale`](PasswordChecker::score_zxcvbn_scale), 0 to 4.
+    pub strength: [usize; 5],
+    /// Number of passwords of each length in characters.
+    pub lengths: BTreeMap<usize, usize>,
+}
+
+/// Checks every password in `passwords` and sums up the results.
+pub fn audit<'p>(checker: &PasswordChecker<'_>, passwords: impl IntoIterator<Item = &'p str>) -> AuditSummary {
+    let mut summary = AuditSummary::new();
+    for password in passwords {
+        // Only the counts are wanted here.
+        let _ = summary.add(checker, password);
+    }
+    summary
+}
+
+impl AuditSummary {
+    /// An empty summary, to [`add`](Self::add) passwords to one at a time.
+    pub fn new() -> Self {
+        AuditSummary::default()
+    }
+
+    /// Checks `password` and counts the result, which is returned for
+    /// callers that also report each password.
+    pub fn add(&mut self, checker: &PasswordChecker<'_>, password: &str) -> Result<(), Violations> {
+        let result = checker.check(password);
+        self.total += 1;
+        match &result {
+            Ok(()) => self.passed += 1,
+            Err(violations) => {
+                for code in violations.codes() {
+                    *self.failures.entry(code).or_default() += 1;
+                }
+            }
+        }
+        self.strength[usize::from(checker.score_zxcvbn_scale(password))] += 1;
+        *self.lengths.entry(password.chars().count()).or_default() += 1;
+        result
+    }
+
+    /// Passwords that failed the checker.
+    pub fn failed(&self) -> usize {
+        self.total - self.passed
+    }
+
+    /// Share of passwords that passed, from 0 to 1; 0 when there are none.
+    pub fn pass_rate(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.passed as f64 / self.total as f64 }
+    }
+
+    /// The smallest length at least `percentile` percent of the passwords
+    /// do not exceed (nearest rank), `None` without passwords. Percentiles
+    /// are clamped to `0..=100`, where 0 is the shortest length.
+    pub fn length_percentile(&self, percentile: f64) -> Option<usize> {
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0 * self.total as f64).ceil() as usize).max(1);
+        let mut seen = 0;
+        self.lengths.iter().find_map(|(&len, &count)| {
+            seen += count;
+            (seen >= rank).then_some(len)
+        })
+    }
+
+    /// Serializes the summary, e.g.
+    /// `{"total":4,"passed":3,"pass_rate":0.75,"failures":{"min_length":1},"strength":[1,0,1,2,0],"length":{"min":5,"p10":5,"p50":12,"p90":16,"max":16}}`.
+    /// Length statistics are `null` without passwords.
+    pub fn to_json(&self) -> String {
+        let number = |n: Option<usize>| n.map_or(Value::Null, |n| Value::Number(n as f64));
+        let mut length = vec![("min".to_string(), number(self.lengths.keys().next().copied()))];
+        for p in PERCENTILES {
+            length.push((format!("p{}", p), number(self.length_percentile(p.into()))));
+        }
+        length.push(("max".to_string(), number(self.lengths.keys().next_back().copied())));
+        Value::Object(vec![
+            ("total".to_string(), Value::Number(self.total as f64)),
+            ("passed".to_string(), Value::Number(self.passed as f64)),
+            ("pass_rate".to_string(), Value::Number(self.pass_rate())),
+            (
+                "failures".to_string(),
+                Value::Object(self.failures.iter().map(|(code, n)| (code.to_string(), Value::Number(*n as f64))).collect()),
+            ),
+            ("strength".to_string(), Value::Array(self.strength.iter().map(|n| Value::Number(*n as f64)).collect())),
+            ("length".to_string(), Value::Object(length)),
+        ])
+        .to_string()
+    }
+}
+
+/// A compact table:
+///
+/// ```text
+/// total      4
+/// passed     3 (75.0%)
+/// failures   min_length 1
+/// strength   0:1 1:0 2:1 3:2 4:0
+/// length     min 5, p10 5, p50 12, p90 16, max 16
+/// ```
+impl fmt::Display for AuditSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<10} {}", "total", self.total)?;
+        writeln!(f, "{:<10} {} ({:.1}%)", "passed", self.passed, self.pass_rate() * 100.0)?;
+        let failures: Vec<String> = self.failures.iter().map(|(code, n)| format!("{} {}", code, n)).collect();
+        writeln!(f, "{:<10} {}", "failures", if failures.is_empty() { "none".to_string() } else { failures.join(", ") })?;
+        let strength: Vec<String> = self.strength.iter().enumerate().map(|(bucket, n)| format!("{}:{}", bucket, n)).collect();
+        writeln!(f, "{:<10} {}", "strength", strength.join(" "))?;
+        let (Some(min), Some(max)) = (self.lengths.keys().next(), self.lengths.keys().next_back()) else {
+            return write!(f, "{:<10} -", "length");
+        };
+        let percentiles: Vec<String> = PERCENTILES.iter().map(|&p| format!("p{} {}", p, self.length_percentile(p.into()).unwrap_or(0))).collect();
+        write!(f, "{:<10} min {}, {}, max {}", "length", min, percentiles.join(", "), max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<String> {
+        // 10 passwords of each length from 5 to 14: those of 8 or more
+        // characters pass, and the ones without a digit also fail.
+        (5..15).flat_map(|len| (0..10).map(move |i| if i < 3 { "x".repeat(len) } else { format!("{}{}", "y".repeat(len - 1), i) })).collect()
+    }
+
+    fn checker() -> PasswordChecker<'static> {
+        PasswordChecker::new().min_length(8, None).require_number(None)
+    }
+
+    #[test]
+    fn counts_a_synthetic_corpus() {
+        let corpus = corpus();
+        let summary = audit(&checker(), corpus.iter().map(String::as_str));
+        assert_eq!(summary.total, 100);
+        assert_eq!(summary.passed, 49);
+        assert_eq!(summary.failed(), 51);
+        assert!((summary.pass_rate() - 0.49).abs() < 1e-12);
+        assert_eq!(summary.failures, BTreeMap::from([("min_length", 30), ("require_number", 30)]));
+        // Repeated characters take few guesses, one character alone fewest.
+        assert_eq!(summary.strength, [30, 70, 0, 0, 0]);
+        assert_eq!(summary.lengths.len(), 10);
+        assert!(summary.lengths.values().all(|&n| n == 10));
+        assert_eq!(summary.length_percentile(0.0), Some(5));
+        assert_eq!(summary.length_percentile(10.0), Some(5));
+        assert_eq!(summary.length_percentile(10.5), Some(6));
+        assert_eq!(summary.length_percentile(50.0), Some(9));
+        assert_eq!(summary.length_percentile(90.0), Some(13));
+        assert_eq!(summary.length_percentile(100.0), Some(14));
+    }
+
+    #[test]
+    fn renders_without_passwords() {
+        let corpus = ["short", "longer password 1", "Z"];
+        let summary = audit(&checker(), corpus);
+        let strength = summary.strength.map(|n| n.to_string());
+        assert_eq!(
+            summary.to_string(),
+            format!(
+                "total      3\npassed     1 (33.3%)\nfailures   min_length 2, require_number 2\nstrength   0:{} 1:{} 2:{} 3:{} 4:{}\nlength     min 1, p10 1, p50 5, p90 17, max 17",
+                strength[0], strength[1], strength[2], strength[3], strength[4]
+            )
+        );
+        let json = summary.to_json();
+        assert!(json.starts_with(r#"{"total":3,"passed":1,"pass_rate":0.3333333333333333,"failures":{"min_length":2,"require_number":2},"strength":["#));
+        assert!(json.ends_with(r#""length":{"min":1,"p10":1,"p50":5,"p90":17,"max":17}}"#), "{}", json);
+        for password in corpus {
+            assert!(!json.contains(password) && !summary.to_string().contains(password));
+        }
+
+        let empty = AuditSummary::new();
+        assert_eq!(empty.length_percentile(50.0), None);
+        assert_eq!(empty.to_string(), "total      0\npassed     0 (0.0%)\nfailures   none\nstrength   0:0 1:0 2:0 3:0 4:0\nlength     -");
+        assert!(empty.to_json().ends_with(r#""length":{"min":null,"p10":null,"p50":null,"p90":null,"max":null}}"#));
+    }
+
+    #[test]
+    fn add_returns_each_result() {
+        let mut summary = AuditSummary::new();
+        assert!(summary.add(&checker(), "password1").is_ok());
+        assert_eq!(summary.add(&checker(), "pw").unwrap_err().codes().collect::<Vec<_>>(), ["min_length", "require_number"]);
+        assert_eq!((summary.total, summary.passed), (2, 1));
+    }
+}
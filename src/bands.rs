@@ -0,0 +1,150 @@
+//! Length recommendations above the minimum, reported as warnings.
+
+use std::borrow::Cow;
+
+use crate::{PasswordChecker, Rule, Violations};
+
+impl<'a> PasswordChecker<'a> {
+    /// Rejects passwords shorter than `error_below` and warns about those
+    /// shorter than `warn_below`, so a password of `error_below` up to
+    /// `warn_below - 1` characters passes with a nudge towards a longer one.
+    /// Lengths are measured as for [`min_length`](Self::min_length).
+    ///
+    /// This adds [`min_length`](Self::min_length) and
+    /// [`recommended_length`](Self::recommended_length) rules; the warning
+    /// is reported by [`check_with_warnings`](Self::check_with_warnings).
+    ///
+    /// ```
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let checker = PasswordChecker::new().length_bands(8, 12, None, Some("12 or more characters are safer."));
+    /// let (result, warnings) = checker.check_with_warnings("sunflower");
+    /// assert!(result.is_ok());
+    /// assert_eq!(warnings.codes().collect::<Vec<_>>(), ["recommended_length"]);
+    /// assert!(checker.check_with_warnings("sunflower-field").1.is_empty());
+    /// assert!(checker.check("sunfl").is_err());
+    /// ```
+    pub fn length_bands(self, error_below: usize, warn_below: usize, error_msg: Option<&'a str>, warn_msg: Option<&'a str>) -> Self {
+        self.min_length(error_below, error_msg).recommended_length(warn_below, warn_msg)
+    }
+
+    /// Adds a rule warning about passwords shorter than `len`, without
+    /// rejecting them.
+    pub fn recommended_length(mut self, len: usize, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::RecommendedLength(len, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Like [`check`](Self::check), also returning the warnings of
+    /// [`recommended_length`](Self::recommended_length) rules for an
+    /// accepted password. Warnings have the same shape as violations, so
+    /// UIs can render them with their own style. A rejected password has
+    /// no warnings: its errors come first.
+    pub fn check_with_warnings(&self, password: &str) -> (Result<(), Violations>, Violations) {
+        let result = self.check(password);
+        let warnings = match result {
+            Ok(()) => self.outcomes(password, None, None, |_, rule| rule.warns(password)).filter_map(|outcome| outcome.violation).collect(),
+            Err(_) => Violations::default(),
+        };
+        (result, warnings)
+    }
+
+    /// Length rules the others make redundant, by index, with the reason:
+    /// a minimum below another minimum, a maximum above another of the
+    /// same kind, a repeated limit, or a recommended length that never
+    /// warns because a minimum already rejects shorter passwords.
+    ///
+    /// ```
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let checker = PasswordChecker::new().min_length(10, None).length_bands(8, 12, None, None);
+    /// assert_eq!(checker.redundant_rules(), [(1, "implied by rules[0] (at least 10 characters)".to_string())]);
+    /// ```
+    pub fn redundant_rules(&self) -> Vec<(usize, String)> {
+        let mut redundant = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            // Of two equal limits, the later one is redundant.
+            let covers = |j: usize, other: &Rule<'_>| match (rule, other) {
+                (Rule::MinLength(n, _), Rule::MinLength(m, _)) | (Rule::RecommendedLength(n, _), Rule::RecommendedLength(m, _)) => {
+                    m > n || m == n && j < i
+                }
+                (Rule::MaxLength(n, _), Rule::MaxLength(m, _)) | (Rule::MaxBytes(n, _), Rule::MaxBytes(m, _)) => m < n || m == n && j < i,
+                (Rule::RecommendedLength(n, _), Rule::MinLength(m, _)) => m >= n,
+                _ => false,
+            };
+            let Some((j, other)) = self.rules.iter().enumerate().find(|&(j, other)| covers(j, other)) else { continue };
+            let requirement = lowercase_first(&other.description());
+            let reason = match (rule, other) {
+                (Rule::RecommendedLength(..), Rule::MinLength(..)) => format!("never warns, as rules[{}] requires {}", j, requirement),
+                _ => format!("implied by rules[{}] ({})", j, requirement),
+            };
+            redundant.push((i, reason));
+        }
+        redundant
+    }
+}
+
+fn lowercase_first(s: &str) -> String {
+    let mut chars = s.chars();
+    chars.next().map_or_else(String::new, |first| first.to_lowercase().chain(chars).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParamValue;
+
+    fn codes(violations: &Violations) -> Vec<&'static str> {
+        violations.codes().collect()
+    }
+
+    #[test]
+    fn bands_are_inclusive_below_and_exclusive_above() {
+        let checker = PasswordChecker::new().length_bands(8, 12, Some("Use at least 8 characters."), Some("12 or more is safer."));
+        let check = |len: usize| checker.check_with_warnings(&"x".repeat(len));
+        let (result, warnings) = check(7);
+        assert_eq!(codes(&result.unwrap_err()), ["min_length"]);
+        assert!(warnings.is_empty());
+        for len in [8, 11] {
+            let (result, warnings) = check(len);
+            assert!(result.is_ok(), "{}", len);
+            assert_eq!(codes(&warnings), ["recommended_length"]);
+            assert_eq!(warnings[0].message, "12 or more is safer.");
+            assert_eq!(warnings[0].params, [("min", ParamValue::Int(12)), ("actual", ParamValue::Int(len))]);
+        }
+        let (result, warnings) = check(12);
+        assert!(result.is_ok() && warnings.is_empty());
+        // Warnings never reject, so plain checks and failure listings ignore them.
+        assert!(checker.check("x".repeat(9).as_str()).is_ok());
+        assert!(checker.check_iter("xxxxxxxxx").all(|outcome| outcome.violation.is_none()));
+    }
+
+    #[test]
+    fn warnings_have_default_messages_and_keys() {
+        let checker = PasswordChecker::new().length_bands(8, 12, None, None);
+        assert_eq!(checker.check_with_warnings("sunflower").1[0].message, "Consider a password of at least 12 characters; longer is stronger.");
+        let keyed = checker.keys_only(true);
+        assert!(keyed.check_with_warnings("sunflower").1[0].message.is_empty());
+    }
+
+    #[test]
+    fn explicit_minimums_interact_with_bands() {
+        let checker = PasswordChecker::new().min_length(10, None).length_bands(8, 12, None, None);
+        assert_eq!(codes(&checker.check_with_warnings("sunflowe").0.unwrap_err()), ["min_length"]);
+        assert_eq!(codes(&checker.check_with_warnings("sunflower").0.unwrap_err()), ["min_length"]);
+        assert_eq!(codes(&checker.check_with_warnings("sunflowers").1), ["recommended_length"]);
+        assert_eq!(checker.redundant_rules(), [(1, "implied by rules[0] (at least 10 characters)".to_string())]);
+
+        let silent = PasswordChecker::new().min_length(12, None).length_bands(8, 12, None, None);
+        assert_eq!(silent.redundant_rules(), [
+            (1, "implied by rules[0] (at least 12 characters)".to_string()),
+            (2, "never warns, as rules[0] requires at least 12 characters".to_string()),
+        ]);
+        let limits = PasswordChecker::new().max_length(64, None).max_length(32, None).max_bytes(72, None).max_bytes(72, None);
+        assert_eq!(limits.redundant_rules(), [
+            (0, "implied by rules[1] (at most 32 characters)".to_string()),
+            (3, "implied by rules[2] (at most 72 bytes)".to_string()),
+        ]);
+        assert!(PasswordChecker::new().length_bands(8, 12, None, None).max_length(64, None).redundant_rules().is_empty());
+    }
+}
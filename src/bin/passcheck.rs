@@ -0,0 +1,517 @@
+//! Command-line front end for passcheck.
+//!
+//! Passwords are always read from stdin, never from arguments, so they do
+//! not end up in `ps` output or shell history. `interactive` additionally
+//! turns off terminal echo and prints only what it derives from each
+//! password.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::process::ExitCode;
+
+use passcheck::{Analysis, AuditSummary, CrackScenario, PasswordChecker, format_duration_human, parse_policy};
+
+const USAGE: &str = "\
+usage: passcheck <command> [options]
+
+commands:
+  validate     validate the password read from stdin
+  describe     print the policy requirements
+  score        print the strength (0-100) of the password read from stdin
+  audit        validate every line of --input and report results by line number
+  interactive  read passwords from the terminal without echo and show the
+               checklist, strength and crack times of each, until an empty line
+
+options:
+  --policy <file>        load the policy from a JSON file
+  --rules <spec>         use a policy string, e.g. \"min:12 digit special\"
+  --input <file>         newline-separated candidates for `audit`
+  --format <json|csv>    output format for `audit` (default: json)
+";
+
+/// Exit code for usage and configuration errors; 1 means validation failed.
+const EXIT_USAGE: u8 = 2;
+
+enum PolicySource {
+    File(String),
+    Rules(String),
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Json,
+    Csv,
+}
+
+struct Args {
+    command: String,
+    policy: Option<PolicySource>,
+    input: Option<String>,
+    format: Format,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = args.next().ok_or("missing command")?;
+    let mut policy = None;
+    let mut input = None;
+    let mut format = Format::Json;
+    while let Some(arg) = args.next() {
+        let source = match arg.as_str() {
+            "--policy" => PolicySource::File(args.next().ok_or("--policy needs a file")?),
+            "--rules" => PolicySource::Rules(args.next().ok_or("--rules needs a policy string")?),
+            "--input" => {
+                input = Some(args.next().ok_or("--input needs a file")?);
+                continue;
+            }
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    _ => return Err("--format must be `json` or `csv`".to_string()),
+                };
+                continue;
+            }
+            other => return Err(format!("unexpected argument `{}`", other)),
+        };
+        if policy.replace(source).is_some() {
+            return Err("use only one of --policy and --rules".to_string());
+        }
+    }
+    Ok(Args { command, policy, input, format })
+}
+
+fn load_policy(source: Option<&PolicySource>) -> Result<PasswordChecker<'static>, String> {
+    match source {
+        Some(PolicySource::File(path)) => {
+            let json = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+            PasswordChecker::from_json(&json).map_err(|e| format!("{}: {}", path, e))
+        }
+        Some(PolicySource::Rules(spec)) => parse_policy(spec).map_err(|e| e.to_string()),
+        None => Err("a policy is required (--policy or --rules)".to_string()),
+    }
+}
+
+/// Reads the first line of stdin without its line terminator.
+fn read_password() -> Result<String, String> {
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).map_err(|e| format!("cannot read stdin: {}", e))?;
+    let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+    let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+    Ok(trimmed.to_string())
+}
+
+fn run(args: Args) -> Result<ExitCode, String> {
+    let mut out = io::stdout().lock();
+    match args.command.as_str() {
+        "validate" => {
+            let checker = load_policy(args.policy.as_ref())?;
+            let password = read_password()?;
+            match checker.validate(&password) {
+                Ok(()) => Ok(ExitCode::SUCCESS),
+                Err(errors) => {
+                    for err in errors {
+                        let _ = writeln!(out, "{}", err);
+                    }
+                    Ok(ExitCode::FAILURE)
+                }
+            }
+        }
+        "describe" => {
+            let checker = load_policy(args.policy.as_ref())?;
+            for line in checker.describe() {
+                let _ = writeln!(out, "{}", line);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        "score" => {
+            // The score does not depend on the policy, but accept one anyway
+            // so the same flags work for every command.
+            let checker = match &args.policy {
+                Some(_) => load_policy(args.policy.as_ref())?,
+                None => PasswordChecker::new(),
+            };
+            let password = read_password()?;
+            let _ = writeln!(out, "{}", checker.score(&password));
+            Ok(ExitCode::SUCCESS)
+        }
+        "audit" => {
+            let checker = load_policy(args.policy.as_ref())?;
+            let path = args.input.as_deref().ok_or("audit needs --input")?;
+            let file = File::open(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+            audit(&checker, BufReader::new(file), args.format, &mut out)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        "interactive" => {
+            let checker = load_policy(args.policy.as_ref())?;
+            interactive(&checker, &mut out)?;
+            Ok(ExitCode::SUCCESS)
+        }
+        other => Err(format!("unknown command `{}`", other)),
+    }
+}
+
+/// Prompts for passwords until an empty line, printing the checklist,
+/// strength and crack times of each. Echo is off while a password is
+/// typed, so it never appears on screen or in the scrollback.
+fn interactive(checker: &PasswordChecker, out: &mut impl Write) -> Result<(), String> {
+    if !io::stdin().is_terminal() {
+        return Err("interactive needs a terminal on stdin".to_string());
+    }
+    let write_err = |e: io::Error| format!("cannot write output: {}", e);
+    let labels = checker.describe();
+    loop {
+        let password = {
+            // Before the prompt, so a failure never leaves one waiting.
+            let _echo = EchoOff::new().map_err(|e| format!("cannot turn off terminal echo: {}", e))?;
+            write!(out, "Password (empty line to quit): ").and_then(|()| out.flush()).map_err(write_err)?;
+            read_password()?
+        };
+        // The newline typed after the password was not echoed either.
+        writeln!(out).map_err(write_err)?;
+        if password.is_empty() {
+            return Ok(());
+        }
+        let report = render(&checklist(&labels, checker, &password), checker.score_zxcvbn_scale(&password), &checker.analyze(&password));
+        writeln!(out, "{}", report).map_err(write_err)?;
+    }
+}
+
+/// Each requirement in `labels` with whether `password` meets it. Without
+/// the user's details, requirements about them are unmet.
+fn checklist<'l>(labels: &'l [String], checker: &PasswordChecker, password: &str) -> Vec<(&'l str, bool)> {
+    labels.iter().map(String::as_str).zip(checker.check_iter(password).map(|outcome| !outcome.failed())).collect()
+}
+
+/// Formats the result of one password for [`interactive`]: each
+/// requirement with ✓ or ✗, the 0–4 strength bucket and the crack time in
+/// every scenario. Takes only information derived from the password.
+fn render(checklist: &[(&str, bool)], bucket: u8, analysis: &Analysis) -> String {
+    const BUCKETS: [&str; 5] = ["very weak", "weak", "fair", "strong", "very strong"];
+    let mut report = String::new();
+    for (label, passed) in checklist {
+        report.push_str(&format!("{} {}\n", if *passed { '✓' } else { '✗' }, label));
+    }
+    let bucket_name = BUCKETS[usize::from(bucket.min(4))];
+    report.push_str(&format!("Strength: {} ({}/4), score {}/100\n", bucket_name, bucket, analysis.score));
+    report.push_str("Time to crack:\n");
+    for (scenario, duration) in analysis.crack_times() {
+        let scenario = match scenario {
+            CrackScenario::OnlineThrottled => "online, throttled",
+            CrackScenario::OnlineUnthrottled => "online, unthrottled",
+            CrackScenario::OfflineSlowHash => "offline, slow hash",
+            CrackScenario::OfflineFastHash => "offline, fast hash",
+        };
+        report.push_str(&format!("  {:<20} {}\n", scenario, format_duration_human(duration)));
+    }
+    report
+}
+
+/// Turns off terminal echo on stdin until dropped, restoring the previous
+/// terminal settings. They are also restored if the process is hung up,
+/// interrupted or terminated meanwhile, when `Drop` would not run.
+#[cfg(unix)]
+struct EchoOff;
+
+#[cfg(unix)]
+mod tty {
+    use std::ffi::c_int;
+    use std::io;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    pub const SIGNALS: [c_int; 3] = [SIGHUP, SIGINT, SIGTERM];
+    const SIGHUP: c_int = 1;
+    const SIGINT: c_int = 2;
+    const SIGTERM: c_int = 15;
+    const SIG_DFL: usize = 0;
+    const SIG_ERR: usize = usize::MAX;
+    const TCSANOW: c_int = 0;
+
+    /// Room for any platform's `struct termios`, which is only copied, never
+    /// read.
+    type Termios = [u64; 32];
+
+    unsafe extern "C" {
+        fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+        fn tcsetattr(fd: c_int, action: c_int, termios: *const Termios) -> c_int;
+        fn signal(signum: c_int, handler: usize) -> usize;
+        fn raise(signum: c_int) -> c_int;
+    }
+
+    /// The settings to restore, atomic so the signal handler can read them.
+    static SAVED: [AtomicU64; 32] = [const { AtomicU64::new(0) }; 32];
+
+    /// Saves the settings of the terminal on stdin and restores them on
+    /// [`SIGNALS`] until [`restore`].
+    pub fn save() -> io::Result<()> {
+        let mut termios: Termios = [0; 32];
+        // SAFETY: `termios` is larger than any `struct termios`.
+        if unsafe { tcgetattr(0, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        for (saved, word) in SAVED.iter().zip(termios) {
+            saved.store(word, Ordering::Relaxed);
+        }
+        for signum in SIGNALS {
+            // SAFETY: `on_signal` only calls async-signal-safe functions.
+            if unsafe { signal(signum, on_signal as extern "C" fn(c_int) as usize) } == SIG_ERR {
+                let err = io::Error::last_os_error();
+                restore();
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the saved settings and the default signal handlers.
+    pub fn restore() {
+        for signum in SIGNALS {
+            // SAFETY: resetting a handler to the default is always sound.
+            unsafe { signal(signum, SIG_DFL) };
+        }
+        reset();
+    }
+
+    fn reset() {
+        let termios: Termios = std::array::from_fn(|i| SAVED[i].load(Ordering::Relaxed));
+        // SAFETY: `termios` holds what `tcgetattr` wrote in `save`.
+        unsafe { tcsetattr(0, TCSANOW, &termios) };
+    }
+
+    /// Restores the terminal, then dies of `signum` as if unhandled.
+    extern "C" fn on_signal(signum: c_int) {
+        reset();
+        // SAFETY: `signal` and `raise` are async-signal-safe. The signal is
+        // blocked while its handler runs, so it is delivered on return.
+        unsafe {
+            signal(signum, SIG_DFL);
+            raise(signum);
+        }
+    }
+}
+
+#[cfg(unix)]
+impl EchoOff {
+    fn new() -> io::Result<Self> {
+        tty::save()?;
+        let guard = EchoOff;
+        stty("-echo")?;
+        Ok(guard)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        tty::restore();
+    }
+}
+
+/// Runs `stty` on the terminal connected to stdin.
+#[cfg(unix)]
+fn stty(setting: &str) -> io::Result<()> {
+    let status = match std::process::Command::new("stty").arg(setting).stdin(std::process::Stdio::inherit()).status() {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Err(io::Error::new(err.kind(), "`stty` is not installed")),
+        result => result?,
+    };
+    if status.success() { Ok(()) } else { Err(io::Error::other(format!("stty {} failed: {}", setting, status))) }
+}
+
+/// Turns off console echo on stdin until dropped, restoring the previous
+/// console mode.
+#[cfg(windows)]
+struct EchoOff {
+    handle: *mut std::ffi::c_void,
+    mode: u32,
+}
+
+#[cfg(windows)]
+mod console {
+    use std::ffi::c_void;
+
+    pub const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+    pub const ENABLE_ECHO_INPUT: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        pub fn GetStdHandle(handle: u32) -> *mut c_void;
+        pub fn GetConsoleMode(console: *mut c_void, mode: *mut u32) -> i32;
+        pub fn SetConsoleMode(console: *mut c_void, mode: u32) -> i32;
+    }
+}
+
+#[cfg(windows)]
+impl EchoOff {
+    fn new() -> io::Result<Self> {
+        // SAFETY: the standard input handle stays valid for the life of the
+        // process, and `mode` is a valid place to write the console mode.
+        unsafe {
+            let handle = console::GetStdHandle(console::STD_INPUT_HANDLE);
+            let mut mode = 0;
+            if console::GetConsoleMode(handle, &mut mode) == 0 || console::SetConsoleMode(handle, mode & !console::ENABLE_ECHO_INPUT) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(EchoOff { handle, mode })
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for EchoOff {
+    fn drop(&mut self) {
+        // SAFETY: `handle` is the console handle the mode was read from.
+        unsafe { console::SetConsoleMode(self.handle, self.mode) };
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+struct EchoOff;
+
+#[cfg(not(any(unix, windows)))]
+impl EchoOff {
+    fn new() -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "no terminal echo control on this platform"))
+    }
+}
+
+/// Quotes a CSV field when it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Validates each line of `input`, streaming one result per line. A line
+/// that is not UTF-8 is invalid with the code `invalid_utf8`.
+///
+/// Only line numbers and violation codes are written, never the candidates.
+fn audit(checker: &PasswordChecker, mut input: impl BufRead, format: Format, out: &mut impl Write) -> Result<(), String> {
+    let write_err = |e: io::Error| format!("cannot write output: {}", e);
+    let mut summary = AuditSummary::new();
+    let mut buf = Vec::new();
+
+    match format {
+        Format::Json => write!(out, "{{\"results\":[").map_err(write_err)?,
+        Format::Csv => writeln!(out, "line,valid,violations").map_err(write_err)?,
+    }
+    loop {
+        buf.clear();
+        let read = input.read_until(b'\n', &mut buf).map_err(|e| format!("cannot read input: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        let total = summary.total + 1;
+        let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        let codes: Vec<&'static str> = match std::str::from_utf8(line) {
+            Ok(password) => match summary.add(checker, password) {
+                Ok(()) => Vec::new(),
+                Err(violations) => violations.codes().collect(),
+            },
+            // A line that cannot be checked fails without ending the audit.
+            Err(_) => {
+                summary.total += 1;
+                *summary.failures.entry("invalid_utf8").or_default() += 1;
+                vec!["invalid_utf8"]
+            }
+        };
+        match format {
+            Format::Json => {
+                let codes: Vec<String> = codes.iter().map(|c| format!("\"{}\"", c)).collect();
+                let sep = if total > 1 { "," } else { "" };
+                write!(out, "{}{{\"line\":{},\"valid\":{},\"violations\":[{}]}}", sep, total, codes.is_empty(), codes.join(","))
+            }
+            Format::Csv => writeln!(out, "{},{},{}", total, codes.is_empty(), csv_field(&codes.join(","))),
+        }
+        .map_err(write_err)?;
+    }
+
+    match format {
+        Format::Json => {
+            let counts: Vec<String> = summary.failures.iter().map(|(code, n)| format!("\"{}\":{}", code, n)).collect();
+            writeln!(
+                out,
+                "],\"summary\":{{\"total\":{},\"valid\":{},\"invalid\":{},\"violations\":{{{}}}}}}}",
+                summary.total,
+                summary.passed,
+                summary.failed(),
+                counts.join(",")
+            )
+        }
+        Format::Csv => {
+            // The summary follows the results as a second table after a blank line.
+            let mut table = format!("\ncode,count\ntotal,{}\nvalid,{}\ninvalid,{}\n", summary.total, summary.passed, summary.failed());
+            for (code, n) in &summary.failures {
+                table.push_str(&format!("{},{}\n", csv_field(code), n));
+            }
+            write!(out, "{}", table)
+        }
+    }
+    .map_err(write_err)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("passcheck: {}\n\n{}", err, USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+    match run(args) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("passcheck: {}", err);
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use passcheck::UserInfoCheck;
+
+    use super::*;
+
+    #[test]
+    fn renders_only_derived_information() {
+        let checker = PasswordChecker::new().min_length(12, None).require_number(None);
+        let password = "correct horse";
+        let labels = checker.describe();
+        let checklist = checklist(&labels, &checker, password);
+        let analysis = checker.analyze(password);
+        let report = render(&checklist, checker.score_zxcvbn_scale(password), &analysis);
+        let times: Vec<String> = analysis.crack_times().iter().map(|(_, duration)| format_duration_human(*duration)).collect();
+        let expected = format!(
+            "✓ At least 12 characters\n✗ At least one number\nStrength: {} ({}/4), score {}/100\nTime to crack:\n  online, throttled    {}\n  online, unthrottled  {}\n  offline, slow hash   {}\n  offline, fast hash   {}\n",
+            ["very weak", "weak", "fair", "strong", "very strong"][usize::from(checker.score_zxcvbn_scale(password))],
+            checker.score_zxcvbn_scale(password),
+            analysis.score,
+            times[0],
+            times[1],
+            times[2],
+            times[3],
+        );
+        assert_eq!(report, expected);
+        assert!(!report.contains("horse"));
+    }
+
+    #[test]
+    fn requirements_about_the_user_are_unmet() {
+        let checker = PasswordChecker::new().min_length(4, None).not_contain_user_info(UserInfoCheck::new(), None);
+        let labels = checker.describe();
+        assert_eq!(checklist(&labels, &checker, "alice123"), [(labels[0].as_str(), true), (labels[1].as_str(), false)]);
+    }
+
+    #[test]
+    fn renders_fixed_analyses() {
+        let analysis = Analysis { score: 3, guesses: 100.0, guesses_log10: 2.0, patterns: Vec::new(), fingerprint: None };
+        let report = render(&[("At least 8 characters", false)], 0, &analysis);
+        assert_eq!(
+            report,
+            "✗ At least 8 characters\nStrength: very weak (0/4), score 3/100\nTime to crack:\n  online, throttled    1 hour\n  online, unthrottled  10 seconds\n  offline, slow hash   less than a second\n  offline, fast hash   less than a second\n"
+        );
+    }
+}
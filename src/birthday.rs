@@ -0,0 +1,82 @@
+//! Dates of birth embedded in passwords.
+
+use crate::phone::digit_runs;
+
+/// Characters ignored between two digits of a date, as in `15/03/1985`.
+const SEPARATORS: [char; 4] = ['/', '.', '-', ' '];
+
+/// A date of birth, set with [`UserContext::date_of_birth`](crate::UserContext::date_of_birth).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Birthday {
+    pub(crate) year: u16,
+    pub(crate) month: u8,
+    pub(crate) day: u8,
+}
+
+impl Birthday {
+    /// Digit renderings looked for anywhere in a run: `YYYYMMDD`,
+    /// `DDMMYYYY`, `MMDDYYYY`, `DDMMYY`, `MMDDYY`, `DDMM` and `MMDD`.
+    fn renderings(&self) -> [String; 7] {
+        let (y, yy, m, d) = (format!("{:04}", self.year), format!("{:02}", self.year % 100), format!("{:02}", self.month), format!("{:02}", self.day));
+        [
+            format!("{}{}{}", y, m, d),
+            format!("{}{}{}", d, m, y),
+            format!("{}{}{}", m, d, y),
+            format!("{}{}{}", d, m, yy),
+            format!("{}{}{}", m, d, yy),
+            format!("{}{}", d, m),
+            format!("{}{}", m, d),
+        ]
+    }
+
+    /// Whether `password` contains this date. The year alone only counts
+    /// as a run of its own, as in `Summer1985!`, or next to the month or
+    /// day, as in `031985`, since other years in passwords are common.
+    pub(crate) fn found_in(&self, password: &str) -> bool {
+        let renderings = self.renderings();
+        let year = format!("{:04}", self.year);
+        let parts = [format!("{:02}", self.month), format!("{:02}", self.day)];
+        digit_runs(password, &SEPARATORS).iter().any(|run| {
+            if run == &year || renderings.iter().any(|r| run.contains(r.as_str())) {
+                return true;
+            }
+            run.match_indices(year.as_str()).any(|(i, _)| {
+                let (before, after) = (&run[..i], &run[i + year.len()..]);
+                parts.iter().any(|part| before.ends_with(part.as_str()) || after.starts_with(part.as_str()))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIRTHDAY: Birthday = Birthday { year: 1985, month: 3, day: 15 };
+
+    #[test]
+    fn every_rendering_is_found() {
+        for password in [
+            "Summer1985!",
+            "tom1503!",
+            "tom0315!",
+            "x150385x",
+            "x031585x",
+            "born19850315",
+            "15/03/1985!",
+            "03.15.1985",
+            "15-03-85",
+            "March031985",
+            "x198515y",
+        ] {
+            assert!(BIRTHDAY.found_in(password), "{}", password);
+        }
+    }
+
+    #[test]
+    fn unrelated_years_and_digits_pass() {
+        for password in ["Summer2024!", "x1985777x", "7198577", "Summer 2024 15", "pass15x03", "abc"] {
+            assert!(!BIRTHDAY.found_in(password), "{}", password);
+        }
+    }
+}
@@ -0,0 +1,491 @@
+//! Passwords known from data breaches, looked up in pluggable sources.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::dictionary::SortedFile;
+use crate::json::Value;
+
+/// The password a [`BreachProvider`] is asked about, as its SHA-1 hash.
+///
+/// Providers never see the password itself. An online service can be
+/// queried by [`prefix`](Self::prefix) alone, in the manner of the Pwned
+/// Passwords range API, and the [`suffix`](Self::suffix) looked up in the
+/// answer, so the service cannot tell which password was checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BreachQuery {
+    sha1: [u8; 20],
+}
+
+impl BreachQuery {
+    pub fn new(password: &str) -> Self {
+        BreachQuery { sha1: sha1(password.as_bytes()) }
+    }
+
+    /// The SHA-1 hash of the password.
+    pub fn sha1(&self) -> [u8; 20] {
+        self.sha1
+    }
+
+    /// The hash as 40 uppercase hex digits.
+    pub fn hex(&self) -> String {
+        self.sha1.iter().map(|byte| format!("{:02X}", byte)).collect()
+    }
+
+    /// The first 5 hex digits of the hash, the part sent to a range API.
+    pub fn prefix(&self) -> String {
+        self.hex()[..5].to_string()
+    }
+
+    /// The other 35 hex digits, to look up in a range API's answer.
+    pub fn suffix(&self) -> String {
+        self.hex()[5..].to_string()
+    }
+}
+
+/// Error from a [`BreachProvider`] that could not answer, such as a
+/// timeout or an unreadable file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderError {
+    pub reason: String,
+}
+
+impl ProviderError {
+    pub fn new(reason: impl Into<String>) -> Self {
+        ProviderError { reason: reason.into() }
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "breach provider unavailable: {}", self.reason)
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+impl From<io::Error> for ProviderError {
+    fn from(e: io::Error) -> Self {
+        ProviderError::new(e.to_string())
+    }
+}
+
+/// A source of compromised passwords, such as a breach corpus on disk or
+/// an internal feed, used by
+/// [`PasswordChecker::not_breached`](crate::PasswordChecker::not_breached).
+///
+/// Validation is synchronous, so providers answer synchronously too. A
+/// client of an online service blocks on its request with whatever
+/// runtime it uses, and should bound it with a timeout, returning an
+/// error when it runs out.
+///
+/// ```
+/// use passcheck::{BreachProvider, BreachQuery, Breaches, PasswordChecker, ProviderError};
+///
+/// struct Feed(Vec<BreachQuery>);
+///
+/// impl BreachProvider for Feed {
+///     fn breach_count(&self, query: &BreachQuery) -> Result<Option<u64>, ProviderError> {
+///         Ok(self.0.contains(query).then_some(1))
+///     }
+/// }
+///
+/// let checker = PasswordChecker::new().not_breached(Breaches::new(Feed(vec![BreachQuery::new("hunter2")])), None);
+/// assert_eq!(checker.check("hunter2").unwrap_err()[0].code, "not_breached");
+/// assert!(checker.check("correct horse").is_ok());
+/// ```
+pub trait BreachProvider: Send + Sync {
+    /// How often the password was seen in breaches: `Some` with the count
+    /// if the provider knows it, `None` if it does not. Providers without
+    /// counts answer `Some(1)`.
+    fn breach_count(&self, query: &BreachQuery) -> Result<Option<u64>, ProviderError>;
+}
+
+/// How [`Breaches`] combines the answers of several providers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    /// Asks every provider and reports the highest count.
+    Max,
+    /// Asks providers in order, stopping at the first that reports the
+    /// password as breached.
+    FirstPositive,
+}
+
+/// Whether a password passes when providers could not answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailMode {
+    /// Accepts the password, so an outage never blocks sign-ups.
+    Open,
+    /// Rejects the password, so an outage never lets a breached one in.
+    Closed,
+}
+
+/// The combined answer of the providers of [`Breaches`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BreachLookup {
+    /// At least one provider reported the password at least
+    /// [`threshold`](Breaches::threshold) times; the count is the highest
+    /// reported.
+    Breached(u64),
+    /// Every provider answered and none reached the threshold.
+    Clean,
+    /// None reached the threshold, but these providers could not answer,
+    /// so the password may still be breached.
+    Unavailable(Vec<ProviderError>),
+}
+
+/// Breach providers asked together, for
+/// [`PasswordChecker::not_breached`](crate::PasswordChecker::not_breached).
+///
+/// A count from any provider reaching the threshold rejects the password,
+/// even when others failed; failures decide only when no provider found
+/// it, as the [fail mode](FailMode) says.
+///
+/// ```
+/// use passcheck::{BreachLookup, BreachProvider, BreachQuery, Breaches, Combine, FailMode, ProviderError};
+///
+/// struct Down;
+///
+/// impl BreachProvider for Down {
+///     fn breach_count(&self, _: &BreachQuery) -> Result<Option<u64>, ProviderError> {
+///         Err(ProviderError::new("timed out"))
+///     }
+/// }
+///
+/// let breaches = Breaches::new(Down).combine(Combine::FirstPositive).fail_mode(FailMode::Open);
+/// assert_eq!(breaches.lookup("hunter2"), BreachLookup::Unavailable(vec![ProviderError::new("timed out")]));
+/// assert!(!breaches.rejects("hunter2"));
+/// ```
+#[derive(Clone)]
+pub struct Breaches {
+    providers: Vec<Arc<dyn BreachProvider>>,
+    combine: Combine,
+    fail_mode: FailMode,
+    threshold: u64,
+    /// The last answer, so a failing rule's params do not ask the
+    /// providers again. The lock is not held while they are asked, and
+    /// each setting starts a new one, as the answer depends on them.
+    last: Arc<Mutex<Option<(BreachQuery, BreachLookup)>>>,
+}
+
+impl Breaches {
+    /// Asks `provider`, taking the highest count, failing closed and
+    /// rejecting passwords seen once.
+    pub fn new(provider: impl BreachProvider + 'static) -> Self {
+        Breaches { providers: vec![Arc::new(provider)], combine: Combine::Max, fail_mode: FailMode::Closed, threshold: 1, last: Arc::default() }
+    }
+
+    /// Also asks `provider`, after the ones added before.
+    pub fn with(mut self, provider: impl BreachProvider + 'static) -> Self {
+        self.providers.push(Arc::new(provider));
+        self.last = Arc::default();
+        self
+    }
+
+    pub fn combine(mut self, combine: Combine) -> Self {
+        self.combine = combine;
+        self.last = Arc::default();
+        self
+    }
+
+    pub fn fail_mode(mut self, fail_mode: FailMode) -> Self {
+        self.fail_mode = fail_mode;
+        self.last = Arc::default();
+        self
+    }
+
+    /// Rejects passwords seen at least `count` times, so a policy can let
+    /// through ones seen only once or twice. Counts below 1 count as 1.
+    pub fn threshold(mut self, count: u64) -> Self {
+        self.threshold = count.max(1);
+        self.last = Arc::default();
+        self
+    }
+
+    /// How the providers are asked, as written to a policy document:
+    /// providers are code, so only their number is.
+    pub(crate) fn to_params(&self) -> Vec<(&'static str, Value)> {
+        let combine = match self.combine {
+            Combine::Max => "max",
+            Combine::FirstPositive => "first_positive",
+        };
+        let fail_mode = match self.fail_mode {
+            FailMode::Open => "open",
+            FailMode::Closed => "closed",
+        };
+        vec![
+            ("providers", Value::Number(self.providers.len() as f64)),
+            ("combine", Value::String(combine.to_string())),
+            ("fail_mode", Value::String(fail_mode.to_string())),
+            ("threshold", Value::Number(self.threshold as f64)),
+        ]
+    }
+
+    /// Asks the providers about `password`.
+    pub fn lookup(&self, password: &str) -> BreachLookup {
+        let query = BreachQuery::new(password);
+        if let Some((seen, lookup)) = &*self.last.lock().unwrap_or_else(|e| e.into_inner())
+            && *seen == query
+        {
+            return lookup.clone();
+        }
+        let (mut highest, mut errors) = (None::<u64>, Vec::new());
+        for provider in &self.providers {
+            match provider.breach_count(&query) {
+                Ok(count) => highest = highest.max(count),
+                Err(e) => errors.push(e),
+            }
+            if self.combine == Combine::FirstPositive && highest.is_some_and(|n| n >= self.threshold) {
+                break;
+            }
+        }
+        let lookup = match highest {
+            Some(count) if count >= self.threshold => BreachLookup::Breached(count),
+            _ if errors.is_empty() => BreachLookup::Clean,
+            _ => BreachLookup::Unavailable(errors),
+        };
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = Some((query, lookup.clone()));
+        lookup
+    }
+
+    /// Whether the [lookup](Self::lookup) rejects `password` under the
+    /// fail mode.
+    pub fn rejects(&self, password: &str) -> bool {
+        match self.lookup(password) {
+            BreachLookup::Breached(_) => true,
+            BreachLookup::Clean => false,
+            BreachLookup::Unavailable(_) => self.fail_mode == FailMode::Closed,
+        }
+    }
+}
+
+impl fmt::Debug for Breaches {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Breaches")
+            .field("providers", &self.providers.len())
+            .field("combine", &self.combine)
+            .field("fail_mode", &self.fail_mode)
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
+
+/// Breaches are equal when they ask the same providers the same way, as
+/// clones do.
+impl PartialEq for Breaches {
+    fn eq(&self, other: &Self) -> bool {
+        self.combine == other.combine &&
+            self.fail_mode == other.fail_mode &&
+            self.threshold == other.threshold &&
+            self.providers.len() == other.providers.len() &&
+            self.providers.iter().zip(&other.providers).all(|(a, b)| Arc::ptr_eq(a, b))
+    }
+}
+
+/// A breach corpus on disk: lines of `HASH:COUNT` sorted by hash, where
+/// `HASH` is an uppercase hex SHA-1, as in the Pwned Passwords downloads
+/// ordered by hash. Lookups are binary searches, as for
+/// [sorted dictionaries](crate::Dictionary::open), so the file is never
+/// loaded.
+///
+/// Unlike a dictionary, a read error during a lookup is reported, so the
+/// [fail mode](FailMode) decides.
+#[derive(Debug)]
+pub struct RangeFile {
+    file: SortedFile,
+}
+
+impl RangeFile {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        SortedFile::open_headerless(path).map(|file| RangeFile { file })
+    }
+}
+
+impl BreachProvider for RangeFile {
+    fn breach_count(&self, query: &BreachQuery) -> Result<Option<u64>, ProviderError> {
+        let Some(line) = self.file.find(query.hex().as_bytes(), hash)? else { return Ok(None) };
+        let count = std::str::from_utf8(&line[hash(&line).len()..])
+            .ok()
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(|count| count.trim_end_matches('\r'))
+            .and_then(|count| count.parse().ok());
+        match count {
+            Some(count) => Ok(Some(count)),
+            None => Err(ProviderError::new(format!("malformed line for {}", query.hex()))),
+        }
+    }
+}
+
+/// The hash a range file line starts with.
+fn hash(line: &[u8]) -> &[u8] {
+    line.split(|&b| b == b':').next().unwrap_or(line)
+}
+
+/// SHA-1 as specified in FIPS 180-4. It is broken for signatures, but it
+/// is what breach corpora are keyed by.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    for block in padded.chunks_exact(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..20 => ((b & c) | (!b & d), 0x5a827999),
+                20..40 => (b ^ c ^ d, 0x6ed9eba1),
+                40..60 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                _ => (b ^ c ^ d, 0xca62c1d6),
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, t);
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+    let mut out = [0u8; 20];
+    for (chunk, s) in out.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&s.to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{ParamValue, PasswordChecker};
+
+    /// Answers from a fixed list, counting the questions.
+    struct Mock {
+        known: Vec<(&'static str, u64)>,
+        down: bool,
+        asked: Arc<AtomicUsize>,
+    }
+
+    impl Mock {
+        fn new(known: &[(&'static str, u64)]) -> (Self, Arc<AtomicUsize>) {
+            let asked = Arc::new(AtomicUsize::new(0));
+            (Mock { known: known.to_vec(), down: false, asked: Arc::clone(&asked) }, asked)
+        }
+
+        fn down() -> (Self, Arc<AtomicUsize>) {
+            let (mock, asked) = Mock::new(&[]);
+            (Mock { down: true, ..mock }, asked)
+        }
+    }
+
+    impl BreachProvider for Mock {
+        fn breach_count(&self, query: &BreachQuery) -> Result<Option<u64>, ProviderError> {
+            self.asked.fetch_add(1, Ordering::Relaxed);
+            if self.down {
+                return Err(ProviderError::new("timed out"));
+            }
+            Ok(self.known.iter().find(|(pw, _)| BreachQuery::new(pw) == *query).map(|&(_, n)| n))
+        }
+    }
+
+    #[test]
+    fn sha1_test_vectors() {
+        let hex = |data: &[u8]| BreachQuery { sha1: sha1(data) }.hex();
+        assert_eq!(hex(b""), "DA39A3EE5E6B4B0D3255BFEF95601890AFD80709");
+        assert_eq!(hex(b"abc"), "A9993E364706816ABA3E25717850C26C9CD0D89D");
+        assert_eq!(hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"), "84983E441C3BD26EBAAE4AA1F95129E5E54670F1");
+        assert_eq!(hex(&[b'a'; 1000]), "291E9A6C66994949B57BA5E650361E98FC36B1BA");
+        let query = BreachQuery::new("password");
+        assert_eq!((query.prefix(), query.suffix()), ("5BAA6".to_string(), "1E4C9B93F3F0682250B6CF8331B7EE68FD8".to_string()));
+    }
+
+    #[test]
+    fn max_asks_every_provider_and_first_positive_stops() {
+        let (a, asked_a) = Mock::new(&[("hunter2", 3), ("letmein", 1)]);
+        let (b, asked_b) = Mock::new(&[("hunter2", 40)]);
+        let max = Breaches::new(a).with(b);
+        assert_eq!(max.lookup("hunter2"), BreachLookup::Breached(40));
+        assert_eq!(max.lookup("letmein"), BreachLookup::Breached(1));
+        assert_eq!(max.lookup("correct horse"), BreachLookup::Clean);
+        assert_eq!((asked_a.load(Ordering::Relaxed), asked_b.load(Ordering::Relaxed)), (3, 3));
+
+        let (a, asked_a) = Mock::new(&[("hunter2", 3)]);
+        let (b, asked_b) = Mock::new(&[("hunter2", 40), ("letmein", 2)]);
+        let first = Breaches::new(a).with(b).combine(Combine::FirstPositive);
+        assert_eq!(first.lookup("hunter2"), BreachLookup::Breached(3));
+        assert_eq!(first.lookup("letmein"), BreachLookup::Breached(2));
+        assert_eq!((asked_a.load(Ordering::Relaxed), asked_b.load(Ordering::Relaxed)), (2, 1));
+        // Below the threshold, the first provider's count does not stop the search.
+        assert_eq!(first.clone().threshold(10).lookup("hunter2"), BreachLookup::Breached(40));
+        assert_eq!(first.threshold(100).lookup("hunter2"), BreachLookup::Clean);
+    }
+
+    #[test]
+    fn partial_failures_only_decide_without_a_positive() {
+        for combine in [Combine::Max, Combine::FirstPositive] {
+            let (down, _) = Mock::down();
+            let (up, _) = Mock::new(&[("hunter2", 5)]);
+            let breaches = Breaches::new(down).with(up).combine(combine);
+            assert_eq!(breaches.lookup("hunter2"), BreachLookup::Breached(5), "{:?}", combine);
+            assert_eq!(breaches.lookup("correct horse"), BreachLookup::Unavailable(vec![ProviderError::new("timed out")]));
+            assert!(breaches.rejects("correct horse"));
+            let open = breaches.fail_mode(FailMode::Open);
+            assert!(open.rejects("hunter2") && !open.rejects("correct horse"));
+        }
+    }
+
+    #[test]
+    fn fail_modes_decide_the_rule() {
+        let (down, _) = Mock::down();
+        let (up, _) = Mock::new(&[("hunter2", 5)]);
+        let closed = PasswordChecker::new().not_breached(Breaches::new(down).with(up), None);
+        let violations = closed.check("hunter2").unwrap_err();
+        assert_eq!(violations[0].message, "Password has appeared in a data breach.");
+        assert_eq!(violations[0].params, [("count", ParamValue::Int(5))]);
+        let violations = closed.check("correct horse").unwrap_err();
+        assert_eq!(violations[0].params, [("unavailable", ParamValue::Int(1))]);
+
+        let (down, _) = Mock::down();
+        let open = PasswordChecker::new().not_breached(Breaches::new(down).fail_mode(FailMode::Open), Some("Pick another."));
+        assert!(open.check("hunter2").is_ok());
+    }
+
+    #[test]
+    fn params_reuse_the_lookup() {
+        let (mock, asked) = Mock::new(&[("hunter2", 5)]);
+        let checker = PasswordChecker::new().not_breached(Breaches::new(mock), None);
+        let _ = checker.check("hunter2");
+        let _ = checker.check("correct horse");
+        assert_eq!(asked.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn range_file_lookups() {
+        let path = std::env::temp_dir().join(format!("passcheck-{}-breaches.txt", std::process::id()));
+        let mut lines: Vec<String> = ["password", "hunter2", "letmein"].iter().map(|pw| BreachQuery::new(pw).hex()).collect();
+        lines.extend((0..500).map(|i| BreachQuery::new(&format!("filler{}", i)).hex()));
+        lines.sort();
+        let body: String = lines.iter().enumerate().map(|(i, hash)| format!("{}:{}\r\n", hash, i + 1)).collect();
+        std::fs::write(&path, body).unwrap();
+        let file = RangeFile::open(&path).unwrap();
+        for (i, hash) in lines.iter().enumerate() {
+            let query = BreachQuery { sha1: std::array::from_fn(|j| u8::from_str_radix(&hash[2 * j..2 * j + 2], 16).unwrap()) };
+            assert_eq!(file.breach_count(&query), Ok(Some(i as u64 + 1)));
+        }
+        assert_eq!(file.breach_count(&BreachQuery::new("correct horse")), Ok(None));
+        std::fs::write(&path, format!("{}:lots\n", BreachQuery::new("hunter2").hex())).unwrap();
+        assert!(RangeFile::open(&path).unwrap().breach_count(&BreachQuery::new("hunter2")).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
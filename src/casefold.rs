@@ -0,0 +1,102 @@
+//! Unicode full case folding.
+//!
+//! Follows the C and F mappings of `CaseFolding.txt` (no Turkic `T`
+//! mappings) for Latin, Greek, Cyrillic, Armenian and Cherokee, which is
+//! where folding and lowercasing disagree in practice. Everything else
+//! falls back to the Unicode lowercase mapping, which is the same as
+//! the fold for the remaining scripts.
+
+/// Characters whose fold is not their lowercase mapping.
+const SPECIAL: &[(char, &str)] = &[
+    ('\u{00B5}', "\u{03BC}"),
+    ('\u{00DF}', "ss"),
+    ('\u{0130}', "i\u{0307}"),
+    ('\u{0149}', "\u{02BC}n"),
+    ('\u{017F}', "s"),
+    ('\u{01F0}', "j\u{030C}"),
+    ('\u{0345}', "\u{03B9}"),
+    ('\u{0390}', "\u{03B9}\u{0308}\u{0301}"),
+    ('\u{03B0}', "\u{03C5}\u{0308}\u{0301}"),
+    ('\u{03C2}', "\u{03C3}"),
+    ('\u{03D0}', "\u{03B2}"),
+    ('\u{03D1}', "\u{03B8}"),
+    ('\u{03D5}', "\u{03C6}"),
+    ('\u{03D6}', "\u{03C0}"),
+    ('\u{03F0}', "\u{03BA}"),
+    ('\u{03F1}', "\u{03C1}"),
+    ('\u{03F5}', "\u{03B5}"),
+    ('\u{0587}', "\u{0565}\u{0582}"),
+    ('\u{1E96}', "h\u{0331}"),
+    ('\u{1E97}', "t\u{0308}"),
+    ('\u{1E98}', "w\u{030A}"),
+    ('\u{1E99}', "y\u{030A}"),
+    ('\u{1E9A}', "a\u{02BE}"),
+    ('\u{1E9B}', "\u{1E61}"),
+    ('\u{1E9E}', "ss"),
+    ('\u{1FBE}', "\u{03B9}"),
+    ('\u{FB00}', "ff"),
+    ('\u{FB01}', "fi"),
+    ('\u{FB02}', "fl"),
+    ('\u{FB03}', "ffi"),
+    ('\u{FB04}', "ffl"),
+    ('\u{FB05}', "st"),
+    ('\u{FB06}', "st"),
+    ('\u{FB13}', "\u{0574}\u{0576}"),
+    ('\u{FB14}', "\u{0574}\u{0565}"),
+    ('\u{FB15}', "\u{0574}\u{056B}"),
+    ('\u{FB16}', "\u{057E}\u{0576}"),
+    ('\u{FB17}', "\u{0574}\u{056D}"),
+];
+
+/// Appends the full case fold of `c` to `out`.
+fn fold_char(c: char, out: &mut String) {
+    if c.is_ascii() {
+        out.push(c.to_ascii_lowercase());
+        return;
+    }
+    if let Ok(i) = SPECIAL.binary_search_by_key(&c, |&(from, _)| from) {
+        out.push_str(SPECIAL[i].1);
+        return;
+    }
+    // Cherokee folds to the uppercase letters, unlike every other script.
+    let cherokee = match c as u32 {
+        0xAB70..=0xABBF => Some(c as u32 - 0xAB70 + 0x13A0),
+        0x13F8..=0x13FD => Some(c as u32 - 8),
+        _ => None,
+    };
+    match cherokee.and_then(char::from_u32) {
+        Some(upper) => out.push(upper),
+        None => out.extend(c.to_lowercase()),
+    }
+}
+
+/// Returns the full case fold of `s`.
+pub(crate) fn fold(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        fold_char(c, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(SPECIAL.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn folds_where_lowercase_does_not() {
+        assert_eq!(fold("Straße"), "strasse");
+        assert_eq!(fold("STRASSE"), "strasse");
+        assert_eq!(fold("ẞ"), "ss");
+        assert_eq!(fold("ΣΊΣΥΦΟΣ"), fold("σίσυφος"));
+        assert_eq!(fold("ﬁle"), "file");
+        assert_eq!(fold("İ"), "i\u{0307}");
+        assert_eq!(fold("ı"), "ı");
+        assert_eq!(fold("ꭰ"), "Ꭰ");
+    }
+}
@@ -0,0 +1,141 @@
+//! Character classes shared by validation and generation.
+
+use crate::{Rule, SpecialCharPolicy};
+
+/// The character classes of a checker, from
+/// [`PasswordChecker::char_classes`](crate::PasswordChecker::char_classes).
+///
+/// The rules decide what passes, and the generator draws from the same
+/// classes, so generated passwords pass their own policy. Letters and
+/// digits are the ASCII ones for both, as counted by
+/// [`Rule::RequireUpperLower`] and [`Rule::RequireNumber`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CharClasses {
+    /// The characters counted by the checker's
+    /// [`require_special_char`](crate::PasswordChecker::require_special_char)
+    /// rules. A checker has one special set, set with
+    /// [`require_special_char_with`](crate::PasswordChecker::require_special_char_with)
+    /// and used by the rules added after it.
+    pub special: SpecialCharPolicy,
+    /// The only characters passwords may contain, or `None` for any:
+    /// ASCII digits once [`Rule::DigitsOnly`] is added.
+    pub allowed: Option<String>,
+}
+
+/// The positions of the first special-character rule of `rules` and of
+/// the first one counting other characters, if any.
+pub(crate) fn special_conflict(rules: &[Rule<'_>]) -> Option<(usize, usize)> {
+    let mut first = None;
+    for (i, rule) in rules.iter().enumerate() {
+        if let Rule::RequireSpecialChar(policy, _) = rule {
+            match first {
+                None => first = Some((i, policy)),
+                Some((j, existing)) if existing != policy => return Some((j, i)),
+                Some(_) => {}
+            }
+        }
+    }
+    None
+}
+
+/// Panics if `rules` hold special-character rules with different sets.
+#[track_caller]
+pub(crate) fn assert_one_special_set(rules: &[Rule<'_>]) {
+    if let Some((first, other)) = special_conflict(rules) {
+        panic!("a checker has one special set, and the special characters of rules[{}] differ from those of rules[{}]", other, first);
+    }
+}
+
+/// The special set of `rules`: that of their special-character rules, or
+/// the builtin one.
+pub(crate) fn special_of<'r>(rules: &'r [Rule<'_>]) -> &'r SpecialCharPolicy {
+    static BUILTIN: SpecialCharPolicy = SpecialCharPolicy::Builtin;
+    rules
+        .iter()
+        .find_map(|rule| match rule {
+            Rule::RequireSpecialChar(policy, _) => Some(policy),
+            _ => None,
+        })
+        .unwrap_or(&BUILTIN)
+}
+
+impl CharClasses {
+    pub(crate) fn of(rules: &[Rule<'_>]) -> Self {
+        let digits_only = rules.iter().any(|rule| matches!(rule, Rule::DigitsOnly(_)));
+        CharClasses { special: special_of(rules).clone(), allowed: digits_only.then(|| "0123456789".to_string()) }
+    }
+
+    /// Whether `c` counts as special.
+    pub fn is_special(&self, c: char) -> bool {
+        self.special.contains(c)
+    }
+
+    /// Whether passwords may contain `c`.
+    pub fn allows(&self, c: char) -> bool {
+        self.allowed.as_ref().is_none_or(|allowed| allowed.contains(c))
+    }
+
+    /// The special characters the generator draws from: an explicit set as
+    /// given, otherwise the builtin ones, which are also Unicode
+    /// punctuation.
+    #[cfg(feature = "generate")]
+    pub(crate) fn special_chars(&self) -> Vec<char> {
+        match &self.special {
+            SpecialCharPolicy::Chars(chars) => chars.chars().collect(),
+            _ => crate::SPECIAL_CHARS.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordChecker;
+
+    #[test]
+    fn reflects_the_configuration() {
+        assert_eq!(PasswordChecker::new().min_length(8, None).char_classes(), CharClasses::default());
+        let classes = PasswordChecker::new().require_special_char_with(SpecialCharPolicy::Chars("#%".into()), None).char_classes();
+        assert_eq!(classes.special, SpecialCharPolicy::Chars("#%".into()));
+        assert!(classes.is_special('#') && !classes.is_special('!'));
+        assert!(classes.allows('x') && classes.allows('#'));
+        let pin = PasswordChecker::pin().char_classes();
+        assert_eq!(pin.allowed.as_deref(), Some("0123456789"));
+        assert!(pin.allows('7') && !pin.allows('a'));
+        assert_eq!(PasswordChecker::new().require_special_char(None).char_classes().special, SpecialCharPolicy::Builtin);
+    }
+
+    #[test]
+    fn the_special_set_applies_to_later_rules() {
+        let narrowed = SpecialCharPolicy::Chars("#%".into());
+        let checker = PasswordChecker::new().require_special_char_with(narrowed.clone(), Some("Add # or %")).require_special_char(None);
+        assert!(checker.rules().iter().all(|rule| matches!(rule, Rule::RequireSpecialChar(policy, _) if *policy == narrowed)));
+        assert!(checker.check("pass!word").is_err());
+        assert!(checker.check("pass#word").is_ok());
+        assert_eq!(special_conflict(checker.rules()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "special characters of rules[1] differ from those of rules[0]")]
+    fn a_second_special_set_panics() {
+        // Rewriting the first rule would let "abc€" through.
+        let _ = PasswordChecker::new().require_special_char(None).require_special_char_with(SpecialCharPolicy::UnicodeCategories, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "special characters of rules[1] differ from those of rules[0]")]
+    fn extending_with_a_second_special_set_panics() {
+        let mut checker = PasswordChecker::new().require_special_char_with(SpecialCharPolicy::Chars("!".into()), None);
+        checker.extend([Rule::RequireSpecialChar(SpecialCharPolicy::Chars("#".into()), None)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "special characters of rules[2] differ from those of rules[0]")]
+    fn rules_with_two_special_sets_panic() {
+        let _ = PasswordChecker::from_rules(vec![
+            Rule::RequireSpecialChar(SpecialCharPolicy::Builtin, None),
+            Rule::MinLength(8, None),
+            Rule::RequireSpecialChar(SpecialCharPolicy::Chars("#".into()), None),
+        ]);
+    }
+}
@@ -0,0 +1,971 @@
+//! Serializable policy configuration.
+//!
+//! A policy is stored as JSON listing its rules in evaluation order:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "rules": [
+//!     { "rule": "min_length", "length": 12 },
+//!     { "rule": "require_upper_lower" },
+//!     { "rule": "require_number", "message": "Add a digit." }
+//!   ]
+//! }
+//! ```
+//!
+//! Rule names match the builder methods on [`PasswordChecker`]. Unknown keys
+//! are rejected so that a typo never silently drops a rule. `version` is the
+//! format version, 1 when absent; see [`PolicyConfig::migrate`].
+//!
+//! A policy without rules accepts every password, so loading one fails with
+//! [`ConfigError::EmptyPolicy`] unless the document says
+//! `"allow_empty": true`.
+//!
+//! A [length exemption](PasswordChecker::length_exemption) is stored next
+//! to the rules as `"length_exemption": {"threshold": 20, "waive":
+//! ["require_number"]}`; without `waive`, the composition rules are
+//! waived.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::json::{self, Value};
+use crate::dictionary::DictionarySource;
+use crate::classes::special_conflict;
+use crate::features::{SkippedRule, disabled_feature};
+use crate::{
+    DEFAULT_WORD_SEPARATORS, Dictionary, Estimator, KeyboardLayout, KeyboardWalk, LengthExemption, MatchMode, PasswordChecker, Pattern, PatternError, Rule, Similarity,
+    SpecialCharPolicy, UserInfoCheck, WalkShape,
+};
+
+/// Error returned when a policy configuration cannot be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The input is not well-formed JSON.
+    Syntax { offset: usize, reason: &'static str },
+    /// A field is missing, has the wrong type, or holds an invalid value.
+    InvalidField { path: String, reason: String },
+    /// A pattern field does not compile.
+    InvalidPattern { path: String, source: PatternError },
+    /// A rule entry names a rule this version does not know.
+    UnknownRule(String),
+    /// A token in a [`parse_policy`](crate::parse_policy) string is invalid.
+    InvalidToken { token: String, reason: &'static str },
+    /// The policy was written in a newer format version than this one
+    /// supports.
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// An environment variable read by [`PasswordChecker::from_env`] is
+    /// unknown or holds an invalid value.
+    InvalidVariable { name: String, value: String, reason: String },
+    /// [`PasswordChecker::from_env`] found no variable with the prefix.
+    NoVariables { prefix: String },
+    /// The policy goes over [`PolicyLimits`](crate::PolicyLimits), with
+    /// every limit exceeded.
+    LimitsExceeded(Vec<LimitExceeded>),
+    /// The policy has no rules, so it would accept every password, and
+    /// does not say that is intended.
+    EmptyPolicy,
+    /// A field needs a cargo feature this build was compiled without.
+    /// [`PolicyConfig::from_json_with_report`] skips such rules instead.
+    FeatureDisabled { path: String, feature: &'static str },
+    /// A document read by [`Violations::from_json`](crate::Violations::from_json)
+    /// or [`Violation::from_json`](crate::Violation::from_json) is malformed;
+    /// `path` is empty for the whole document.
+    InvalidViolation { path: String, reason: String },
+}
+
+/// One [`PolicyLimits`](crate::PolicyLimits) limit a policy goes over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitExceeded {
+    /// The name of the limit's field, e.g. `"max_rules"`.
+    pub limit: &'static str,
+    /// The part of the policy over the limit, e.g. `rules[3].pattern`.
+    pub path: String,
+    pub max: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` has {}, over {} of {}", self.path, self.found, self.limit, self.max)
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Syntax { offset, reason } => {
+                write!(f, "invalid policy JSON: {} at byte {}", reason, offset)
+            }
+            ConfigError::InvalidField { path, reason } => write!(f, "invalid policy field `{}`: {}", path, reason),
+            ConfigError::InvalidPattern { path, source } => write!(f, "invalid policy field `{}`: {}", path, source),
+            ConfigError::UnknownRule(name) => write!(f, "unknown rule `{}`", name),
+            ConfigError::InvalidToken { token, reason } => write!(f, "invalid policy token `{}`: {}", token, reason),
+            ConfigError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "policy format version {} is newer than the supported version {}; upgrade passcheck to load it",
+                found, supported
+            ),
+            ConfigError::InvalidVariable { name, value, reason } => {
+                write!(f, "invalid environment variable {}={:?}: {}", name, value, reason)
+            }
+            ConfigError::NoVariables { prefix } => write!(f, "no {}_* environment variables are set", prefix),
+            ConfigError::EmptyPolicy => f.write_str("policy has no rules and would accept every password"),
+            ConfigError::FeatureDisabled { path, feature } => {
+                write!(f, "policy field `{}` needs the `{}` feature, which this build lacks", path, feature)
+            }
+            ConfigError::InvalidViolation { path, reason } if path.is_empty() => write!(f, "invalid violation document: {}", reason),
+            ConfigError::InvalidViolation { path, reason } => write!(f, "invalid violation document field `{}`: {}", path, reason),
+            ConfigError::LimitsExceeded(exceeded) => {
+                f.write_str("policy exceeds its limits: ")?;
+                for (i, limit) in exceeded.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str("; ")?;
+                    }
+                    write!(f, "{}", limit)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ConfigError {
+    /// Qualifies the field path with `prefix`, for policies embedded in a
+    /// larger document.
+    pub(crate) fn prefixed(self, prefix: &str) -> Self {
+        let join = |path: String| if path.is_empty() { prefix.to_string() } else { format!("{}.{}", prefix, path) };
+        match self {
+            ConfigError::InvalidField { path, reason } => ConfigError::InvalidField { path: join(path), reason },
+            ConfigError::InvalidPattern { path, source } => ConfigError::InvalidPattern { path: join(path), source },
+            ConfigError::FeatureDisabled { path, feature } => ConfigError::FeatureDisabled { path: join(path), feature },
+            ConfigError::LimitsExceeded(exceeded) => {
+                ConfigError::LimitsExceeded(exceeded.into_iter().map(|limit| LimitExceeded { path: join(limit.path), ..limit }).collect())
+            }
+            other => other,
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::InvalidPattern { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<json::ParseError> for ConfigError {
+    fn from(err: json::ParseError) -> Self {
+        ConfigError::Syntax { offset: err.offset, reason: err.reason }
+    }
+}
+
+/// The serializable form of a [`PasswordChecker`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PolicyConfig {
+    pub rules: Vec<Rule<'static>>,
+    pub length_exemption: Option<LengthExemption>,
+}
+
+impl PolicyConfig {
+    /// Version of the policy format written by [`PolicyConfig::to_json`].
+    /// Documents without a `version` field are version 1.
+    pub const VERSION: u32 = 1;
+
+    /// Parses a policy from its JSON representation, migrating documents
+    /// written in an older format version.
+    pub fn from_json(input: &str) -> Result<Self, ConfigError> {
+        Self::from_value(&json::parse(input)?)
+    }
+
+    /// Like [`from_json`](Self::from_json), for a policy embedded in a
+    /// larger document.
+    pub(crate) fn from_value(root: &Value) -> Result<Self, ConfigError> {
+        Self::from_document(&migrate_root(root)?)
+    }
+
+    /// Parses the rules of a migrated document.
+    pub(crate) fn from_document(document: &Document) -> Result<Self, ConfigError> {
+        Self::from_document_skipping(document, None)
+    }
+
+    /// Like [`from_document`](Self::from_document), leaving out the rules
+    /// that need a disabled feature if `skipped` collects them.
+    pub(crate) fn from_document_skipping(document: &Document, mut skipped: Option<&mut Vec<SkippedRule>>) -> Result<Self, ConfigError> {
+        let mut rules = Vec::new();
+        for (i, item) in document.rules.iter().enumerate() {
+            if let Some(skipped) = skipped.as_deref_mut() &&
+                let Some(rule) = SkippedRule::find(item, i)
+            {
+                skipped.push(rule);
+                continue;
+            }
+            let rule = parse_rule(item, &format!("rules[{}]", i))?;
+            rules.push(rule);
+            // A policy has one special set, as the builders keep it.
+            if let Some((first, _)) = special_conflict(&rules) {
+                return Err(invalid(&format!("rules[{}]", i), format!("special characters differ from those of rules[{}]", first)));
+            }
+        }
+        Ok(PolicyConfig { rules, length_exemption: document.length_exemption.clone() })
+    }
+
+    /// Rewrites a stored policy in the current format version without
+    /// changing its meaning, so it can be saved back.
+    ///
+    /// Each format change adds a transform from the version before it, and
+    /// older documents pass through every transform in turn. Documents from a
+    /// newer version fail with [`ConfigError::UnsupportedVersion`] rather
+    /// than load with rules this version would read differently.
+    pub fn migrate(input: &str) -> Result<String, ConfigError> {
+        let root = json::parse(input)?;
+        Ok(migrate_root(&root)?.into_json())
+    }
+
+    /// Serializes the policy to compact JSON accepted by [`PolicyConfig::from_json`].
+    pub fn to_json(&self) -> String {
+        Document { rules: self.rules.iter().map(rule_to_value).collect(), length_exemption: self.length_exemption.clone() }.into_json()
+    }
+}
+
+/// Transforms between format versions: `MIGRATIONS[i]` rewrites the rules
+/// of a version `i + 1` document as version `i + 2`.
+const MIGRATIONS: &[fn(&mut Vec<Value>)] = &[];
+
+/// A policy document migrated to [`PolicyConfig::VERSION`], with its
+/// rules not yet parsed.
+pub(crate) struct Document {
+    pub(crate) rules: Vec<Value>,
+    pub(crate) length_exemption: Option<LengthExemption>,
+}
+
+impl Document {
+    /// The document as JSON; one without rules says it is empty on
+    /// purpose, so it loads again.
+    fn into_json(self) -> String {
+        let version = Value::Number(f64::from(PolicyConfig::VERSION));
+        let mut fields = vec![("version".to_string(), version)];
+        if self.rules.is_empty() {
+            fields.push(("allow_empty".to_string(), Value::Bool(true)));
+        }
+        if let Some(exemption) = &self.length_exemption {
+            fields.push(("length_exemption".to_string(), exemption.to_value()));
+        }
+        fields.push(("rules".to_string(), Value::Array(self.rules)));
+        Value::Object(fields).to_string()
+    }
+}
+
+/// Checks the top-level fields and returns the document migrated to
+/// [`PolicyConfig::VERSION`].
+pub(crate) fn migrate_root(root: &Value) -> Result<Document, ConfigError> {
+    let Value::Object(fields) = root else {
+        return Err(invalid("", format!("expected object, found {}", root.type_name())));
+    };
+    if let Some((key, _)) = fields.iter().find(|(k, _)| !["rules", "version", "allow_empty", "length_exemption"].contains(&k.as_str())) {
+        return Err(invalid(key, "unknown field".to_string()));
+    }
+    let version = match root.get("version") {
+        None => 1,
+        Some(Value::Number(n)) if *n >= 1.0 && n.fract() == 0.0 && *n <= u32::MAX as f64 => *n as u32,
+        Some(other) => return Err(invalid("version", format!("expected a positive integer, found {}", other))),
+    };
+    if version > PolicyConfig::VERSION {
+        return Err(ConfigError::UnsupportedVersion { found: version, supported: PolicyConfig::VERSION });
+    }
+    let mut rules = match root.get("rules") {
+        None => Vec::new(),
+        Some(Value::Array(items)) => items.clone(),
+        Some(other) => return Err(invalid("rules", format!("expected array, found {}", other.type_name()))),
+    };
+    for migrate in &MIGRATIONS[version as usize - 1..] {
+        migrate(&mut rules);
+    }
+    let allow_empty = match root.get("allow_empty") {
+        None => false,
+        Some(Value::Bool(allow)) => *allow,
+        Some(other) => return Err(invalid("allow_empty", format!("expected boolean, found {}", other.type_name()))),
+    };
+    if rules.is_empty() && !allow_empty {
+        return Err(ConfigError::EmptyPolicy);
+    }
+    let length_exemption = root.get("length_exemption").map(LengthExemption::from_value).transpose()?;
+    Ok(Document { rules, length_exemption })
+}
+
+impl PasswordChecker<'static> {
+    /// Builds a checker from a loaded configuration. Loading rejects
+    /// empty policies, so this accepts any configuration, including one
+    /// built in code.
+    ///
+    /// # Panics
+    ///
+    /// Panics if special-character rules have different sets, which
+    /// loading rejects.
+    #[track_caller]
+    pub fn from_config(config: PolicyConfig) -> Self {
+        crate::classes::assert_one_special_set(&config.rules);
+        PasswordChecker { rules: config.rules, length_exemption: config.length_exemption, ..PasswordChecker::default() }
+    }
+
+    /// Parses a JSON policy and builds a checker from it.
+    pub fn from_json(input: &str) -> Result<Self, ConfigError> {
+        PolicyConfig::from_json(input).map(Self::from_config)
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// Returns the serializable configuration of this checker.
+    pub fn to_config(&self) -> PolicyConfig {
+        PolicyConfig { rules: self.rules.iter().cloned().map(Rule::into_owned).collect(), length_exemption: self.length_exemption.clone() }
+    }
+}
+
+pub(crate) fn invalid(path: &str, reason: String) -> ConfigError {
+    ConfigError::InvalidField { path: path.to_string(), reason }
+}
+
+fn parse_rule(value: &Value, path: &str) -> Result<Rule<'static>, ConfigError> {
+    let Value::Object(fields) = value else {
+        return Err(invalid(path, format!("expected object, found {}", value.type_name())));
+    };
+    let name = match value.get("rule") {
+        Some(Value::String(name)) => name.as_str(),
+        Some(other) => return Err(invalid(&format!("{}.rule", path), format!("expected string, found {}", other.type_name()))),
+        None => return Err(invalid(&format!("{}.rule", path), "missing field".to_string())),
+    };
+    let allowed: &[&str] = match name {
+        "min_length" | "max_length" | "max_class_run" | "recommended_length" => &["rule", "message", "length"],
+        "max_bytes" => &["rule", "message", "bytes"],
+        "min_words" => &["rule", "message", "words", "separators"],
+        "require_upper_lower" | "require_number" | "forbid_unsafe_chars" | "not_all_numeric" | "not_all_alphabetic" => &["rule", "message"],
+        "digits_only" | "not_sequential_digits" | "not_repeated_char" | "not_palindrome" => &["rule", "message"],
+        "no_phone_patterns" | "not_contain_phone_number" | "not_contain_date_of_birth" | "single_script_only" | "not_email_or_url" => &["rule", "message"],
+        "require_special_char" => &["rule", "message", "chars", "unicode_categories"],
+        "blacklist" => &["rule", "message", "words", "file", "match", "fuzzy"],
+        "must_match" | "must_not_match" => &["rule", "message", "pattern"],
+        "keyboard_walk" => &["rule", "message", "layouts", "min_length", "include_shifted", "shape", "min_share"],
+        "min_entropy" => &["rule", "message", "bits", "estimator"],
+        "length_or_entropy" => &["rule", "message", "min_len_strong", "min_entropy_bits", "min_len_fallback"],
+        "not_similar_to_previous" => &["rule", "message", "max_ratio", "min_edits"],
+        "not_contain_user_info" => &["rule", "message", "email_domain", "bidirectional", "bidirectional_min_length"],
+        "service_names" => &["rule", "message", "names"],
+        "custom" => return Err(invalid(&format!("{}.rule", path), "custom rules are defined in code and cannot be loaded".to_string())),
+        "not_breached" => return Err(invalid(&format!("{}.rule", path), "breach providers are defined in code and cannot be loaded".to_string())),
+        _ => return Err(ConfigError::UnknownRule(name.to_string())),
+    };
+    if let Some((key, _)) = fields.iter().find(|(k, _)| !allowed.contains(&k.as_str())) {
+        return Err(invalid(&format!("{}.{}", path, key), "unknown field".to_string()));
+    }
+
+    let msg = match value.get("message") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(msg)) => Some(Cow::Owned(msg.clone())),
+        Some(other) => {
+            return Err(invalid(&format!("{}.message", path), format!("expected string, found {}", other.type_name())));
+        }
+    };
+    Ok(match name {
+        "min_length" => Rule::MinLength(usize_field(value, path, "length")?, msg),
+        "max_length" => Rule::MaxLength(usize_field(value, path, "length")?, msg),
+        "max_bytes" => Rule::MaxBytes(usize_field(value, path, "bytes")?, msg),
+        "max_class_run" => Rule::MaxClassRun(usize_field(value, path, "length")?, msg),
+        "recommended_length" => Rule::RecommendedLength(usize_field(value, path, "length")?, msg),
+        "min_words" => {
+            let separators = match value.get("separators") {
+                None => DEFAULT_WORD_SEPARATORS.to_string(),
+                Some(Value::String(separators)) => separators.clone(),
+                Some(other) => return Err(invalid(&format!("{}.separators", path), format!("expected string, found {}", other.type_name()))),
+            };
+            Rule::MinWords(usize_field(value, path, "words")?, separators, msg)
+        }
+        "require_upper_lower" => Rule::RequireUpperLower(msg),
+        "require_number" => Rule::RequireNumber(msg),
+        "require_special_char" => Rule::RequireSpecialChar(special_char_policy(value, path)?, msg),
+        "forbid_unsafe_chars" => Rule::ForbidUnsafeChars(msg),
+        "not_all_numeric" => Rule::NotAllNumeric(msg),
+        "not_all_alphabetic" => Rule::NotAllAlphabetic(msg),
+        "digits_only" => Rule::DigitsOnly(msg),
+        "not_sequential_digits" => Rule::NotSequentialDigits(msg),
+        "not_repeated_char" => Rule::NotRepeatedChar(msg),
+        "not_palindrome" => Rule::NotPalindrome(msg),
+        "no_phone_patterns" => Rule::NoPhonePatterns(msg),
+        "not_contain_phone_number" => Rule::NotContainPhoneNumber(msg),
+        "not_contain_date_of_birth" => Rule::NotContainDateOfBirth(msg),
+        "single_script_only" => Rule::SingleScriptOnly(msg),
+        "not_email_or_url" => Rule::NotEmailOrUrl(msg),
+        "blacklist" => Rule::Blacklist(dictionary_field(value, path)?, msg),
+        "must_match" => Rule::MustMatch(pattern_field(value, path)?, msg),
+        "must_not_match" => Rule::MustNotMatch(pattern_field(value, path)?, msg),
+        "keyboard_walk" => Rule::KeyboardWalk(keyboard_walk(value, path)?, msg),
+        "min_entropy" => Rule::MinEntropy(bits_field(value, path, "bits")?, estimator_field(value, path)?, msg),
+        "not_similar_to_previous" => Rule::NotSimilarToPrevious(similarity_field(value, path)?, msg),
+        "service_names" => Rule::ServiceNames(strings_field(value, path, "names")?, msg),
+        "not_contain_user_info" => {
+            let mut check = UserInfoCheck::new();
+            for key in ["email_domain", "bidirectional"] {
+                let enabled = match value.get(key) {
+                    None => continue,
+                    Some(Value::Bool(enabled)) => *enabled,
+                    Some(other) => return Err(invalid(&format!("{}.{}", path, key), format!("expected bool, found {}", other.type_name()))),
+                };
+                check = if key == "email_domain" { check.email_domain(enabled) } else { check.bidirectional(enabled) };
+            }
+            if value.get("bidirectional_min_length").is_some() {
+                check = check.bidirectional_min_length(usize_field(value, path, "bidirectional_min_length")?);
+            }
+            Rule::NotContainUserInfo(check, msg)
+        }
+        _ => Rule::LengthOrEntropy {
+            min_len_strong: usize_field(value, path, "min_len_strong")?,
+            min_entropy_bits: bits_field(value, path, "min_entropy_bits")?,
+            min_len_fallback: usize_field(value, path, "min_len_fallback")?,
+            msg,
+        },
+    })
+}
+
+fn bits_field(rule: &Value, path: &str, key: &str) -> Result<f64, ConfigError> {
+    let path = format!("{}.{}", path, key);
+    match rule.get(key) {
+        Some(Value::Number(bits)) if *bits >= 0.0 => Ok(*bits),
+        Some(Value::Number(bits)) => Err(invalid(&path, format!("expected non-negative number, found {}", bits))),
+        Some(other) => Err(invalid(&path, format!("expected number, found {}", other.type_name()))),
+        None => Err(invalid(&path, "missing field".to_string())),
+    }
+}
+
+/// Reads exactly one of `max_ratio`, from 0 to 1, and `min_edits`.
+fn similarity_field(rule: &Value, path: &str) -> Result<Similarity, ConfigError> {
+    match (rule.get("max_ratio"), rule.get("min_edits")) {
+        (Some(_), Some(_)) => Err(invalid(&format!("{}.min_edits", path), "cannot be combined with `max_ratio`".to_string())),
+        (Some(Value::Number(ratio)), None) if (0.0..=1.0).contains(ratio) => Ok(Similarity::MaxRatio(*ratio)),
+        (Some(other), None) => Err(invalid(&format!("{}.max_ratio", path), format!("expected a number from 0 to 1, found {}", other))),
+        (None, Some(_)) => Ok(Similarity::MinEdits(usize_field(rule, path, "min_edits")?)),
+        (None, None) => Err(invalid(&format!("{}.max_ratio", path), "missing field, or `min_edits`".to_string())),
+    }
+}
+
+/// Reads the optional `estimator`, defaulting to [`Estimator::Pool`].
+fn estimator_field(rule: &Value, path: &str) -> Result<Estimator, ConfigError> {
+    let path = format!("{}.estimator", path);
+    match rule.get("estimator") {
+        None => Ok(Estimator::default()),
+        Some(Value::String(name)) => Estimator::from_name(name).ok_or_else(|| match disabled_feature("estimator", name) {
+            Some(feature) => ConfigError::FeatureDisabled { path, feature },
+            None => invalid(&path, format!("unknown estimator `{}`", name)),
+        }),
+        Some(other) => Err(invalid(&path, format!("expected string, found {}", other.type_name()))),
+    }
+}
+
+/// Reads either inline `words`, optionally with a `match` mode, or a
+/// `file` written by [`dictionary::prepare`](crate::dictionary::prepare).
+fn dictionary_field(rule: &Value, path: &str) -> Result<Dictionary, ConfigError> {
+    let field = |key: &str| format!("{}.{}", path, key);
+    let mode = match rule.get("match") {
+        None => None,
+        Some(Value::String(name)) => match MatchMode::from_name(name) {
+            Some(mode) => Some(mode),
+            None => return Err(invalid(&field("match"), format!("unknown match mode `{}`", name))),
+        },
+        Some(other) => return Err(invalid(&field("match"), format!("expected string, found {}", other.type_name()))),
+    };
+    let fuzzy = match rule.get("fuzzy") {
+        None | Some(Value::Bool(false)) => 0,
+        Some(Value::Bool(true)) => 1,
+        Some(_) => usize_field(rule, path, "fuzzy")?,
+    };
+    if fuzzy > 0 && rule.get("file").is_some() {
+        return Err(invalid(&field("fuzzy"), "only words held in memory can be matched fuzzily".to_string()));
+    }
+    let words = match (rule.get("words"), rule.get("file")) {
+        (Some(_), Some(_)) => Err(invalid(&field("file"), "cannot be combined with `words`".to_string())),
+        (_, Some(_)) if mode.is_some() => {
+            Err(invalid(&field("match"), "the match mode of a file is set when it is prepared".to_string()))
+        }
+        (_, Some(Value::String(file))) => {
+            Dictionary::open(file).map_err(|err| invalid(&field("file"), format!("{}: {}", file, err)))
+        }
+        (_, Some(other)) => Err(invalid(&field("file"), format!("expected string, found {}", other.type_name()))),
+        (_, None) => Ok(Dictionary::with_mode(strings_field(rule, path, "words")?, mode.unwrap_or_default())),
+    };
+    Ok(words?.fuzzy(fuzzy))
+}
+
+/// Reads `layouts`, each a built-in name or a custom
+/// `{"name": ..., "adjacency": {"a": "neighbors", ...}}` object, and the
+/// other [`KeyboardWalk`] settings. Missing keys keep their defaults.
+fn keyboard_walk(rule: &Value, path: &str) -> Result<KeyboardWalk, ConfigError> {
+    let mut walk = KeyboardWalk::new();
+    let field = |key: &str| format!("{}.{}", path, key);
+    if rule.get("min_length").is_some() {
+        walk = walk.min_length(usize_field(rule, path, "min_length")?);
+    }
+    match rule.get("include_shifted") {
+        None => {}
+        Some(Value::Bool(enabled)) => walk = walk.include_shifted(*enabled),
+        Some(other) => return Err(invalid(&field("include_shifted"), format!("expected bool, found {}", other.type_name()))),
+    }
+    match rule.get("shape") {
+        None => {}
+        Some(Value::String(shape)) if shape == "any_chain" => walk = walk.shape(WalkShape::AnyChain),
+        Some(Value::String(shape)) if shape == "straight" => walk = walk.shape(WalkShape::Straight),
+        Some(Value::String(shape)) => return Err(invalid(&field("shape"), format!("unknown shape `{}`", shape))),
+        Some(other) => return Err(invalid(&field("shape"), format!("expected string, found {}", other.type_name()))),
+    }
+    match rule.get("min_share") {
+        None => {}
+        Some(Value::Number(share)) if (0.0..=1.0).contains(share) => walk = walk.min_share(*share),
+        Some(other) => return Err(invalid(&field("min_share"), format!("expected a number from 0 to 1, found {}", other))),
+    }
+    let Some(layouts) = rule.get("layouts") else {
+        return Ok(walk);
+    };
+    let path = format!("{}.layouts", path);
+    let Value::Array(items) = layouts else {
+        return Err(invalid(&path, format!("expected array, found {}", layouts.type_name())));
+    };
+    let layouts = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let path = format!("{}[{}]", path, i);
+            match item {
+                Value::String(name) => {
+                    KeyboardLayout::from_name(name).ok_or_else(|| invalid(&path, format!("unknown layout `{}`", name)))
+                }
+                Value::Object(_) => custom_layout(item, &path),
+                other => Err(invalid(&path, format!("expected string or object, found {}", other.type_name()))),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(walk.layouts(layouts))
+}
+
+fn custom_layout(layout: &Value, path: &str) -> Result<KeyboardLayout, ConfigError> {
+    let Value::Object(fields) = layout else {
+        return Err(invalid(path, format!("expected object, found {}", layout.type_name())));
+    };
+    if let Some((key, _)) = fields.iter().find(|(k, _)| k != "name" && k != "adjacency") {
+        return Err(invalid(&format!("{}.{}", path, key), "unknown field".to_string()));
+    }
+    let name = match layout.get("name") {
+        Some(Value::String(name)) => name,
+        Some(other) => return Err(invalid(&format!("{}.name", path), format!("expected string, found {}", other.type_name()))),
+        None => return Err(invalid(&format!("{}.name", path), "missing field".to_string())),
+    };
+    let path = format!("{}.adjacency", path);
+    let Some(Value::Object(table)) = layout.get("adjacency") else {
+        return Err(invalid(&path, "expected an object mapping keys to their neighbors".to_string()));
+    };
+    let mut entries = Vec::new();
+    for (key, neighbors) in table {
+        let mut chars = key.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return Err(invalid(&path, format!("`{}` is not a single character", key)));
+        };
+        let Value::String(neighbors) = neighbors else {
+            return Err(invalid(&format!("{}.{}", path, key), format!("expected string, found {}", neighbors.type_name())));
+        };
+        entries.push((c, neighbors.as_str()));
+    }
+    Ok(KeyboardLayout::custom(name, entries))
+}
+
+fn special_char_policy(rule: &Value, path: &str) -> Result<SpecialCharPolicy, ConfigError> {
+    let unicode = match rule.get("unicode_categories") {
+        None | Some(Value::Bool(false)) => false,
+        Some(Value::Bool(true)) => true,
+        Some(other) => {
+            return Err(invalid(&format!("{}.unicode_categories", path), format!("expected boolean, found {}", other.type_name())));
+        }
+    };
+    match (rule.get("chars"), unicode) {
+        (None, false) => Ok(SpecialCharPolicy::Builtin),
+        (None, true) => Ok(SpecialCharPolicy::UnicodeCategories),
+        (Some(_), true) => Err(invalid(&format!("{}.chars", path), "cannot be combined with `unicode_categories`".to_string())),
+        (Some(Value::String(chars)), false) if !chars.is_empty() => Ok(SpecialCharPolicy::Chars(chars.clone())),
+        (Some(Value::String(_)), false) => Err(invalid(&format!("{}.chars", path), "expected at least one character".to_string())),
+        (Some(other), false) => Err(invalid(&format!("{}.chars", path), format!("expected string, found {}", other.type_name()))),
+    }
+}
+
+fn pattern_field(rule: &Value, path: &str) -> Result<Pattern, ConfigError> {
+    let path = format!("{}.pattern", path);
+    match rule.get("pattern") {
+        Some(Value::String(source)) => Pattern::new(source).map_err(|source| ConfigError::InvalidPattern { path, source }),
+        Some(other) => Err(invalid(&path, format!("expected string, found {}", other.type_name()))),
+        None => Err(invalid(&path, "missing field".to_string())),
+    }
+}
+
+fn usize_field(rule: &Value, path: &str, key: &str) -> Result<usize, ConfigError> {
+    let path = format!("{}.{}", path, key);
+    match rule.get(key) {
+        Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 && *n <= u32::MAX as f64 => Ok(*n as usize),
+        Some(Value::Number(n)) => Err(invalid(&path, format!("expected non-negative integer, found {}", n))),
+        Some(other) => Err(invalid(&path, format!("expected number, found {}", other.type_name()))),
+        None => Err(invalid(&path, "missing field".to_string())),
+    }
+}
+
+fn strings_field(rule: &Value, path: &str, key: &str) -> Result<Vec<String>, ConfigError> {
+    let path = format!("{}.{}", path, key);
+    let Some(value) = rule.get(key) else {
+        return Err(invalid(&path, "missing field".to_string()));
+    };
+    let Value::Array(items) = value else {
+        return Err(invalid(&path, format!("expected array, found {}", value.type_name())));
+    };
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| match item {
+            Value::String(s) => Ok(s.clone()),
+            other => Err(invalid(&format!("{}[{}]", path, i), format!("expected string, found {}", other.type_name()))),
+        })
+        .collect()
+}
+
+pub(crate) fn rule_to_value(rule: &Rule<'_>) -> Value {
+    let (name, msg, params) = match rule {
+        Rule::MinLength(len, msg) => ("min_length", msg, vec![("length", Value::Number(*len as f64))]),
+        Rule::MaxLength(len, msg) => ("max_length", msg, vec![("length", Value::Number(*len as f64))]),
+        Rule::MaxBytes(len, msg) => ("max_bytes", msg, vec![("bytes", Value::Number(*len as f64))]),
+        Rule::RecommendedLength(len, msg) => ("recommended_length", msg, vec![("length", Value::Number(*len as f64))]),
+        Rule::MaxClassRun(len, msg) => ("max_class_run", msg, vec![("length", Value::Number(*len as f64))]),
+        Rule::MinWords(n, separators, msg) => {
+            let mut params = vec![("words", Value::Number(*n as f64))];
+            if separators != DEFAULT_WORD_SEPARATORS {
+                params.push(("separators", Value::String(separators.clone())));
+            }
+            ("min_words", msg, params)
+        }
+        Rule::RequireUpperLower(msg) => ("require_upper_lower", msg, vec![]),
+        Rule::RequireNumber(msg) => ("require_number", msg, vec![]),
+        Rule::RequireSpecialChar(policy, msg) => {
+            let params = match policy {
+                SpecialCharPolicy::Builtin => vec![],
+                SpecialCharPolicy::Chars(chars) => vec![("chars", Value::String(chars.clone()))],
+                SpecialCharPolicy::UnicodeCategories => vec![("unicode_categories", Value::Bool(true))],
+            };
+            ("require_special_char", msg, params)
+        }
+        Rule::ForbidUnsafeChars(msg) => ("forbid_unsafe_chars", msg, vec![]),
+        Rule::NotAllNumeric(msg) => ("not_all_numeric", msg, vec![]),
+        Rule::NotAllAlphabetic(msg) => ("not_all_alphabetic", msg, vec![]),
+        Rule::DigitsOnly(msg) => ("digits_only", msg, vec![]),
+        Rule::NotSequentialDigits(msg) => ("not_sequential_digits", msg, vec![]),
+        Rule::NotRepeatedChar(msg) => ("not_repeated_char", msg, vec![]),
+        Rule::NotPalindrome(msg) => ("not_palindrome", msg, vec![]),
+        Rule::NoPhonePatterns(msg) => ("no_phone_patterns", msg, vec![]),
+        Rule::NotContainPhoneNumber(msg) => ("not_contain_phone_number", msg, vec![]),
+        Rule::NotContainDateOfBirth(msg) => ("not_contain_date_of_birth", msg, vec![]),
+        Rule::SingleScriptOnly(msg) => ("single_script_only", msg, vec![]),
+        Rule::NotEmailOrUrl(msg) => ("not_email_or_url", msg, vec![]),
+        // Written so saved policies show what is missing; loading fails.
+        Rule::Custom(rule, msg) => {
+            let mut params = vec![("code", Value::String(rule.code().to_string()))];
+            if let Some(version) = rule.declared_version() {
+                params.push(("version", Value::String(version.to_string())));
+            }
+            ("custom", msg, params)
+        }
+        Rule::NotBreached(breaches, msg) => ("not_breached", msg, breaches.to_params()),
+        Rule::Blacklist(words, msg) => match words.source() {
+            DictionarySource::SortedFile(file) => {
+                ("blacklist", msg, vec![("file", Value::String(file.path().to_string_lossy().into_owned()))])
+            }
+            DictionarySource::Memory(_) => {
+                let (mode, fuzzy) = (words.mode(), words.fuzzy_distance());
+                // Sorted so the same list always serializes identically.
+                let mut words: Vec<&str> = words.iter().collect();
+                words.sort_unstable();
+                let words = words.into_iter().map(|w| Value::String(w.to_string())).collect();
+                let mut params = vec![("words", Value::Array(words))];
+                if mode != MatchMode::Exact {
+                    params.push(("match", Value::String(mode.name().to_string())));
+                }
+                if fuzzy > 0 {
+                    params.push(("fuzzy", Value::Number(fuzzy as f64)));
+                }
+                ("blacklist", msg, params)
+            }
+        },
+        Rule::MustMatch(pattern, msg) => ("must_match", msg, vec![("pattern", Value::String(pattern.as_str().to_string()))]),
+        Rule::MustNotMatch(pattern, msg) => {
+            ("must_not_match", msg, vec![("pattern", Value::String(pattern.as_str().to_string()))])
+        }
+        Rule::KeyboardWalk(walk, msg) => {
+            let layouts = walk
+                .layouts
+                .iter()
+                .map(|layout| match layout {
+                    KeyboardLayout::Custom(_) => {
+                        let table = layout.adjacency().into_iter().map(|(c, n)| (c.to_string(), Value::String(n)));
+                        Value::Object(vec![
+                            ("name".to_string(), Value::String(layout.name().to_string())),
+                            ("adjacency".to_string(), Value::Object(table.collect())),
+                        ])
+                    }
+                    _ => Value::String(layout.name().to_string()),
+                })
+                .collect();
+            let shape = match walk.shape {
+                WalkShape::AnyChain => "any_chain",
+                WalkShape::Straight => "straight",
+            };
+            let params = vec![
+                ("layouts", Value::Array(layouts)),
+                ("min_length", Value::Number(walk.min_length as f64)),
+                ("include_shifted", Value::Bool(walk.include_shifted)),
+                ("shape", Value::String(shape.to_string())),
+                ("min_share", Value::Number(walk.min_share)),
+            ];
+            ("keyboard_walk", msg, params)
+        }
+        Rule::NotSimilarToPrevious(similarity, msg) => match similarity {
+            Similarity::MaxRatio(ratio) => ("not_similar_to_previous", msg, vec![("max_ratio", Value::Number(*ratio))]),
+            Similarity::MinEdits(edits) => ("not_similar_to_previous", msg, vec![("min_edits", Value::Number(*edits as f64))]),
+        },
+        Rule::ServiceNames(names, msg) => {
+            ("service_names", msg, vec![("names", Value::Array(names.iter().cloned().map(Value::String).collect()))])
+        }
+        Rule::NotContainUserInfo(check, msg) => {
+            let params = vec![
+                ("email_domain", Value::Bool(check.email_domain)),
+                ("bidirectional", Value::Bool(check.bidirectional)),
+                ("bidirectional_min_length", Value::Number(check.bidirectional_min_length as f64)),
+            ];
+            ("not_contain_user_info", msg, params)
+        }
+        Rule::MinEntropy(bits, estimator, msg) => (
+            "min_entropy",
+            msg,
+            vec![("bits", Value::Number(*bits)), ("estimator", Value::String(estimator.name().to_string()))],
+        ),
+        Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, msg } => (
+            "length_or_entropy",
+            msg,
+            vec![
+                ("min_len_strong", Value::Number(*min_len_strong as f64)),
+                ("min_entropy_bits", Value::Number(*min_entropy_bits)),
+                ("min_len_fallback", Value::Number(*min_len_fallback as f64)),
+            ],
+        ),
+    };
+    let mut fields = vec![("rule".to_string(), Value::String(name.to_string()))];
+    fields.extend(params.into_iter().map(|(k, v)| (k.to_string(), v)));
+    if let Some(msg) = msg {
+        fields.push(("message".to_string(), Value::String(msg.to_string())));
+    }
+    Value::Object(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_rules_in_order() {
+        let checker = PasswordChecker::from_json(
+            r#"{"rules": [
+                {"rule": "min_length", "length": 12},
+                {"rule": "require_number", "message": "Add a digit."}
+            ]}"#,
+        )
+        .unwrap();
+        let expected = PasswordChecker::new().min_length(12, None).require_number(Some("Add a digit."));
+        assert_eq!(checker, expected);
+        assert_eq!(checker.validate("short").unwrap_err(), vec![
+            "Password must be at least 12 characters long.".to_string(),
+            "Add a digit.".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let checker = PasswordChecker::new()
+            .min_length(8, Some("Too \"short\""))
+            .recommended_length(12, Some("Longer is safer"))
+            .max_length(64, None)
+            .for_bcrypt()
+            .forbid_unsafe_chars(None)
+            .not_similar_to_previous(Similarity::MaxRatio(0.8), None)
+            .not_similar_to_previous(Similarity::MinEdits(3), Some("Change more"))
+            .not_contain_user_info(UserInfoCheck::new().email_domain(false).bidirectional(true).bidirectional_min_length(8), None)
+            .with_service_names(["acme", "acme portal"], None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char_with(SpecialCharPolicy::Chars("!@#".into()), Some("Add !, @ or #"))
+            .require_special_char(None)
+            .blacklist(["password", "qwerty"], Some("Too common"))
+            .blacklist(Dictionary::with_mode(["Straße"], MatchMode::UnicodeCaseFold), None)
+            .blacklist(Dictionary::new(["letmein"]).fuzzy(2), None)
+            .must_match(Pattern::new("^\\S+$").unwrap(), None)
+            .must_not_match(Pattern::new("(?:19|20)\\d{2}").unwrap(), Some("No years"))
+            .keyboard_walk(KeyboardWalk::new(), None)
+            .keyboard_walk(
+                KeyboardWalk::new()
+                    .layouts([KeyboardLayout::Azerty, KeyboardLayout::custom("phone", [('1', "24"), ('2', "3")])])
+                    .min_length(3)
+                    .include_shifted(false)
+                    .shape(WalkShape::Straight)
+                    .min_share(0.25),
+                None,
+            )
+            .min_entropy(40.0, Estimator::Pool, Some("Too guessable"))
+            .length_or_entropy(10, 60.0, 16, None)
+            .max_class_run(6, Some("Mix it up"))
+            .min_words(4, None)
+            .min_words_with(3, "+", Some("Three words"))
+            .not_all_numeric(None)
+            .not_all_alphabetic(Some("Add a digit or symbol"))
+            .digits_only(None)
+            .not_sequential_digits(Some("No 1234"))
+            .not_repeated_char(None)
+            .not_palindrome(None)
+            .no_phone_patterns(None)
+            .not_contain_phone_number(Some("That is your phone number"))
+            .not_contain_date_of_birth(None)
+            .single_script_only(Some("One alphabet, please"))
+            .not_email_or_url(None);
+        let json = checker.to_config().to_json();
+        assert!(json.contains(r#"{"rule":"require_special_char","chars":"!@#","message":"Add !, @ or #"},{"rule":"require_special_char","chars":"!@#"}"#), "{}", json);
+        assert_eq!(PasswordChecker::from_json(&json).unwrap(), checker);
+        for policy in [SpecialCharPolicy::Builtin, SpecialCharPolicy::UnicodeCategories] {
+            let checker = PasswordChecker::new().require_special_char_with(policy, None).require_special_char(Some("Add a symbol"));
+            assert_eq!(PasswordChecker::from_json(&checker.to_config().to_json()).unwrap(), checker);
+        }
+        for rule in checker.rules() {
+            assert!(crate::violation::CODES.contains(&rule.code()), "{} missing from violation::CODES", rule.code());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_policies() {
+        assert!(matches!(PasswordChecker::from_json("{"), Err(ConfigError::Syntax { .. })));
+        assert_eq!(
+            PasswordChecker::from_json(r#"{"rules": [{"rule": "min_strength"}]}"#),
+            Err(ConfigError::UnknownRule("min_strength".into()))
+        );
+        assert_eq!(
+            PasswordChecker::from_json(r#"{"rules": [{"rule": "min_length", "length": -1}]}"#),
+            Err(invalid("rules[0].length", "expected non-negative integer, found -1".into()))
+        );
+        assert_eq!(
+            PasswordChecker::from_json(r#"{"rules": [{"rule": "require_number", "mesage": "typo"}]}"#),
+            Err(invalid("rules[0].mesage", "unknown field".into()))
+        );
+        assert_eq!(PasswordChecker::from_json(r#"{"rule": []}"#), Err(invalid("rule", "unknown field".into())));
+        assert_eq!(
+            PasswordChecker::from_json(r#"{"rules": [{"rule": "must_match", "pattern": "(ab"}]}"#),
+            Err(ConfigError::InvalidPattern { path: "rules[0].pattern".into(), source: PatternError { position: 3, reason: "unclosed group" } })
+        );
+        assert_eq!(
+            PasswordChecker::from_json(r#"{"rules": [{"rule": "min_entropy", "bits": 40, "estimator": "guess"}]}"#),
+            Err(invalid("rules[0].estimator", "unknown estimator `guess`".into()))
+        );
+        assert_eq!(
+            PasswordChecker::from_json(r##"{"rules": [{"rule": "require_special_char"}, {"rule": "require_special_char", "chars": "#%"}]}"##),
+            Err(invalid("rules[1]", "special characters differ from those of rules[0]".into()))
+        );
+    }
+
+    #[test]
+    fn custom_rules_are_saved_but_not_loaded() {
+        use crate::CustomRule;
+        let rule = CustomRule::new("no_company_name", |pw| !pw.contains("acme")).unwrap();
+        let json = PasswordChecker::new().custom(rule, Some("No company names")).to_config().to_json();
+        assert_eq!(json, r#"{"version":1,"rules":[{"rule":"custom","code":"no_company_name","message":"No company names"}]}"#);
+        assert_eq!(
+            PasswordChecker::from_json(&json),
+            Err(invalid("rules[0].rule", "custom rules are defined in code and cannot be loaded".into()))
+        );
+    }
+
+    #[test]
+    fn breach_rules_are_saved_but_not_loaded() {
+        use crate::{BreachProvider, BreachQuery, Breaches, Combine, ProviderError};
+        struct Never;
+        impl BreachProvider for Never {
+            fn breach_count(&self, _: &BreachQuery) -> Result<Option<u64>, ProviderError> {
+                Ok(None)
+            }
+        }
+        let breaches = Breaches::new(Never).with(Never).combine(Combine::FirstPositive).threshold(3);
+        let json = PasswordChecker::new().not_breached(breaches, None).to_config().to_json();
+        assert_eq!(
+            json,
+            r#"{"version":1,"rules":[{"rule":"not_breached","providers":2,"combine":"first_positive","fail_mode":"closed","threshold":3}]}"#
+        );
+        assert_eq!(
+            PasswordChecker::from_json(&json),
+            Err(invalid("rules[0].rule", "breach providers are defined in code and cannot be loaded".into()))
+        );
+    }
+
+    #[test]
+    fn loads_versioned_fixtures() {
+        let fixture = |name: &str| std::fs::read_to_string(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap();
+        let v1 = PolicyConfig::from_json(&fixture("policy-v1.json")).unwrap();
+        let current = PolicyConfig::from_json(&fixture("policy-current.json")).unwrap();
+        assert_eq!(current.rules[..4], v1.rules[..]);
+        assert_eq!(current.rules.len(), 5);
+
+        let migrated = PolicyConfig::migrate(&fixture("policy-v1.json")).unwrap();
+        assert!(migrated.starts_with(r#"{"version":1,"rules":[{"rule":"min_length","length":12}"#), "{}", migrated);
+        assert_eq!(PolicyConfig::from_json(&migrated).unwrap(), v1);
+        assert_eq!(PolicyConfig::from_json(&current.to_json()).unwrap(), current);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let future = r#"{"version": 2, "rules": [{"rule": "min_length", "length": 8}]}"#;
+        let err = PolicyConfig::from_json(future).unwrap_err();
+        assert_eq!(err, ConfigError::UnsupportedVersion { found: 2, supported: 1 });
+        assert_eq!(err.to_string(), "policy format version 2 is newer than the supported version 1; upgrade passcheck to load it");
+        assert_eq!(PolicyConfig::migrate(future), Err(err));
+        // Malformed versions are field errors, not unknown versions.
+        assert_eq!(
+            PolicyConfig::from_json(r#"{"version": "1", "rules": []}"#),
+            Err(invalid("version", "expected a positive integer, found \"1\"".into()))
+        );
+        assert_eq!(PolicyConfig::from_json(r#"{"version": 0}"#), Err(invalid("version", "expected a positive integer, found 0".into())));
+    }
+
+    #[test]
+    fn empty_policies_must_say_so() {
+        for json in [r#"{}"#, r#"{"rules": []}"#, r#"{"version": 1, "rules": [], "allow_empty": false}"#] {
+            assert_eq!(PasswordChecker::from_json(json), Err(ConfigError::EmptyPolicy), "{}", json);
+        }
+        assert_eq!(PolicyConfig::migrate("{}"), Err(ConfigError::EmptyPolicy));
+        assert_eq!(ConfigError::EmptyPolicy.to_string(), "policy has no rules and would accept every password");
+        let empty = PasswordChecker::from_json(r#"{"allow_empty": true}"#).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert_eq!(PasswordChecker::from_json(&empty.to_config().to_json()), Ok(PasswordChecker::new()));
+        assert_eq!(PasswordChecker::from_json(r#"{"allow_empty": 1}"#), Err(invalid("allow_empty", "expected boolean, found number".into())));
+        let one = PasswordChecker::from_json(r#"{"allow_empty": true, "rules": [{"rule": "require_number"}]}"#).unwrap();
+        assert_eq!(one.len(), 1);
+        assert!(!one.is_empty());
+    }
+
+    #[test]
+    fn blacklist_file_round_trip() {
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/common-passwords.sorted");
+        let mut json = r#"{"rules": [{"rule": "blacklist", "file": "#.to_string();
+        json::write_string(&mut json, file);
+        json.push_str("}]}");
+        let checker = PasswordChecker::from_json(&json).unwrap();
+        assert!(checker.validate("letmein").is_err());
+        assert_eq!(PasswordChecker::from_json(&checker.to_config().to_json()).unwrap(), checker);
+        assert!(matches!(
+            PasswordChecker::from_json(r#"{"rules": [{"rule": "blacklist", "file": "missing.sorted"}]}"#),
+            Err(ConfigError::InvalidField { path, .. }) if path == "rules[0].file"
+        ));
+    }
+}
@@ -0,0 +1,422 @@
+//! Regression corpora: passwords with the decision a policy must reach.
+//!
+//! A corpus pins what a checker accepts and, for rejected passwords,
+//! which rules reject them, so an upgrade that changes a decision fails a
+//! test instead of reaching users. Corpora are JSON Lines, one case per
+//! line:
+//!
+//! ```text
+//! {"name": "too short", "password": "Ab3$", "accept": false, "codes": ["min_length"]}
+//! {"name": "strong", "password": "Tr0ub4dor&3-horse", "accept": true}
+//! {"name": "username", "password": "jsmith-2024", "context": {"username": "jsmith"}, "accept": false, "codes": ["not_contain_user_info"]}
+//! ```
+//!
+//! `codes` lists every violation in order, as
+//! [`Violations::codes`](crate::Violations::codes) yields them. A
+//! `context` may give `username`, `email`, `full_name`, `phone` and
+//! `previous_password`. For passwords that must not be committed, a case may
+//! give `sha256`, the hex digest of the password, instead of `password`;
+//! [`run_corpus_with_secrets`] then takes the passwords from a secret store
+//! and matches them to cases by digest.
+//!
+//! ```
+//! use passcheck::PasswordChecker;
+//! use passcheck::conformance::{parse_corpus, run_corpus};
+//!
+//! let checker = PasswordChecker::new().min_length(8, None);
+//! let corpus = parse_corpus(r#"{"password": "sunflower", "accept": true}
+//! {"password": "sun", "accept": false, "codes": ["min_length"]}"#).unwrap();
+//! let result = run_corpus(&checker, &corpus);
+//! assert!(result.is_ok(), "{}", result);
+//! ```
+//!
+//! [`builtin_suites`] is the crate's own corpus, a few policies covering
+//! every rule family, run by the crate's tests. Its expectations are part
+//! of the public contract: a release that changes one changes what some
+//! policy accepts, so it is a breaking change under semver.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::fingerprint::sha256;
+use crate::json::{self, Value};
+use crate::{PasswordChecker, PolicyFingerprint, UserContext};
+
+/// The password of a [`CorpusCase`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorpusInput {
+    Password(String),
+    /// The SHA-256 of a password kept out of the corpus.
+    Sha256([u8; 32]),
+}
+
+/// What a checker decides for a password: accepted, or rejected with
+/// these violation codes in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Accept,
+    Reject(Vec<String>),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Accept => f.write_str("accept"),
+            Outcome::Reject(codes) => write!(f, "reject [{}]", codes.join(", ")),
+        }
+    }
+}
+
+/// What a case knows about the user, for rules that need a
+/// [`UserContext`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CaseContext {
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub full_name: Option<String>,
+    pub phone: Option<String>,
+    pub previous_password: Option<String>,
+}
+
+impl CaseContext {
+    fn user_context(&self) -> UserContext<'_> {
+        let mut context = UserContext::new();
+        if let Some(username) = &self.username {
+            context = context.username(username);
+        }
+        if let Some(email) = &self.email {
+            context = context.email(email);
+        }
+        if let Some(name) = &self.full_name {
+            context = context.full_name(name);
+        }
+        if let Some(phone) = &self.phone {
+            context = context.phone(phone);
+        }
+        if let Some(previous) = &self.previous_password {
+            context = context.previous_password(previous);
+        }
+        context
+    }
+}
+
+/// One password of a corpus and the outcome it must have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusCase {
+    /// Identifies the case in mismatches; the loaders default it to
+    /// `line N`.
+    pub name: String,
+    pub input: CorpusInput,
+    /// Validates with this context if set, otherwise without one.
+    pub context: Option<CaseContext>,
+    pub expected: Outcome,
+}
+
+/// A case whose outcome differs from the expected one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub name: String,
+    pub expected: Outcome,
+    pub actual: Outcome,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: expected {}, got {}", self.name, self.expected, self.actual)
+    }
+}
+
+/// The result of [`run_corpus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusResult {
+    /// The fingerprint of the checker the corpus ran against.
+    pub fingerprint: PolicyFingerprint,
+    /// Cases with the expected outcome.
+    pub passed: usize,
+    pub mismatches: Vec<Mismatch>,
+    /// Names of [`Sha256`](CorpusInput::Sha256) cases no secret matched.
+    pub unresolved: Vec<String>,
+}
+
+impl CorpusResult {
+    /// Whether every case was run and had the expected outcome.
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty() && self.unresolved.is_empty()
+    }
+}
+
+/// A summary line, then one line per mismatch and unresolved case.
+impl fmt::Display for CorpusResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} passed, {} mismatched, {} unresolved (policy {})", self.passed, self.mismatches.len(), self.unresolved.len(), self.fingerprint)?;
+        for mismatch in &self.mismatches {
+            write!(f, "\n  {}", mismatch)?;
+        }
+        for name in &self.unresolved {
+            write!(f, "\n  {}: no secret has its digest", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every case of `corpus` against `checker`. Cases given by digest
+/// are [unresolved](CorpusResult::unresolved); run them with
+/// [`run_corpus_with_secrets`].
+pub fn run_corpus(checker: &PasswordChecker<'_>, corpus: &[CorpusCase]) -> CorpusResult {
+    run_corpus_with_secrets(checker, corpus, &[])
+}
+
+/// Like [`run_corpus`], running each [`Sha256`](CorpusInput::Sha256) case
+/// with the secret whose digest it holds.
+pub fn run_corpus_with_secrets(checker: &PasswordChecker<'_>, corpus: &[CorpusCase], secrets: &[&str]) -> CorpusResult {
+    let digests: Vec<[u8; 32]> = secrets.iter().map(|secret| sha256(secret.as_bytes())).collect();
+    let mut result = CorpusResult { fingerprint: checker.fingerprint(), passed: 0, mismatches: Vec::new(), unresolved: Vec::new() };
+    for case in corpus {
+        let password = match &case.input {
+            CorpusInput::Password(password) => password.as_str(),
+            CorpusInput::Sha256(digest) => match digests.iter().position(|d| d == digest) {
+                Some(i) => secrets[i],
+                None => {
+                    result.unresolved.push(case.name.clone());
+                    continue;
+                }
+            },
+        };
+        let checked = match &case.context {
+            Some(context) => checker.check_with_context(password, &context.user_context()),
+            None => checker.check(password),
+        };
+        let actual = match checked {
+            Ok(()) => Outcome::Accept,
+            Err(violations) => Outcome::Reject(violations.codes().map(str::to_string).collect()),
+        };
+        if actual == case.expected {
+            result.passed += 1;
+        } else {
+            result.mismatches.push(Mismatch { name: case.name.clone(), expected: case.expected.clone(), actual });
+        }
+    }
+    result
+}
+
+/// Error returned by [`parse_corpus`] and [`load_corpus`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorpusError {
+    /// The file could not be read.
+    Read(String),
+    /// A line is not a valid case; lines count from 1.
+    InvalidLine { line: usize, reason: String },
+}
+
+impl fmt::Display for CorpusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorpusError::Read(reason) => write!(f, "cannot read corpus: {}", reason),
+            CorpusError::InvalidLine { line, reason } => write!(f, "invalid corpus line {}: {}", line, reason),
+        }
+    }
+}
+
+impl std::error::Error for CorpusError {}
+
+/// Parses a JSON Lines corpus. Blank lines are skipped.
+pub fn parse_corpus(input: &str) -> Result<Vec<CorpusCase>, CorpusError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_case(line, i + 1).map_err(|reason| CorpusError::InvalidLine { line: i + 1, reason }))
+        .collect()
+}
+
+/// Reads and parses a corpus file.
+pub fn load_corpus(path: impl AsRef<Path>) -> Result<Vec<CorpusCase>, CorpusError> {
+    let input = std::fs::read_to_string(path).map_err(|e| CorpusError::Read(e.to_string()))?;
+    parse_corpus(&input)
+}
+
+fn parse_case(line: &str, number: usize) -> Result<CorpusCase, String> {
+    let value = json::parse(line).map_err(|e| format!("{} at byte {}", e.reason, e.offset))?;
+    let Value::Object(fields) = &value else { return Err(format!("expected object, found {}", value.type_name())) };
+    const KEYS: &[&str] = &["name", "password", "sha256", "context", "accept", "codes"];
+    if let Some((key, _)) = fields.iter().find(|(key, _)| !KEYS.contains(&key.as_str())) {
+        return Err(format!("unknown field `{}`", key));
+    }
+    let string = |key: &str| match value.get(key) {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(other) => Err(format!("`{}`: expected string, found {}", key, other.type_name())),
+    };
+    let input = match (string("password")?, string("sha256")?) {
+        (Some(password), None) => CorpusInput::Password(password),
+        (None, Some(hex)) => CorpusInput::Sha256(parse_digest(&hex).ok_or("`sha256`: expected 64 hex digits")?),
+        (Some(_), Some(_)) => return Err("`password` and `sha256` cannot be combined".to_string()),
+        (None, None) => return Err("missing `password` or `sha256`".to_string()),
+    };
+    let context = match value.get("context") {
+        None => None,
+        Some(context @ Value::Object(fields)) => {
+            let mut parsed = CaseContext::default();
+            for (key, _) in fields {
+                let slot = match key.as_str() {
+                    "username" => &mut parsed.username,
+                    "email" => &mut parsed.email,
+                    "full_name" => &mut parsed.full_name,
+                    "phone" => &mut parsed.phone,
+                    "previous_password" => &mut parsed.previous_password,
+                    _ => return Err(format!("unknown field `context.{}`", key)),
+                };
+                *slot = match context.get(key) {
+                    Some(Value::String(s)) => Some(s.clone()),
+                    Some(other) => return Err(format!("`context.{}`: expected string, found {}", key, other.type_name())),
+                    None => None,
+                };
+            }
+            Some(parsed)
+        }
+        Some(other) => return Err(format!("`context`: expected object, found {}", other.type_name())),
+    };
+    let codes = match value.get("codes") {
+        None => Vec::new(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(code) => Ok(code.clone()),
+                other => Err(format!("`codes`: expected string, found {}", other.type_name())),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(other) => return Err(format!("`codes`: expected array, found {}", other.type_name())),
+    };
+    let expected = match (value.get("accept"), codes.is_empty()) {
+        (Some(Value::Bool(true)), true) => Outcome::Accept,
+        (Some(Value::Bool(true)), false) => return Err("an accepted password has no `codes`".to_string()),
+        (Some(Value::Bool(false)), false) => Outcome::Reject(codes),
+        (Some(Value::Bool(false)), true) => return Err("a rejected password needs `codes`".to_string()),
+        (Some(other), _) => return Err(format!("`accept`: expected boolean, found {}", other.type_name())),
+        (None, _) => return Err("missing `accept`".to_string()),
+    };
+    let name = string("name")?.unwrap_or_else(|| format!("line {}", number));
+    Ok(CorpusCase { name, input, context, expected })
+}
+
+fn parse_digest(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut digest = [0; 32];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(digest)
+}
+
+/// A policy with the corpus it must pass, from [`builtin_suites`].
+#[derive(Debug, Clone)]
+pub struct Suite {
+    pub name: &'static str,
+    pub checker: PasswordChecker<'static>,
+    pub corpus: Vec<CorpusCase>,
+}
+
+impl Suite {
+    pub fn run(&self) -> CorpusResult {
+        run_corpus(&self.checker, &self.corpus)
+    }
+}
+
+/// The policies and corpora in `tests/fixtures/conformance`.
+const BUILTIN: &[(&str, &str, &str)] = &[
+    ("general", include_str!("../tests/fixtures/conformance/general.json"), include_str!("../tests/fixtures/conformance/general.jsonl")),
+    ("passphrase", include_str!("../tests/fixtures/conformance/passphrase.json"), include_str!("../tests/fixtures/conformance/passphrase.jsonl")),
+    ("pin", include_str!("../tests/fixtures/conformance/pin.json"), include_str!("../tests/fixtures/conformance/pin.jsonl")),
+];
+
+/// The crate's own corpus: a general policy, a passphrase policy and a PIN
+/// policy, which between them use every rule family loadable from a
+/// [`PolicyConfig`](crate::PolicyConfig), each with passwords passing and
+/// breaking its rules.
+pub fn builtin_suites() -> Vec<Suite> {
+    BUILTIN
+        .iter()
+        .map(|&(name, policy, corpus)| Suite {
+            name,
+            checker: PasswordChecker::from_json(policy).expect("built-in policy loads"),
+            corpus: parse_corpus(corpus).expect("built-in corpus parses"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_corpus_passes() {
+        for suite in builtin_suites() {
+            let result = suite.run();
+            assert!(result.is_ok(), "{}: {}", suite.name, result);
+            assert_eq!(result.passed, suite.corpus.len());
+        }
+    }
+
+    #[test]
+    fn builtin_corpus_covers_every_loadable_rule() {
+        let used: Vec<&str> = builtin_suites().iter().flat_map(|suite| suite.checker.rules().iter().map(|rule| rule.code()).collect::<Vec<_>>()).collect();
+        let loadable = crate::violation::CODES.iter().filter(|code| {
+            !["not_contain_phone_number", "not_contain_date_of_birth", "not_breached", "not_in_history", "input_too_large", "context_required", "field_missing", "confirmation_mismatch"].contains(code)
+        });
+        for code in loadable {
+            assert!(used.contains(code), "{}", code);
+        }
+    }
+
+    #[test]
+    fn perturbed_expectations_are_reported_exactly() {
+        let mut suite = builtin_suites().remove(2);
+        let case = suite.corpus.iter_mut().find(|case| case.name == "repeated").unwrap();
+        case.expected = Outcome::Reject(vec!["not_repeated_char".to_string()]);
+        suite.corpus[0].expected = Outcome::Reject(vec!["blacklist".to_string()]);
+        let result = suite.run();
+        assert_eq!(result.passed, suite.corpus.len() - 2);
+        assert_eq!(
+            result.mismatches,
+            [
+                Mismatch { name: "random".into(), expected: Outcome::Reject(vec!["blacklist".into()]), actual: Outcome::Accept },
+                Mismatch {
+                    name: "repeated".into(),
+                    expected: Outcome::Reject(vec!["not_repeated_char".into()]),
+                    actual: Outcome::Reject(vec!["not_repeated_char".into(), "not_palindrome".into()]),
+                },
+            ]
+        );
+        let report = result.to_string();
+        assert!(report.ends_with(
+            "\n  random: expected reject [blacklist], got accept\n  repeated: expected reject [not_repeated_char], got reject [not_repeated_char, not_palindrome]"
+        ));
+    }
+
+    #[test]
+    fn digest_cases_need_their_secret() {
+        let checker = PasswordChecker::new().min_length(8, None);
+        let digest = crate::PolicyFingerprint(sha256(b"hunter2")).to_string();
+        let corpus = parse_corpus(&format!(r#"{{"name": "leaked", "sha256": "{}", "accept": false, "codes": ["min_length"]}}"#, digest)).unwrap();
+        let result = run_corpus(&checker, &corpus);
+        assert_eq!((result.passed, result.unresolved.as_slice()), (0, ["leaked".to_string()].as_slice()));
+        assert!(!result.is_ok());
+        assert!(run_corpus_with_secrets(&checker, &corpus, &["sunflower", "hunter2"]).is_ok());
+    }
+
+    #[test]
+    fn malformed_lines_are_located() {
+        let invalid = |input: &str| match parse_corpus(input) {
+            Err(CorpusError::InvalidLine { line, reason }) => (line, reason),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(invalid("\n{\"password\": \"x\"}"), (2, "missing `accept`".to_string()));
+        assert_eq!(invalid(r#"{"password": "x", "accept": true, "codes": ["min_length"]}"#), (1, "an accepted password has no `codes`".to_string()));
+        assert_eq!(invalid(r#"{"password": "x", "accept": false}"#), (1, "a rejected password needs `codes`".to_string()));
+        assert_eq!(invalid(r#"{"sha256": "abc", "accept": true}"#), (1, "`sha256`: expected 64 hex digits".to_string()));
+        assert_eq!(invalid(r#"{"password": "x", "accept": true, "context": {"age": "3"}}"#), (1, "unknown field `context.age`".to_string()));
+        assert_eq!(parse_corpus(r#"{"password": "x", "accept": true}"#).unwrap()[0].name, "line 1");
+    }
+}
@@ -0,0 +1,106 @@
+//! Mapping of visually confusable characters to ASCII.
+//!
+//! A curated subset of the Unicode TR39 confusables data: Cyrillic and
+//! Greek letters that look like Latin ones, a few Latin lookalikes, and the
+//! fullwidth ASCII block. Characters outside the table are kept as they
+//! are, so text that is genuinely non-Latin stays distinct from Latin words.
+
+/// Sorted by source character.
+const CONFUSABLES: &[(char, char)] = &[
+    ('\u{0131}', 'i'), // ı dotless i
+    ('\u{0251}', 'a'), // ɑ
+    ('\u{0261}', 'g'), // ɡ
+    ('\u{0391}', 'A'),
+    ('\u{0392}', 'B'),
+    ('\u{0395}', 'E'),
+    ('\u{0396}', 'Z'),
+    ('\u{0397}', 'H'),
+    ('\u{0399}', 'I'),
+    ('\u{039A}', 'K'),
+    ('\u{039C}', 'M'),
+    ('\u{039D}', 'N'),
+    ('\u{039F}', 'O'),
+    ('\u{03A1}', 'P'),
+    ('\u{03A4}', 'T'),
+    ('\u{03A5}', 'Y'),
+    ('\u{03A7}', 'X'),
+    ('\u{03B1}', 'a'),
+    ('\u{03B9}', 'i'),
+    ('\u{03BA}', 'k'),
+    ('\u{03BD}', 'v'),
+    ('\u{03BF}', 'o'),
+    ('\u{03C1}', 'p'),
+    ('\u{03C5}', 'u'),
+    ('\u{0405}', 'S'),
+    ('\u{0406}', 'I'),
+    ('\u{0408}', 'J'),
+    ('\u{0410}', 'A'),
+    ('\u{0412}', 'B'),
+    ('\u{0415}', 'E'),
+    ('\u{041A}', 'K'),
+    ('\u{041C}', 'M'),
+    ('\u{041D}', 'H'),
+    ('\u{041E}', 'O'),
+    ('\u{0420}', 'P'),
+    ('\u{0421}', 'C'),
+    ('\u{0422}', 'T'),
+    ('\u{0423}', 'Y'),
+    ('\u{0425}', 'X'),
+    ('\u{0430}', 'a'),
+    ('\u{0435}', 'e'),
+    ('\u{043E}', 'o'),
+    ('\u{0440}', 'p'),
+    ('\u{0441}', 'c'),
+    ('\u{0443}', 'y'),
+    ('\u{0445}', 'x'),
+    ('\u{0455}', 's'),
+    ('\u{0456}', 'i'),
+    ('\u{0458}', 'j'),
+    ('\u{04BB}', 'h'),
+    ('\u{04CF}', 'l'),
+    ('\u{0501}', 'd'),
+    ('\u{051A}', 'Q'),
+    ('\u{051B}', 'q'),
+    ('\u{051C}', 'W'),
+    ('\u{051D}', 'w'),
+    ('\u{2113}', 'l'), // ℓ
+];
+
+/// Returns the ASCII skeleton of `s`: every confusable character replaced
+/// by the ASCII character it imitates.
+///
+/// ```
+/// assert_eq!(passcheck::skeleton("p\u{0430}ssword"), "password");
+/// assert_eq!(passcheck::skeleton("ｐａｓｓ"), "pass");
+/// ```
+pub fn skeleton(s: &str) -> String {
+    s.chars()
+        .map(|c| match c as u32 {
+            // Fullwidth forms of `!` through `~`.
+            0xFF01..=0xFF5E => char::from_u32(c as u32 - 0xFF01 + 0x21).unwrap_or(c),
+            _ if c.is_ascii() => c,
+            _ => CONFUSABLES.binary_search_by_key(&c, |&(from, _)| from).map_or(c, |i| CONFUSABLES[i].1),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(CONFUSABLES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn maps_lookalikes_and_keeps_other_text() {
+        assert_eq!(skeleton("p\u{0430}ssw\u{043E}rd"), "password");
+        assert_eq!(skeleton("\u{03C1}\u{03B1}ss"), "pass");
+        assert_eq!(skeleton("\u{0410}DMIN"), "ADMIN");
+        assert_eq!(skeleton("ｑｗｅｒｔｙ１"), "qwerty1");
+        assert_eq!(skeleton("пароль"), "пapoль");
+        assert_eq!(skeleton("κωδικός"), "kωδikός");
+        assert_eq!(skeleton("密码123"), "密码123");
+    }
+}
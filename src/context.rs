@@ -0,0 +1,296 @@
+//! Per-request information about the user setting a password.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::birthday::Birthday;
+use crate::multisearch::Substrings;
+use crate::{accents, casefold};
+
+/// What is known about the account a password is for, passed to
+/// [`PasswordChecker::check_with_context`](crate::PasswordChecker::check_with_context)
+/// for rules that compare the password against it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserContext<'c> {
+    pub(crate) previous_password: Option<&'c str>,
+    pub(crate) username: Option<&'c str>,
+    pub(crate) email: Option<&'c str>,
+    pub(crate) full_name: Option<&'c str>,
+    pub(crate) phone: Option<&'c str>,
+    pub(crate) date_of_birth: Option<Birthday>,
+    tokens: TokenCache,
+}
+
+/// The [tokens](UserContext::tokens) of a context, searched for by
+/// [`UserInfoCheck`] and built on first use, once with and once without
+/// [`email_domain`](UserInfoCheck::email_domain).
+#[derive(Clone, Default)]
+struct TokenCache([OnceLock<Substrings>; 2]);
+
+impl fmt::Debug for TokenCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenCache")
+    }
+}
+
+// The cache is derived from the other fields.
+impl PartialEq for TokenCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for TokenCache {}
+
+impl<'c> UserContext<'c> {
+    pub fn new() -> Self {
+        UserContext::default()
+    }
+
+    /// The password being replaced, when the user supplied it to change it.
+    pub fn previous_password(mut self, password: &'c str) -> Self {
+        self.previous_password = Some(password);
+        self
+    }
+
+    pub fn username(mut self, username: &'c str) -> Self {
+        self.username = Some(username);
+        self.tokens = TokenCache::default();
+        self
+    }
+
+    pub fn email(mut self, email: &'c str) -> Self {
+        self.email = Some(email);
+        self.tokens = TokenCache::default();
+        self
+    }
+
+    /// The user's name, such as `"José García"`.
+    pub fn full_name(mut self, name: &'c str) -> Self {
+        self.full_name = Some(name);
+        self.tokens = TokenCache::default();
+        self
+    }
+
+    /// The user's phone number, in any format, such as `"+44 7911 123456"`.
+    pub fn phone(mut self, phone: &'c str) -> Self {
+        self.phone = Some(phone);
+        self
+    }
+
+    /// The user's date of birth; `month` and `day` count from 1.
+    pub fn date_of_birth(mut self, year: u16, month: u8, day: u8) -> Self {
+        self.date_of_birth = Some(Birthday { year, month, day });
+        self
+    }
+
+    /// [Normalized](normalize) strings that [`UserInfoCheck`] looks for in
+    /// passwords.
+    pub(crate) fn tokens(&self, check: &UserInfoCheck) -> Vec<String> {
+        let mut tokens = Vec::new();
+        if let Some(username) = self.username {
+            let username = normalize(username);
+            tokens.extend(username.split(SEPARATORS).map(str::to_string));
+            tokens.push(username);
+        }
+        if let Some(name) = self.full_name {
+            let name = normalize(name);
+            let parts: Vec<&str> = name.split(|c: char| c.is_whitespace() || matches!(c, '-' | '\'' | '.')).filter(|p| !p.is_empty()).collect();
+            tokens.extend(parts.iter().map(|part| part.to_string()));
+            // Adjacent parts written together, as in `josegarcia`.
+            tokens.extend(parts.windows(2).map(|pair| pair.concat()));
+        }
+        if let Some(email) = self.email {
+            let email = normalize(email);
+            let (local, domain) = email.rsplit_once('@').unwrap_or((&email, ""));
+            tokens.extend(local.split(SEPARATORS).map(str::to_string));
+            if check.email_domain {
+                let labels: Vec<&str> = domain.split('.').collect();
+                let suffix = public_suffix_len(&labels);
+                tokens.extend(labels[..labels.len() - suffix].iter().flat_map(|label| label.split('-')).map(str::to_string));
+            }
+        }
+        tokens.retain(|token| token.chars().count() >= MIN_TOKEN_CHARS);
+        tokens.sort_unstable();
+        tokens.dedup();
+        tokens
+    }
+
+    /// The [`tokens`](Self::tokens) for `check`, built once per context.
+    fn token_search(&self, check: &UserInfoCheck) -> &Substrings {
+        self.tokens.0[usize::from(check.email_domain)].get_or_init(|| Substrings::new(self.tokens(check)))
+    }
+}
+
+/// Case-folds `s` and removes diacritics.
+fn normalize(s: &str) -> String {
+    accents::strip(&casefold::fold(s))
+}
+
+/// Characters separating the parts of usernames and email local parts.
+const SEPARATORS: [char; 4] = ['.', '+', '_', '-'];
+
+/// Shorter tokens, like the `j` of `j.smith`, match too many passwords.
+const MIN_TOKEN_CHARS: usize = 3;
+
+/// Second-level labels that are registries rather than owners under many
+/// country-code domains, as in `example.co.uk`.
+const SECOND_LEVEL: [&str; 8] = ["ac", "co", "com", "edu", "gov", "net", "org", "ne"];
+
+/// Number of trailing labels of a domain that form its public suffix: the
+/// top-level domain, plus a registry label such as `co` under a two-letter
+/// country code. This approximates the public suffix list without
+/// embedding it.
+fn public_suffix_len(labels: &[&str]) -> usize {
+    match labels {
+        [] | [_] => labels.len(),
+        [.., second, top] if top.len() == 2 && SECOND_LEVEL.contains(second) && labels.len() > 2 => 2,
+        _ => 1,
+    }
+}
+
+/// Settings of the rule rejecting passwords that contain the user's
+/// username, name or email address, as found in the [`UserContext`].
+/// Matching ignores case and diacritics.
+///
+/// A username contributes itself and its parts split like an email local
+/// part. A full name contributes each of its parts and each pair of adjacent
+/// parts written together: `José García` yields `jose`, `garcia` and
+/// `josegarcia`.
+/// An email address contributes the parts of its local part split at `.`,
+/// `+`, `_` and `-`, so `j.smith+shop@example-corp.com` yields `smith`
+/// and `shop`; parts shorter than three characters are ignored. With
+/// [`email_domain`](Self::email_domain), the domain's labels below the
+/// public suffix count too: `example` and `corp`, but not `com`.
+///
+/// With [`bidirectional`](Self::bidirectional), passwords that are
+/// themselves part of the username, name or email address are rejected
+/// too, such as `xander.ham` for `alexander.hamilton`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserInfoCheck {
+    pub email_domain: bool,
+    pub bidirectional: bool,
+    /// Shortest password checked for being part of the user information.
+    pub bidirectional_min_length: usize,
+}
+
+impl Default for UserInfoCheck {
+    fn default() -> Self {
+        UserInfoCheck { email_domain: true, bidirectional: false, bidirectional_min_length: 6 }
+    }
+}
+
+/// How a password matched the user information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Containment {
+    /// The password contains a token.
+    Contains,
+    /// The password is part of the username, name or email address.
+    ContainedIn,
+}
+
+impl UserInfoCheck {
+    pub fn new() -> Self {
+        UserInfoCheck::default()
+    }
+
+    /// Whether the labels of the email domain count as user information.
+    pub fn email_domain(mut self, enabled: bool) -> Self {
+        self.email_domain = enabled;
+        self
+    }
+
+    /// Whether passwords that are part of the user information fail too.
+    pub fn bidirectional(mut self, enabled: bool) -> Self {
+        self.bidirectional = enabled;
+        self
+    }
+
+    /// Sets the shortest password checked by
+    /// [`bidirectional`](Self::bidirectional) matching, 6 by default, as
+    /// short passwords are part of many names by chance.
+    pub fn bidirectional_min_length(mut self, len: usize) -> Self {
+        self.bidirectional_min_length = len;
+        self
+    }
+
+    pub(crate) fn fails(&self, password: &str, context: &UserContext<'_>) -> bool {
+        self.find(password, context).is_some()
+    }
+
+    /// Returns how `password` matches the user information in `context`.
+    pub(crate) fn find(&self, password: &str, context: &UserContext<'_>) -> Option<Containment> {
+        self.find_normalized(&normalize(password), context)
+    }
+
+    /// Like [`find`](Self::find), for a password already [normalized](normalize).
+    pub(crate) fn find_normalized(&self, password: &str, context: &UserContext<'_>) -> Option<Containment> {
+        if context.token_search(self).found_in(password) {
+            return Some(Containment::Contains);
+        }
+        if self.bidirectional && password.chars().count() >= self.bidirectional_min_length.max(1) {
+            let mut fields = [context.username, context.full_name, context.email].into_iter().flatten();
+            if fields.any(|field| normalize(field).contains(password)) {
+                return Some(Containment::ContainedIn);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_email_addresses() {
+        let context = UserContext::new().email("j.smith+shop@example-corp.com");
+        assert_eq!(context.tokens(&UserInfoCheck::new()), ["corp", "example", "shop", "smith"]);
+        assert_eq!(context.tokens(&UserInfoCheck::new().email_domain(false)), ["shop", "smith"]);
+        let context = UserContext::new().username("Ana").email("ana.lopez@mail.example.co.uk");
+        assert_eq!(context.tokens(&UserInfoCheck::new()), ["ana", "example", "lopez", "mail"]);
+        assert_eq!(UserContext::new().email("bob@localhost").tokens(&UserInfoCheck::new()), ["bob"]);
+        assert!(UserContext::new().email("@").tokens(&UserInfoCheck::new()).is_empty());
+    }
+
+    #[test]
+    fn matches_names_without_diacritics() {
+        let check = UserInfoCheck::new();
+        let context = UserContext::new().full_name("José García");
+        assert_eq!(context.tokens(&check), ["garcia", "jose", "josegarcia"]);
+        assert!(check.fails("JoseG2024!", &context));
+        assert!(check.fails("xGARCÍAx", &context));
+        let context = UserContext::new().full_name("Li Wei");
+        assert!(check.fails("LiWei1990", &context));
+        for password in ["Limerick99!", "Oliver2024", "Wellington"] {
+            assert!(!check.fails(password, &context), "{}", password);
+        }
+    }
+
+    #[test]
+    fn bidirectional_matching_is_opt_in() {
+        let context = UserContext::new().username("alexander.hamilton").email("ahamilton@example.org");
+        let check = UserInfoCheck::new();
+        assert_eq!(check.find("xander.ham", &context), None);
+        let check = check.bidirectional(true);
+        assert_eq!(check.find("Xander.Ham", &context), Some(Containment::ContainedIn));
+        assert_eq!(check.find("hamilton", &context), Some(Containment::Contains));
+        assert_eq!(check.find("ilton@ex", &context), Some(Containment::ContainedIn));
+        // Shorter than the minimum.
+        assert_eq!(check.find("xande", &context), None);
+        assert_eq!(check.clone().bidirectional_min_length(4).find("xande", &context), Some(Containment::ContainedIn));
+        assert_eq!(check.find("", &context), None);
+    }
+
+    #[test]
+    fn matches_case_insensitively() {
+        let context = UserContext::new().email("j.smith+shop@example-corp.com");
+        let check = UserInfoCheck::new();
+        assert!(check.fails("Smith1990!", &context));
+        assert!(check.fails("ExampleRocks!", &context));
+        assert!(!check.email_domain(false).fails("ExampleRocks!", &context));
+        for password in ["j-is-for-jazz", "dotcom4ever", "unrelated"] {
+            assert!(!UserInfoCheck::new().fails(password, &context), "{}", password);
+        }
+    }
+}
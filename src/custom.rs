@@ -0,0 +1,158 @@
+//! Rules defined by the application.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::ParamValue;
+
+type Passes = dyn Fn(&str) -> bool + Send + Sync;
+type Params = dyn Fn(&str) -> Vec<(&'static str, ParamValue)> + Send + Sync;
+
+/// Error returned by [`CustomRule::new`] for a code that cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCode {
+    pub code: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for InvalidCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule code `{}`: {}", self.code, self.reason)
+    }
+}
+
+impl std::error::Error for InvalidCode {}
+
+/// A rule implemented by a closure, added with
+/// [`PasswordChecker::custom`](crate::PasswordChecker::custom).
+///
+/// Its violations have the same shape as those of built-in rules: the
+/// rule's code, a message and the params from [`params`](Self::params), so
+/// frontends can key and localize them alike.
+///
+/// ```
+/// use passcheck::{CustomRule, ParamValue, PasswordChecker};
+///
+/// let rule = CustomRule::new("no_company_name", |pw| !pw.to_lowercase().contains("acme"))
+///     .unwrap()
+///     .params(|pw| vec![("position", ParamValue::Int(pw.to_lowercase().find("acme").unwrap_or(0)))]);
+/// let checker = PasswordChecker::new().custom(rule, Some("Leave the company name out."));
+/// let violations = checker.check("MyAcme2024!").unwrap_err();
+/// assert_eq!(violations[0].code, "no_company_name");
+/// assert_eq!(violations[0].param("position"), Some(&ParamValue::Int(2)));
+/// ```
+#[derive(Clone)]
+pub struct CustomRule {
+    code: &'static str,
+    passes: Arc<Passes>,
+    params: Option<Arc<Params>>,
+    version: Option<String>,
+}
+
+impl CustomRule {
+    /// A rule with the violation code `code`, failing every password for
+    /// which `passes` returns false.
+    ///
+    /// Codes must be snake_case: lowercase ASCII letters, digits and
+    /// single underscores, starting with a letter. They must also differ
+    /// from the codes of built-in rules.
+    pub fn new(code: &'static str, passes: impl Fn(&str) -> bool + Send + Sync + 'static) -> Result<Self, InvalidCode> {
+        let invalid = |reason| Err(InvalidCode { code: code.to_string(), reason });
+        if code.is_empty() {
+            return invalid("empty");
+        }
+        let snake_case = code.starts_with(|c: char| c.is_ascii_lowercase()) &&
+            code.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') &&
+            !code.ends_with('_') &&
+            !code.contains("__");
+        if !snake_case {
+            return invalid("expected snake_case");
+        }
+        if crate::violation::CODES.contains(&code) {
+            return invalid("used by a built-in rule");
+        }
+        Ok(CustomRule { code, passes: Arc::new(passes), params: None, version: None })
+    }
+
+    /// Sets the function computing [`Violation::params`](crate::Violation::params)
+    /// for a failing password. Without it, violations have no params.
+    pub fn params(mut self, params: impl Fn(&str) -> Vec<(&'static str, ParamValue)> + Send + Sync + 'static) -> Self {
+        self.params = Some(Arc::new(params));
+        self
+    }
+
+    /// Declares the version of the closure's logic, such as `"2024-06"`.
+    /// Closures cannot be serialized, so the code and this version are what
+    /// a custom rule contributes to the checker's
+    /// [fingerprint](crate::PasswordChecker::fingerprint): change it
+    /// whenever the closure starts accepting different passwords.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// The violation code.
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// The version set with [`version`](Self::version).
+    pub fn declared_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub(crate) fn fails(&self, password: &str) -> bool {
+        !(self.passes)(password)
+    }
+
+    pub(crate) fn violation_params(&self, password: &str) -> Vec<(&'static str, ParamValue)> {
+        self.params.as_ref().map_or_else(Vec::new, |params| params(password))
+    }
+}
+
+impl fmt::Debug for CustomRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomRule").field("code", &self.code).finish_non_exhaustive()
+    }
+}
+
+/// Rules are equal when they share a code, version and the same closure, as
+/// clones of one rule do.
+impl PartialEq for CustomRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.code == other.code && self.version == other.version && Arc::ptr_eq(&self.passes, &other.passes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_must_be_snake_case() {
+        for code in ["no_acme", "rule2", "a"] {
+            assert!(CustomRule::new(code, |_| true).is_ok(), "{}", code);
+        }
+        for (code, reason) in [
+            ("", "empty"),
+            ("NoAcme", "expected snake_case"),
+            ("no-acme", "expected snake_case"),
+            ("_acme", "expected snake_case"),
+            ("2fa", "expected snake_case"),
+            ("no__acme", "expected snake_case"),
+            ("no_acme_", "expected snake_case"),
+            ("min_length", "used by a built-in rule"),
+        ] {
+            assert_eq!(CustomRule::new(code, |_| true).unwrap_err().reason, reason, "{:?}", code);
+        }
+        assert_eq!(CustomRule::new("No", |_| true).unwrap_err().to_string(), "invalid rule code `No`: expected snake_case");
+    }
+
+    #[test]
+    fn clones_are_equal() {
+        let rule = CustomRule::new("no_acme", |pw| !pw.contains("acme")).unwrap();
+        assert_eq!(rule.clone(), rule);
+        assert_ne!(CustomRule::new("no_acme", |pw| !pw.contains("acme")).unwrap(), rule);
+        assert_eq!(format!("{:?}", rule), r#"CustomRule { code: "no_acme", .. }"#);
+    }
+}
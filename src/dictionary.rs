@@ -0,0 +1,702 @@
+//! Word lists for the blacklist rule.
+//!
+//! Small lists are kept in memory. Large lists can be converted once with
+//! [`prepare`] into a sorted file that is searched on disk, so opening it
+//! loads nothing up front:
+//!
+//! ```no_run
+//! use passcheck::{Dictionary, PasswordChecker};
+//!
+//! passcheck::dictionary::prepare("rockyou.txt", "rockyou.sorted")?;
+//! let checker = PasswordChecker::new().blacklist(Dictionary::open("rockyou.sorted")?, None);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Common-password lists for several languages are embedded behind the
+//! `dict-en`, `dict-de` and `dict-es` features and combined by
+//! [`Dictionary::builtin`].
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use crate::leet::unleet;
+use crate::similarity::within_distance;
+
+/// Start of the first line of every file written by [`prepare`]. A
+/// [`MatchMode`] other than exact follows after a space.
+const HEADER: &[u8] = b"#passcheck-sorted-v1";
+
+/// How passwords are compared with dictionary entries.
+///
+/// Entries are normalized once when the dictionary is built; each lookup
+/// only normalizes the password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MatchMode {
+    /// Byte-for-byte comparison.
+    #[default]
+    Exact,
+    /// ASCII letters compare case-insensitively; other characters exactly.
+    AsciiCaseInsensitive,
+    /// Unicode full case folding, so `"STRASSE"` matches `"straße"`. Uses
+    /// the default (non-Turkic) mappings: `"İ"` folds to `"i\u{307}"`, not
+    /// `"i"`, and dotless `"ı"` stays distinct from `"i"`.
+    UnicodeCaseFold,
+}
+
+impl MatchMode {
+    /// Name used in policy files and sorted-file headers.
+    pub fn name(self) -> &'static str {
+        match self {
+            MatchMode::Exact => "exact",
+            MatchMode::AsciiCaseInsensitive => "ascii_case_insensitive",
+            MatchMode::UnicodeCaseFold => "unicode_case_fold",
+        }
+    }
+
+    /// Parses a name returned by [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        [MatchMode::Exact, MatchMode::AsciiCaseInsensitive, MatchMode::UnicodeCaseFold]
+            .into_iter()
+            .find(|mode| mode.name() == name)
+    }
+
+    /// Normalizes `s` for comparison under this mode.
+    pub fn normalize(self, s: &str) -> Cow<'_, str> {
+        match self {
+            MatchMode::Exact => Cow::Borrowed(s),
+            MatchMode::AsciiCaseInsensitive if !s.bytes().any(|b| b.is_ascii_uppercase()) => Cow::Borrowed(s),
+            MatchMode::AsciiCaseInsensitive => Cow::Owned(s.to_ascii_lowercase()),
+            MatchMode::UnicodeCaseFold => Cow::Owned(crate::casefold::fold(s)),
+        }
+    }
+}
+
+/// Embedded common-password lists, one per enabled `dict-*` feature.
+const PACKS: &[(&str, &str)] = &[
+    #[cfg(feature = "dict-en")]
+    ("en", include_str!("../data/common-en.txt")),
+    #[cfg(feature = "dict-de")]
+    ("de", include_str!("../data/common-de.txt")),
+    #[cfg(feature = "dict-es")]
+    ("es", include_str!("../data/common-es.txt")),
+];
+
+/// Language codes of the embedded lists enabled at build time.
+pub fn enabled_packs() -> impl Iterator<Item = &'static str> {
+    PACKS.iter().map(|(lang, _)| *lang)
+}
+
+fn pack_words() -> impl Iterator<Item = &'static str> {
+    PACKS.iter().flat_map(|(_, list)| list.lines()).filter(|w| !w.is_empty())
+}
+
+/// A set of forbidden passwords.
+///
+/// Cloning is cheap: clones share the same immutable source, so one list can
+/// back many checkers and threads.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Dictionary {
+    source: Arc<DictionarySource>,
+    mode: MatchMode,
+    fuzzy: Option<Arc<FuzzyIndex>>,
+}
+
+/// In-memory entries grouped by length in characters, for
+/// [`Dictionary::fuzzy`].
+struct FuzzyIndex {
+    distance: usize,
+    by_len: Vec<Vec<Box<[char]>>>,
+}
+
+impl FuzzyIndex {
+    fn new(words: &Dictionary, distance: usize) -> Self {
+        let mut by_len: Vec<Vec<Box<[char]>>> = Vec::new();
+        for word in words.iter() {
+            let chars: Box<[char]> = word.chars().collect();
+            if by_len.len() <= chars.len() {
+                by_len.resize_with(chars.len() + 1, Vec::new);
+            }
+            by_len[chars.len()].push(chars);
+        }
+        FuzzyIndex { distance, by_len }
+    }
+
+    /// Whether an entry is within the distance of `word`, looking only at
+    /// entries whose length differs by at most the distance.
+    fn near(&self, word: &[char]) -> bool {
+        let lengths = word.len().saturating_sub(self.distance)..(word.len() + self.distance + 1).min(self.by_len.len());
+        let buckets = self.by_len.get(lengths).into_iter().flatten();
+        let mut rows = Default::default();
+        buckets.flatten().any(|entry| within_distance(word, entry, self.distance, &mut rows))
+    }
+}
+
+// The index is derived from the entries, which the dictionary compares.
+impl PartialEq for FuzzyIndex {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance == other.distance
+    }
+}
+
+impl Eq for FuzzyIndex {}
+
+/// Where the entries of a [`Dictionary`] live.
+#[derive(PartialEq, Eq)]
+pub enum DictionarySource {
+    /// Every entry held in a hash set.
+    Memory(HashSet<String>),
+    /// A file produced by [`prepare`], searched on each lookup.
+    SortedFile(SortedFile),
+}
+
+impl Dictionary {
+    /// Builds an in-memory dictionary from a list of words.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Dictionary::with_mode(words, MatchMode::Exact)
+    }
+
+    /// Builds an in-memory dictionary whose entries are normalized for `mode`.
+    pub fn with_mode<I, S>(words: I, mode: MatchMode) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| {
+                let word = word.into();
+                match mode.normalize(&word) {
+                    Cow::Borrowed(_) => word,
+                    Cow::Owned(normalized) => normalized,
+                }
+            })
+            .collect();
+        Dictionary { source: Arc::new(DictionarySource::Memory(words)), mode, fuzzy: None }
+    }
+
+    /// The embedded lists of every enabled `dict-*` feature, merged into one
+    /// set. Empty when no pack is enabled.
+    ///
+    /// Built on first call; later calls return a cheap clone.
+    pub fn builtin() -> Self {
+        static BUILTIN: OnceLock<Dictionary> = OnceLock::new();
+        BUILTIN.get_or_init(|| Dictionary::new(pack_words())).clone()
+    }
+
+    /// PINs that are used far more often than others, such as `1234`,
+    /// `2580` (a keypad column) or years from 1940 to 2039, as used by
+    /// [`PasswordChecker::pin`](crate::PasswordChecker::pin). Always
+    /// available, independently of the `dict-*` features.
+    pub fn common_pins() -> Self {
+        Dictionary::new(crate::pin::common_pins())
+    }
+
+    /// The enabled embedded lists plus `words`, merged into one set so a
+    /// lookup is still a single membership test.
+    pub fn builtin_with<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Dictionary::new(pack_words().map(String::from).chain(words.into_iter().map(Into::into)))
+    }
+
+    /// Opens a file written by [`prepare`] without reading its entries.
+    ///
+    /// The match mode is the one the file was prepared with.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = SortedFile::open(path)?;
+        let mode = file.mode;
+        Ok(Dictionary { source: Arc::new(DictionarySource::SortedFile(file)), mode, fuzzy: None })
+    }
+
+    /// Also matches passwords within `distance` edits (insertions, deletions
+    /// or substitutions) of an entry, such as `passw0rd` for `password` at
+    /// distance 1, comparing the normalized password as written and with
+    /// leetspeak undone. A distance of 0 turns fuzzy matching off.
+    ///
+    /// Entries are grouped by length once, here. A lookup compares the
+    /// password with the k entries whose length is within `distance` of
+    /// its n characters, at O(n · distance) each and usually less, as a
+    /// comparison stops once every alignment is more than `distance` away.
+    /// Only in-memory entries are matched fuzzily: those of a sorted file
+    /// stay on disk and are still matched exactly.
+    pub fn fuzzy(mut self, distance: usize) -> Self {
+        self.fuzzy = (distance > 0).then(|| Arc::new(FuzzyIndex::new(&self, distance)));
+        self
+    }
+
+    /// The distance set with [`fuzzy`](Self::fuzzy), 0 if matching is exact.
+    pub fn fuzzy_distance(&self) -> usize {
+        self.fuzzy.as_ref().map_or(0, |index| index.distance)
+    }
+
+    /// Returns where the entries are stored.
+    pub fn source(&self) -> &DictionarySource {
+        &self.source
+    }
+
+    /// Returns how passwords are compared with the entries.
+    pub fn mode(&self) -> MatchMode {
+        self.mode
+    }
+
+    /// Returns whether `password` is in the dictionary under its match mode.
+    pub fn contains(&self, password: &str) -> bool {
+        match &*self.source {
+            DictionarySource::Memory(words) => words.contains(&*self.mode.normalize(password)),
+            DictionarySource::SortedFile(file) => file.contains(password),
+        }
+    }
+
+    /// Like [`contains`](Self::contains), also matching entries within the
+    /// [fuzzy](Self::fuzzy) distance. This is what the blacklist rule uses.
+    pub fn matches(&self, password: &str) -> bool {
+        self.matches_normalized(&self.mode.normalize(password))
+    }
+
+    /// Like [`matches`](Self::matches), for a password already normalized
+    /// for the [match mode](Self::mode).
+    pub(crate) fn matches_normalized(&self, password: &str) -> bool {
+        let found = match &*self.source {
+            DictionarySource::Memory(words) => words.contains(password),
+            DictionarySource::SortedFile(file) => file.contains_normalized(password),
+        };
+        if found {
+            return true;
+        }
+        let Some(index) = &self.fuzzy else { return false };
+        let normalized: Vec<char> = password.chars().collect();
+        let unleeted: Vec<char> = normalized.iter().copied().map(unleet).collect();
+        index.near(&normalized) || (unleeted != normalized && index.near(&unleeted))
+    }
+
+    /// Number of entries.
+    ///
+    /// For a sorted file this scans the file once and caches the result.
+    pub fn len(&self) -> usize {
+        match &*self.source {
+            DictionarySource::Memory(words) => words.len(),
+            DictionarySource::SortedFile(file) => file.len(),
+        }
+    }
+
+    /// Returns whether the dictionary has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the in-memory entries, as normalized for the match
+    /// mode, in no particular order.
+    ///
+    /// Yields nothing for a sorted file, whose entries stay on disk.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let words = match &*self.source {
+            DictionarySource::Memory(words) => Some(words.iter().map(String::as_str)),
+            DictionarySource::SortedFile(_) => None,
+        };
+        words.into_iter().flatten()
+    }
+}
+
+impl Default for Dictionary {
+    fn default() -> Self {
+        Dictionary::new(Vec::<String>::new())
+    }
+}
+
+impl<S: Into<String>, const N: usize> From<[S; N]> for Dictionary {
+    fn from(words: [S; N]) -> Self {
+        Dictionary::new(words)
+    }
+}
+
+impl<S: Into<String>> From<Vec<S>> for Dictionary {
+    fn from(words: Vec<S>) -> Self {
+        Dictionary::new(words)
+    }
+}
+
+// Word lists can be huge, so only the size or location is printed.
+impl fmt::Debug for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &*self.source {
+            DictionarySource::Memory(words) => write!(f, "Dictionary(entries: {}", words.len())?,
+            DictionarySource::SortedFile(file) => write!(f, "Dictionary(file: {:?}", file.path)?,
+        }
+        match self.fuzzy_distance() {
+            0 => f.write_str(")"),
+            distance => write!(f, ", fuzzy: {})", distance),
+        }
+    }
+}
+
+/// A sorted word list on disk, looked up by binary search.
+///
+/// Each lookup reads O(log n) small chunks with positional reads, so
+/// concurrent lookups do not contend. A read error during a lookup counts
+/// as a miss; errors opening the file are reported by [`SortedFile::open`].
+pub struct SortedFile {
+    path: PathBuf,
+    file: File,
+    mode: MatchMode,
+    /// Offset of the first entry, just past the header line.
+    start: u64,
+    size: u64,
+    count: OnceLock<usize>,
+    #[cfg(not(any(unix, windows)))]
+    lock: std::sync::Mutex<()>,
+}
+
+impl SortedFile {
+    /// Opens a file written by [`prepare`], checking only its header.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut sorted = SortedFile::open_headerless(path)?;
+        let mut buf = [0; 64];
+        let n = sorted.read_at(&mut buf, 0)?;
+        let header = buf[..n].iter().position(|&b| b == b'\n').map(|end| &buf[..end]);
+        let mode = match header.and_then(|line| line.strip_prefix(HEADER)) {
+            Some(b"") => Some(MatchMode::Exact),
+            Some([b' ', name @ ..]) => std::str::from_utf8(name).ok().and_then(MatchMode::from_name),
+            _ => None,
+        };
+        let (Some(mode), Some(header)) = (mode, header) else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a dictionary written by `prepare`"));
+        };
+        sorted.mode = mode;
+        sorted.start = header.len() as u64 + 1;
+        Ok(sorted)
+    }
+
+    /// Opens a file of sorted lines without a header, such as a
+    /// [breach range file](crate::RangeFile).
+    pub(crate) fn open_headerless(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(SortedFile {
+            path,
+            file,
+            mode: MatchMode::Exact,
+            start: 0,
+            size,
+            count: OnceLock::new(),
+            #[cfg(not(any(unix, windows)))]
+            lock: std::sync::Mutex::new(()),
+        })
+    }
+
+    /// Path the file was opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Match mode recorded in the header.
+    pub fn mode(&self) -> MatchMode {
+        self.mode
+    }
+
+    /// Returns whether `word` is one of the entries under the file's mode.
+    pub fn contains(&self, word: &str) -> bool {
+        self.contains_normalized(&self.mode.normalize(word))
+    }
+
+    /// Like [`contains`](Self::contains), for a word already normalized for
+    /// the file's match mode.
+    pub(crate) fn contains_normalized(&self, word: &str) -> bool {
+        self.search(word.as_bytes()).unwrap_or(false)
+    }
+
+    /// Number of entries, counted on first call.
+    pub fn len(&self) -> usize {
+        *self.count.get_or_init(|| {
+            let mut count = 0;
+            let mut offset = self.start;
+            let mut buf = [0; 8192];
+            while let Ok(n @ 1..) = self.read_at(&mut buf, offset) {
+                count += buf[..n].iter().filter(|&&b| b == b'\n').count();
+                offset += n as u64;
+            }
+            count
+        })
+    }
+
+    /// Returns whether the file has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.size == self.start
+    }
+
+    fn search(&self, word: &[u8]) -> io::Result<bool> {
+        if word.contains(&b'\n') {
+            return Ok(false);
+        }
+        Ok(self.find(word, |line| line)?.is_some())
+    }
+
+    /// The line whose key is `key`, for lines sorted by the key `key_of`
+    /// picks out of them.
+    ///
+    /// Binary search over byte offsets. `lo..hi` always contains the start
+    /// of the target line, if the target is present.
+    pub(crate) fn find(&self, key: &[u8], key_of: fn(&[u8]) -> &[u8]) -> io::Result<Option<Vec<u8>>> {
+        let (mut lo, mut hi) = (self.start, self.size);
+        let mut line = Vec::new();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let start = self.line_start(mid)?;
+            if start >= hi {
+                hi = mid;
+                continue;
+            }
+            let end = self.read_line(start, &mut line)?;
+            match key_of(&line).cmp(key) {
+                std::cmp::Ordering::Equal => return Ok(Some(line)),
+                std::cmp::Ordering::Less => lo = end,
+                std::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Smallest line start at or after `pos`.
+    fn line_start(&self, pos: u64) -> io::Result<u64> {
+        if pos == 0 {
+            return Ok(0);
+        }
+        let mut offset = pos - 1;
+        let mut buf = [0; 256];
+        loop {
+            let n = self.read_at(&mut buf, offset)?;
+            if n == 0 {
+                return Ok(self.size);
+            }
+            if let Some(i) = buf[..n].iter().position(|&b| b == b'\n') {
+                return Ok(offset + i as u64 + 1);
+            }
+            offset += n as u64;
+        }
+    }
+
+    /// Reads the line starting at `start` into `line`, returning the offset
+    /// just past its newline.
+    fn read_line(&self, start: u64, line: &mut Vec<u8>) -> io::Result<u64> {
+        line.clear();
+        let mut offset = start;
+        let mut buf = [0; 256];
+        loop {
+            let n = self.read_at(&mut buf, offset)?;
+            if n == 0 {
+                return Ok(offset);
+            }
+            if let Some(i) = buf[..n].iter().position(|&b| b == b'\n') {
+                line.extend_from_slice(&buf[..i]);
+                return Ok(offset + i as u64 + 1);
+            }
+            line.extend_from_slice(&buf[..n]);
+            offset += n as u64;
+        }
+    }
+
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::FileExt::read_at(&self.file, buf, offset)
+        }
+        #[cfg(windows)]
+        {
+            std::os::windows::fs::FileExt::seek_read(&self.file, buf, offset)
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            use std::io::{Read, Seek, SeekFrom};
+            let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+            let mut file = &self.file;
+            file.seek(SeekFrom::Start(offset))?;
+            file.read(buf)
+        }
+    }
+}
+
+// Two handles on the same path hold the same entries.
+impl PartialEq for SortedFile {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl Eq for SortedFile {}
+
+impl fmt::Debug for SortedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SortedFile").field("path", &self.path).field("bytes", &self.size).finish()
+    }
+}
+
+/// Converts a raw word list, one entry per line, into the sorted format
+/// read by [`Dictionary::open`]. Returns the number of entries written.
+///
+/// Line endings (`\n` or `\r\n`) are stripped, empty lines and lines that
+/// are not valid UTF-8 are dropped, and duplicates are removed.
+pub fn prepare(input: impl AsRef<Path>, output: impl AsRef<Path>) -> io::Result<usize> {
+    prepare_with_mode(input, output, MatchMode::Exact)
+}
+
+/// Like [`prepare`], normalizing every entry for `mode`. The mode is
+/// recorded in the file and applied to passwords looked up in it.
+pub fn prepare_with_mode(input: impl AsRef<Path>, output: impl AsRef<Path>, mode: MatchMode) -> io::Result<usize> {
+    let mut words = Vec::new();
+    for line in BufReader::new(File::open(input)?).split(b'\n') {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if let (false, Ok(word)) = (line.is_empty(), std::str::from_utf8(&line)) {
+            words.push(mode.normalize(word).into_owned().into_bytes());
+        }
+    }
+    words.sort_unstable();
+    words.dedup();
+
+    let mut out = BufWriter::new(File::create(output)?);
+    out.write_all(HEADER)?;
+    if mode != MatchMode::Exact {
+        write!(out, " {}", mode.name())?;
+    }
+    out.write_all(b"\n")?;
+    for word in &words {
+        out.write_all(word)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(words.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/common-passwords");
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("passcheck-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn sorted_file_matches_in_memory_set() {
+        let words: Vec<String> = (0..5000).map(|i| format!("word{}", i * 3)).chain(["", "ünïcödé"].map(String::from)).collect();
+        let raw = temp_path("parity.txt");
+        let sorted = temp_path("parity.sorted");
+        std::fs::write(&raw, words.join("\r\n")).unwrap();
+        assert_eq!(prepare(&raw, &sorted).unwrap(), words.len() - 1);
+
+        let memory = Dictionary::new(words.iter().filter(|w| !w.is_empty()).cloned());
+        let file = Dictionary::open(&sorted).unwrap();
+        for probe in (0..16000).map(|i| format!("word{}", i)).chain(["", "ünïcödé", "word", "word1\nword3"].map(String::from)) {
+            assert_eq!(file.contains(&probe), memory.contains(&probe), "{:?}", probe);
+        }
+        assert_eq!(file.len(), memory.len());
+        std::fs::remove_file(raw).unwrap();
+        std::fs::remove_file(sorted).unwrap();
+    }
+
+    #[test]
+    fn language_packs() {
+        let builtin = Dictionary::builtin();
+        let expect = |words: &[&str], enabled: bool| {
+            for word in words {
+                assert_eq!(builtin.contains(word), enabled, "{}", word);
+            }
+        };
+        expect(&["password", "trustno1", "letmein"], cfg!(feature = "dict-en"));
+        expect(&["passwort", "fussball1", "schalke04"], cfg!(feature = "dict-de"));
+        expect(&["contraseña", "teamo", "realmadrid"], cfg!(feature = "dict-es"));
+        assert_eq!(enabled_packs().count(), PACKS.len());
+
+        let combined = Dictionary::builtin_with(["tenant-secret"]);
+        assert!(combined.contains("tenant-secret"));
+        assert_eq!(combined.contains("fussball1"), cfg!(feature = "dict-de"));
+    }
+
+    #[test]
+    fn match_modes() {
+        let words = ["password", "straße", "istanbul"];
+        let exact = Dictionary::new(words);
+        let ascii = Dictionary::with_mode(words, MatchMode::AsciiCaseInsensitive);
+        let fold = Dictionary::with_mode(words, MatchMode::UnicodeCaseFold);
+        let cases = [
+            // (password, exact, ascii, fold)
+            ("password", true, true, true),
+            ("PassWord", false, true, true),
+            ("PASSWORD", false, true, true),
+            ("STRASSE", false, false, true),
+            ("Straße", false, true, true),
+            ("STRAẞE", false, false, true),
+            ("ISTANBUL", false, true, true),
+            ("İstanbul", false, false, false),
+            ("ıstanbul", false, false, false),
+        ];
+        for (password, in_exact, in_ascii, in_fold) in cases {
+            assert_eq!(exact.contains(password), in_exact, "exact {}", password);
+            assert_eq!(ascii.contains(password), in_ascii, "ascii {}", password);
+            assert_eq!(fold.contains(password), in_fold, "fold {}", password);
+        }
+        assert!(Dictionary::with_mode(["İstanbul"], MatchMode::UnicodeCaseFold).contains("i\u{307}STANBUL"));
+    }
+
+    #[test]
+    fn fuzzy_matches_within_the_distance() {
+        let words = Dictionary::with_mode(["password", "dragon", "letmein"], MatchMode::AsciiCaseInsensitive).fuzzy(1);
+        for password in ["password", "passw0rd", "pasword", "passwords", "Dragon1", "Passw0rd!", "letme1n"] {
+            assert!(words.matches(password), "{}", password);
+        }
+        for password in ["pazzword", "pass", "dragonfly", "letmein123", ""] {
+            assert!(!words.matches(password), "{}", password);
+        }
+        assert!(!words.contains("passw0rd"));
+        assert!(words.matches("p4ssw0rd"), "leetspeak is undone before comparing");
+        assert!(words.clone().fuzzy(2).matches("pazzword"));
+        assert!(!words.clone().fuzzy(0).matches("passw0rd"));
+        assert_eq!(words.fuzzy_distance(), 1);
+        assert_eq!(format!("{:?}", words), "Dictionary(entries: 3, fuzzy: 1)");
+    }
+
+    #[test]
+    fn sorted_file_records_match_mode() {
+        let raw = temp_path("mode.txt");
+        let sorted = temp_path("mode.sorted");
+        std::fs::write(&raw, "Straße\nPASSWORD\npassword\n").unwrap();
+        assert_eq!(prepare_with_mode(&raw, &sorted, MatchMode::UnicodeCaseFold).unwrap(), 2);
+        let words = Dictionary::open(&sorted).unwrap();
+        assert_eq!(words.mode(), MatchMode::UnicodeCaseFold);
+        assert!(words.contains("STRASSE") && words.contains("Password"));
+        std::fs::remove_file(raw).unwrap();
+        std::fs::remove_file(sorted).unwrap();
+    }
+
+    #[test]
+    fn looks_up_fixture_file() {
+        let sorted = temp_path("fixture.sorted");
+        prepare(format!("{}.txt", FIXTURE), &sorted).unwrap();
+        assert_eq!(std::fs::read(&sorted).unwrap(), std::fs::read(format!("{}.sorted", FIXTURE)).unwrap());
+        std::fs::remove_file(sorted).unwrap();
+
+        let words = Dictionary::open(format!("{}.sorted", FIXTURE)).unwrap();
+        for word in ["123456", "password", "qwerty", "letmein", "zxcvbnm"] {
+            assert!(words.contains(word), "{}", word);
+        }
+        for word in ["Password", "passwor", "password1x", "", "zzzzzz"] {
+            assert!(!words.contains(word), "{}", word);
+        }
+        assert_eq!(words.iter().count(), 0);
+        assert!(Dictionary::open(format!("{}.txt", FIXTURE)).is_err());
+    }
+}
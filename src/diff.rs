@@ -0,0 +1,314 @@
+//! Differences between two policies, for audit logs and for users.
+
+use std::fmt;
+
+use crate::config::rule_to_value;
+use crate::json::{Value, write_string};
+use crate::{PasswordChecker, Rule, Similarity};
+
+/// A rule as it appears in a [`PolicyDiff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMeta {
+    /// The rule's [`code`](crate::Rule::code), e.g. `"min_length"`.
+    pub rule: &'static str,
+    /// The rule's parameters and custom message as a JSON object, in the
+    /// policy file format without the `"rule"` key, e.g. `{"length":12}`.
+    pub params: String,
+}
+
+/// One entry of a [`PolicyDiff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleChange {
+    Added(RuleMeta),
+    Removed(RuleMeta),
+    /// A rule of the same kind is present in both policies with different
+    /// parameters.
+    Changed { rule: &'static str, old_params: String, new_params: String },
+}
+
+impl fmt::Display for RuleChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleChange::Added(meta) => write!(f, "added {} {}", meta.rule, meta.params),
+            RuleChange::Removed(meta) => write!(f, "removed {} {}", meta.rule, meta.params),
+            RuleChange::Changed { rule, old_params, new_params } => write!(f, "changed {} {} → {}", rule, old_params, new_params),
+        }
+    }
+}
+
+/// What changed from one policy to another, as returned by [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PolicyDiff {
+    /// Removed and changed rules in the old policy's order, then added
+    /// rules in the new policy's order.
+    pub changes: Vec<RuleChange>,
+}
+
+impl PolicyDiff {
+    /// Whether the policies have the same rules.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Serializes the diff as JSON, e.g.
+    /// `{"changes":[{"change":"changed","rule":"min_length","old_params":{"length":8},"new_params":{"length":12}}]}`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"changes\":[");
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let (kind, rule, params) = match change {
+                RuleChange::Added(meta) => ("added", meta.rule, vec![("params", &meta.params)]),
+                RuleChange::Removed(meta) => ("removed", meta.rule, vec![("params", &meta.params)]),
+                RuleChange::Changed { rule, old_params, new_params } => {
+                    ("changed", *rule, vec![("old_params", old_params), ("new_params", new_params)])
+                }
+            };
+            out.push_str("{\"change\":");
+            write_string(&mut out, kind);
+            out.push_str(",\"rule\":");
+            write_string(&mut out, rule);
+            for (key, value) in params {
+                out.push(',');
+                write_string(&mut out, key);
+                out.push(':');
+                // Already JSON.
+                out.push_str(value);
+            }
+            out.push('}');
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
+impl fmt::Display for PolicyDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two policies. Rules are matched by kind regardless of their
+/// order, so reordering rules is not a change; a kind listed more than once
+/// is paired in order after setting aside identical rules.
+pub fn diff(old: &PasswordChecker<'_>, new: &PasswordChecker<'_>) -> PolicyDiff {
+    let (old, new) = (metas(old), metas(new));
+    let changes = pair(&old, &new)
+        .into_iter()
+        .map(|pair| match pair {
+            (Some(i), Some(j)) => RuleChange::Changed { rule: old[i].rule, old_params: old[i].params.clone(), new_params: new[j].params.clone() },
+            (Some(i), None) => RuleChange::Removed(old[i].clone()),
+            (None, Some(j)) => RuleChange::Added(new[j].clone()),
+            (None, None) => unreachable!("every pair has a rule"),
+        })
+        .collect();
+    PolicyDiff { changes }
+}
+
+/// Sentences for each requirement of `new` that is stricter than in `old`,
+/// to tell users why a password that used to be accepted is not anymore,
+/// e.g. `Minimum length increased from 8 to 12 characters` or
+/// `New requirement: at least one special character`.
+///
+/// Rules are paired as by [`diff`]. Added rules are listed with their
+/// [`describe`](PasswordChecker::describe) text, and thresholds that rose,
+/// such as a minimum length, or fell, such as a maximum length, with both
+/// values. Other changed rules are listed with their new text when it
+/// differs from the old one. Removed and loosened rules, and changes to
+/// messages alone, produce nothing.
+///
+/// ```
+/// use passcheck::{PasswordChecker, explain_new_requirements};
+///
+/// let old = PasswordChecker::new().min_length(8, None).require_number(None);
+/// let new = PasswordChecker::new().min_length(12, None).require_special_char(None);
+/// assert_eq!(
+///     explain_new_requirements(&old, &new),
+///     ["Minimum length increased from 8 to 12 characters", "New requirement: at least one special character"]
+/// );
+/// ```
+pub fn explain_new_requirements(old: &PasswordChecker<'_>, new: &PasswordChecker<'_>) -> Vec<String> {
+    pair(&metas(old), &metas(new))
+        .into_iter()
+        .filter_map(|pair| match pair {
+            (Some(i), Some(j)) => tightened(&old.rules[i], &new.rules[j]),
+            (None, Some(j)) => Some(format!("New requirement: {}", requirement(&new.rules[j]))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The sentence describing how `new` is stricter than `old`, a rule of the
+/// same kind, if it is.
+fn tightened(old: &Rule<'_>, new: &Rule<'_>) -> Option<String> {
+    match (old, new) {
+        (Rule::MinLength(old, _), Rule::MinLength(new, _)) => {
+            (new > old).then(|| format!("Minimum length increased from {} to {} characters", old, new))
+        }
+        (Rule::MaxLength(old, _), Rule::MaxLength(new, _)) => {
+            (new < old).then(|| format!("Maximum length decreased from {} to {} characters", old, new))
+        }
+        (Rule::MaxBytes(old, _), Rule::MaxBytes(new, _)) => (new < old).then(|| format!("Maximum length decreased from {} to {} bytes", old, new)),
+        (Rule::MinEntropy(old, ..), Rule::MinEntropy(new, ..)) => {
+            (new > old).then(|| format!("Minimum estimated strength increased from {} to {} bits", old, new))
+        }
+        (Rule::KeyboardWalk(old, _), Rule::KeyboardWalk(new, _)) if old.min_length != new.min_length => (new.min_length < old.min_length)
+            .then(|| format!("Runs of adjacent keyboard keys are now rejected from {} keys instead of {}", new.min_length, old.min_length)),
+        (Rule::MaxClassRun(old, _), Rule::MaxClassRun(new, _)) => (new < old).then(|| {
+            format!("Maximum number of letters, digits or special characters in a row decreased from {} to {}", old, new)
+        }),
+        (Rule::MinWords(old, ..), Rule::MinWords(new, ..)) => (new > old).then(|| format!("Minimum number of words increased from {} to {}", old, new)),
+        (Rule::NotSimilarToPrevious(Similarity::MaxRatio(old), _), Rule::NotSimilarToPrevious(Similarity::MaxRatio(new), _)) => (new < old)
+            .then(|| format!("Maximum similarity to the previous password decreased from {}% to {}%", old * 100.0, new * 100.0)),
+        (Rule::NotSimilarToPrevious(Similarity::MinEdits(old), _), Rule::NotSimilarToPrevious(Similarity::MinEdits(new), _)) => (new > old)
+            .then(|| format!("Minimum number of changes from the previous password increased from {} to {}", old, new)),
+        _ => {
+            (new.description() != old.description()).then(|| format!("Changed requirement: {}", requirement(new)))
+        }
+    }
+}
+
+/// The description of `rule`, to follow a colon.
+fn requirement(rule: &Rule<'_>) -> String {
+    let description = rule.description();
+    let mut chars = description.chars();
+    chars.next().map(|first| first.to_lowercase().chain(chars).collect()).unwrap_or_default()
+}
+
+fn metas(checker: &PasswordChecker<'_>) -> Vec<RuleMeta> {
+    checker.rules.iter().map(RuleMeta::of).collect()
+}
+
+/// Indices of the rules that differ between `old` and `new`, paired as
+/// documented on [`diff`] and in its order: `(Some, Some)` for changed
+/// rules, `(Some, None)` for removed ones and `(None, Some)` for added ones.
+fn pair(old: &[RuleMeta], new: &[RuleMeta]) -> Vec<(Option<usize>, Option<usize>)> {
+    let mut old_used = vec![false; old.len()];
+    let mut new_used = vec![false; new.len()];
+    for (i, meta) in old.iter().enumerate() {
+        if let Some(j) = (0..new.len()).find(|&j| !new_used[j] && new[j] == *meta) {
+            old_used[i] = true;
+            new_used[j] = true;
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for i in (0..old.len()).filter(|&i| !old_used[i]) {
+        let j = (0..new.len()).find(|&j| !new_used[j] && new[j].rule == old[i].rule);
+        if let Some(j) = j {
+            new_used[j] = true;
+        }
+        pairs.push((Some(i), j));
+    }
+    pairs.extend((0..new.len()).filter(|&j| !new_used[j]).map(|j| (None, Some(j))));
+    pairs
+}
+
+impl RuleMeta {
+    pub(crate) fn of(rule: &Rule<'_>) -> Self {
+        let params = match rule_to_value(rule) {
+            Value::Object(fields) => Value::Object(fields.into_iter().filter(|(key, _)| key != "rule").collect()),
+            other => other,
+        };
+        RuleMeta { rule: rule.code(), params: params.to_string() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(rule: &'static str, params: &str) -> RuleMeta {
+        RuleMeta { rule, params: params.to_string() }
+    }
+
+    #[test]
+    fn unchanged_policies_have_an_empty_diff() {
+        let policy = PasswordChecker::new().min_length(8, None).require_number(None).blacklist(["qwerty"], None);
+        assert!(diff(&policy, &policy).is_empty());
+        let reordered = PasswordChecker::new().blacklist(["qwerty"], None).require_number(None).min_length(8, None);
+        assert!(diff(&policy, &reordered).is_empty());
+        assert_eq!(diff(&policy, &reordered).to_json(), r#"{"changes":[]}"#);
+    }
+
+    #[test]
+    fn detects_each_kind_of_change() {
+        let old = PasswordChecker::new().min_length(8, None).require_number(None).must_match(crate::Pattern::new("^\\S+$").unwrap(), None);
+        let new = PasswordChecker::new()
+            .require_special_char(None)
+            .must_match(crate::Pattern::new("^\\S+$").unwrap(), Some("No spaces"))
+            .min_length(12, None);
+        let diff = diff(&old, &new);
+        assert_eq!(
+            diff.changes,
+            [
+                RuleChange::Changed { rule: "min_length", old_params: r#"{"length":8}"#.into(), new_params: r#"{"length":12}"#.into() },
+                RuleChange::Removed(meta("require_number", "{}")),
+                RuleChange::Changed {
+                    rule: "must_match",
+                    old_params: r#"{"pattern":"^\\S+$"}"#.into(),
+                    new_params: r#"{"pattern":"^\\S+$","message":"No spaces"}"#.into(),
+                },
+                RuleChange::Added(meta("require_special_char", "{}")),
+            ]
+        );
+        assert_eq!(diff.changes[0].to_string(), r#"changed min_length {"length":8} → {"length":12}"#);
+        assert!(diff.to_json().starts_with(
+            r#"{"changes":[{"change":"changed","rule":"min_length","old_params":{"length":8},"new_params":{"length":12}},{"change":"removed","rule":"require_number","params":{}}"#
+        ));
+    }
+
+    #[test]
+    fn repeated_kinds_pair_after_identical_rules() {
+        let old = PasswordChecker::new().blacklist(["a"], None).blacklist(["b"], None);
+        let new = PasswordChecker::new().blacklist(["c"], None).blacklist(["a"], None);
+        assert_eq!(
+            diff(&old, &new).changes,
+            [RuleChange::Changed { rule: "blacklist", old_params: r#"{"words":["b"]}"#.into(), new_params: r#"{"words":["c"]}"#.into() }]
+        );
+    }
+
+    #[test]
+    fn explains_raised_thresholds() {
+        let old = PasswordChecker::new().min_length(8, None).max_length(64, None).min_words(2, None).not_similar_to_previous(Similarity::MaxRatio(0.8), None);
+        let new = PasswordChecker::new().min_length(12, None).max_length(32, None).min_words(3, None).not_similar_to_previous(Similarity::MaxRatio(0.5), None);
+        assert_eq!(
+            explain_new_requirements(&old, &new),
+            [
+                "Minimum length increased from 8 to 12 characters",
+                "Maximum length decreased from 64 to 32 characters",
+                "Minimum number of words increased from 2 to 3",
+                "Maximum similarity to the previous password decreased from 80% to 50%",
+            ]
+        );
+        // The same changes the other way round loosen the policy.
+        assert!(explain_new_requirements(&new, &old).is_empty());
+    }
+
+    #[test]
+    fn explains_added_and_changed_rules() {
+        let old = PasswordChecker::new().min_length(8, None).require_special_char_with(crate::SpecialCharPolicy::Chars("!@#".into()), None);
+        let new = PasswordChecker::new()
+            .min_length(8, Some("Make it longer"))
+            .require_special_char(None)
+            .require_upper_lower(None)
+            .blacklist(["qwerty"], None);
+        assert_eq!(
+            explain_new_requirements(&old, &new),
+            ["Changed requirement: at least one special character", "New requirement: both uppercase and lowercase letters", "New requirement: not a commonly used password"]
+        );
+    }
+
+    #[test]
+    fn removed_and_unchanged_rules_are_silent() {
+        let old = PasswordChecker::new().min_length(8, None).require_number(None).blacklist(["a"], None);
+        let new = PasswordChecker::new().blacklist(["b"], None).min_length(8, None);
+        assert!(explain_new_requirements(&old, &new).is_empty());
+        assert!(explain_new_requirements(&old, &old).is_empty());
+        assert!(explain_new_requirements(&PasswordChecker::new(), &PasswordChecker::new()).is_empty());
+    }
+}
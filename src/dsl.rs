@@ -0,0 +1,99 @@
+//! Compact policy strings such as `"min:12 upper_lower digit special"`.
+//!
+//! Tokens are separated by whitespace:
+//!
+//! | Token                    | Rule                                   |
+//! |--------------------------|----------------------------------------|
+//! | `min:N`                  | `min_length(N)`                        |
+//! | `max:N`                  | `max_length(N)`                        |
+//! | `upper_lower`, `case`    | `require_upper_lower`                  |
+//! | `digit`, `number`        | `require_number`                       |
+//! | `special`                | `require_special_char`                 |
+//! | `keyboard`, `keyboard:N` | `keyboard_walk`, N keys (default 4)    |
+//! | `none`                   | no rule; the policy is empty on purpose |
+//!
+//! A string without rules fails with [`ConfigError::EmptyPolicy`] unless it
+//! is `none`. Policy strings cannot carry custom messages; use JSON for
+//! that.
+
+use crate::{ConfigError, KeyboardWalk, PasswordChecker, Rule, SpecialCharPolicy};
+
+/// Parses a policy string into a checker using default messages.
+pub fn parse_policy(spec: &str) -> Result<PasswordChecker<'static>, ConfigError> {
+    let mut checker = PasswordChecker::new();
+    if spec.trim() == "none" {
+        return Ok(checker);
+    }
+    for token in spec.split_whitespace() {
+        let rule = match token.split_once(':') {
+            Some(("min", value)) => Rule::MinLength(number(token, value)?, None),
+            Some(("max", value)) => Rule::MaxLength(number(token, value)?, None),
+            Some(("keyboard", value)) => Rule::KeyboardWalk(KeyboardWalk::new().min_length(number(token, value)?), None),
+            Some(_) => return Err(invalid(token, "unknown rule")),
+            None => match token {
+                "upper_lower" | "case" => Rule::RequireUpperLower(None),
+                "digit" | "number" => Rule::RequireNumber(None),
+                "special" => Rule::RequireSpecialChar(SpecialCharPolicy::Builtin, None),
+                "keyboard" => Rule::KeyboardWalk(KeyboardWalk::new(), None),
+                "min" | "max" => return Err(invalid(token, "expected a value, e.g. `min:12`")),
+                "none" => return Err(invalid(token, "cannot be combined with rules")),
+                _ => return Err(invalid(token, "unknown rule")),
+            },
+        };
+        checker.rules.push(rule);
+    }
+    if checker.is_empty() {
+        return Err(ConfigError::EmptyPolicy);
+    }
+    Ok(checker)
+}
+
+fn invalid(token: &str, reason: &'static str) -> ConfigError {
+    ConfigError::InvalidToken { token: token.to_string(), reason }
+}
+
+fn number(token: &str, value: &str) -> Result<usize, ConfigError> {
+    value.parse().map_err(|_| invalid(token, "expected a non-negative integer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_all_tokens() {
+        let checker = parse_policy("min:12 max:64  case digit\tspecial keyboard keyboard:5").unwrap();
+        let expected = PasswordChecker::new()
+            .min_length(12, None)
+            .max_length(64, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None)
+            .keyboard_walk(KeyboardWalk::new(), None)
+            .keyboard_walk(KeyboardWalk::new().min_length(5), None);
+        assert_eq!(checker, expected);
+    }
+
+    #[test]
+    fn special_rules_share_the_builtin_set() {
+        let checker = parse_policy("special min:8 special").unwrap();
+        assert_eq!(checker.char_classes().special, SpecialCharPolicy::Builtin);
+        assert_eq!(PasswordChecker::from_json(&checker.to_config().to_json()).unwrap(), checker);
+    }
+
+    #[test]
+    fn empty_policies_must_say_so() {
+        assert_eq!(parse_policy(""), Err(ConfigError::EmptyPolicy));
+        assert_eq!(parse_policy(" \t"), Err(ConfigError::EmptyPolicy));
+        assert_eq!(parse_policy(" none ").unwrap(), PasswordChecker::new());
+        assert_eq!(parse_policy("min:8 none"), Err(invalid("none", "cannot be combined with rules")));
+    }
+
+    #[test]
+    fn rejects_bad_tokens() {
+        assert_eq!(parse_policy("min:12 digits"), Err(invalid("digits", "unknown rule")));
+        assert_eq!(parse_policy("min:-1"), Err(invalid("min:-1", "expected a non-negative integer")));
+        assert_eq!(parse_policy("min"), Err(invalid("min", "expected a value, e.g. `min:12`")));
+        assert_eq!(parse_policy("len:8"), Err(invalid("len:8", "unknown rule")));
+    }
+}
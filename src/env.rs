@@ -0,0 +1,216 @@
+//! Policies configured through environment variables.
+
+use crate::{ConfigError, Dictionary, Estimator, PasswordChecker, Rule, SpecialCharPolicy, parse_policy};
+
+const VARS: [&str; 10] = [
+    "RULES",
+    "MIN_LENGTH",
+    "MAX_LENGTH",
+    "MAX_BYTES",
+    "REQUIRE_UPPER_LOWER",
+    "REQUIRE_NUMBER",
+    "REQUIRE_SPECIAL_CHAR",
+    "SPECIAL_CHARS",
+    "MIN_ENTROPY",
+    "BLACKLIST_FILE",
+];
+
+impl PasswordChecker<'static> {
+    /// Builds a checker from the `{prefix}_*` environment variables. With
+    /// the prefix `PASSCHECK`, these variables are read, in this order:
+    ///
+    /// | Variable                         | Rule                                       |
+    /// |----------------------------------|--------------------------------------------|
+    /// | `PASSCHECK_RULES`                | a [`parse_policy`] string, added first     |
+    /// | `PASSCHECK_MIN_LENGTH`           | `min_length`                               |
+    /// | `PASSCHECK_MAX_LENGTH`           | `max_length`                               |
+    /// | `PASSCHECK_MAX_BYTES`            | `max_bytes`                                |
+    /// | `PASSCHECK_REQUIRE_UPPER_LOWER`  | `require_upper_lower` if `true`            |
+    /// | `PASSCHECK_REQUIRE_NUMBER`       | `require_number` if `true`                 |
+    /// | `PASSCHECK_REQUIRE_SPECIAL_CHAR` | `require_special_char` if `true`           |
+    /// | `PASSCHECK_SPECIAL_CHARS`        | at least one of these characters           |
+    /// | `PASSCHECK_MIN_ENTROPY`          | `min_entropy` in bits, default estimator   |
+    /// | `PASSCHECK_BLACKLIST_FILE`       | `blacklist` from a prepared file           |
+    ///
+    /// Booleans are `true`/`false` or `1`/`0`. Unset variables configure
+    /// nothing, but a policy with no variable set at all is an error rather
+    /// than one accepting every password, and so is one whose variables add
+    /// no rule, such as only `PASSCHECK_REQUIRE_NUMBER=false`, with
+    /// [`ConfigError::EmptyPolicy`]; set `PASSCHECK_RULES=none` to allow it. Unknown variables with the prefix
+    /// are rejected so that a typo never silently drops a rule.
+    ///
+    /// `PASSCHECK_SPECIAL_CHARS` replaces the built-in special characters,
+    /// as [`SpecialCharPolicy::Chars`].
+    pub fn from_env(prefix: &str) -> Result<Self, ConfigError> {
+        let vars = std::env::vars_os().map(|(name, value)| (name.to_string_lossy().into_owned(), value.into_string()));
+        from_vars(prefix, vars)
+    }
+}
+
+/// [`PasswordChecker::from_env`] over `vars`; values that are not valid
+/// Unicode are `Err` with the raw value.
+fn from_vars(
+    prefix: &str,
+    vars: impl IntoIterator<Item = (String, Result<String, std::ffi::OsString>)>,
+) -> Result<PasswordChecker<'static>, ConfigError> {
+    let mut values: [Option<(String, String)>; VARS.len()] = Default::default();
+    let var_prefix = format!("{}_", prefix);
+    for (name, value) in vars {
+        let Some(suffix) = name.strip_prefix(&var_prefix) else { continue };
+        let Some(i) = VARS.iter().position(|v| *v == suffix) else {
+            return Err(invalid(&name, &value.unwrap_or_default(), "unknown variable".to_string()));
+        };
+        let value = value.map_err(|raw| invalid(&name, &raw.to_string_lossy(), "not valid Unicode".to_string()))?;
+        values[i] = Some((name, value));
+    }
+    if values.iter().all(Option::is_none) {
+        return Err(ConfigError::NoVariables { prefix: prefix.to_string() });
+    }
+    let get = |var: &str| values[VARS.iter().position(|v| *v == var).unwrap()].as_ref().map(|(name, value)| (name.as_str(), value.as_str()));
+
+    let mut checker = match get("RULES") {
+        // Rules may come from the other variables alone.
+        Some((_, spec)) if spec.trim().is_empty() => PasswordChecker::new(),
+        Some((name, spec)) => parse_policy(spec).map_err(|err| invalid(name, spec, err.to_string()))?,
+        None => PasswordChecker::new(),
+    };
+    let mut push = |rule| checker.rules.push(rule);
+    if let Some((name, value)) = get("MIN_LENGTH") {
+        push(Rule::MinLength(number(name, value)?, None));
+    }
+    if let Some((name, value)) = get("MAX_LENGTH") {
+        push(Rule::MaxLength(number(name, value)?, None));
+    }
+    if let Some((name, value)) = get("MAX_BYTES") {
+        push(Rule::MaxBytes(number(name, value)?, None));
+    }
+    if let Some((name, value)) = get("REQUIRE_UPPER_LOWER")
+        && boolean(name, value)?
+    {
+        push(Rule::RequireUpperLower(None));
+    }
+    if let Some((name, value)) = get("REQUIRE_NUMBER")
+        && boolean(name, value)?
+    {
+        push(Rule::RequireNumber(None));
+    }
+    let require_special = get("REQUIRE_SPECIAL_CHAR").map(|(name, value)| boolean(name, value)).transpose()?;
+    match (require_special, get("SPECIAL_CHARS")) {
+        (Some(false), Some((name, chars))) => {
+            return Err(invalid(name, chars, "conflicts with a false REQUIRE_SPECIAL_CHAR".to_string()));
+        }
+        (_, Some((name, ""))) => return Err(invalid(name, "", "expected at least one character".to_string())),
+        (_, Some((_, chars))) => push(Rule::RequireSpecialChar(SpecialCharPolicy::Chars(chars.to_string()), None)),
+        (Some(true), None) => push(Rule::RequireSpecialChar(SpecialCharPolicy::Builtin, None)),
+        (Some(false) | None, None) => {}
+    }
+    if let Some((name, value)) = get("MIN_ENTROPY") {
+        let bits = value.parse::<f64>().ok().filter(|bits| bits.is_finite() && *bits >= 0.0);
+        let bits = bits.ok_or_else(|| invalid(name, value, "expected a non-negative number of bits".to_string()))?;
+        push(Rule::MinEntropy(bits, Estimator::default(), None));
+    }
+    if let Some((name, path)) = get("BLACKLIST_FILE") {
+        push(Rule::Blacklist(Dictionary::open(path).map_err(|err| invalid(name, path, err.to_string()))?, None));
+    }
+    // A checker has one special set, so `special` in RULES means the
+    // builtin one.
+    if let Some((name, chars)) = get("SPECIAL_CHARS")
+        && crate::classes::special_conflict(&checker.rules).is_some()
+    {
+        return Err(invalid(name, chars, "differs from the builtin special set of the `special` rule in RULES".to_string()));
+    }
+    if checker.is_empty() && get("RULES").is_none_or(|(_, spec)| spec.trim() != "none") {
+        return Err(ConfigError::EmptyPolicy);
+    }
+    Ok(checker)
+}
+
+fn invalid(name: &str, value: &str, reason: String) -> ConfigError {
+    ConfigError::InvalidVariable { name: name.to_string(), value: value.to_string(), reason }
+}
+
+fn number(name: &str, value: &str) -> Result<usize, ConfigError> {
+    value.trim().parse().map_err(|_| invalid(name, value, "expected a non-negative integer".to_string()))
+}
+
+fn boolean(name: &str, value: &str) -> Result<bool, ConfigError> {
+    match value.trim() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(invalid(name, value, "expected `true` or `false`".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(vars: &[(&str, &str)]) -> Result<PasswordChecker<'static>, ConfigError> {
+        from_vars("PASSCHECK", vars.iter().map(|(name, value)| (name.to_string(), Ok(value.to_string()))))
+    }
+
+    #[test]
+    fn reads_every_variable() {
+        let checker = load(&[
+            ("HOME", "/root"),
+            ("PASSCHECK_MIN_LENGTH", "12"),
+            ("PASSCHECK_REQUIRE_NUMBER", "true"),
+            ("PASSCHECK_REQUIRE_UPPER_LOWER", "0"),
+            ("PASSCHECK_SPECIAL_CHARS", "!@#$"),
+            ("PASSCHECK_RULES", "max:64 keyboard"),
+        ])
+        .unwrap();
+        let codes: Vec<_> = checker.rules().iter().map(Rule::code).collect();
+        assert_eq!(codes, ["max_length", "keyboard_walk", "min_length", "require_number", "require_special_char"]);
+        assert!(checker.check("Longer than 12 chars 1 #").is_ok());
+        assert_eq!(
+            checker.validate("Longer than 12 chars 1 %").unwrap_err(),
+            ["Password must include at least one of these characters: !@#$"]
+        );
+    }
+
+    #[test]
+    fn partial_configurations() {
+        assert_eq!(load(&[("PASSCHECK_REQUIRE_SPECIAL_CHAR", "1")]), Ok(PasswordChecker::new().require_special_char(None)));
+        assert_eq!(load(&[("PASSCHECK_MIN_ENTROPY", "40")]), Ok(PasswordChecker::new().min_entropy(40.0, Estimator::default(), None)));
+        assert_eq!(load(&[("PASSCHECK_RULES", ""), ("PASSCHECK_MIN_LENGTH", "8")]), Ok(PasswordChecker::new().min_length(8, None)));
+    }
+
+    #[test]
+    fn special_chars_cannot_differ_from_a_special_rule() {
+        let err = load(&[("PASSCHECK_RULES", "special min:8"), ("PASSCHECK_SPECIAL_CHARS", "#")]).unwrap_err();
+        assert_eq!(err, invalid("PASSCHECK_SPECIAL_CHARS", "#", "differs from the builtin special set of the `special` rule in RULES".to_string()));
+        let checker = load(&[("PASSCHECK_RULES", "special min:8"), ("PASSCHECK_REQUIRE_SPECIAL_CHAR", "true")]).unwrap();
+        assert_eq!(checker.char_classes().special, SpecialCharPolicy::Builtin);
+        assert_eq!(PasswordChecker::from_json(&checker.to_config().to_json()).unwrap(), checker);
+    }
+
+    #[test]
+    fn variables_adding_no_rule_are_an_error() {
+        assert_eq!(load(&[("PASSCHECK_REQUIRE_NUMBER", "false")]), Err(ConfigError::EmptyPolicy));
+        assert_eq!(load(&[("PASSCHECK_RULES", " ")]), Err(ConfigError::EmptyPolicy));
+        assert_eq!(load(&[("PASSCHECK_RULES", "none"), ("PASSCHECK_REQUIRE_NUMBER", "false")]), Ok(PasswordChecker::new()));
+    }
+
+    #[test]
+    fn errors_name_the_variable_and_value() {
+        let err = |vars: &[(&str, &str)]| load(vars).unwrap_err().to_string();
+        assert_eq!(
+            err(&[("PASSCHECK_MIN_LENGTH", "twelve")]),
+            "invalid environment variable PASSCHECK_MIN_LENGTH=\"twelve\": expected a non-negative integer"
+        );
+        assert_eq!(
+            err(&[("PASSCHECK_REQUIRE_NUMBER", "yes please")]),
+            "invalid environment variable PASSCHECK_REQUIRE_NUMBER=\"yes please\": expected `true` or `false`"
+        );
+        assert_eq!(
+            err(&[("PASSCHECK_MIN_LENGHT", "12")]),
+            "invalid environment variable PASSCHECK_MIN_LENGHT=\"12\": unknown variable"
+        );
+        assert_eq!(
+            err(&[("PASSCHECK_RULES", "min:12 digits")]),
+            "invalid environment variable PASSCHECK_RULES=\"min:12 digits\": invalid policy token `digits`: unknown rule"
+        );
+        assert_eq!(err(&[("HOME", "/root")]), "no PASSCHECK_* environment variables are set");
+    }
+}
@@ -0,0 +1,177 @@
+//! The crate-wide error type.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+#[cfg(feature = "generate")]
+use crate::GenerateError;
+#[cfg(feature = "test-util")]
+use crate::testing::TestingError;
+use crate::{ConfigError, PasswordChecker, PatternError, RegistryError, Violations};
+
+/// Any error returned by this crate, for callers that want a single type.
+///
+/// Each variant wraps the specific error, available through
+/// [`source`](std::error::Error::source); the message itself only names the
+/// operation that failed. No message ever includes the password.
+#[derive(Debug)]
+pub enum Error {
+    /// A policy could not be loaded.
+    Config(ConfigError),
+    /// A pattern did not compile.
+    Pattern(PatternError),
+    /// The password breaks the policy.
+    Invalid(Violations),
+    /// Reading a policy or dictionary failed.
+    Io(io::Error),
+    /// A registry lookup or validation failed.
+    Registry(RegistryError),
+    /// No password could be generated.
+    #[cfg(feature = "generate")]
+    Generate(GenerateError),
+    /// No example password could be found.
+    #[cfg(feature = "test-util")]
+    Testing(TestingError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Error::Config(_) => "invalid policy configuration",
+            Error::Pattern(_) => "invalid pattern",
+            Error::Invalid(_) => "password does not meet the policy",
+            Error::Io(_) => "I/O error",
+            Error::Registry(_) => "policy registry error",
+            #[cfg(feature = "generate")]
+            Error::Generate(_) => "password generation failed",
+            #[cfg(feature = "test-util")]
+            Error::Testing(_) => "no example password found",
+        })
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(match self {
+            Error::Config(err) => err,
+            Error::Pattern(err) => err,
+            Error::Invalid(err) => err,
+            Error::Io(err) => err,
+            Error::Registry(err) => err,
+            #[cfg(feature = "generate")]
+            Error::Generate(err) => err,
+            #[cfg(feature = "test-util")]
+            Error::Testing(err) => err,
+        })
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        Error::Config(err)
+    }
+}
+
+impl From<PatternError> for Error {
+    fn from(err: PatternError) -> Self {
+        Error::Pattern(err)
+    }
+}
+
+impl From<Violations> for Error {
+    fn from(err: Violations) -> Self {
+        Error::Invalid(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<RegistryError> for Error {
+    fn from(err: RegistryError) -> Self {
+        Error::Registry(err)
+    }
+}
+
+#[cfg(feature = "generate")]
+impl From<GenerateError> for Error {
+    fn from(err: GenerateError) -> Self {
+        Error::Generate(err)
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl From<TestingError> for Error {
+    fn from(err: TestingError) -> Self {
+        Error::Testing(err)
+    }
+}
+
+impl PasswordChecker<'static> {
+    /// Reads a policy file in the format of [`from_json`](Self::from_json).
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self::from_json(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use super::*;
+
+    /// Messages along the source chain, outermost first.
+    fn chain(err: &(dyn std::error::Error + 'static)) -> Vec<String> {
+        std::iter::successors(Some(err), |&err| err.source()).map(|err| err.to_string()).collect()
+    }
+
+    #[test]
+    fn sources_are_preserved() {
+        let err = PasswordChecker::from_json_file("tests/fixtures/missing-policy.json").unwrap_err();
+        assert!(matches!(&err, Error::Io(io) if io.kind() == io::ErrorKind::NotFound));
+        assert_eq!(chain(&err)[0], "I/O error");
+
+        let err = PasswordChecker::from_json(r#"{"rules": [{"rule": "must_match", "pattern": "(ab"}]}"#).map_err(Error::from).unwrap_err();
+        assert_eq!(
+            chain(&err),
+            [
+                "invalid policy configuration",
+                "invalid policy field `rules[0].pattern`: invalid pattern at character 3: unclosed group",
+                "invalid pattern at character 3: unclosed group",
+            ]
+        );
+
+        let checker = PasswordChecker::new().min_length(8, None);
+        let err = Error::from(checker.check("hunter2").unwrap_err());
+        assert_eq!(chain(&err), ["password does not meet the policy", "Password must be at least 8 characters long."]);
+        assert!(chain(&err).iter().all(|message| !message.contains("hunter2")));
+    }
+
+    #[test]
+    fn messages_are_stable() {
+        let errors = [
+            Error::Config(ConfigError::UnknownRule("x".into())),
+            Error::Pattern(PatternError { position: 0, reason: "empty" }),
+            Error::Invalid(Violations::default()),
+            Error::Io(io::Error::other("disk")),
+            Error::Registry(RegistryError::UnknownPolicy("admin".into())),
+        ];
+        let messages: Vec<_> = errors.iter().map(Error::to_string).collect();
+        assert_eq!(messages, ["invalid policy configuration", "invalid pattern", "password does not meet the policy", "I/O error", "policy registry error"]);
+        assert!(errors.iter().all(|err| err.source().is_some()));
+        #[cfg(feature = "generate")]
+        assert_eq!(Error::from(GenerateError::Random(io::Error::other("rng"))).to_string(), "password generation failed");
+        #[cfg(feature = "test-util")]
+        assert_eq!(Error::from(TestingError::Unsatisfiable).to_string(), "no example password found");
+    }
+
+    #[test]
+    fn loads_policy_files() {
+        let checker = PasswordChecker::from_json_file("tests/fixtures/policy-current.json").unwrap();
+        assert_eq!(checker.rules()[0].code(), "min_length");
+    }
+}
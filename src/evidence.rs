@@ -0,0 +1,277 @@
+//! Per-rule evaluation records for compliance evidence.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::rule_to_value;
+use crate::json::Value;
+use crate::{DEFAULT_MAX_INPUT_BYTES, PasswordChecker, PolicyFingerprint, Violations};
+
+/// Upper bounds of the length buckets in [`Evidence::length_bucket`].
+const LENGTH_BUCKETS: [(usize, &str); 5] = [(7, "0-7"), (11, "8-11"), (15, "12-15"), (23, "16-23"), (63, "24-63")];
+
+/// How a rule judged the password in [`RuleEvidence::outcome`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvidenceOutcome {
+    Passed,
+    Failed,
+    /// A [recommended length](crate::PasswordChecker::recommended_length)
+    /// the password is below; it was not rejected for it.
+    Warned,
+    /// Not evaluated: the rule compares the password with a
+    /// [`UserContext`](crate::UserContext), or the input was over the
+    /// [size guard](crate::PasswordChecker::max_input_bytes).
+    Skipped,
+}
+
+impl EvidenceOutcome {
+    /// The name used in [`Evidence::to_json`]: `passed`, `failed`,
+    /// `warned` or `skipped`.
+    pub fn name(self) -> &'static str {
+        match self {
+            EvidenceOutcome::Passed => "passed",
+            EvidenceOutcome::Failed => "failed",
+            EvidenceOutcome::Warned => "warned",
+            EvidenceOutcome::Skipped => "skipped",
+        }
+    }
+}
+
+/// The evaluation of one rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleEvidence {
+    pub code: &'static str,
+    /// The rule's fields as written to policy files, without `rule` and
+    /// `message`, as a JSON object. Word lists are given as their length,
+    /// e.g. `{"words":10000}`, so neither the evidence nor its size
+    /// depends on their contents.
+    pub params: String,
+    pub outcome: EvidenceOutcome,
+    /// Time spent evaluating the rule.
+    pub duration: Duration,
+}
+
+/// Record that a password was evaluated against every rule of a checker,
+/// from [`PasswordChecker::validate_with_evidence`].
+///
+/// Only coarse statistics of the password are kept: its length bucket
+/// and the character classes it uses. Rule parameters come from the
+/// policy, never from the password, so evidence can be stored alongside
+/// audit logs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evidence {
+    pub fingerprint: PolicyFingerprint,
+    /// When the evaluation started.
+    pub timestamp: SystemTime,
+    /// The password's length in characters, as a range: `0-7`, `8-11`,
+    /// `12-15`, `16-23`, `24-63` or `64+`.
+    pub length_bucket: &'static str,
+    /// The classes of characters the password contains, in this order:
+    /// `lowercase`, `uppercase`, `digit` (ASCII), `special` (as counted by
+    /// the checker's special-character rules) and `other`.
+    pub classes: Vec<&'static str>,
+    /// Whether the password was accepted.
+    pub valid: bool,
+    /// Time spent on the whole evaluation, at least the sum of the rules'.
+    pub duration: Duration,
+    /// One entry per rule, in rule order.
+    pub rules: Vec<RuleEvidence>,
+}
+
+impl Evidence {
+    /// Serializes the evidence, e.g.
+    /// `{"fingerprint":"3f…","timestamp_ms":1718000000000,"length_bucket":"12-15","classes":["lowercase","digit"],"valid":true,"duration_ns":2100,"rules":[{"code":"min_length","params":{"length":12},"outcome":"passed","duration_ns":350}]}`.
+    /// `fingerprint` is the hex [`PolicyFingerprint`] and `timestamp_ms`
+    /// counts milliseconds since the Unix epoch.
+    pub fn to_json(&self) -> String {
+        let number = |n: u128| Value::Number(n as f64);
+        let timestamp = self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                Value::Object(vec![
+                    ("code".to_string(), Value::String(rule.code.to_string())),
+                    ("params".to_string(), crate::json::parse(&rule.params).unwrap_or(Value::Null)),
+                    ("outcome".to_string(), Value::String(rule.outcome.name().to_string())),
+                    ("duration_ns".to_string(), number(rule.duration.as_nanos())),
+                ])
+            })
+            .collect();
+        Value::Object(vec![
+            ("fingerprint".to_string(), Value::String(self.fingerprint.to_string())),
+            ("timestamp_ms".to_string(), number(timestamp.as_millis())),
+            ("length_bucket".to_string(), Value::String(self.length_bucket.to_string())),
+            ("classes".to_string(), Value::Array(self.classes.iter().map(|c| Value::String(c.to_string())).collect())),
+            ("valid".to_string(), Value::Bool(self.valid)),
+            ("duration_ns".to_string(), number(self.duration.as_nanos())),
+            ("rules".to_string(), Value::Array(rules)),
+        ])
+        .to_string()
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// Like [`check`](Self::check), also recording [`Evidence`] that every
+    /// rule was evaluated: each rule's outcome and timing, the policy
+    /// [fingerprint](Self::fingerprint) and a timestamp.
+    ///
+    /// Every rule is evaluated even with [`fail_fast`](Self::fail_fast), and
+    /// the fingerprint is computed on each call, so this costs more than
+    /// `check`; use it where auditors need the record.
+    ///
+    /// ```
+    /// use passcheck::{EvidenceOutcome, PasswordChecker};
+    ///
+    /// let checker = PasswordChecker::new().min_length(12, None).require_number(None);
+    /// let (result, evidence) = checker.validate_with_evidence("correct horse battery");
+    /// assert!(result.is_err() && !evidence.valid);
+    /// assert_eq!(evidence.rules[0].outcome, EvidenceOutcome::Passed);
+    /// assert_eq!(evidence.rules[1].outcome, EvidenceOutcome::Failed);
+    /// assert_eq!(evidence.length_bucket, "16-23");
+    /// ```
+    pub fn validate_with_evidence(&self, password: &str) -> (Result<(), Violations>, Evidence) {
+        let timestamp = SystemTime::now();
+        let started = Instant::now();
+        let guarded = password.len() > self.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+        let fails = self.failure_test(password, None);
+        let mut failed = Vec::with_capacity(self.rules.len());
+        let mut rules = Vec::with_capacity(self.rules.len());
+        for rule in &self.rules {
+            let started = Instant::now();
+            let outcome = if guarded || rule.needs_context() {
+                EvidenceOutcome::Skipped
+            } else if fails(rule) {
+                EvidenceOutcome::Failed
+            } else if rule.warns(password) {
+                EvidenceOutcome::Warned
+            } else {
+                EvidenceOutcome::Passed
+            };
+            let duration = started.elapsed();
+            failed.push(outcome == EvidenceOutcome::Failed);
+            rules.push(RuleEvidence { code: rule.code(), params: params(rule_to_value(rule)), outcome, duration });
+        }
+        let result = self.check_using(password, |i, _| failed[i]);
+        let evidence = Evidence {
+            fingerprint: self.fingerprint(),
+            timestamp,
+            length_bucket: length_bucket(password.chars().count()),
+            classes: self.classes_present(password),
+            valid: result.is_ok(),
+            duration: started.elapsed(),
+            rules,
+        };
+        (result, evidence)
+    }
+
+    fn classes_present(&self, password: &str) -> Vec<&'static str> {
+        let classes = self.char_classes();
+        let tests: [(&str, &dyn Fn(char) -> bool); 4] = [
+            ("lowercase", &|c| c.is_ascii_lowercase()),
+            ("uppercase", &|c| c.is_ascii_uppercase()),
+            ("digit", &|c| c.is_ascii_digit()),
+            ("special", &|c| classes.is_special(c)),
+        ];
+        let mut present: Vec<&'static str> = tests.iter().filter(|(_, test)| password.chars().any(test)).map(|(name, _)| *name).collect();
+        if password.chars().any(|c| !c.is_ascii_alphanumeric() && !classes.is_special(c)) {
+            present.push("other");
+        }
+        present
+    }
+}
+
+fn length_bucket(chars: usize) -> &'static str {
+    LENGTH_BUCKETS.iter().find(|&&(max, _)| chars <= max).map_or("64+", |&(_, name)| name)
+}
+
+/// The fields of a serialized rule that describe the policy, with word
+/// lists replaced by their length.
+fn params(rule: Value) -> String {
+    let Value::Object(fields) = rule else { return rule.to_string() };
+    let fields = fields
+        .into_iter()
+        .filter(|(key, _)| key != "rule" && key != "message")
+        .map(|(key, value)| match value {
+            Value::Array(items) if key == "words" || key == "names" => (key, Value::Number(items.len() as f64)),
+            value => (key, value),
+        })
+        .collect();
+    Value::Object(fields).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Pattern, Similarity};
+
+    fn checker() -> PasswordChecker<'static> {
+        PasswordChecker::new()
+            .min_length(10, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None)
+            .blacklist(["Kx7#pQ2@wL9v", "password1"], Some("Too common"))
+            .must_not_match(Pattern::new("^\\s").unwrap(), None)
+            .not_similar_to_previous(Similarity::MaxRatio(0.8), None)
+            .recommended_length(16, None)
+            .fail_fast(true)
+    }
+
+    #[test]
+    fn evidence_covers_every_rule() {
+        let checker = checker();
+        let (result, evidence) = checker.validate_with_evidence("Kx7#pQ2@wL9v");
+        assert_eq!(result.unwrap_err().codes().collect::<Vec<_>>(), ["blacklist"]);
+        let outcomes: Vec<_> = evidence.rules.iter().map(|rule| (rule.code, rule.outcome)).collect();
+        assert_eq!(outcomes, [
+            ("min_length", EvidenceOutcome::Passed),
+            ("require_upper_lower", EvidenceOutcome::Passed),
+            ("require_number", EvidenceOutcome::Passed),
+            ("require_special_char", EvidenceOutcome::Passed),
+            ("blacklist", EvidenceOutcome::Failed),
+            ("must_not_match", EvidenceOutcome::Passed),
+            ("not_similar_to_previous", EvidenceOutcome::Skipped),
+            ("recommended_length", EvidenceOutcome::Warned),
+        ]);
+        assert_eq!(evidence.fingerprint, checker.fingerprint());
+        assert_eq!((evidence.length_bucket, evidence.valid), ("12-15", false));
+        assert_eq!(evidence.classes, ["lowercase", "uppercase", "digit", "special"]);
+        assert_eq!(evidence.rules[4].params, r#"{"words":2}"#);
+        assert!(evidence.rules.iter().map(|rule| rule.duration).sum::<Duration>() <= evidence.duration);
+
+        // Without a context, the skipped rule keeps the password from passing.
+        let (result, evidence) = checker.validate_with_evidence("Correct-Horse-9-Battery");
+        assert_eq!(result.unwrap_err().codes().collect::<Vec<_>>(), ["context_required"]);
+        assert!(!evidence.valid);
+        assert!(evidence.rules.iter().all(|rule| matches!(rule.outcome, EvidenceOutcome::Passed | EvidenceOutcome::Skipped)));
+        assert_eq!(checker.validate_with_evidence("é").1.classes, ["other"]);
+        assert_eq!(checker.validate_with_evidence("").1.length_bucket, "0-7");
+    }
+
+    #[test]
+    fn oversized_input_skips_every_rule() {
+        let checker = checker().max_input_bytes(16);
+        let (result, evidence) = checker.validate_with_evidence(&"a".repeat(100));
+        assert_eq!(result.unwrap_err().codes().collect::<Vec<_>>(), ["input_too_large"]);
+        assert!(evidence.rules.iter().all(|rule| rule.outcome == EvidenceOutcome::Skipped));
+        assert_eq!(evidence.length_bucket, "64+");
+    }
+
+    #[test]
+    fn serializes_to_the_documented_schema_without_the_password() {
+        let password = "Kx7#pQ2@wL9v";
+        let (_, evidence) = checker().validate_with_evidence(password);
+        let json = evidence.to_json();
+        let Value::Object(root) = crate::json::parse(&json).unwrap() else { panic!("{}", json) };
+        let keys: Vec<&str> = root.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["fingerprint", "timestamp_ms", "length_bucket", "classes", "valid", "duration_ns", "rules"]);
+        assert!(json.starts_with(&format!(r#"{{"fingerprint":"{}","timestamp_ms":"#, evidence.fingerprint)), "{}", json);
+        assert!(json.contains(r#"{"code":"blacklist","params":{"words":2},"outcome":"failed","duration_ns":"#), "{}", json);
+        assert!(json.contains(r#"{"code":"min_length","params":{"length":10},"outcome":"passed","duration_ns":"#), "{}", json);
+        let chars: Vec<char> = password.chars().collect();
+        for window in chars.windows(3) {
+            let part: String = window.iter().collect();
+            assert!(!json.contains(&part), "{} in {}", part, json);
+        }
+    }
+}
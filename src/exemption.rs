@@ -0,0 +1,276 @@
+//! Waiving composition rules for long passphrases.
+
+use crate::config::invalid;
+use crate::json::Value;
+use crate::{ConfigError, PasswordChecker, Rule};
+
+/// The rules [`PasswordChecker::length_exemption`] waives: those asking
+/// for a mix of character classes.
+const COMPOSITION: &[&str] =
+    &["require_upper_lower", "require_number", "require_special_char", "not_all_numeric", "not_all_alphabetic", "max_class_run"];
+
+/// The rules a length exemption may waive. Blacklists, breach lookups,
+/// checks against the user's details and length limits always apply.
+const WAIVABLE: &[&str] = &[
+    "require_upper_lower",
+    "require_number",
+    "require_special_char",
+    "not_all_numeric",
+    "not_all_alphabetic",
+    "max_class_run",
+    "min_entropy",
+    "length_or_entropy",
+    "min_words",
+    "must_match",
+    "keyboard_walk",
+    "single_script_only",
+];
+
+/// Rules a checker waives for passwords of at least
+/// [`threshold`](Self::threshold) characters, from
+/// [`PasswordChecker::length_exemption`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LengthExemption {
+    threshold: usize,
+    waived: Vec<&'static str>,
+}
+
+impl LengthExemption {
+    /// Waives the composition rules for passwords of at least `threshold`
+    /// characters.
+    pub fn new(threshold: usize) -> Self {
+        LengthExemption { threshold, waived: COMPOSITION.to_vec() }
+    }
+
+    /// Waives the rules with the given codes instead, or `None` if one of
+    /// them cannot be waived.
+    pub fn waiving(threshold: usize, codes: &[&str]) -> Option<Self> {
+        let mut waived = Vec::new();
+        for code in codes {
+            let code = WAIVABLE.iter().find(|waivable| *waivable == code)?;
+            if !waived.contains(code) {
+                waived.push(*code);
+            }
+        }
+        Some(LengthExemption { threshold, waived })
+    }
+
+    /// The length, in characters as the length rules count them, from
+    /// which the rules are waived.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// The codes of the waived rules.
+    pub fn waived(&self) -> &[&'static str] {
+        &self.waived
+    }
+
+    /// Whether `rule` is one of the waived rules, whatever the password.
+    pub(crate) fn covers(&self, rule: &Rule<'_>) -> bool {
+        !matches!(rule, Rule::Custom(..)) && self.waived.contains(&rule.code())
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("threshold".to_string(), Value::Number(self.threshold as f64)),
+            ("waive".to_string(), Value::Array(self.waived.iter().map(|code| Value::String(code.to_string())).collect())),
+        ])
+    }
+
+    /// Parses the `length_exemption` field of a policy document.
+    pub(crate) fn from_value(value: &Value) -> Result<Self, ConfigError> {
+        let Value::Object(fields) = value else {
+            return Err(invalid("length_exemption", format!("expected object, found {}", value.type_name())));
+        };
+        if let Some((key, _)) = fields.iter().find(|(k, _)| !["threshold", "waive"].contains(&k.as_str())) {
+            return Err(invalid(&format!("length_exemption.{}", key), "unknown field".to_string()));
+        }
+        let threshold = match value.get("threshold") {
+            Some(Value::Number(n)) if *n >= 1.0 && n.fract() == 0.0 && *n <= u32::MAX as f64 => *n as usize,
+            Some(other) => return Err(invalid("length_exemption.threshold", format!("expected a positive integer, found {}", other))),
+            None => return Err(invalid("length_exemption.threshold", "missing field".to_string())),
+        };
+        let items = match value.get("waive") {
+            None => return Ok(LengthExemption::new(threshold)),
+            Some(Value::Array(items)) => items,
+            Some(other) => return Err(invalid("length_exemption.waive", format!("expected array, found {}", other.type_name()))),
+        };
+        let mut codes = Vec::new();
+        for (i, item) in items.iter().enumerate() {
+            let path = format!("length_exemption.waive[{}]", i);
+            match item {
+                Value::String(code) if WAIVABLE.contains(&code.as_str()) => codes.push(code.as_str()),
+                Value::String(code) => return Err(invalid(&path, format!("`{}` cannot be waived", code))),
+                other => return Err(invalid(&path, format!("expected string, found {}", other.type_name()))),
+            }
+        }
+        Ok(LengthExemption::waiving(threshold, &codes).expect("codes are waivable"))
+    }
+}
+
+impl<'a> PasswordChecker<'a> {
+    /// Waives the composition rules, such as
+    /// [`require_number`](Self::require_number) and
+    /// [`require_upper_lower`](Self::require_upper_lower), for passwords of
+    /// at least `threshold` characters, counted as by
+    /// [`min_length`](Self::min_length): a long passphrase needs no symbols.
+    /// Every other rule, such as blacklists, breach lookups and checks
+    /// against the user's details, still applies. The waived rules'
+    /// descriptions say so, and their entries in
+    /// [`requirements_json`](Self::requirements_json) have a
+    /// `waived_at_length` field.
+    ///
+    /// ```
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let checker = PasswordChecker::new().min_length(12, None).require_number(None).blacklist(["correct horse battery staple"], None).length_exemption(20);
+    /// assert!(checker.check("sunflowerfield").is_err());
+    /// assert!(checker.check("sunflowers in the field").is_ok());
+    /// assert!(checker.check("correct horse battery staple").is_err());
+    /// assert_eq!(checker.describe()[1], "At least one number, or use a passphrase of 20+ characters");
+    /// ```
+    pub fn length_exemption(self, threshold: usize) -> Self {
+        self.with_length_exemption(LengthExemption::new(threshold))
+    }
+
+    /// Like [`length_exemption`](Self::length_exemption), waiving the rules
+    /// with the given codes instead of the composition rules.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a code names a rule that cannot be waived, such as
+    /// `blacklist`, `not_breached`, `not_contain_user_info` or a length
+    /// limit.
+    pub fn length_exemption_waiving(self, threshold: usize, codes: &[&str]) -> Self {
+        let exemption = LengthExemption::waiving(threshold, codes).unwrap_or_else(|| panic!("cannot waive all of {:?} for long passwords", codes));
+        self.with_length_exemption(exemption)
+    }
+
+    /// Sets the length exemption, replacing any earlier one.
+    pub fn with_length_exemption(mut self, exemption: LengthExemption) -> Self {
+        self.length_exemption = Some(exemption);
+        self
+    }
+
+    /// Whether `rule` is waived for `password`.
+    pub(crate) fn waives(&self, rule: &Rule<'_>, password: &str) -> bool {
+        self.length_exemption.as_ref().is_some_and(|exemption| exemption.covers(rule) && password.chars().count() >= exemption.threshold)
+    }
+
+    /// The threshold from which `rule` is waived, if it is.
+    pub(crate) fn waived_at(&self, rule: &Rule<'_>) -> Option<usize> {
+        self.length_exemption.as_ref().filter(|exemption| exemption.covers(rule)).map(|exemption| exemption.threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PolicyConfig, UserContext, UserInfoCheck, ValidationSession};
+
+    fn checker() -> PasswordChecker<'static> {
+        PasswordChecker::new()
+            .min_length(12, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None)
+            .blacklist(["correct horse battery staple"], None)
+            .not_contain_user_info(UserInfoCheck::new(), None)
+            .length_exemption(20)
+    }
+
+    fn codes(checker: &PasswordChecker<'_>, password: &str) -> Vec<&'static str> {
+        checker.check_with_context(password, &UserContext::new().username("alice")).err().map(|v| v.codes().collect()).unwrap_or_default()
+    }
+
+    #[test]
+    fn composition_rules_are_waived_from_the_threshold() {
+        let checker = checker();
+        let short = "sunflowers in fields";
+        assert_eq!(short.chars().count(), 20);
+        assert_eq!(codes(&checker, &short[1..]), ["require_upper_lower", "require_number", "require_special_char"]);
+        assert!(codes(&checker, short).is_empty());
+        // Characters count, not bytes.
+        assert_eq!(codes(&checker, "ñandúes en el campo"), ["require_upper_lower", "require_number", "require_special_char"]);
+        let composition = PasswordChecker::new().require_number(None).require_special_char(None).length_exemption(20);
+        assert!(!composition.check_iter(short).any(|outcome| outcome.failed()));
+        let mut session = ValidationSession::new(&composition);
+        session.push_str(&short[1..]);
+        assert!(session.check().is_err());
+        session.push_str("!");
+        assert!(session.check().is_ok());
+        assert!(PasswordChecker::new().require_number(None).check(short).is_err());
+    }
+
+    #[test]
+    fn the_threshold_counts_like_the_length_rules() {
+        let checker = PasswordChecker::new().min_length(20, None).require_number(None).length_exemption(20);
+        // Twenty characters in 39 bytes, and one character short of that.
+        let at_threshold = "ééééééééééééééééééé ";
+        assert_eq!((at_threshold.chars().count(), at_threshold.len()), (20, 39));
+        assert!(checker.check(at_threshold).is_ok());
+        assert_eq!(checker.check(&at_threshold[..at_threshold.len() - 1]).unwrap_err().codes().collect::<Vec<_>>(), ["min_length", "require_number"]);
+    }
+
+    #[test]
+    fn other_rules_still_apply_to_long_passwords() {
+        let checker = checker();
+        assert_eq!(codes(&checker, "correct horse battery staple"), ["blacklist"]);
+        assert_eq!(codes(&checker, "alice in the sunflower fields"), ["not_contain_user_info"]);
+        let custom = PasswordChecker::new().min_words(4, None).require_number(None).length_exemption_waiving(20, &["min_words"]);
+        assert_eq!(custom.check("sunflowers-in-the-field").unwrap_err().codes().collect::<Vec<_>>(), ["require_number"]);
+        for code in ["blacklist", "not_breached", "not_contain_user_info", "min_length", "max_length"] {
+            assert_eq!(LengthExemption::waiving(20, &[code]), None, "{}", code);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot waive")]
+    fn waiving_a_blacklist_panics() {
+        let _ = PasswordChecker::new().length_exemption_waiving(20, &["require_number", "blacklist"]);
+    }
+
+    #[test]
+    fn descriptions_mention_the_passphrase() {
+        let checker = checker();
+        assert_eq!(
+            checker.describe(),
+            [
+                "At least 12 characters",
+                "Both uppercase and lowercase letters, or use a passphrase of 20+ characters",
+                "At least one number, or use a passphrase of 20+ characters",
+                "At least one special character, or use a passphrase of 20+ characters",
+                "Not a commonly used password",
+                "Does not contain your name, username or email address",
+            ]
+        );
+        let requirements = checker.requirements_json();
+        assert!(requirements.contains(r#"{"code":"require_number","description":"At least one number, or use a passphrase of 20+ characters","waived_at_length":20}"#));
+        assert!(requirements.contains(r#"{"code":"blacklist","description":"Not a commonly used password"}"#));
+    }
+
+    #[test]
+    fn exemption_round_trips_through_policy_config() {
+        let checker = PasswordChecker::new().min_length(12, None).require_number(None).length_exemption(20);
+        let json = checker.to_config().to_json();
+        assert!(json.contains(r#""length_exemption":{"threshold":20,"waive":["require_upper_lower","require_number","require_special_char","not_all_numeric","not_all_alphabetic","max_class_run"]}"#));
+        let loaded = PasswordChecker::from_json(&json).unwrap();
+        assert_eq!(loaded, checker);
+        assert_eq!(loaded.fingerprint(), checker.fingerprint());
+        assert_ne!(checker.fingerprint(), PasswordChecker::new().min_length(12, None).require_number(None).fingerprint());
+        assert_eq!(PolicyConfig::migrate(&json).unwrap(), json);
+
+        let short = r#"{"length_exemption": {"threshold": 16}, "rules": [{"rule": "require_number"}]}"#;
+        assert_eq!(PolicyConfig::from_json(short).unwrap().length_exemption, Some(LengthExemption::new(16)));
+        let waive = r#"{"length_exemption": {"threshold": 16, "waive": ["min_words"]}, "rules": [{"rule": "require_number"}]}"#;
+        assert_eq!(PolicyConfig::from_json(waive).unwrap().length_exemption.unwrap().waived(), ["min_words"]);
+        for (document, path, reason) in [
+            (waive.replace("min_words", "blacklist"), "length_exemption.waive[0]", "`blacklist` cannot be waived"),
+            (short.replace("16", "0"), "length_exemption.threshold", "expected a positive integer, found 0"),
+            (short.replace("threshold", "length"), "length_exemption.length", "unknown field"),
+        ] {
+            assert_eq!(PolicyConfig::from_json(&document).unwrap_err(), invalid(path, reason.to_string()), "{}", document);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Loading policies that use features this build was compiled without.
+
+use crate::config::migrate_root;
+use crate::json::{self, Value};
+use crate::{ConfigError, PasswordChecker, PolicyConfig};
+
+/// Field values that need a cargo feature: the field, the value, the
+/// feature, and whether this build has it.
+const GATED: &[(&str, &str, &str, bool)] = &[("estimator", "markov", "markov", cfg!(feature = "markov"))];
+
+/// The feature `value` of `field` needs, if this build lacks it.
+pub(crate) fn disabled_feature(field: &str, value: &str) -> Option<&'static str> {
+    GATED.iter().find(|&&(f, v, _, enabled)| f == field && v == value && !enabled).map(|&(_, _, feature, _)| feature)
+}
+
+/// A rule [`PolicyConfig::from_json_with_report`] left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRule {
+    /// Position of the rule in the document's `rules`.
+    pub index: usize,
+    /// The rule's name, which is also its violation code.
+    pub code: String,
+    /// The field needing the feature, e.g. `rules[2].estimator`.
+    pub path: String,
+    pub feature: &'static str,
+}
+
+impl SkippedRule {
+    /// The first field of the rule `item` that needs a disabled feature.
+    pub(crate) fn find(item: &Value, index: usize) -> Option<Self> {
+        let Value::Object(fields) = item else { return None };
+        let code = match item.get("rule") {
+            Some(Value::String(name)) => name.clone(),
+            _ => return None,
+        };
+        fields.iter().find_map(|(key, value)| match value {
+            Value::String(value) => disabled_feature(key, value).map(|feature| SkippedRule {
+                index,
+                code: code.clone(),
+                path: format!("rules[{}].{}", index, key),
+                feature,
+            }),
+            _ => None,
+        })
+    }
+}
+
+/// What [`PolicyConfig::from_json_with_report`] could not load.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoadReport {
+    pub skipped: Vec<SkippedRule>,
+}
+
+impl LoadReport {
+    /// Whether every rule of the document was loaded.
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+impl PolicyConfig {
+    /// Like [`from_json`](Self::from_json), skipping rules that need a
+    /// cargo feature this build lacks instead of failing with
+    /// [`ConfigError::FeatureDisabled`], and listing them in the report.
+    /// A policy shared by services built with different features then
+    /// loads everywhere, and each service can log what it does not
+    /// enforce. A policy whose rules are all skipped fails with
+    /// [`ConfigError::EmptyPolicy`].
+    pub fn from_json_with_report(input: &str) -> Result<(Self, LoadReport), ConfigError> {
+        let document = migrate_root(&json::parse(input)?)?;
+        let mut skipped = Vec::new();
+        let config = Self::from_document_skipping(&document, Some(&mut skipped))?;
+        if config.rules.is_empty() && !document.rules.is_empty() {
+            return Err(ConfigError::EmptyPolicy);
+        }
+        Ok((config, LoadReport { skipped }))
+    }
+}
+
+impl PasswordChecker<'static> {
+    /// Parses a JSON policy as [`PolicyConfig::from_json_with_report`]
+    /// does and builds a checker from the rules it loaded. The checker's
+    /// [fingerprint](Self::fingerprint) covers only those, so builds
+    /// enforcing different rules record different fingerprints.
+    ///
+    /// ```
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let policy = r#"{"rules": [{"rule": "min_length", "length": 12}, {"rule": "min_entropy", "bits": 40, "estimator": "markov"}]}"#;
+    /// let (checker, report) = PasswordChecker::from_json_with_report(policy).unwrap();
+    /// if cfg!(feature = "markov") {
+    ///     assert!(report.is_complete());
+    /// } else {
+    ///     assert_eq!((report.skipped[0].code.as_str(), report.skipped[0].feature), ("min_entropy", "markov"));
+    ///     assert_eq!(checker.len(), 1);
+    /// }
+    /// ```
+    pub fn from_json_with_report(input: &str) -> Result<(Self, LoadReport), ConfigError> {
+        PolicyConfig::from_json_with_report(input).map(|(config, report)| (Self::from_config(config), report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARED: &str = r#"{"rules": [
+        {"rule": "min_length", "length": 12},
+        {"rule": "min_entropy", "bits": 40, "estimator": "markov"},
+        {"rule": "require_number"}
+    ]}"#;
+
+    #[test]
+    fn strict_loading_names_the_missing_feature() {
+        let result = PasswordChecker::from_json(SHARED);
+        #[cfg(feature = "markov")]
+        assert!(result.is_ok());
+        #[cfg(not(feature = "markov"))]
+        {
+            let err = result.unwrap_err();
+            assert_eq!(err, ConfigError::FeatureDisabled { path: "rules[1].estimator".into(), feature: "markov" });
+            assert_eq!(err.to_string(), "policy field `rules[1].estimator` needs the `markov` feature, which this build lacks");
+        }
+        // Values no build knows stay invalid.
+        let unknown = SHARED.replace(r#""markov""#, r#""guess""#);
+        assert!(matches!(PasswordChecker::from_json(&unknown), Err(ConfigError::InvalidField { .. })));
+        assert!(matches!(PasswordChecker::from_json_with_report(&unknown), Err(ConfigError::InvalidField { .. })));
+    }
+
+    #[test]
+    fn lenient_loading_lists_skipped_rules() {
+        let (checker, report) = PasswordChecker::from_json_with_report(SHARED).unwrap();
+        let codes: Vec<_> = checker.rules().iter().map(|rule| rule.code()).collect();
+        #[cfg(feature = "markov")]
+        {
+            assert!(report.is_complete());
+            assert_eq!(codes, ["min_length", "min_entropy", "require_number"]);
+        }
+        #[cfg(not(feature = "markov"))]
+        {
+            let skipped = SkippedRule { index: 1, code: "min_entropy".into(), path: "rules[1].estimator".into(), feature: "markov" };
+            assert_eq!(report.skipped, [skipped]);
+            assert_eq!(codes, ["min_length", "require_number"]);
+            let only = r#"{"rules": [{"rule": "min_entropy", "bits": 40, "estimator": "markov"}]}"#;
+            assert_eq!(PasswordChecker::from_json_with_report(only).unwrap_err(), ConfigError::EmptyPolicy);
+        }
+        // Positions in errors still count the skipped rules.
+        let broken = SHARED.replace(r#""require_number"}"#, r#""require_number", "x": 1}"#);
+        assert_eq!(PasswordChecker::from_json_with_report(&broken).unwrap_err(), crate::config::invalid("rules[2].x", "unknown field".into()));
+    }
+
+    #[test]
+    fn fingerprints_cover_only_loaded_rules() {
+        let (checker, _) = PasswordChecker::from_json_with_report(SHARED).unwrap();
+        let reduced = PasswordChecker::new().min_length(12, None).require_number(None);
+        assert_eq!(checker.fingerprint() == reduced.fingerprint(), cfg!(not(feature = "markov")));
+    }
+}
@@ -0,0 +1,208 @@
+//! Content hashes identifying a policy, for audit trails.
+
+use std::fmt;
+
+use crate::config::rule_to_value;
+use crate::json::Value;
+use crate::{PasswordChecker, PolicyConfig};
+
+/// SHA-256 of a checker's rules, from [`PasswordChecker::fingerprint`].
+///
+/// The hash covers each rule and its parameters as serialized by
+/// [`PolicyConfig::to_json`], so a checker built with the builders and one
+/// loaded from the equivalent policy file share a fingerprint, and
+/// changing any threshold or list changes it, as does a
+/// [length exemption](PasswordChecker::length_exemption). Custom messages
+/// are left out, as are settings outside the policy format such as catalogs and
+/// observers: they never decide which passwords pass. Blacklists read from
+/// a [sorted file](crate::Dictionary::open) contribute their
+/// path, not their contents, and [custom rules](crate::CustomRule)
+/// contribute their code and declared
+/// [version](crate::CustomRule::version).
+///
+/// ```
+/// use passcheck::PasswordChecker;
+///
+/// let checker = PasswordChecker::new().min_length(12, None);
+/// let loaded = PasswordChecker::from_json(r#"{"rules": [{"rule": "min_length", "length": 12, "message": "Too short"}]}"#).unwrap();
+/// assert_eq!(checker.fingerprint(), loaded.fingerprint());
+/// assert_ne!(checker.fingerprint(), PasswordChecker::new().min_length(14, None).fingerprint());
+/// assert_eq!(checker.fingerprint().to_string().len(), 64);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PolicyFingerprint(pub [u8; 32]);
+
+/// Lowercase hex, as stored in audit records.
+impl fmt::Display for PolicyFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// The [fingerprint](PolicyFingerprint) of the checker's rules.
+    ///
+    /// Computing it serializes every rule, blacklists included; to record
+    /// it for each password, keep the value rather than calling this per
+    /// validation.
+    pub fn fingerprint(&self) -> PolicyFingerprint {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| match rule_to_value(rule) {
+                Value::Object(fields) => Value::Object(fields.into_iter().filter(|(key, _)| key != "message").collect()),
+                other => other,
+            })
+            .collect();
+        let version = Value::Number(f64::from(PolicyConfig::VERSION));
+        let mut fields = vec![("version".to_string(), version)];
+        if let Some(exemption) = &self.length_exemption {
+            fields.push(("length_exemption".to_string(), exemption.to_value()));
+        }
+        fields.push(("rules".to_string(), Value::Array(rules)));
+        let document = Value::Object(fields);
+        PolicyFingerprint(sha256(document.to_string().as_bytes()))
+    }
+
+    /// Includes the [`fingerprint`](Self::fingerprint) in
+    /// [`Analysis::fingerprint`](crate::Analysis::fingerprint) and
+    /// [`ValidationEvent::fingerprint`](crate::ValidationEvent::fingerprint),
+    /// so audit records name the policy that judged each password. It is
+    /// computed for each analysis and each observed validation.
+    pub fn record_fingerprint(mut self, record: bool) -> Self {
+        self.record_fingerprint = record;
+        self
+    }
+
+    /// The fingerprint, if [recorded](Self::record_fingerprint).
+    pub(crate) fn recorded_fingerprint(&self) -> Option<PolicyFingerprint> {
+        self.record_fingerprint.then(|| self.fingerprint())
+    }
+}
+
+/// Round constants: the first 32 bits of the fractional parts of the cube
+/// roots of the first 64 primes.
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be,
+    0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa,
+    0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85,
+    0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3,
+    0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f,
+    0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 as specified in FIPS 180-4.
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    for block in padded.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+    let mut out = [0u8; 32];
+    for (chunk, s) in out.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&s.to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomRule, Dictionary};
+
+    fn hex(bytes: [u8; 32]) -> String {
+        PolicyFingerprint(bytes).to_string()
+    }
+
+    #[test]
+    fn sha256_test_vectors() {
+        assert_eq!(hex(sha256(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            hex(sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(hex(sha256(&[b'a'; 1000])), "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3");
+    }
+
+    #[test]
+    fn builders_and_configs_share_fingerprints() {
+        let built = PasswordChecker::new()
+            .min_length(12, Some("Use 12 characters."))
+            .require_special_char(None)
+            .blacklist(["password", "letmein"], None)
+            .fail_fast(true);
+        let loaded = PasswordChecker::from_json(
+            r#"{"version": 1, "rules": [
+                {"rule": "min_length", "length": 12},
+                {"rule": "require_special_char"},
+                {"rule": "blacklist", "words": ["letmein", "password"]}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(built.fingerprint(), loaded.fingerprint());
+        assert_ne!(built.fingerprint(), PasswordChecker::new().fingerprint());
+        let longer = PasswordChecker::new().min_length(13, None).require_special_char(None).blacklist(["password", "letmein"], None);
+        assert_ne!(built.fingerprint(), longer.fingerprint());
+        let more_words = PasswordChecker::new().min_length(12, None).require_special_char(None).blacklist(["password", "letmein", "qwerty"], None);
+        assert_ne!(built.fingerprint(), more_words.fingerprint());
+    }
+
+    #[test]
+    fn custom_rules_contribute_code_and_version() {
+        let rule = |version: Option<&str>| {
+            let rule = CustomRule::new("no_acme", |pw| !pw.contains("acme")).unwrap();
+            let rule = match version {
+                Some(version) => rule.version(version),
+                None => rule,
+            };
+            PasswordChecker::new().min_length(8, None).custom(rule, None).fingerprint()
+        };
+        assert_eq!(rule(Some("2024-06")), rule(Some("2024-06")));
+        assert_ne!(rule(Some("2024-06")), rule(Some("2024-07")));
+        assert_ne!(rule(None), rule(Some("2024-06")));
+        let other = CustomRule::new("no_globex", |pw| !pw.contains("acme")).unwrap();
+        assert_ne!(rule(None), PasswordChecker::new().min_length(8, None).custom(other, None).fingerprint());
+    }
+
+    #[test]
+    fn fingerprints_are_recorded_on_request() {
+        use std::sync::{Arc, Mutex};
+
+        let checker = PasswordChecker::new().blacklist(Dictionary::new(["hunter2"]), None);
+        assert_eq!(checker.analyze("hunter2").fingerprint, None);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let checker = checker.record_fingerprint(true).with_observer(move |event| sink.lock().unwrap().push(event.fingerprint));
+        let fingerprint = checker.fingerprint();
+        assert_eq!(checker.analyze("hunter2").fingerprint, Some(fingerprint));
+        let _ = checker.check("hunter2");
+        assert_eq!(*seen.lock().unwrap(), [Some(fingerprint)]);
+    }
+}
@@ -0,0 +1,125 @@
+//! Rendering violation messages.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{ParamValue, Rule};
+
+/// Renders the message of a violation whose rule has no custom message.
+///
+/// Set with [`PasswordChecker::with_formatter`](crate::PasswordChecker::with_formatter);
+/// custom messages given to the rule builders are used as they are.
+pub trait MessageFormatter: Send + Sync {
+    /// Renders the message for a failed `rule`, with the
+    /// [params](crate::Violation::params) of the violation.
+    fn format(&self, rule: &Rule<'_>, params: &[(&'static str, ParamValue)]) -> String;
+}
+
+/// The built-in English sentences, e.g. "Password must be at least 12
+/// characters long."
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFormatter;
+
+impl MessageFormatter for DefaultFormatter {
+    fn format(&self, rule: &Rule<'_>, params: &[(&'static str, ParamValue)]) -> String {
+        let param = |name| params.iter().find(|(k, _)| *k == name).map(|(_, v)| v);
+        if let (Rule::RequireUpperLower(_), Some(ParamValue::List(missing))) = (rule, param("missing"))
+            && let [case] = missing[..]
+        {
+            return format!("Password must include at least one {} letter.", case);
+        }
+        match (rule, param("codepoint"), param("position"), param("contained_in")) {
+            // Counted from 1, in logical order: for right-to-left text this
+            // is not where the character is displayed.
+            (Rule::ForbidUnsafeChars(_), Some(ParamValue::Int(c)), Some(ParamValue::Int(position)), _) => {
+                format!("Password contains U+{:04X} as character {}, which cannot be stored safely.", c, position + 1)
+            }
+            (Rule::NotContainUserInfo(..), _, _, Some(ParamValue::Int(1))) => {
+                "Password must not be part of your name, username or email address.".to_string()
+            }
+            (Rule::NotEmailOrUrl(_), ..) => match param("shape") {
+                Some(ParamValue::Name("email")) => "Password must not be an email address.".to_string(),
+                Some(ParamValue::Name("url")) => "Password must not be a web address.".to_string(),
+                _ => rule.default_message(),
+            },
+            _ => rule.default_message(),
+        }
+    }
+}
+
+/// Prefixes another formatter's messages with the rule code, e.g.
+/// "[min_length] Password must be at least 12 characters long.", for
+/// support tickets.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrefixedFormatter<F = DefaultFormatter>(pub F);
+
+impl<F: MessageFormatter> MessageFormatter for PrefixedFormatter<F> {
+    fn format(&self, rule: &Rule<'_>, params: &[(&'static str, ParamValue)]) -> String {
+        format!("[{}] {}", rule.code(), self.0.format(rule, params))
+    }
+}
+
+/// The formatter set on a checker, [`DefaultFormatter`] if none.
+#[derive(Clone, Default)]
+pub(crate) struct Formatter(pub(crate) Option<Arc<dyn MessageFormatter>>);
+
+impl Formatter {
+    pub(crate) fn get(&self) -> &dyn MessageFormatter {
+        self.0.as_deref().unwrap_or(&DefaultFormatter)
+    }
+}
+
+impl fmt::Debug for Formatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Formatter(custom)" } else { "Formatter(default)" })
+    }
+}
+
+// Like observers, formatters never change which passwords a checker
+// accepts, so they are ignored when comparing checkers.
+impl PartialEq for Formatter {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordChecker;
+
+    /// Markdown bullets, as a consumer outside the crate would write one.
+    struct Bullets;
+
+    impl MessageFormatter for Bullets {
+        fn format(&self, rule: &Rule<'_>, params: &[(&'static str, ParamValue)]) -> String {
+            format!("- {}", DefaultFormatter.format(rule, params))
+        }
+    }
+
+    fn messages(checker: &PasswordChecker<'_>) -> Vec<String> {
+        checker.check("short\0").unwrap_err().into_messages()
+    }
+
+    #[test]
+    fn formatters_render_the_same_violations() {
+        let checker = PasswordChecker::new().min_length(12, None).require_number(Some("Add a digit.")).forbid_unsafe_chars(None);
+        assert_eq!(
+            messages(&checker),
+            ["Password must be at least 12 characters long.", "Add a digit.", "Password contains U+0000 as character 6, which cannot be stored safely."]
+        );
+        assert_eq!(
+            messages(&checker.clone().with_formatter(PrefixedFormatter(DefaultFormatter))),
+            [
+                "[min_length] Password must be at least 12 characters long.",
+                "Add a digit.",
+                "[forbid_unsafe_chars] Password contains U+0000 as character 6, which cannot be stored safely."
+            ]
+        );
+        assert_eq!(
+            messages(&checker.clone().with_formatter(PrefixedFormatter(Bullets))),
+            ["[min_length] - Password must be at least 12 characters long.", "Add a digit.", "[forbid_unsafe_chars] - Password contains U+0000 as character 6, which cannot be stored safely."]
+        );
+        assert_eq!(checker.clone().with_formatter(Bullets), checker);
+    }
+}
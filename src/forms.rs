@@ -0,0 +1,173 @@
+//! Validating passwords submitted as form fields.
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use passcheck::PasswordChecker;
+//! use passcheck::forms::{FormSpec, validate_form};
+//!
+//! let checker = PasswordChecker::new().min_length(8, None);
+//! let form: HashMap<String, String> = [("password", "sunflower"), ("password_confirmation", "sunflowers")]
+//!     .into_iter()
+//!     .map(|(k, v)| (k.to_string(), v.to_string()))
+//!     .collect();
+//! let spec = FormSpec::new("password").confirmation("password_confirmation");
+//! let violations = validate_form(&checker, &form, &spec).unwrap_err();
+//! assert_eq!(violations.codes().collect::<Vec<_>>(), ["confirmation_mismatch"]);
+//! ```
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use crate::{ParamValue, PasswordChecker, ValidateOptions, Violation, Violations};
+
+/// The fields of a form holding the password and, optionally, its
+/// confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormSpec<'f> {
+    pub password_field: &'f str,
+    /// If set, the form must repeat the password in this field.
+    pub confirmation_field: Option<&'f str>,
+}
+
+impl<'f> FormSpec<'f> {
+    pub fn new(password_field: &'f str) -> Self {
+        FormSpec { password_field, confirmation_field: None }
+    }
+
+    pub fn confirmation(mut self, field: &'f str) -> Self {
+        self.confirmation_field = Some(field);
+        self
+    }
+}
+
+/// Validates the password in `form` with `checker`.
+///
+/// A missing field is a `field_missing` violation whose `field` param is
+/// `"password"` or `"confirmation"`, never the form's key. A missing
+/// password is not validated further. A confirmation differing from the
+/// password is a `confirmation_mismatch` violation, after those of the
+/// rules. Violations are built as by [`PasswordChecker::check`], so they
+/// hold no part of either field.
+pub fn validate_form<S: BuildHasher>(checker: &PasswordChecker<'_>, form: &HashMap<String, String, S>, spec: &FormSpec<'_>) -> Result<(), Violations> {
+    validate_form_in(checker, form, spec, None)
+}
+
+/// Like [`validate_form`], rendering messages for `options` as
+/// [`check_with_options`](PasswordChecker::check_with_options) does.
+pub fn validate_form_with_options<S: BuildHasher>(
+    checker: &PasswordChecker<'_>,
+    form: &HashMap<String, String, S>,
+    spec: &FormSpec<'_>,
+    options: &ValidateOptions<'_>,
+) -> Result<(), Violations> {
+    validate_form_in(checker, form, spec, Some(options))
+}
+
+fn validate_form_in<S: BuildHasher>(
+    checker: &PasswordChecker<'_>,
+    form: &HashMap<String, String, S>,
+    spec: &FormSpec<'_>,
+    options: Option<&ValidateOptions<'_>>,
+) -> Result<(), Violations> {
+    let password = form.get(spec.password_field);
+    let confirmation = spec.confirmation_field.map(|field| form.get(field));
+    let mut violations = Vec::new();
+    match password {
+        None => violations.push(checker.form_violation("field_missing", vec![("field", ParamValue::Name("password"))], options)),
+        Some(password) => {
+            if let Err(found) = checker.check_in(password, None, options) {
+                violations.extend(found.0);
+            }
+        }
+    }
+    match (password, confirmation) {
+        (_, Some(None)) => violations.push(checker.form_violation("field_missing", vec![("field", ParamValue::Name("confirmation"))], options)),
+        (Some(password), Some(Some(confirmation))) if confirmation != password => {
+            violations.push(checker.form_violation("confirmation_mismatch", Vec::new(), options));
+        }
+        _ => {}
+    }
+    if violations.is_empty() { Ok(()) } else { Err(Violations(violations)) }
+}
+
+impl PasswordChecker<'_> {
+    /// A violation about the form rather than the password.
+    fn form_violation(&self, code: &'static str, params: Vec<(&'static str, ParamValue)>, options: Option<&ValidateOptions<'_>>) -> Violation {
+        let message = if self.keys_only {
+            String::new()
+        } else {
+            self.render_code(code, &params, options).unwrap_or_else(|| {
+                match (code, params.first()) {
+                    ("field_missing", Some((_, ParamValue::Name("confirmation")))) => "Confirm the password.",
+                    ("field_missing", _) => "Enter a password.",
+                    _ => "Passwords do not match.",
+                }
+                .to_string()
+            })
+        };
+        Violation { code, message, params, span: None, group: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(fields: &[(&str, &str)]) -> HashMap<String, String> {
+        fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn codes(result: Result<(), Violations>) -> Vec<&'static str> {
+        result.err().map(|v| v.codes().collect()).unwrap_or_default()
+    }
+
+    fn checker() -> PasswordChecker<'static> {
+        PasswordChecker::new().min_length(8, None).require_number(None)
+    }
+
+    const SPEC: FormSpec<'static> = FormSpec { password_field: "password", confirmation_field: Some("password_confirmation") };
+
+    #[test]
+    fn matching_pair() {
+        assert!(validate_form(&checker(), &form(&[("password", "sunflower7"), ("password_confirmation", "sunflower7")]), &SPEC).is_ok());
+        let weak = form(&[("password", "sun"), ("password_confirmation", "sun")]);
+        assert_eq!(codes(validate_form(&checker(), &weak, &SPEC)), ["min_length", "require_number"]);
+        assert!(validate_form(&checker(), &form(&[("pw", "sunflower7")]), &FormSpec::new("pw")).is_ok());
+    }
+
+    #[test]
+    fn mismatch_composes_with_rule_violations() {
+        let submitted = form(&[("password", "sunflower"), ("password_confirmation", "Sunflower")]);
+        let violations = validate_form(&checker(), &submitted, &SPEC).unwrap_err();
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["require_number", "confirmation_mismatch"]);
+        assert_eq!(violations[1].message, "Passwords do not match.");
+        assert!(violations[1].params.is_empty());
+        // Nothing from either field reaches the output.
+        for output in [violations.to_string(), violations.to_json(), format!("{:?}", violations)] {
+            assert!(!output.to_lowercase().contains("sunflower"), "{}", output);
+        }
+    }
+
+    #[test]
+    fn missing_confirmation_when_required() {
+        let violations = validate_form(&checker(), &form(&[("password", "sunflower7")]), &SPEC).unwrap_err();
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["field_missing"]);
+        assert_eq!(violations[0].params, [("field", ParamValue::Name("confirmation"))]);
+        assert_eq!(violations[0].message, "Confirm the password.");
+        assert!(validate_form(&checker(), &form(&[("password", "sunflower7")]), &FormSpec::new("password")).is_ok());
+    }
+
+    #[test]
+    fn missing_password_field() {
+        let violations = validate_form(&checker(), &form(&[("passwd", "sunflower7"), ("password_confirmation", "sunflower7")]), &SPEC).unwrap_err();
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["field_missing"]);
+        assert_eq!(violations[0].params, [("field", ParamValue::Name("password"))]);
+        assert_eq!(violations[0].message, "Enter a password.");
+        assert_eq!(codes(validate_form(&checker(), &form(&[]), &SPEC)), ["field_missing", "field_missing"]);
+        let options = ValidateOptions::new().message("field_missing", "Required.");
+        let violations = validate_form_with_options(&checker(), &form(&[]), &SPEC, &options).unwrap_err();
+        assert!(violations.iter().all(|v| v.message == "Required."));
+        assert!(validate_form(&checker().keys_only(true), &form(&[]), &SPEC).unwrap_err()[0].message.is_empty());
+    }
+}
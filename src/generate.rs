@@ -0,0 +1,648 @@
+//! Random password generation.
+//!
+//! Randomness comes straight from the operating system's CSPRNG:
+//! `/dev/urandom` on Unix and `BCryptGenRandom` on Windows.
+
+use std::fmt;
+use std::io;
+
+use crate::{PasswordChecker, Rule};
+
+/// Candidates tried before a policy is reported as unsatisfiable.
+const MAX_ATTEMPTS: usize = 10_000;
+
+/// Longest password [`PasswordChecker::generate_for_entropy`] considers.
+const MAX_ENTROPY_LENGTH: usize = 4096;
+
+/// Error returned by [`PasswordChecker::generate`].
+#[derive(Debug)]
+pub enum GenerateError {
+    /// No password of the requested length can pass the policy, or none
+    /// was found within the attempt limit.
+    Unsatisfiable { len: usize, reason: String },
+    /// The operating system's random number generator failed.
+    Random(io::Error),
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::Unsatisfiable { len, reason } => {
+                write!(f, "cannot generate a {}-character password for this policy: {}", len, reason)
+            }
+            GenerateError::Random(err) => write!(f, "random number generator failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GenerateError::Random(err) => Some(err),
+            GenerateError::Unsatisfiable { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for GenerateError {
+    fn from(err: io::Error) -> Self {
+        GenerateError::Random(err)
+    }
+}
+
+/// Buffered reader of operating-system randomness.
+pub(crate) struct OsRng {
+    buf: [u8; 256],
+    pos: usize,
+}
+
+impl OsRng {
+    pub(crate) fn new() -> Self {
+        OsRng { buf: [0; 256], pos: 256 }
+    }
+
+    fn next_u32(&mut self) -> io::Result<u32> {
+        if self.pos + 4 > self.buf.len() {
+            fill(&mut self.buf)?;
+            self.pos = 0;
+        }
+        let bytes = [self.buf[self.pos], self.buf[self.pos + 1], self.buf[self.pos + 2], self.buf[self.pos + 3]];
+        self.pos += 4;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Uniform integer in `0..n`, by rejection so no value is favored.
+    pub(crate) fn below(&mut self, n: usize) -> io::Result<usize> {
+        let n = u32::try_from(n).expect("range fits in u32");
+        let zone = u32::MAX - u32::MAX % n;
+        loop {
+            let x = self.next_u32()?;
+            if x < zone {
+                return Ok((x % n) as usize);
+            }
+        }
+    }
+
+    /// Fisher–Yates shuffle.
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) -> io::Result<()> {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1)?);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn pick<T: Copy>(&mut self, items: &[T]) -> io::Result<T> {
+        Ok(items[self.below(items.len())?])
+    }
+}
+
+#[cfg(unix)]
+fn fill(buf: &mut [u8]) -> io::Result<()> {
+    use std::io::Read;
+    std::fs::File::open("/dev/urandom")?.read_exact(buf)
+}
+
+#[cfg(windows)]
+fn fill(buf: &mut [u8]) -> io::Result<()> {
+    use std::ffi::c_void;
+
+    const BCRYPT_USE_SYSTEM_PREFERRED_RNG: u32 = 2;
+    #[link(name = "bcrypt")]
+    unsafe extern "system" {
+        fn BCryptGenRandom(algorithm: *mut c_void, buffer: *mut u8, len: u32, flags: u32) -> i32;
+    }
+    let len = u32::try_from(buf.len()).map_err(io::Error::other)?;
+    // SAFETY: `buf` is valid for writes of `len` bytes, and no algorithm
+    // handle is needed with BCRYPT_USE_SYSTEM_PREFERRED_RNG.
+    let status = unsafe { BCryptGenRandom(std::ptr::null_mut(), buf.as_mut_ptr(), len, BCRYPT_USE_SYSTEM_PREFERRED_RNG) };
+    if status != 0 {
+        return Err(io::Error::other(format!("BCryptGenRandom returned {:#x}", status)));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn fill(_: &mut [u8]) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "no operating-system random number generator"))
+}
+
+const LOWER: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm',
+    'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+const UPPER: &[char] = &[
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M',
+    'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+];
+const DIGITS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+/// Consonants that read unambiguously in syllables; `q`, `w`, `x` and `y`
+/// are left out.
+const CONSONANTS: &[char] = &['b', 'c', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'm', 'n', 'p', 'r', 's', 't', 'v', 'z'];
+const VOWELS: &[char] = &['a', 'e', 'i', 'o', 'u'];
+/// Characters easily mistaken for one another: `0`/`O` and `1`/`l`/`I`.
+const AMBIGUOUS: &[char] = &['0', 'O', '1', 'l', 'I'];
+
+/// How [`PasswordChecker::generate_with`] builds passwords.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GenerateStyle {
+    /// Every character drawn uniformly from letters, digits and special
+    /// characters.
+    #[default]
+    Random,
+    /// Alternating consonants and vowels, such as `Tavomeki`, that can be
+    /// read over the phone. The first letter is capitalized and any digit
+    /// or special character the policy requires is appended.
+    ///
+    /// Each letter comes from a handful of choices, so this style carries
+    /// far less entropy per character than [`Random`](Self::Random); see
+    /// [`GenerateOptions::entropy`].
+    Pronounceable,
+}
+
+/// Settings for [`PasswordChecker::generate_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GenerateOptions {
+    pub style: GenerateStyle,
+    /// Leave out `0`, `O`, `1`, `l` and `I`.
+    pub exclude_ambiguous: bool,
+}
+
+impl GenerateOptions {
+    /// Theoretical entropy in bits of a `len`-character password generated
+    /// with these options for `checker`, before candidates failing the
+    /// policy are discarded.
+    pub fn entropy(&self, checker: &PasswordChecker<'_>, len: usize) -> f64 {
+        self.entropy_in(checker, len, None)
+    }
+
+    /// [`entropy`](Self::entropy) with characters restricted to `charset`.
+    fn entropy_in(&self, checker: &PasswordChecker<'_>, len: usize, charset: Option<&str>) -> f64 {
+        let sets = self.sets(checker, charset);
+        // Nothing drawn from a set adds nothing, even from an empty one.
+        let bits = |set: &[char], n: usize| if n == 0 { 0.0 } else { n as f64 * (set.len() as f64).log2() };
+        match self.style {
+            GenerateStyle::Random => bits(&sets.all(), len),
+            GenerateStyle::Pronounceable => {
+                let (digit, special) = checker.required_suffix();
+                let letters = len.saturating_sub(digit as usize + special as usize);
+                bits(&sets.consonants, letters.div_ceil(2)) +
+                    bits(&sets.vowels, letters / 2) +
+                    bits(&sets.digits, digit as usize) +
+                    bits(&sets.special, special as usize)
+            }
+        }
+    }
+
+    /// The characters to draw from: those of `checker`'s
+    /// [`char_classes`](PasswordChecker::char_classes), only those in
+    /// `charset` if given.
+    fn sets(&self, checker: &PasswordChecker<'_>, charset: Option<&str>) -> CharSets {
+        let classes = checker.char_classes();
+        let keep = |set: &[char]| -> Vec<char> {
+            set.iter()
+                .copied()
+                .filter(|c| !self.exclude_ambiguous || !AMBIGUOUS.contains(c))
+                .filter(|c| charset.is_none_or(|charset| charset.contains(*c)) && classes.allows(*c))
+                .collect()
+        };
+        CharSets {
+            lower: keep(LOWER),
+            upper: keep(UPPER),
+            digits: keep(DIGITS),
+            special: keep(&classes.special_chars()),
+            consonants: keep(CONSONANTS),
+            vowels: keep(VOWELS),
+        }
+    }
+}
+
+/// Settings for [`PasswordChecker::generate_for_entropy`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GenerateSpec {
+    /// Entropy the password must carry at least, in bits.
+    pub min_entropy_bits: f64,
+    pub style: GenerateStyle,
+    /// Leave out `0`, `O`, `1`, `l` and `I`.
+    pub exclude_ambiguous: bool,
+    /// Draw only characters from this set, e.g. `"0123456789abcdef"`.
+    pub charset: Option<String>,
+}
+
+/// A password from [`PasswordChecker::generate_for_entropy`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generated {
+    pub password: String,
+    /// Theoretical entropy of the password, at least the requested bits.
+    pub entropy_bits: f64,
+    /// Length in characters.
+    pub length: usize,
+}
+
+struct CharSets {
+    lower: Vec<char>,
+    upper: Vec<char>,
+    digits: Vec<char>,
+    special: Vec<char>,
+    consonants: Vec<char>,
+    vowels: Vec<char>,
+}
+
+impl CharSets {
+    fn all(&self) -> Vec<char> {
+        [&self.lower[..], &self.upper, &self.digits, &self.special].concat()
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// Generates a random password of `len` characters that passes this
+    /// checker.
+    ///
+    /// Characters are drawn uniformly from lowercase and uppercase letters,
+    /// digits and the special characters. One character of each class the
+    /// policy requires is included and the result is shuffled, so required
+    /// classes do not sit in predictable positions; candidates that still
+    /// fail, e.g. a blacklist, are drawn again. Every rule applies, even
+    /// under [`dry_run`](Self::dry_run). A policy with a rule needing a
+    /// [`UserContext`](crate::UserContext), such as
+    /// [`not_contain_user_info`](Self::not_contain_user_info), fails with
+    /// [`GenerateError::Unsatisfiable`].
+    pub fn generate(&self, len: usize) -> Result<String, GenerateError> {
+        self.generate_with(len, GenerateOptions::default())
+    }
+
+    /// Like [`generate`](Self::generate), in the given style and, if
+    /// requested, without ambiguous characters.
+    pub fn generate_with(&self, len: usize, options: GenerateOptions) -> Result<String, GenerateError> {
+        self.generate_from(len, options, None)
+    }
+
+    /// Generates the shortest password that carries at least
+    /// `spec.min_entropy_bits` of entropy, counted over the characters the
+    /// spec leaves to draw from, and passes this checker. The length is
+    /// rounded up to whole characters and to the policy's minimum length.
+    ///
+    /// Fails with [`GenerateError::Unsatisfiable`] if the policy's maximum
+    /// length is too short for the target.
+    pub fn generate_for_entropy(&self, spec: &GenerateSpec) -> Result<Generated, GenerateError> {
+        let options = GenerateOptions { style: spec.style, exclude_ambiguous: spec.exclude_ambiguous };
+        let charset = spec.charset.as_deref();
+        let min = self.rules.iter().map(|rule| match rule {
+            Rule::MinLength(min, _) | Rule::LengthOrEntropy { min_len_strong: min, .. } => *min,
+            _ => 1,
+        });
+        let min = min.max().unwrap_or(1).max(1);
+        let Some(length) = (min..=MAX_ENTROPY_LENGTH).find(|&len| options.entropy_in(self, len, charset) >= spec.min_entropy_bits) else {
+            let reason = format!("{} characters carry less than {} bits of entropy", MAX_ENTROPY_LENGTH, spec.min_entropy_bits);
+            return Err(GenerateError::Unsatisfiable { len: MAX_ENTROPY_LENGTH, reason });
+        };
+        let password = self.generate_from(length, options, charset)?;
+        Ok(Generated { password, entropy_bits: options.entropy_in(self, length, charset), length })
+    }
+
+    fn generate_from(&self, len: usize, options: GenerateOptions, charset: Option<&str>) -> Result<String, GenerateError> {
+        let unsatisfiable = |reason: String| GenerateError::Unsatisfiable { len, reason };
+        if let Some(rule) = self.rules.iter().find(|rule| rule.needs_context()) {
+            return Err(unsatisfiable(format!("the policy requires a UserContext for its `{}` rule", rule.code())));
+        }
+        let sets = options.sets(self, charset);
+        let mut required: Vec<&[char]> = Vec::new();
+        for rule in &self.rules {
+            let classes: &[&[char]] = match rule {
+                Rule::MinLength(min, _) if len < *min => {
+                    return Err(unsatisfiable(format!("the policy requires at least {} characters", min)));
+                }
+                // Every character takes at least one byte; the bytes of the
+                // drawn characters are checked below.
+                Rule::MaxLength(max, _) | Rule::MaxBytes(max, _) if len > *max => {
+                    return Err(unsatisfiable(format!("the policy allows at most {} characters", max)));
+                }
+                Rule::LengthOrEntropy { min_len_strong, .. } if len < *min_len_strong => {
+                    return Err(unsatisfiable(format!("the policy requires at least {} characters", min_len_strong)));
+                }
+                Rule::RequireUpperLower(_) => &[&sets.lower, &sets.upper],
+                Rule::RequireNumber(_) => &[&sets.digits],
+                Rule::RequireSpecialChar(..) => &[&sets.special],
+                _ => &[],
+            };
+            for &class in classes {
+                if !required.contains(&class) {
+                    required.push(class);
+                }
+            }
+        }
+        if required.len() > len {
+            return Err(unsatisfiable(format!("the policy requires {} kinds of characters", required.len())));
+        }
+
+        let charset = sets.all();
+        let (digit, special) = self.required_suffix();
+        let letters = len.saturating_sub(digit as usize + special as usize);
+        let drawn: Vec<&[char]> = match options.style {
+            GenerateStyle::Random if len > required.len() => [&required[..], &[&charset]].concat(),
+            GenerateStyle::Random => required.clone(),
+            GenerateStyle::Pronounceable => [
+                (letters > 0, &sets.consonants[..]),
+                (letters > 1, &sets.vowels),
+                (digit, &sets.digits),
+                (special, &sets.special),
+            ]
+            .into_iter()
+            .filter_map(|(used, set)| used.then_some(set))
+            .collect(),
+        };
+        if drawn.iter().any(|set| set.is_empty()) {
+            return Err(unsatisfiable("the character set lacks characters the policy requires".to_string()));
+        }
+        // Byte limits count UTF-8 bytes, and special characters may take
+        // several.
+        let fewest_bytes = |set: &[char]| set.iter().map(|c| c.len_utf8()).min().unwrap_or(0);
+        let least_bytes = match options.style {
+            GenerateStyle::Random => required.iter().map(|class| fewest_bytes(class)).sum::<usize>() + (len - required.len()) * fewest_bytes(&charset),
+            GenerateStyle::Pronounceable => {
+                (0..letters).map(|i| fewest_bytes(if i % 2 == 0 { &sets.consonants } else { &sets.vowels })).sum::<usize>()
+                    + usize::from(digit) * fewest_bytes(&sets.digits)
+                    + usize::from(special) * fewest_bytes(&sets.special)
+            }
+        };
+        let max_bytes = self.rules.iter().filter_map(|rule| match rule {
+            Rule::MaxBytes(max, _) => Some(*max),
+            _ => None,
+        });
+        if let Some(max) = max_bytes.min()
+            && least_bytes > max
+        {
+            return Err(unsatisfiable(format!("the policy allows at most {} bytes, and the characters drawn take at least {}", max, least_bytes)));
+        }
+        let mut rng = OsRng::new();
+        for _ in 0..MAX_ATTEMPTS {
+            let password: String = match options.style {
+                GenerateStyle::Random => {
+                    let mut chars = Vec::with_capacity(len);
+                    for class in &required {
+                        chars.push(rng.pick(class)?);
+                    }
+                    while chars.len() < len {
+                        chars.push(rng.pick(&charset)?);
+                    }
+                    rng.shuffle(&mut chars)?;
+                    chars.into_iter().collect()
+                }
+                GenerateStyle::Pronounceable => {
+                    let mut password = String::with_capacity(len);
+                    for i in 0..letters {
+                        let c = rng.pick(if i % 2 == 0 { &sets.consonants } else { &sets.vowels })?;
+                        password.extend(if i == 0 { c.to_uppercase().next() } else { Some(c) });
+                    }
+                    if digit {
+                        password.push(rng.pick(&sets.digits)?);
+                    }
+                    if special {
+                        password.push(rng.pick(&sets.special)?);
+                    }
+                    password
+                }
+            };
+            // Not `check`, which a dry run makes accept everything.
+            let fails = self.failure_test(&password, None);
+            let passes = !self.rules.iter().any(fails);
+            if passes {
+                return Ok(password);
+            }
+        }
+        Err(unsatisfiable(format!("no passing password found in {} attempts", MAX_ATTEMPTS)))
+    }
+
+    /// Whether the policy requires a digit and a special character, which
+    /// pronounceable passwords append after their letters.
+    fn required_suffix(&self) -> (bool, bool) {
+        let requires = |f: fn(&Rule<'_>) -> bool| self.rules.iter().any(f);
+        (requires(|r| matches!(r, Rule::RequireNumber(_))), requires(|r| matches!(r, Rule::RequireSpecialChar(..))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SPECIAL_CHARS, SpecialCharPolicy};
+
+    fn policy() -> PasswordChecker<'static> {
+        PasswordChecker::new()
+            .min_length(12, None)
+            .max_length(64, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None)
+            .blacklist(["password"], None)
+    }
+
+    #[test]
+    fn generated_passwords_use_the_narrowed_special_set() {
+        let checker = PasswordChecker::new()
+            .require_special_char_with(SpecialCharPolicy::Chars("#%".into()), None)
+            .min_length(12, None)
+            .max_length(64, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None);
+        for _ in 0..500 {
+            let password = checker.generate(12).unwrap();
+            assert!(checker.validate(&password).is_ok(), "{}", password);
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric() || c == '#' || c == '%'), "{}", password);
+        }
+        let pin = PasswordChecker::pin().generate(6).unwrap();
+        assert!(pin.bytes().all(|b| b.is_ascii_digit()) && PasswordChecker::pin().check(&pin).is_ok(), "{}", pin);
+    }
+
+    #[test]
+    fn generated_passwords_pass_the_policy() {
+        let checker = policy();
+        for _ in 0..1_000 {
+            let password = checker.generate(12).unwrap();
+            assert_eq!(password.chars().count(), 12);
+            assert!(checker.validate(&password).is_ok(), "{}", password);
+        }
+    }
+
+    #[test]
+    fn required_classes_are_not_in_fixed_positions() {
+        let checker = policy();
+        let mut counts = [0usize; 4];
+        let samples = 4_000;
+        for _ in 0..samples {
+            let first = checker.generate(12).unwrap().chars().next().unwrap();
+            let class = [LOWER, UPPER, DIGITS, &SPECIAL_CHARS].iter().position(|class| class.contains(&first)).unwrap();
+            counts[class] += 1;
+        }
+        // Expected shares: 8/12 of the class's share of the charset plus
+        // 1/12 for the required character, e.g. 0.27 for lowercase.
+        let share = |class: usize| counts[class] as f64 / samples as f64;
+        assert!((0.22..0.33).contains(&share(0)), "{:?}", counts);
+        assert!((0.22..0.33).contains(&share(1)), "{:?}", counts);
+        assert!((0.11..0.21).contains(&share(2)), "{:?}", counts);
+        assert!((0.25..0.36).contains(&share(3)), "{:?}", counts);
+    }
+
+    #[test]
+    fn excludes_ambiguous_characters() {
+        let checker = policy();
+        for style in [GenerateStyle::Random, GenerateStyle::Pronounceable] {
+            let options = GenerateOptions { style, exclude_ambiguous: true };
+            for _ in 0..300 {
+                let password = checker.generate_with(12, options).unwrap();
+                assert!(!password.contains(AMBIGUOUS), "{}", password);
+            }
+        }
+    }
+
+    #[test]
+    fn draws_from_an_explicit_special_set() {
+        let checker = PasswordChecker::new().require_number(None).require_special_char_with(SpecialCharPolicy::Chars("!#".into()), None);
+        for _ in 0..300 {
+            let password = checker.generate(12).unwrap();
+            assert!(checker.validate(&password).is_ok(), "{}", password);
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric() || "!#".contains(c)), "{}", password);
+        }
+    }
+
+    #[test]
+    fn pronounceable_passwords_alternate_syllables() {
+        let checker = policy();
+        let options = GenerateOptions { style: GenerateStyle::Pronounceable, exclude_ambiguous: false };
+        for _ in 0..300 {
+            let password = checker.generate_with(12, options).unwrap();
+            assert!(checker.validate(&password).is_ok(), "{}", password);
+            let letters: Vec<char> = password.chars().take(10).map(|c| c.to_ascii_lowercase()).collect();
+            for (i, c) in letters.iter().enumerate() {
+                assert!(if i % 2 == 0 { CONSONANTS } else { VOWELS }.contains(c), "{}", password);
+            }
+        }
+    }
+
+    #[test]
+    fn pronounceable_entropy_is_lower() {
+        let checker = policy();
+        for exclude_ambiguous in [false, true] {
+            let random = GenerateOptions { style: GenerateStyle::Random, exclude_ambiguous };
+            let pronounceable = GenerateOptions { style: GenerateStyle::Pronounceable, exclude_ambiguous };
+            assert!(pronounceable.entropy(&checker, 12) < random.entropy(&checker, 12) * 0.6);
+        }
+        let random = GenerateOptions::default();
+        assert!((random.entropy(&checker, 12) - 12.0 * 92f64.log2()).abs() < 1e-9);
+        // Five consonants, five vowels, a digit and a special character.
+        let pronounceable = GenerateOptions { style: GenerateStyle::Pronounceable, exclude_ambiguous: false };
+        let expected = 5.0 * 17f64.log2() + 5.0 * 5f64.log2() + 10f64.log2() + 30f64.log2();
+        assert!((pronounceable.entropy(&checker, 12) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn length_follows_the_pool_size() {
+        let checker = PasswordChecker::new();
+        let spec = |charset: Option<&str>, exclude_ambiguous| GenerateSpec {
+            min_entropy_bits: 80.0,
+            exclude_ambiguous,
+            charset: charset.map(str::to_string),
+            ..GenerateSpec::default()
+        };
+        for (spec, pool, length) in [
+            (spec(None, false), 92, 13),
+            (spec(None, true), 87, 13),
+            (spec(Some("0123456789"), false), 10, 25),
+            (spec(Some("0123456789abcdef"), false), 16, 20),
+            (spec(Some("0123456789abcdef"), true), 14, 22),
+        ] {
+            let generated = checker.generate_for_entropy(&spec).unwrap();
+            assert_eq!(generated.length, length, "{:?}", spec);
+            assert_eq!(generated.password.chars().count(), length);
+            assert!((generated.entropy_bits - length as f64 * (pool as f64).log2()).abs() < 1e-9);
+            assert!(generated.entropy_bits >= 80.0);
+            assert!(generated.password.chars().all(|c| spec.charset.as_ref().is_none_or(|set| set.contains(c))));
+        }
+    }
+
+    #[test]
+    fn entropy_targets_respect_the_policy() {
+        let checker = policy();
+        for style in [GenerateStyle::Random, GenerateStyle::Pronounceable] {
+            for bits in [20.0, 60.0, 100.0] {
+                let spec = GenerateSpec { min_entropy_bits: bits, style, ..GenerateSpec::default() };
+                let generated = checker.generate_for_entropy(&spec).unwrap();
+                assert!(checker.validate(&generated.password).is_ok(), "{}", generated.password);
+                assert!(generated.length >= 12);
+                assert!(generated.entropy_bits >= bits);
+                let options = GenerateOptions { style, exclude_ambiguous: false };
+                assert_eq!(generated.entropy_bits, options.entropy(&checker, generated.length));
+            }
+        }
+        // Below the minimum length the target is already met.
+        let short = checker.generate_for_entropy(&GenerateSpec { min_entropy_bits: 20.0, ..GenerateSpec::default() }).unwrap();
+        assert_eq!(short.length, 12);
+    }
+
+    #[test]
+    fn unreachable_entropy_targets() {
+        let checker = policy().max_length(16, None);
+        let spec = GenerateSpec { min_entropy_bits: 128.0, ..GenerateSpec::default() };
+        let err = checker.generate_for_entropy(&spec).unwrap_err();
+        assert_eq!(err.to_string(), "cannot generate a 20-character password for this policy: the policy allows at most 16 characters");
+        let spec = GenerateSpec { min_entropy_bits: 1.0, charset: Some("a".into()), ..GenerateSpec::default() };
+        assert!(matches!(PasswordChecker::new().generate_for_entropy(&spec), Err(GenerateError::Unsatisfiable { len: 4096, .. })));
+        let spec = GenerateSpec { min_entropy_bits: 40.0, charset: Some("abc123".into()), ..GenerateSpec::default() };
+        let err = policy().generate_for_entropy(&spec).unwrap_err();
+        assert!(err.to_string().ends_with("the character set lacks characters the policy requires"), "{}", err);
+    }
+
+    #[test]
+    fn reports_unsatisfiable_policies() {
+        let err = policy().generate(8).unwrap_err();
+        assert_eq!(err.to_string(), "cannot generate a 8-character password for this policy: the policy requires at least 12 characters");
+        let classes = PasswordChecker::new().require_upper_lower(None).require_number(None).require_special_char(None);
+        assert!(matches!(classes.generate(3), Err(GenerateError::Unsatisfiable { len: 3, .. })));
+        assert!(classes.generate(4).is_ok());
+        let impossible = PasswordChecker::new().must_match(crate::Pattern::new("^x+$").unwrap(), None);
+        assert!(matches!(impossible.generate(16), Err(GenerateError::Unsatisfiable { .. })));
+    }
+
+    #[test]
+    fn policies_needing_a_context_are_unsatisfiable() {
+        let checker = PasswordChecker::new().min_length(12, None).not_contain_user_info(crate::UserInfoCheck::new(), None);
+        let err = checker.generate(16).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot generate a 16-character password for this policy: the policy requires a UserContext for its `not_contain_user_info` rule"
+        );
+        assert!(checker.generate_for_entropy(&GenerateSpec { min_entropy_bits: 60.0, ..GenerateSpec::default() }).is_err());
+    }
+
+    #[test]
+    fn dry_runs_still_generate_passing_passwords() {
+        let no_digits = PasswordChecker::new().min_length(12, None).must_not_match(crate::Pattern::new("[0-9]").unwrap(), None);
+        let staged = no_digits.clone().dry_run(true);
+        for _ in 0..50 {
+            let password = staged.generate(20).unwrap();
+            assert!(no_digits.check(&password).is_ok(), "{}", password);
+        }
+        let impossible = PasswordChecker::new().must_match(crate::Pattern::new("^x+$").unwrap(), None).dry_run(true);
+        assert!(matches!(impossible.generate(16), Err(GenerateError::Unsatisfiable { .. })));
+    }
+
+    #[test]
+    fn byte_limits_count_multi_byte_special_characters() {
+        let checker = PasswordChecker::new().max_bytes(8, None).require_special_char_with(SpecialCharPolicy::Chars("é".into()), None);
+        let err = checker.generate(8).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot generate a 8-character password for this policy: the policy allows at most 8 bytes, and the characters drawn take at least 9"
+        );
+        for _ in 0..100 {
+            let password = checker.generate(7).unwrap();
+            assert!(password.len() <= 8 && password.contains('é'), "{}", password);
+        }
+        let pronounceable = GenerateOptions { style: GenerateStyle::Pronounceable, ..GenerateOptions::default() };
+        let euro = PasswordChecker::new().max_bytes(6, None).require_special_char_with(SpecialCharPolicy::Chars("€".into()), None);
+        assert!(matches!(euro.generate_with(5, pronounceable), Err(GenerateError::Unsatisfiable { len: 5, .. })));
+        assert_eq!(euro.generate_with(4, pronounceable).unwrap().len(), 6);
+        // Character limits are met by any password of that many characters.
+        let max_length = PasswordChecker::new().max_length(8, None).require_special_char_with(SpecialCharPolicy::Chars("é".into()), None);
+        assert_eq!(max_length.generate(8).unwrap().chars().count(), 8);
+    }
+}
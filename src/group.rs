@@ -0,0 +1,107 @@
+//! Rules sharing one user-facing message.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::PasswordChecker;
+
+/// Consecutive rules of a checker reported under one message, from
+/// [`PasswordChecker::group`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RuleGroup<'a> {
+    rules: Range<usize>,
+    message: Cow<'a, str>,
+}
+
+impl<'a> PasswordChecker<'a> {
+    /// Adds the rules `members` adds, reporting any of them failing with
+    /// `msg` alone. Members are still evaluated one by one, so
+    /// [`check_iter`](Self::check_iter), [`progress`](Self::progress) and
+    /// the codes and params of the [`Violations`](crate::Violations) show
+    /// each failed member; its violation carries `msg` and the group's
+    /// index in [`Violation::group`](crate::Violation::group), and the
+    /// group's message appears once in
+    /// [`into_messages`](crate::Violations::into_messages) and the
+    /// `Display` output. The message replaces the members' own and
+    /// localized messages. Groups are not part of the policy file format.
+    ///
+    /// ```
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let checker = PasswordChecker::new().min_length(8, None).group("Mix letters, numbers and symbols.", |g| {
+    ///     g.require_number(None).require_special_char(None).require_upper_lower(None)
+    /// });
+    /// let violations = checker.check("sunflower").unwrap_err();
+    /// assert_eq!(violations.codes().collect::<Vec<_>>(), ["require_number", "require_special_char", "require_upper_lower"]);
+    /// assert_eq!(violations.into_messages(), ["Mix letters, numbers and symbols."]);
+    /// ```
+    pub fn group(self, msg: &'a str, members: impl FnOnce(Self) -> Self) -> Self {
+        let start = self.rules.len();
+        let mut checker = members(self);
+        let rules = start..checker.rules.len();
+        checker.groups.push(RuleGroup { rules, message: Cow::Borrowed(msg) });
+        checker
+    }
+
+    /// The index and message of the innermost group the `i`th rule
+    /// belongs to. Inner groups are added first.
+    pub(crate) fn group_of(&self, i: usize) -> Option<(usize, &str)> {
+        self.groups.iter().enumerate().find(|(_, group)| group.rules.contains(&i)).map(|(index, group)| (index, &*group.message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PasswordChecker, Violations};
+
+    fn checker() -> PasswordChecker<'static> {
+        PasswordChecker::new().min_length(8, Some("Use 8 characters.")).group("Password must contain a mix of letters, numbers and symbols", |g| {
+            g.require_number(None).require_special_char(Some("Add a symbol.")).require_upper_lower(None)
+        })
+    }
+
+    fn codes(violations: &Violations) -> Vec<&'static str> {
+        violations.codes().collect()
+    }
+
+    #[test]
+    fn failing_members_share_one_message() {
+        let checker = checker();
+        let message = "Password must contain a mix of letters, numbers and symbols";
+        for (password, failed) in [
+            ("Sunflower!", vec!["require_number"]),
+            ("Sunflower9", vec!["require_special_char"]),
+            ("sunflower!", vec!["require_number", "require_upper_lower"]),
+            ("sunflower", vec!["require_number", "require_special_char", "require_upper_lower"]),
+        ] {
+            let violations = checker.check(password).unwrap_err();
+            assert_eq!(codes(&violations), failed, "{}", password);
+            assert!(violations.iter().all(|v| v.message == message && v.group == Some(0)));
+            assert_eq!(violations.to_string(), message);
+            assert_eq!(violations.into_messages(), [message]);
+        }
+        assert!(checker.check("Sunflower9!").is_ok());
+    }
+
+    #[test]
+    fn ungrouped_rules_keep_their_messages() {
+        let checker = checker();
+        let violations = checker.check("sun").unwrap_err();
+        assert_eq!(violations[0].group, None);
+        assert_eq!(violations.into_messages(), ["Use 8 characters.", "Password must contain a mix of letters, numbers and symbols"]);
+        let outcomes: Vec<_> = checker.check_iter("sunflower!").filter(|outcome| outcome.failed()).map(|outcome| outcome.rule.code()).collect();
+        assert_eq!(outcomes, ["require_number", "require_upper_lower"]);
+        assert!(checker.keys_only(true).check("sunflower").unwrap_err().iter().all(|v| v.message.is_empty()));
+    }
+
+    #[test]
+    fn groups_are_told_apart() {
+        let checker = PasswordChecker::new()
+            .group("Mix cases and digits.", |g| g.require_upper_lower(None).require_number(None))
+            .group("Avoid common words.", |g| g.blacklist(["sunflower"], None).not_all_alphabetic(None));
+        let violations = checker.check("sunflower").unwrap_err();
+        let groups: Vec<_> = violations.iter().map(|v| v.group).collect();
+        assert_eq!(groups, [Some(0), Some(0), Some(1), Some(1)]);
+        assert_eq!(violations.into_messages(), ["Mix cases and digits.", "Avoid common words."]);
+    }
+}
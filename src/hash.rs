@@ -0,0 +1,104 @@
+//! Validating a password before it is hashed.
+
+use std::fmt;
+
+use crate::{PasswordChecker, Violations};
+
+/// A password hashing scheme, such as an Argon2 or bcrypt implementation,
+/// producing self-describing hash strings like PHC strings.
+pub trait PasswordHasher {
+    type Error;
+
+    /// Hashes `password` with a fresh salt.
+    fn hash(&self, password: &str) -> Result<String, Self::Error>;
+
+    /// Returns whether `password` matches `hash`.
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, Self::Error>;
+}
+
+/// Error returned by [`PasswordChecker::hash_if_valid`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum HashOrValidateError<E> {
+    /// The password breaks the policy and was not hashed.
+    Invalid(Violations),
+    /// The hasher failed.
+    Hash(E),
+}
+
+impl<E: fmt::Display> fmt::Display for HashOrValidateError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashOrValidateError::Invalid(violations) => write!(f, "invalid password: {}", violations),
+            HashOrValidateError::Hash(err) => write!(f, "hashing failed: {}", err),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for HashOrValidateError<E> {}
+
+impl PasswordChecker<'_> {
+    /// Validates `password` and hashes it only if it passes.
+    pub fn hash_if_valid<H: PasswordHasher>(&self, password: &str, hasher: &H) -> Result<String, HashOrValidateError<H::Error>> {
+        self.check(password).map_err(HashOrValidateError::Invalid)?;
+        hasher.hash(password).map_err(HashOrValidateError::Hash)
+    }
+
+    /// Checks `password` against a stored `hash` with `hasher`.
+    ///
+    /// The policy is not applied: logins must keep working for passwords
+    /// set before it was tightened.
+    pub fn verify<H: PasswordHasher>(&self, password: &str, hash: &str, hasher: &H) -> Result<bool, H::Error> {
+        hasher.verify(password, hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    /// Stands in for a real hasher, counting calls. The "hash" is the
+    /// reversed password behind a PHC-style prefix carrying the cost.
+    struct Counting {
+        cost: u32,
+        calls: Cell<usize>,
+    }
+
+    impl PasswordHasher for Counting {
+        type Error = String;
+
+        fn hash(&self, password: &str) -> Result<String, String> {
+            self.calls.set(self.calls.get() + 1);
+            if password.len() > 64 {
+                return Err("password too long".to_string());
+            }
+            Ok(format!("$test$c={}${}", self.cost, password.chars().rev().collect::<String>()))
+        }
+
+        fn verify(&self, password: &str, hash: &str) -> Result<bool, String> {
+            let digest = hash.rsplit('$').next().ok_or("malformed hash")?;
+            Ok(digest.chars().rev().eq(password.chars()))
+        }
+    }
+
+    #[test]
+    fn hashes_only_valid_passwords() {
+        let hasher = Counting { cost: 3, calls: Cell::new(0) };
+        let checker = PasswordChecker::new().min_length(8, None).require_number(None);
+
+        let hash = checker.hash_if_valid("correct horse 1", &hasher).unwrap();
+        assert!(hash.starts_with("$test$c=3$"));
+        assert_eq!(checker.verify("correct horse 1", &hash, &hasher), Ok(true));
+        assert_eq!(checker.verify("correct horse 2", &hash, &hasher), Ok(false));
+
+        let err = checker.hash_if_valid("short", &hasher).unwrap_err();
+        let HashOrValidateError::Invalid(violations) = &err else { panic!("{:?}", err) };
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["min_length", "require_number"]);
+        assert_eq!(hasher.calls.get(), 1);
+
+        let long = format!("{}1", "a".repeat(70));
+        assert_eq!(checker.hash_if_valid(&long, &hasher), Err(HashOrValidateError::Hash("password too long".to_string())));
+        assert_eq!(err.to_string(), "invalid password: Password must be at least 8 characters long. Password must include at least one number.");
+    }
+}
@@ -0,0 +1,407 @@
+//! Previous passwords of one account, kept as salted hashes.
+
+use std::collections::VecDeque;
+
+use crate::casefold;
+use crate::leet::unleet;
+use crate::json::{self, Value};
+use crate::{ConfigError, HashOrValidateError, ParamValue, PasswordChecker, PasswordHasher, ValidateOptions, Violation, Violations};
+
+/// The most recent passwords of an account, stored as hashes from a
+/// [`PasswordHasher`] that salts each entry, to reject reuse.
+///
+/// Salted hashes cannot be compared for similarity, so by default only
+/// exact reuse is detected: `summer2024!` after `Summer2024!` is accepted.
+/// [`normalization`](Self::normalization) also catches variants that
+/// differ in case or leetspeak substitutions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordHistory {
+    capacity: usize,
+    normalization: HistoryNormalization,
+    entries: VecDeque<Entry>,
+}
+
+/// Which variants of a previous password [`PasswordHistory`] rejects.
+///
+/// Normalized modes store a second salted hash per entry, of the
+/// normalized password. That form has fewer possible values than the
+/// password itself, so it is cheaper to crack, and cracking it narrows the
+/// search for the original to its case and substitution variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryNormalization {
+    /// Only the exact password.
+    #[default]
+    Exact,
+    /// Also passwords with the same case fold.
+    CaseFold,
+    /// Also passwords equal after case folding and undoing common leetspeak
+    /// substitutions such as `4` for `a` or `$` for `s`.
+    CaseFoldLeet,
+}
+
+impl HistoryNormalization {
+    fn name(self) -> &'static str {
+        match self {
+            HistoryNormalization::Exact => "exact",
+            HistoryNormalization::CaseFold => "case_fold",
+            HistoryNormalization::CaseFoldLeet => "case_fold_leet",
+        }
+    }
+
+    /// The normalized form of `password`, or `None` in exact mode.
+    fn apply(self, password: &str) -> Option<String> {
+        let folded = casefold::fold(password);
+        match self {
+            HistoryNormalization::Exact => None,
+            HistoryNormalization::CaseFold => Some(folded),
+            HistoryNormalization::CaseFoldLeet => Some(folded.chars().map(unleet).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    hash: String,
+    /// Hash of the normalized password, in normalized modes.
+    normalized: Option<String>,
+}
+
+impl PasswordHistory {
+    /// Creates an empty history remembering the last `capacity` passwords.
+    pub fn new(capacity: usize) -> Self {
+        PasswordHistory { capacity, normalization: HistoryNormalization::Exact, entries: VecDeque::new() }
+    }
+
+    /// Sets which variants of previous passwords are rejected. Entries
+    /// pushed before a normalized mode was set only match exactly.
+    pub fn normalization(mut self, normalization: HistoryNormalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Stored hashes of the exact passwords, oldest first.
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.hash.as_str())
+    }
+
+    /// Hashes and records `password`, evicting the oldest entry when full.
+    pub fn push<H: PasswordHasher>(&mut self, password: &str, hasher: &H) -> Result<(), H::Error> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let hash = hasher.hash(password)?;
+        let normalized = match self.normalization.apply(password) {
+            Some(normalized) => Some(hasher.hash(&normalized)?),
+            None => None,
+        };
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry { hash, normalized });
+        Ok(())
+    }
+
+    /// Returns whether `password`, or in normalized modes a variant of it,
+    /// matches a stored entry.
+    pub fn contains<H: PasswordHasher>(&self, password: &str, hasher: &H) -> Result<bool, H::Error> {
+        let normalized = self.normalization.apply(password);
+        for entry in &self.entries {
+            if hasher.verify(password, &entry.hash)? {
+                return Ok(true);
+            }
+            if let (Some(candidate), Some(stored)) = (&normalized, &entry.normalized)
+                && hasher.verify(candidate, stored)?
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Serializes the history as `{"capacity":5,"entries":["$argon2id$..."]}`.
+    /// In normalized modes, a `normalization` field names the mode and
+    /// entries are `{"hash":...,"normalized":...}` objects.
+    pub fn to_json(&self) -> String {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| match &entry.normalized {
+                None => Value::String(entry.hash.clone()),
+                Some(normalized) => Value::Object(vec![
+                    ("hash".to_string(), Value::String(entry.hash.clone())),
+                    ("normalized".to_string(), Value::String(normalized.clone())),
+                ]),
+            })
+            .collect();
+        let mut fields = vec![("capacity".to_string(), Value::Number(self.capacity as f64))];
+        if self.normalization != HistoryNormalization::Exact {
+            fields.push(("normalization".to_string(), Value::String(self.normalization.name().to_string())));
+        }
+        fields.push(("entries".to_string(), Value::Array(entries)));
+        Value::Object(fields).to_string()
+    }
+
+    /// Parses a history written by [`to_json`](Self::to_json). Entries
+    /// beyond the capacity are dropped, oldest first.
+    pub fn from_json(input: &str) -> Result<Self, ConfigError> {
+        let root = json::parse(input)?;
+        let invalid = |path: &str, reason: String| ConfigError::InvalidField { path: path.to_string(), reason };
+        let Value::Object(fields) = &root else {
+            return Err(invalid("", format!("expected object, found {}", root.type_name())));
+        };
+        if let Some((key, _)) = fields.iter().find(|(k, _)| !["capacity", "normalization", "entries"].contains(&k.as_str())) {
+            return Err(invalid(key, "unknown field".to_string()));
+        }
+        let capacity = match root.get("capacity") {
+            Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 && *n <= u32::MAX as f64 => *n as usize,
+            Some(other) => return Err(invalid("capacity", format!("expected non-negative integer, found {}", other))),
+            None => return Err(invalid("capacity", "missing field".to_string())),
+        };
+        let normalization = match root.get("normalization") {
+            None => HistoryNormalization::Exact,
+            Some(Value::String(name)) => {
+                let modes = [HistoryNormalization::Exact, HistoryNormalization::CaseFold, HistoryNormalization::CaseFoldLeet];
+                match modes.into_iter().find(|mode| mode.name() == name) {
+                    Some(mode) => mode,
+                    None => return Err(invalid("normalization", format!("unknown normalization `{}`", name))),
+                }
+            }
+            Some(other) => return Err(invalid("normalization", format!("expected string, found {}", other.type_name()))),
+        };
+        let mut history = PasswordHistory::new(capacity).normalization(normalization);
+        let items = match root.get("entries") {
+            Some(Value::Array(items)) => items,
+            Some(other) => return Err(invalid("entries", format!("expected array, found {}", other.type_name()))),
+            None => return Err(invalid("entries", "missing field".to_string())),
+        };
+        for (i, item) in items.iter().enumerate() {
+            let path = format!("entries[{}]", i);
+            let entry = match item {
+                Value::String(hash) => Entry { hash: hash.clone(), normalized: None },
+                Value::Object(_) => match (item.get("hash"), item.get("normalized")) {
+                    (Some(Value::String(hash)), Some(Value::String(normalized))) => {
+                        Entry { hash: hash.clone(), normalized: Some(normalized.clone()) }
+                    }
+                    _ => return Err(invalid(&path, "expected string fields `hash` and `normalized`".to_string())),
+                },
+                other => return Err(invalid(&path, format!("expected string or object, found {}", other.type_name()))),
+            };
+            history.entries.push_back(entry);
+        }
+        while history.entries.len() > capacity {
+            history.entries.pop_front();
+        }
+        Ok(history)
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// Like [`check`](Self::check), additionally rejecting passwords in
+    /// `history` with a `not_in_history` violation after the rules'. Under
+    /// [`dry_run`](Self::dry_run), reuse is let through like the rules'
+    /// violations.
+    pub fn check_with_history<H: PasswordHasher>(
+        &self,
+        password: &str,
+        history: &PasswordHistory,
+        hasher: &H,
+    ) -> Result<(), HashOrValidateError<H::Error>> {
+        self.check_with_history_in(password, history, hasher, None)
+    }
+
+    /// Like [`check_with_history`](Self::check_with_history), rendering
+    /// messages for `options` as
+    /// [`check_with_options`](Self::check_with_options) does.
+    pub fn check_with_history_with_options<H: PasswordHasher>(
+        &self,
+        password: &str,
+        history: &PasswordHistory,
+        hasher: &H,
+        options: &ValidateOptions<'_>,
+    ) -> Result<(), HashOrValidateError<H::Error>> {
+        self.check_with_history_in(password, history, hasher, Some(options))
+    }
+
+    fn check_with_history_in<H: PasswordHasher>(
+        &self,
+        password: &str,
+        history: &PasswordHistory,
+        hasher: &H,
+        options: Option<&ValidateOptions<'_>>,
+    ) -> Result<(), HashOrValidateError<H::Error>> {
+        let mut violations = match self.evaluate(password, None, options) {
+            Ok(()) => Vec::new(),
+            Err(Violations(violations)) => violations,
+        };
+        if history.contains(password, hasher).map_err(HashOrValidateError::Hash)? {
+            let params = vec![("history", ParamValue::Int(history.capacity))];
+            let message = if self.keys_only {
+                String::new()
+            } else {
+                self.render_code("not_in_history", &params, options).unwrap_or_else(|| "Password was used recently; choose a new one.".to_string())
+            };
+            violations.push(Violation { code: "not_in_history", message, params, span: None, group: None });
+        }
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let result = if self.dry_run { self.unstaged(Violations(violations)).0 } else { Err(Violations(violations)) };
+        result.map_err(HashOrValidateError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{Catalog, UserInfoCheck};
+
+    /// Salts with a counter; not a real hash.
+    struct Salted(Cell<u32>);
+
+    impl PasswordHasher for Salted {
+        type Error = String;
+
+        fn hash(&self, password: &str) -> Result<String, String> {
+            let salt = self.0.get();
+            self.0.set(salt + 1);
+            Ok(format!("$test${}${}", salt, digest(salt, password)))
+        }
+
+        fn verify(&self, password: &str, hash: &str) -> Result<bool, String> {
+            let mut parts = hash.split('$').skip(2);
+            let (Some(salt), Some(stored)) = (parts.next(), parts.next()) else {
+                return Err(format!("malformed hash {}", hash));
+            };
+            let salt = salt.parse().map_err(|_| "bad salt".to_string())?;
+            Ok(digest(salt, password) == stored)
+        }
+    }
+
+    fn digest(salt: u32, password: &str) -> String {
+        let h = password.bytes().fold(u64::from(salt) ^ 0xcbf2_9ce4_8422_2325, |h, b| (h ^ u64::from(b)).wrapping_mul(0x100_0000_01b3));
+        format!("{:016x}", h)
+    }
+
+    #[test]
+    fn detects_reuse_after_round_trip() {
+        let hasher = Salted(Cell::new(0));
+        let mut history = PasswordHistory::new(5);
+        history.push("first password 1", &hasher).unwrap();
+        history.push("second password 2", &hasher).unwrap();
+        let restored = PasswordHistory::from_json(&history.to_json()).unwrap();
+        assert_eq!(restored, history);
+        assert_eq!(restored.contains("first password 1", &hasher), Ok(true));
+        assert_eq!(restored.contains("third password 3", &hasher), Ok(false));
+
+        let checker = PasswordChecker::new().min_length(20, None);
+        let Err(HashOrValidateError::Invalid(violations)) = checker.check_with_history("second password 2", &restored, &hasher)
+        else {
+            panic!("reuse accepted");
+        };
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["min_length", "not_in_history"]);
+        assert!(PasswordChecker::new().check_with_history("third password 3", &restored, &hasher).is_ok());
+    }
+
+    #[test]
+    fn reuse_messages_follow_the_checker() {
+        let hasher = Salted(Cell::new(0));
+        let mut history = PasswordHistory::new(3);
+        history.push("second password 2", &hasher).unwrap();
+        let message = |checker: &PasswordChecker<'_>, options: &ValidateOptions<'_>| match checker.check_with_history_with_options("second password 2", &history, &hasher, options) {
+            Err(HashOrValidateError::Invalid(violations)) => violations[0].message.clone(),
+            other => panic!("reuse accepted: {:?}", other),
+        };
+        let checker = PasswordChecker::new();
+        assert_eq!(message(&checker, &ValidateOptions::new()), "Password was used recently; choose a new one.");
+        assert_eq!(message(&checker, &ValidateOptions::new().message("not_in_history", "Pick a new one.")), "Pick a new one.");
+        let localized = PasswordChecker::new().with_catalog(Catalog::new("de").message("not_in_history", "Eines der letzten {history} Passwörter."));
+        assert_eq!(message(&localized, &ValidateOptions::new().locale("de")), "Eines der letzten 3 Passwörter.");
+        assert_eq!(message(&checker.clone().keys_only(true), &ValidateOptions::new()), "");
+        let Err(HashOrValidateError::Invalid(violations)) = checker.keys_only(true).check_with_history("second password 2", &history, &hasher) else {
+            panic!("reuse accepted");
+        };
+        assert!(violations[0].message.is_empty());
+    }
+
+    #[test]
+    fn dry_runs_let_reuse_through() {
+        let hasher = Salted(Cell::new(0));
+        let mut history = PasswordHistory::new(3);
+        history.push("second password 2", &hasher).unwrap();
+        let checker = PasswordChecker::new().min_length(20, None).dry_run(true);
+        assert!(checker.check_with_history("second password 2", &history, &hasher).is_ok());
+        let guarded = checker.not_contain_user_info(UserInfoCheck::new(), None);
+        let Err(HashOrValidateError::Invalid(violations)) = guarded.check_with_history("second password 2", &history, &hasher) else {
+            panic!("context_required let through");
+        };
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["context_required"]);
+    }
+
+    #[test]
+    fn evicts_oldest_at_capacity() {
+        let hasher = Salted(Cell::new(0));
+        let mut history = PasswordHistory::new(2);
+        for password in ["one", "two", "three"] {
+            history.push(password, &hasher).unwrap();
+        }
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.contains("one", &hasher), Ok(false));
+        assert_eq!(history.contains("three", &hasher), Ok(true));
+        let shrunk = PasswordHistory::from_json(&history.to_json().replace("\"capacity\":2", "\"capacity\":1")).unwrap();
+        assert_eq!(shrunk.contains("two", &hasher), Ok(false));
+        assert_eq!(shrunk.contains("three", &hasher), Ok(true));
+    }
+
+    #[test]
+    fn normalized_modes_catch_variants() {
+        let hasher = Salted(Cell::new(0));
+        let variants = |normalization| {
+            let mut history = PasswordHistory::new(3).normalization(normalization);
+            history.push("Summer2024!", &hasher).unwrap();
+            let history = PasswordHistory::from_json(&history.to_json()).unwrap();
+            ["Summer2024!", "summer2024!", "SUMMER2024!", "$umm3r2024!", "Winter2024!"].map(|p| history.contains(p, &hasher).unwrap())
+        };
+        assert_eq!(variants(HistoryNormalization::Exact), [true, false, false, false, false]);
+        assert_eq!(variants(HistoryNormalization::CaseFold), [true, true, true, false, false]);
+        assert_eq!(variants(HistoryNormalization::CaseFoldLeet), [true, true, true, true, false]);
+
+        let mut history = PasswordHistory::new(2).normalization(HistoryNormalization::CaseFold);
+        history.push("Summer2024!", &hasher).unwrap();
+        assert!(history.to_json().contains(r#""normalization":"case_fold","entries":[{"hash":"#));
+    }
+
+    #[test]
+    fn identical_passwords_get_different_hashes() {
+        let hasher = Salted(Cell::new(0));
+        let mut history = PasswordHistory::new(3);
+        history.push("same", &hasher).unwrap();
+        history.push("same", &hasher).unwrap();
+        let entries: Vec<&str> = history.entries().collect();
+        assert_ne!(entries[0], entries[1]);
+        assert!(PasswordHistory::from_json(r#"{"capacity": 2, "entries": [1]}"#).is_err());
+    }
+
+    #[test]
+    fn huge_capacity_allocates_nothing_up_front() {
+        let hasher = Salted(Cell::new(0));
+        let mut history = PasswordHistory::from_json(r#"{"capacity":4294967295,"entries":[]}"#).unwrap();
+        assert_eq!(history.capacity(), u32::MAX as usize);
+        history.push("first password 1", &hasher).unwrap();
+        assert_eq!(history.contains("first password 1", &hasher), Ok(true));
+        assert!(PasswordHistory::new(usize::MAX).is_empty());
+    }
+}
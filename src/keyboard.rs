@@ -0,0 +1,485 @@
+//! Keyboard layouts and detection of keyboard walks such as `qwer` or `1qaz`.
+//!
+//! Built-in layouts are described as rows of keys, each row shifted by its
+//! physical stagger, and two keys are adjacent when they touch on the
+//! keyboard. Shifted characters sit on the same key as their base
+//! character, so `QWER` and `!@#$` are walks too. Adjacency graphs are
+//! built on first use and shared.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+/// A keyboard whose adjacent keys form walks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyboardLayout {
+    /// US QWERTY.
+    Qwerty,
+    /// French AZERTY.
+    Azerty,
+    /// German QWERTZ.
+    Qwertz,
+    /// US Dvorak.
+    Dvorak,
+    /// The numeric keypad, where diagonal keys are adjacent too.
+    NumericKeypad,
+    /// A layout registered with [`KeyboardLayout::custom`].
+    Custom(CustomLayout),
+}
+
+/// A layout built from an adjacency table.
+#[derive(Clone, PartialEq)]
+pub struct CustomLayout {
+    name: String,
+    graph: Arc<Graph>,
+}
+
+/// One row of a built-in layout: base characters, the characters typed
+/// with shift on the same keys (a space where there is none), and the
+/// horizontal offset of the first key in quarter-key units.
+/// Counts the keys rather than listing the adjacency graph.
+impl fmt::Debug for CustomLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomLayout").field("name", &self.name).field("keys", &self.graph.neighbors.len()).finish()
+    }
+}
+
+struct Row(&'static str, &'static str, i16);
+
+const QWERTY: &[Row] = &[
+    Row("`1234567890-=", "~!@#$%^&*()_+", 0),
+    Row("qwertyuiop[]\\", "QWERTYUIOP{}|", 6),
+    Row("asdfghjkl;'", "ASDFGHJKL:\"", 7),
+    Row("zxcvbnm,./", "ZXCVBNM<>?", 9),
+];
+
+const AZERTY: &[Row] = &[
+    Row("²&é\"'(-è_çà)=", " 1234567890°+", 0),
+    Row("azertyuiop^$", "AZERTYUIOP¨£", 6),
+    Row("qsdfghjklmù*", "QSDFGHJKLM%µ", 7),
+    Row("<wxcvbn,;:!", ">WXCVBN?./§", 5),
+];
+
+const QWERTZ: &[Row] = &[
+    Row("^1234567890ß´", "°!\"§$%&/()=?`", 0),
+    Row("qwertzuiopü+", "QWERTZUIOPÜ*", 6),
+    Row("asdfghjklöä#", "ASDFGHJKLÖÄ'", 7),
+    Row("<yxcvbnm,.-", ">YXCVBNM;:_", 5),
+];
+
+const DVORAK: &[Row] = &[
+    Row("`1234567890[]", "~!@#$%^&*(){}", 0),
+    Row("',.pyfgcrl/=\\", "\"<>PYFGCRL?+|", 6),
+    Row("aoeuidhtns-", "AOEUIDHTNS_", 7),
+    Row(";qjkxbmwvz", ":QJKXBMWVZ", 9),
+];
+
+const NUMERIC_KEYPAD: &[Row] = &[
+    Row("/*-", "   ", 4),
+    Row("789+", "    ", 0),
+    Row("456", "   ", 0),
+    Row("123", "   ", 0),
+    Row("0 .", "   ", 0),
+];
+
+/// Where a character is typed: which physical key, and whether shift is held.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct KeyRef {
+    key: usize,
+    shifted: bool,
+}
+
+/// Direction of a step between keys: (row delta, sign of the column delta).
+type Direction = (i8, i8);
+
+/// Adjacency between physical keys.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Graph {
+    chars: HashMap<char, KeyRef>,
+    /// Neighbors of each key, with the direction of the step when the
+    /// layout has geometry.
+    neighbors: Vec<Vec<(usize, Option<Direction>)>>,
+}
+
+impl Graph {
+    fn from_rows(rows: &[Row]) -> Self {
+        let mut graph = Graph::default();
+        let mut positions = Vec::new();
+        for (r, Row(base, shifted, offset)) in rows.iter().enumerate() {
+            for (col, (base, shifted)) in base.chars().zip(shifted.chars()).enumerate() {
+                if base == ' ' {
+                    continue;
+                }
+                let key = positions.len();
+                positions.push((r as i16, offset + 4 * col as i16));
+                graph.chars.insert(base, KeyRef { key, shifted: false });
+                if shifted != ' ' {
+                    graph.chars.entry(shifted).or_insert(KeyRef { key, shifted: true });
+                }
+            }
+        }
+        // Keys touch when they are in the same or a neighboring row and at
+        // most one key width apart. Row staggers are never a multiple of a
+        // key width, so this excludes diagonals on staggered rows but keeps
+        // them on the keypad's square grid.
+        graph.neighbors = positions
+            .iter()
+            .map(|&(row, x)| {
+                positions
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &(r, c))| (r - row).abs() <= 1 && (c - x).abs() <= 4 && (r, c) != (row, x))
+                    .map(|(key, &(r, c))| (key, Some(((r - row) as i8, (c - x).signum() as i8))))
+                    .collect()
+            })
+            .collect();
+        graph
+    }
+
+    fn from_table(table: Vec<(char, Vec<char>)>) -> Self {
+        let mut graph = Graph::default();
+        let key_of = |graph: &mut Graph, c: char| {
+            let next = graph.neighbors.len();
+            let key = graph.chars.entry(c).or_insert(KeyRef { key: next, shifted: false }).key;
+            if key == next {
+                graph.neighbors.push(Vec::new());
+            }
+            key
+        };
+        for (c, neighbors) in table {
+            let a = key_of(&mut graph, c);
+            for n in neighbors {
+                let b = key_of(&mut graph, n);
+                for (from, to) in [(a, b), (b, a)] {
+                    if from != to && !graph.neighbors[from].iter().any(|&(k, _)| k == to) {
+                        graph.neighbors[from].push((to, None));
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    fn is_shifted(&self, c: char) -> bool {
+        self.chars.get(&c).is_some_and(|k| k.shifted)
+    }
+
+    /// Direction of the step from `a` to `b` if they are on adjacent keys.
+    /// `Some(None)` means adjacent in a layout without geometry.
+    fn step(&self, a: char, b: char) -> Option<Option<Direction>> {
+        let (a, b) = (self.chars.get(&a)?, self.chars.get(&b)?);
+        self.neighbors[a.key].iter().find(|&&(k, _)| k == b.key).map(|&(_, dir)| dir)
+    }
+}
+
+impl KeyboardLayout {
+    /// Every built-in layout.
+    pub const BUILT_IN: [KeyboardLayout; 5] = [
+        KeyboardLayout::Qwerty,
+        KeyboardLayout::Azerty,
+        KeyboardLayout::Qwertz,
+        KeyboardLayout::Dvorak,
+        KeyboardLayout::NumericKeypad,
+    ];
+
+    /// Registers a layout from an adjacency table listing the neighbors of
+    /// each key. Adjacency is made symmetric.
+    ///
+    /// ```
+    /// use passcheck::keyboard::KeyboardLayout;
+    ///
+    /// let phone = KeyboardLayout::custom("phone", [('1', "24"), ('2', "35"), ('3', "6"), ('4', "57"), ('5', "68")]);
+    /// assert!(phone.is_adjacent('5', '2'));
+    /// ```
+    pub fn custom<I, N>(name: &str, table: I) -> Self
+    where
+        I: IntoIterator<Item = (char, N)>,
+        N: AsRef<str>,
+    {
+        let table = table.into_iter().map(|(c, n)| (c, n.as_ref().chars().collect())).collect();
+        KeyboardLayout::Custom(CustomLayout { name: name.to_string(), graph: Arc::new(Graph::from_table(table)) })
+    }
+
+    /// Name used in policy files: `qwerty`, `azerty`, `qwertz`, `dvorak`,
+    /// `numeric_keypad`, or the name of a custom layout.
+    pub fn name(&self) -> &str {
+        match self {
+            KeyboardLayout::Qwerty => "qwerty",
+            KeyboardLayout::Azerty => "azerty",
+            KeyboardLayout::Qwertz => "qwertz",
+            KeyboardLayout::Dvorak => "dvorak",
+            KeyboardLayout::NumericKeypad => "numeric_keypad",
+            KeyboardLayout::Custom(custom) => &custom.name,
+        }
+    }
+
+    /// Looks up a built-in layout by [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        KeyboardLayout::BUILT_IN.into_iter().find(|layout| layout.name() == name)
+    }
+
+    /// Returns whether `a` and `b` are typed on touching keys.
+    pub fn is_adjacent(&self, a: char, b: char) -> bool {
+        self.graph().step(a, b).is_some()
+    }
+
+    /// Neighbors of each key, in a stable order, for serialization.
+    pub(crate) fn adjacency(&self) -> Vec<(char, String)> {
+        let graph = self.graph();
+        let mut chars: Vec<(char, KeyRef)> = graph.chars.iter().map(|(&c, &k)| (c, k)).collect();
+        chars.sort_unstable_by_key(|&(c, _)| c);
+        chars
+            .iter()
+            .map(|&(c, k)| {
+                let mut neighbors: Vec<char> = chars
+                    .iter()
+                    .filter(|(_, n)| graph.neighbors[k.key].iter().any(|&(key, _)| key == n.key))
+                    .map(|&(n, _)| n)
+                    .collect();
+                neighbors.sort_unstable();
+                (c, neighbors.into_iter().collect())
+            })
+            .collect()
+    }
+
+    fn graph(&self) -> &Graph {
+        fn cached(cell: &'static OnceLock<Graph>, rows: &[Row]) -> &'static Graph {
+            cell.get_or_init(|| Graph::from_rows(rows))
+        }
+        static GRAPHS: [OnceLock<Graph>; 5] = [const { OnceLock::new() }; 5];
+        match self {
+            KeyboardLayout::Qwerty => cached(&GRAPHS[0], QWERTY),
+            KeyboardLayout::Azerty => cached(&GRAPHS[1], AZERTY),
+            KeyboardLayout::Qwertz => cached(&GRAPHS[2], QWERTZ),
+            KeyboardLayout::Dvorak => cached(&GRAPHS[3], DVORAK),
+            KeyboardLayout::NumericKeypad => cached(&GRAPHS[4], NUMERIC_KEYPAD),
+            KeyboardLayout::Custom(custom) => &custom.graph,
+        }
+    }
+}
+
+/// Which runs of adjacent keys count as a walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalkShape {
+    /// Any chain of adjacent keys, e.g. `qwsa`.
+    #[default]
+    AnyChain,
+    /// Only runs that keep one direction, e.g. `qwer` or `1qaz`. Layouts
+    /// built from an adjacency table have no geometry and are treated as
+    /// [`AnyChain`](Self::AnyChain).
+    Straight,
+}
+
+/// Settings of the keyboard-walk rule.
+///
+/// The defaults are the recommended profile: walks of 4 or more keys on
+/// any built-in layout, counting shifted characters, in any shape,
+/// wherever they appear in the password.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardWalk {
+    /// Layouts checked, in order.
+    pub layouts: Vec<KeyboardLayout>,
+    /// Shortest run of adjacent keys that counts as a walk.
+    pub min_length: usize,
+    /// Whether characters typed with shift count as the key they are on,
+    /// so that `!@#$` and `QWER` are walks.
+    pub include_shifted: bool,
+    /// Whether walks may turn.
+    pub shape: WalkShape,
+    /// Fraction of the password, from 0 to 1, a walk must cover to be
+    /// reported. `0.0` reports walks anywhere.
+    pub min_share: f64,
+}
+
+impl Default for KeyboardWalk {
+    fn default() -> Self {
+        KeyboardWalk {
+            layouts: KeyboardLayout::BUILT_IN.to_vec(),
+            min_length: 4,
+            include_shifted: true,
+            shape: WalkShape::AnyChain,
+            min_share: 0.0,
+        }
+    }
+}
+
+impl KeyboardWalk {
+    /// Same as [`KeyboardWalk::default`].
+    pub fn new() -> Self {
+        KeyboardWalk::default()
+    }
+
+    /// Checks only the given layouts.
+    pub fn layouts(mut self, layouts: impl IntoIterator<Item = KeyboardLayout>) -> Self {
+        self.layouts = layouts.into_iter().collect();
+        self
+    }
+
+    /// Sets [`min_length`](Self::min_length); values below 2 are raised to 2.
+    pub fn min_length(mut self, len: usize) -> Self {
+        self.min_length = len.max(2);
+        self
+    }
+
+    /// Sets [`include_shifted`](Self::include_shifted).
+    pub fn include_shifted(mut self, enabled: bool) -> Self {
+        self.include_shifted = enabled;
+        self
+    }
+
+    /// Sets [`shape`](Self::shape).
+    pub fn shape(mut self, shape: WalkShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets [`min_share`](Self::min_share), clamped to `0.0..=1.0`.
+    pub fn min_share(mut self, share: f64) -> Self {
+        self.min_share = share.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the first walk found in `password`, as a substring.
+    pub fn find<'p>(&self, password: &'p str) -> Option<&'p str> {
+        self.layouts.iter().find_map(|layout| self.find_in(layout.graph(), password))
+    }
+
+    fn find_in<'p>(&self, graph: &Graph, password: &'p str) -> Option<&'p str> {
+        let chars: Vec<(usize, char)> = password.char_indices().collect();
+        let reported = |start: usize, end: usize| {
+            let len = end - start;
+            (len >= self.min_length && len as f64 >= self.min_share * chars.len() as f64).then(|| {
+                let end = chars.get(end).map_or(password.len(), |&(pos, _)| pos);
+                &password[chars[start].0..end]
+            })
+        };
+        let mut start = 0;
+        let mut direction = None;
+        for i in 1..chars.len() {
+            match self.step(graph, chars[i - 1].1, chars[i].1) {
+                None => {
+                    if let Some(walk) = reported(start, i) {
+                        return Some(walk);
+                    }
+                    start = i;
+                    direction = None;
+                }
+                Some(step) => {
+                    // A turn ends a straight walk; the next one starts at
+                    // the key where it turned.
+                    let turned = self.shape == WalkShape::Straight && direction.is_some_and(|d| d != step);
+                    if turned {
+                        if let Some(walk) = reported(start, i) {
+                            return Some(walk);
+                        }
+                        start = i - 1;
+                    }
+                    direction = Some(step);
+                }
+            }
+        }
+        if chars.is_empty() { None } else { reported(start, chars.len()) }
+    }
+
+    fn step(&self, graph: &Graph, a: char, b: char) -> Option<Option<Direction>> {
+        if !self.include_shifted && (graph.is_shifted(a) || graph.is_shifted(b)) {
+            return None;
+        }
+        graph.step(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walks(walk: &KeyboardWalk, password: &str) -> bool {
+        walk.find(password).is_some()
+    }
+
+    #[test]
+    fn canonical_walks_per_layout() {
+        let cases: [(KeyboardLayout, &[&str]); 5] = [
+            (KeyboardLayout::Qwerty, &["qwerty", "asdf", "zxcvbn", "1qaz2wsx", "!@#$", "poiu", "QWER"]),
+            (KeyboardLayout::Azerty, &["azerty", "qsdfgh", "wxcvbn", "aqwx"]),
+            (KeyboardLayout::Qwertz, &["qwertz", "yxcvb", "asdfghjklö"]),
+            (KeyboardLayout::Dvorak, &["aoeuid", "htns", "',.pyf"]),
+            (KeyboardLayout::NumericKeypad, &["7410", "8520", "7896", "9514", "0123"]),
+        ];
+        for (layout, words) in cases {
+            let walk = KeyboardWalk::new().layouts([layout.clone()]);
+            for word in words {
+                assert!(walks(&walk, word), "{} on {}", word, layout.name());
+            }
+        }
+    }
+
+    #[test]
+    fn walks_are_layout_specific() {
+        let qwerty = KeyboardWalk::new().layouts([KeyboardLayout::Qwerty]);
+        let azerty = KeyboardWalk::new().layouts([KeyboardLayout::Azerty]);
+        assert!(!walks(&qwerty, "aqwxc"));
+        assert!(walks(&azerty, "aqwxc"));
+        assert_eq!(azerty.find("azerty123"), Some("azerty"));
+        assert!(!walks(&qwerty, "aoeuid"));
+        assert!(!walks(&qwerty, "7410"));
+        assert!(!walks(&KeyboardWalk::new().layouts([KeyboardLayout::Dvorak]), "qwer"));
+        assert!(walks(&KeyboardWalk::new(), "azerty123"));
+        assert!(!walks(&KeyboardWalk::new(), "correct-horse"));
+    }
+
+    #[test]
+    fn min_length_knob() {
+        let qwerty = KeyboardWalk::new().layouts([KeyboardLayout::Qwerty]);
+        assert!(walks(&qwerty, "zqwer"));
+        assert!(!walks(&qwerty.clone().min_length(5), "zqwer"));
+        assert!(walks(&qwerty.min_length(3), "zqwe"));
+    }
+
+    #[test]
+    fn include_shifted_knob() {
+        let qwerty = KeyboardWalk::new().layouts([KeyboardLayout::Qwerty]);
+        for word in ["!@#$", "QWER", "1@3$"] {
+            assert!(walks(&qwerty, word), "{}", word);
+            assert!(!walks(&qwerty.clone().include_shifted(false), word), "{}", word);
+        }
+        assert!(walks(&qwerty.include_shifted(false), "qwer"));
+    }
+
+    #[test]
+    fn shape_knob() {
+        let qwerty = KeyboardWalk::new().layouts([KeyboardLayout::Qwerty]);
+        let straight = qwerty.clone().shape(WalkShape::Straight);
+        for word in ["qwsa", "wedc", "1qw2"] {
+            assert!(walks(&qwerty, word), "{}", word);
+            assert!(!walks(&straight, word), "{}", word);
+        }
+        for word in ["qwer", "1qaz", "poiu", "xqwerdf"] {
+            assert!(walks(&straight, word), "{}", word);
+        }
+        assert_eq!(straight.find("tyuijk"), Some("tyui"));
+    }
+
+    #[test]
+    fn min_share_knob() {
+        let qwerty = KeyboardWalk::new().layouts([KeyboardLayout::Qwerty]);
+        let half = qwerty.clone().min_share(0.5);
+        assert!(walks(&qwerty, "qwer-correct-horse"));
+        assert!(!walks(&half, "qwer-correct-horse"));
+        assert!(walks(&half, "qwerty1"));
+        assert!(walks(&half, "pas-qwer"));
+        assert!(!walks(&half, "pass-wer2"));
+    }
+
+    #[test]
+    fn min_length_and_custom_layouts() {
+        assert!(!walks(&KeyboardWalk::new(), "qwe"));
+        assert!(walks(&KeyboardWalk::new().min_length(3), "xqwe"));
+        let phone = KeyboardLayout::custom("phone", [('1', "24"), ('2', "35"), ('3', "6"), ('4', "57"), ('5', "68"), ('6', "9")]);
+        let walk = KeyboardWalk::new().layouts([phone.clone()]);
+        assert_eq!(walk.find("x1236"), Some("1236"));
+        assert!(!walks(&walk, "1357"));
+        assert!(phone.is_adjacent('6', '3') && !phone.is_adjacent('1', '5'));
+        assert_eq!(KeyboardLayout::from_name("azerty"), Some(KeyboardLayout::Azerty));
+    }
+}
@@ -0,0 +1,18 @@
+//! Undoing leetspeak substitutions.
+
+/// Returns the letter `c` commonly stands in for, such as `a` for `4` or
+/// `@`, or `c` itself.
+pub(crate) fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        '|' => 'l',
+        c => c,
+    }
+}
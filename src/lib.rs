@@ -1,107 +1,1739 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::context::Containment;
+use crate::prepared::Prepared;
+use crate::stats::{class_run_over, longest_class_run};
+
+mod accents;
+mod address;
+mod analysis;
+#[cfg(any(test, feature = "test-util"))]
+pub mod arbitrary;
+mod audit;
+mod bands;
+mod birthday;
+mod breach;
+mod casefold;
+mod categories;
+mod classes;
+mod config;
+#[cfg(feature = "test-util")]
+pub mod conformance;
+mod context;
+mod custom;
+#[cfg(feature = "confusables")]
+mod confusables;
+pub mod dictionary;
+mod diff;
+mod dsl;
+mod fingerprint;
+mod env;
+mod error;
+mod evidence;
+mod exemption;
+mod features;
+mod format;
+pub mod forms;
+#[cfg(feature = "generate")]
+mod generate;
+mod group;
+mod hash;
+mod history;
+mod json;
+pub mod keyboard;
+mod leet;
+mod limits;
+mod locale;
+#[cfg(feature = "markov")]
+mod markov;
+mod meter;
+mod multi;
+mod multisearch;
+mod observer;
+#[cfg(feature = "generate")]
+mod passphrase;
+pub mod pattern;
+mod phone;
+mod pin;
+mod policy;
+mod prepared;
+mod prescreen;
+mod progress;
+mod registry;
+mod scripts;
+mod service;
+mod session;
+mod spec;
+pub mod special_sets;
+mod similarity;
+mod stats;
+mod strength;
+mod strictness;
+#[cfg(feature = "test-util")]
+pub mod testing;
+mod violation;
+mod words;
+
+pub use analysis::{Analysis, CrackRates, CrackScenario, PatternContribution, format_duration_human};
+pub use audit::{AuditSummary, audit};
+pub use breach::{BreachLookup, BreachProvider, BreachQuery, Breaches, Combine, FailMode, ProviderError, RangeFile};
+pub use config::{ConfigError, LimitExceeded, PolicyConfig};
+pub use context::{UserContext, UserInfoCheck};
+pub use custom::{CustomRule, InvalidCode};
+#[cfg(feature = "confusables")]
+pub use confusables::skeleton;
+pub use dictionary::{Dictionary, MatchMode};
+pub use diff::{PolicyDiff, RuleChange, RuleMeta, diff, explain_new_requirements};
+pub use dsl::parse_policy;
+pub use error::Error;
+pub use evidence::{Evidence, EvidenceOutcome, RuleEvidence};
+pub use exemption::LengthExemption;
+pub use features::{LoadReport, SkippedRule};
+pub use fingerprint::PolicyFingerprint;
+pub use format::{DefaultFormatter, MessageFormatter, PrefixedFormatter};
+#[cfg(feature = "generate")]
+pub use generate::{GenerateError, GenerateOptions, GenerateSpec, GenerateStyle, Generated};
+pub use hash::{HashOrValidateError, PasswordHasher};
+pub use history::{HistoryNormalization, PasswordHistory};
+pub use locale::{Catalog, ValidateOptions};
+pub use keyboard::{KeyboardLayout, KeyboardWalk, WalkShape};
+pub use limits::PolicyLimits;
+#[cfg(feature = "markov")]
+pub use markov::guessability;
+pub use meter::{Meter, MeterSpec, Milestone, Segment};
+pub use multi::{MultiResult, evaluate_all};
+pub use policy::PasswordPolicy;
+pub use prescreen::Prescreen;
+pub use observer::{RuleTiming, Timing, ValidationEvent};
+#[cfg(feature = "generate")]
+pub use passphrase::{PassphraseOptions, Wordlist, generate_passphrase, generate_passphrase_with};
+pub use pattern::{Pattern, PatternError};
+pub use progress::Progress;
+pub use registry::{PolicyRegistry, RegistryError};
+pub use similarity::{Similarity, edit_distance};
+pub use classes::CharClasses;
+pub use session::ValidationSession;
+pub use spec::PolicySpec;
+pub use stats::{CharStats, ClassModes, analyze_chars};
+pub use strength::{Estimator, ScoreWeights, WeakPattern, entropy, shannon_entropy, shannon_entropy_per_char};
+pub use strictness::{Relaxation, StrictnessResult};
+pub use violation::{ParamValue, RuleOutcome, StagedViolations, Violation, ViolationKey, Violations};
+pub use words::DEFAULT_WORD_SEPARATORS;
+
+/// Declares a password policy on a struct field.
+///
+/// ```
+/// use passcheck::PasswordPolicy;
+///
+/// #[derive(PasswordPolicy)]
+/// struct Signup {
+///     email: String,
+///     #[password(min_length = 12, max_length = 128, require_number, require_special)]
+///     password: String,
+/// }
+///
+/// let form = Signup { email: "a@b.c".into(), password: "correct horse 1!".into() };
+/// assert!(form.validate_password().is_ok());
+/// assert_eq!(Signup::password_policy().rules().len(), 4);
+/// ```
+///
+/// Unknown keys and malformed values are compile errors pointing at the attribute:
+///
+/// ```compile_fail
+/// #[derive(passcheck::PasswordPolicy)]
+/// struct Signup {
+///     #[password(min_lenght = 12)]
+///     password: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(passcheck::PasswordPolicy)]
+/// struct Signup {
+///     #[password(min_length = "twelve")]
+///     password: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(passcheck::PasswordPolicy)]
+/// struct Signup {
+///     #[password(require_number = true)]
+///     password: String,
+/// }
+/// ```
+///
+/// ```compile_fail
+/// #[derive(passcheck::PasswordPolicy)]
+/// struct Signup {
+///     password: String,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use passcheck_derive::PasswordPolicy;
+
+/// Allowed special characters for validation, as listed by
+/// [`special_sets::DEFAULT`].
+pub(crate) const SPECIAL_CHARS: [char; 30] = special_sets::default_chars();
+
+/// Which characters [`Rule::RequireSpecialChar`] counts as special.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum SpecialCharPolicy {
+    /// The ASCII punctuation `!@#$%^&*()-_=+[]{}\|;:'",.<>/?`, as in
+    /// [`special_sets::DEFAULT`].
+    #[default]
+    Builtin,
+    /// Exactly these characters.
+    Chars(String),
+    /// Any character in a Unicode Punctuation (`P*`) or Symbol (`S*`)
+    /// general category, such as `¡`, `—` or `€`. Combining marks and
+    /// format characters like the zero-width joiner do not count.
+    UnicodeCategories,
+}
+
+impl SpecialCharPolicy {
+    /// Whether `c` counts as special.
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            SpecialCharPolicy::Builtin => SPECIAL_CHARS.contains(&c),
+            SpecialCharPolicy::Chars(chars) => chars.contains(c),
+            SpecialCharPolicy::UnicodeCategories => categories::is_punctuation_or_symbol(c),
+        }
+    }
+}
+
+/// Input limit of bcrypt, in bytes.
+const BCRYPT_MAX_BYTES: usize = 72;
+const BCRYPT_MESSAGE: &str = "Password must be at most 72 bytes long; longer passwords cannot be stored safely.";
+
+/// Default for [`PasswordChecker::max_input_bytes`]: 1 MiB.
+pub const DEFAULT_MAX_INPUT_BYTES: usize = 1 << 20;
+
+/// Returns the byte offset and value of the first NUL or Unicode
+/// non-character (U+FDD0 to U+FDEF, and the last two code points of every
+/// plane) in `password`.
+fn find_unsafe_char(password: &str) -> Option<(usize, char)> {
+    password.char_indices().find(|&(_, c)| {
+        let c = c as u32;
+        c == 0 || (0xFDD0..=0xFDEF).contains(&c) || c & 0xFFFE == 0xFFFE
+    })
+}
+
 /// Password validation rules with optional custom error messages.
-#[derive(Debug)]
+///
+/// Messages are borrowed when set through the builder and owned when the
+/// rule was loaded from a [`PolicyConfig`].
+#[derive(Clone, PartialEq)]
 pub enum Rule<'a> {
-    MinLength(usize, Option<&'a str>),
-    RequireUpperLower(Option<&'a str>),
-    RequireNumber(Option<&'a str>),
-    RequireSpecialChar(Option<&'a str>),
+    /// Rejects passwords shorter than this many characters (Unicode
+    /// scalar values).
+    MinLength(usize, Option<Cow<'a, str>>),
+    /// Rejects passwords longer than this many characters.
+    MaxLength(usize, Option<Cow<'a, str>>),
+    /// Rejects passwords longer than this many UTF-8 bytes, unlike
+    /// [`MaxLength`](Self::MaxLength), which counts characters.
+    MaxBytes(usize, Option<Cow<'a, str>>),
+    /// Warns about passwords shorter than this, measured as for
+    /// [`MinLength`](Self::MinLength), without rejecting them; see
+    /// [`PasswordChecker::check_with_warnings`].
+    RecommendedLength(usize, Option<Cow<'a, str>>),
+    RequireUpperLower(Option<Cow<'a, str>>),
+    RequireNumber(Option<Cow<'a, str>>),
+    /// Requires a character counted as special by the [`SpecialCharPolicy`].
+    RequireSpecialChar(SpecialCharPolicy, Option<Cow<'a, str>>),
+    Blacklist(Dictionary, Option<Cow<'a, str>>),
+    MustMatch(Pattern, Option<Cow<'a, str>>),
+    MustNotMatch(Pattern, Option<Cow<'a, str>>),
+    KeyboardWalk(KeyboardWalk, Option<Cow<'a, str>>),
+    MinEntropy(f64, Estimator, Option<Cow<'a, str>>),
+    /// Rejects NUL and Unicode non-characters, which some hashing libraries
+    /// and C bindings truncate at or mangle.
+    ForbidUnsafeChars(Option<Cow<'a, str>>),
+    /// Rejects passwords too similar to the [previous
+    /// password](UserContext::previous_password). Only checked with a
+    /// [`UserContext`] that has one.
+    NotSimilarToPrevious(Similarity, Option<Cow<'a, str>>),
+    /// Rejects passwords containing the username, name or parts of the
+    /// email address in the [`UserContext`].
+    NotContainUserInfo(UserInfoCheck, Option<Cow<'a, str>>),
+    /// Rejects passwords containing the name of the service they are for,
+    /// ignoring case, spacing, punctuation and leetspeak, or a one-typo
+    /// variant of names of six or more characters.
+    ServiceNames(Vec<String>, Option<Cow<'a, str>>),
+    /// Accepts passwords of `min_len_strong` characters whose [`entropy`] is
+    /// at least `min_entropy_bits`, and any password of `min_len_fallback`
+    /// characters.
+    LengthOrEntropy { min_len_strong: usize, min_entropy_bits: f64, min_len_fallback: usize, msg: Option<Cow<'a, str>> },
+    /// Rejects more than this many consecutive letters, digits or special
+    /// characters, as in `Aaaaaaaaaaaa1!`.
+    MaxClassRun(usize, Option<Cow<'a, str>>),
+    /// Requires this many words of two or more characters, separated by
+    /// whitespace or one of the characters of the string.
+    MinWords(usize, String, Option<Cow<'a, str>>),
+    /// Rejects passwords made only of Unicode digits, such as `19851985`.
+    NotAllNumeric(Option<Cow<'a, str>>),
+    /// Rejects passwords made only of Unicode letters.
+    NotAllAlphabetic(Option<Cow<'a, str>>),
+    /// Requires ASCII digits only, as in a PIN.
+    DigitsOnly(Option<Cow<'a, str>>),
+    /// Rejects three or more digits counting up or down by one, such as
+    /// `1234` or `9876`.
+    NotSequentialDigits(Option<Cow<'a, str>>),
+    /// Rejects one character repeated, such as `1111`.
+    NotRepeatedChar(Option<Cow<'a, str>>),
+    /// Rejects passwords that read the same backwards, such as `1221`.
+    NotPalindrome(Option<Cow<'a, str>>),
+    /// Rejects runs of seven or more digits, which look like a phone
+    /// number, ignoring single spaces or dashes between digits.
+    NoPhonePatterns(Option<Cow<'a, str>>),
+    /// Rejects passwords containing the [phone number](UserContext::phone)
+    /// of the [`UserContext`], however either is formatted.
+    NotContainPhoneNumber(Option<Cow<'a, str>>),
+    /// Rejects passwords containing the [date of
+    /// birth](UserContext::date_of_birth) of the [`UserContext`].
+    NotContainDateOfBirth(Option<Cow<'a, str>>),
+    /// Rejects passwords that are an email address or start like a web
+    /// address, as pasted by a misfiring autofill.
+    NotEmailOrUrl(Option<Cow<'a, str>>),
+    /// Rejects letters from more than one Unicode script, such as Latin
+    /// and Cyrillic in `pаssword`.
+    SingleScriptOnly(Option<Cow<'a, str>>),
+    /// A rule defined by the application, reported under its own code.
+    Custom(CustomRule, Option<Cow<'a, str>>),
+    /// Rejects passwords that [breach providers](BreachProvider) report as
+    /// compromised.
+    NotBreached(Breaches, Option<Cow<'a, str>>),
+}
+
+/// Characters of a custom message shown by the `Debug` output of a rule.
+const DEBUG_MESSAGE_CHARS: usize = 40;
+
+/// A custom message in `Debug` output, cut after [`DEBUG_MESSAGE_CHARS`].
+struct Truncated<'m>(&'m str);
+
+impl fmt::Debug for Truncated<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.char_indices().nth(DEBUG_MESSAGE_CHARS) {
+            Some((end, _)) => fmt::Debug::fmt(&format!("{}…", &self.0[..end]), f),
+            None => fmt::Debug::fmt(self.0, f),
+        }
+    }
+}
+
+/// A collection in `Debug` output, as its length.
+struct Count(&'static str, usize);
+
+impl fmt::Debug for Count {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.0, self.1)
+    }
+}
+
+/// Like the derived output, but bounded whatever the rule holds:
+/// dictionaries and service names are counted, custom rules show their
+/// code and custom messages are truncated, so checkers can be logged.
+impl fmt::Debug for Rule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = self.custom_message().map(Truncated);
+        let names;
+        let (name, fields): (&str, Vec<&dyn fmt::Debug>) = match self {
+            Rule::MinLength(len, _) => ("MinLength", vec![len]),
+            Rule::MaxLength(len, _) => ("MaxLength", vec![len]),
+            Rule::MaxBytes(len, _) => ("MaxBytes", vec![len]),
+            Rule::RecommendedLength(len, _) => ("RecommendedLength", vec![len]),
+            Rule::RequireUpperLower(_) => ("RequireUpperLower", vec![]),
+            Rule::RequireNumber(_) => ("RequireNumber", vec![]),
+            Rule::RequireSpecialChar(policy, _) => ("RequireSpecialChar", vec![policy]),
+            Rule::Blacklist(words, _) => ("Blacklist", vec![words]),
+            Rule::MustMatch(pattern, _) => ("MustMatch", vec![pattern]),
+            Rule::MustNotMatch(pattern, _) => ("MustNotMatch", vec![pattern]),
+            Rule::KeyboardWalk(walk, _) => ("KeyboardWalk", vec![walk]),
+            Rule::MinEntropy(bits, estimator, _) => ("MinEntropy", vec![bits, estimator]),
+            Rule::ForbidUnsafeChars(_) => ("ForbidUnsafeChars", vec![]),
+            Rule::NotSimilarToPrevious(similarity, _) => ("NotSimilarToPrevious", vec![similarity]),
+            Rule::NotContainUserInfo(check, _) => ("NotContainUserInfo", vec![check]),
+            Rule::ServiceNames(service_names, _) => {
+                names = Count("names", service_names.len());
+                ("ServiceNames", vec![&names])
+            }
+            Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, .. } => {
+                return f
+                    .debug_struct("LengthOrEntropy")
+                    .field("min_len_strong", min_len_strong)
+                    .field("min_entropy_bits", min_entropy_bits)
+                    .field("min_len_fallback", min_len_fallback)
+                    .field("msg", &msg)
+                    .finish();
+            }
+            Rule::MaxClassRun(len, _) => ("MaxClassRun", vec![len]),
+            Rule::MinWords(n, separators, _) => ("MinWords", vec![n, separators]),
+            Rule::NotAllNumeric(_) => ("NotAllNumeric", vec![]),
+            Rule::NotAllAlphabetic(_) => ("NotAllAlphabetic", vec![]),
+            Rule::DigitsOnly(_) => ("DigitsOnly", vec![]),
+            Rule::NotSequentialDigits(_) => ("NotSequentialDigits", vec![]),
+            Rule::NotRepeatedChar(_) => ("NotRepeatedChar", vec![]),
+            Rule::NotPalindrome(_) => ("NotPalindrome", vec![]),
+            Rule::NoPhonePatterns(_) => ("NoPhonePatterns", vec![]),
+            Rule::NotContainPhoneNumber(_) => ("NotContainPhoneNumber", vec![]),
+            Rule::NotContainDateOfBirth(_) => ("NotContainDateOfBirth", vec![]),
+            Rule::NotEmailOrUrl(_) => ("NotEmailOrUrl", vec![]),
+            Rule::SingleScriptOnly(_) => ("SingleScriptOnly", vec![]),
+            Rule::Custom(rule, _) => ("Custom", vec![rule]),
+            Rule::NotBreached(breaches, _) => ("NotBreached", vec![breaches]),
+        };
+        let mut tuple = f.debug_tuple(name);
+        for field in fields {
+            tuple.field(field);
+        }
+        tuple.field(&msg).finish()
+    }
+}
+
+impl Rule<'_> {
+    /// Returns the custom message, or the default one for this rule.
+    pub fn message(&self) -> String {
+        self.custom_message().map_or_else(|| self.default_message(), str::to_string)
+    }
+
+    /// The message given to the builder, if any.
+    fn custom_message(&self) -> Option<&str> {
+        match self {
+            Rule::MinLength(_, Some(msg))
+            | Rule::MaxLength(_, Some(msg))
+            | Rule::MaxBytes(_, Some(msg))
+            | Rule::RecommendedLength(_, Some(msg))
+            | Rule::RequireUpperLower(Some(msg))
+            | Rule::RequireNumber(Some(msg))
+            | Rule::RequireSpecialChar(_, Some(msg))
+            | Rule::Blacklist(_, Some(msg))
+            | Rule::MustMatch(_, Some(msg))
+            | Rule::MustNotMatch(_, Some(msg))
+            | Rule::KeyboardWalk(_, Some(msg))
+            | Rule::MinEntropy(_, _, Some(msg))
+            | Rule::ForbidUnsafeChars(Some(msg))
+            | Rule::NotSimilarToPrevious(_, Some(msg))
+            | Rule::NotContainUserInfo(_, Some(msg))
+            | Rule::ServiceNames(_, Some(msg))
+            | Rule::LengthOrEntropy { msg: Some(msg), .. }
+            | Rule::MaxClassRun(_, Some(msg))
+            | Rule::MinWords(_, _, Some(msg))
+            | Rule::NotAllNumeric(Some(msg))
+            | Rule::NotAllAlphabetic(Some(msg))
+            | Rule::DigitsOnly(Some(msg))
+            | Rule::NotSequentialDigits(Some(msg))
+            | Rule::NotRepeatedChar(Some(msg))
+            | Rule::NotPalindrome(Some(msg))
+            | Rule::NoPhonePatterns(Some(msg))
+            | Rule::NotContainPhoneNumber(Some(msg))
+            | Rule::NotContainDateOfBirth(Some(msg))
+            | Rule::SingleScriptOnly(Some(msg))
+            | Rule::NotEmailOrUrl(Some(msg))
+            | Rule::Custom(_, Some(msg))
+            | Rule::NotBreached(_, Some(msg)) => Some(msg),
+            _ => None,
+        }
+    }
+
+    /// The built-in message, without details of a specific password.
+    pub(crate) fn default_message(&self) -> String {
+        match self {
+            Rule::MinLength(len, _) => format!("Password must be at least {} characters long.", len),
+            Rule::MaxLength(len, _) => format!("Password must be at most {} characters long.", len),
+            Rule::MaxBytes(len, _) => format!("Password must be at most {} bytes long.", len),
+            Rule::RecommendedLength(len, _) => format!("Consider a password of at least {} characters; longer is stronger.", len),
+            Rule::RequireUpperLower(_) => "Password must include both uppercase and lowercase letters.".to_string(),
+            Rule::RequireNumber(_) => "Password must include at least one number.".to_string(),
+            Rule::RequireSpecialChar(SpecialCharPolicy::Chars(chars), _) => {
+                format!("Password must include at least one of these characters: {}", chars)
+            }
+            Rule::RequireSpecialChar(..) => "Password must include at least one special character.".to_string(),
+            Rule::Blacklist(_, _) => "Password is too common.".to_string(),
+            Rule::MustMatch(_, _) => "Password does not match the required pattern.".to_string(),
+            Rule::MustNotMatch(_, _) => "Password contains a forbidden pattern.".to_string(),
+            Rule::KeyboardWalk(_, _) => "Password must not contain keyboard patterns.".to_string(),
+            Rule::MinEntropy(..) => "Password is too easy to guess; make it longer or less predictable.".to_string(),
+            Rule::ForbidUnsafeChars(_) => "Password contains a character that cannot be stored safely.".to_string(),
+            Rule::NotSimilarToPrevious(_, _) => "Password is too similar to your previous password.".to_string(),
+            Rule::NotContainUserInfo(_, _) => "Password must not contain your name, username or email address.".to_string(),
+            Rule::ServiceNames(_, _) => "Password must not contain the name of this service.".to_string(),
+            Rule::LengthOrEntropy { min_len_strong, min_len_fallback, .. } => format!(
+                "Password must be at least {} characters long, or at least {} characters if it is random enough to be hard to guess.",
+                min_len_fallback, min_len_strong
+            ),
+            Rule::MaxClassRun(len, _) => {
+                format!("Password must not have more than {} letters, digits or special characters in a row.", len)
+            }
+            Rule::MinWords(n, _, _) => format!("Password must have at least {} words.", n),
+            Rule::NotAllNumeric(_) => "Password must not consist only of digits.".to_string(),
+            Rule::NotAllAlphabetic(_) => "Password must not consist only of letters.".to_string(),
+            Rule::DigitsOnly(_) => "Password must consist only of digits.".to_string(),
+            Rule::NotSequentialDigits(_) => "Password must not be a sequence of consecutive digits.".to_string(),
+            Rule::NotRepeatedChar(_) => "Password must not be one character repeated.".to_string(),
+            Rule::NotPalindrome(_) => "Password must not read the same backwards.".to_string(),
+            Rule::NoPhonePatterns(_) => "Password must not contain a phone number or other long run of digits.".to_string(),
+            Rule::NotContainPhoneNumber(_) => "Password must not contain your phone number.".to_string(),
+            Rule::NotContainDateOfBirth(_) => "Password must not contain your date of birth.".to_string(),
+            Rule::NotEmailOrUrl(_) => "Password must not be an email or web address.".to_string(),
+            Rule::SingleScriptOnly(_) => "Password must not mix letters from different alphabets.".to_string(),
+            Rule::Custom(rule, _) => format!("Password does not meet the `{}` requirement.", rule.code()),
+            Rule::NotBreached(..) => "Password has appeared in a data breach.".to_string(),
+        }
+    }
+
+    /// Returns whether `password` breaks this rule.
+    pub(crate) fn fails(&self, password: &str) -> bool {
+        match self {
+            Rule::MinLength(len, _) => password.chars().count() < *len,
+            Rule::MaxLength(len, _) => password.chars().count() > *len,
+            Rule::MaxBytes(len, _) => password.len() > *len,
+            // Only warns, through `warns`.
+            Rule::RecommendedLength(..) => false,
+            Rule::RequireUpperLower(_) => {
+                !password.chars().any(|c| c.is_ascii_uppercase()) ||
+                !password.chars().any(|c| c.is_ascii_lowercase())
+            }
+            Rule::RequireNumber(_) => !password.chars().any(|c| c.is_ascii_digit()),
+            Rule::RequireSpecialChar(policy, _) => !password.chars().any(|c| policy.contains(c)),
+            Rule::Blacklist(words, _) => words.matches(password),
+            Rule::MustMatch(pattern, _) => !pattern.is_match(password),
+            Rule::MustNotMatch(pattern, _) => pattern.is_match(password),
+            Rule::KeyboardWalk(walk, _) => walk.find(password).is_some(),
+            Rule::MinEntropy(bits, estimator, _) => estimator.bits(password) < *bits,
+            Rule::ForbidUnsafeChars(_) => find_unsafe_char(password).is_some(),
+            Rule::NotSimilarToPrevious(..) | Rule::NotContainUserInfo(..) | Rule::NotContainPhoneNumber(_) | Rule::NotContainDateOfBirth(_) => false,
+            Rule::ServiceNames(names, _) => service::contains_service_name(password, names),
+            Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, .. } => {
+                let chars = password.chars().count();
+                chars < *min_len_fallback && (chars < *min_len_strong || entropy(password) < *min_entropy_bits)
+            }
+            // Decided by `Prepared`, which knows the checker's special set.
+            Rule::MaxClassRun(..) => false,
+            Rule::MinWords(n, separators, _) => words::count_words(password, separators) < *n,
+            // Empty passwords are left to the length rules.
+            Rule::NotAllNumeric(_) => !password.is_empty() && password.chars().all(char::is_numeric),
+            Rule::NotAllAlphabetic(_) => !password.is_empty() && password.chars().all(char::is_alphabetic),
+            Rule::DigitsOnly(_) => !password.bytes().all(|b| b.is_ascii_digit()),
+            Rule::NotSequentialDigits(_) => pin::is_sequential(password),
+            Rule::NotRepeatedChar(_) => pin::is_one_repeated(password),
+            Rule::NotPalindrome(_) => pin::is_palindrome(password),
+            Rule::NoPhonePatterns(_) => phone::longest_digit_run(password) >= phone::MIN_DIGITS,
+            Rule::NotEmailOrUrl(_) => address::detect(password).is_some(),
+            Rule::SingleScriptOnly(_) => !scripts::is_single_script(&scripts::scripts(password)),
+            Rule::Custom(rule, _) => rule.fails(password),
+            Rule::NotBreached(breaches, _) => breaches.rejects(password),
+        }
+    }
+
+    /// Like [`fails`](Self::fails), also checking the rules that need what
+    /// `context` knows about the user.
+    pub(crate) fn fails_in(&self, password: &str, context: Option<&UserContext<'_>>) -> bool {
+        match (self, context) {
+            (Rule::NotSimilarToPrevious(similarity, _), Some(context)) => {
+                context.previous_password.is_some_and(|previous| similarity.too_similar(password, previous))
+            }
+            (Rule::NotContainUserInfo(check, _), Some(context)) => check.fails(password, context),
+            (Rule::NotContainPhoneNumber(_), Some(context)) => context.phone.is_some_and(|phone| phone::contains_number(password, phone)),
+            (Rule::NotContainDateOfBirth(_), Some(context)) => context.date_of_birth.is_some_and(|dob| dob.found_in(password)),
+            _ => self.fails(password),
+        }
+    }
+
+    /// Whether this rule warns about `password` rather than rejecting it.
+    fn warns(&self, password: &str) -> bool {
+        matches!(self, Rule::RecommendedLength(len, _) if password.chars().count() < *len)
+    }
+
+    /// Whether this rule compares the password with a [`UserContext`].
+    fn needs_context(&self) -> bool {
+        matches!(self, Rule::NotSimilarToPrevious(..) | Rule::NotContainUserInfo(..) | Rule::NotContainPhoneNumber(_) | Rule::NotContainDateOfBirth(_))
+    }
+
+    /// Returns whether the confusable skeleton of a password breaks this
+    /// rule. Only rules that forbid content look at the skeleton.
+    #[cfg(feature = "confusables")]
+    fn fails_skeleton(&self, skeleton: &str) -> bool {
+        match self {
+            Rule::Blacklist(..) | Rule::MustNotMatch(..) => self.fails(skeleton),
+            _ => false,
+        }
+    }
+
+    /// Values describing how `password` breaks this rule, reported in
+    /// [`Violation::params`].
+    fn params(&self, password: &str, context: Option<&UserContext<'_>>, special: &SpecialCharPolicy) -> Vec<(&'static str, ParamValue)> {
+        match self {
+            Rule::MinLength(len, _) | Rule::RecommendedLength(len, _) => {
+                vec![("min", ParamValue::Int(*len)), ("actual", ParamValue::Int(password.chars().count()))]
+            }
+            Rule::MaxLength(len, _) => vec![("max", ParamValue::Int(*len)), ("actual", ParamValue::Int(password.chars().count()))],
+            Rule::MaxBytes(len, _) => vec![("max", ParamValue::Int(*len)), ("actual", ParamValue::Int(password.len()))],
+            Rule::MaxClassRun(len, _) => vec![("max", ParamValue::Int(*len)), ("actual", ParamValue::Int(longest_class_run(password, special)))],
+            Rule::MinWords(n, separators, _) => {
+                vec![("min", ParamValue::Int(*n)), ("actual", ParamValue::Int(words::count_words(password, separators)))]
+            }
+            Rule::NoPhonePatterns(_) => vec![("digits", ParamValue::Int(phone::longest_digit_run(password)))],
+            Rule::RequireUpperLower(_) => {
+                let cases = [("uppercase", char::is_ascii_uppercase as fn(&char) -> bool), ("lowercase", char::is_ascii_lowercase)];
+                let missing = cases.into_iter().filter(|(_, is_case)| !password.chars().any(|c| is_case(&c))).map(|(case, _)| case);
+                vec![("missing", ParamValue::List(missing.collect()))]
+            }
+            Rule::MinEntropy(bits, estimator, _) => {
+                vec![("min", ParamValue::Float(*bits)), ("actual", ParamValue::Float(estimator.bits(password)))]
+            }
+            Rule::ForbidUnsafeChars(_) => match find_unsafe_char(password) {
+                Some((offset, c)) => vec![
+                    ("codepoint", ParamValue::Int(c as usize)),
+                    ("offset", ParamValue::Int(offset)),
+                    ("position", ParamValue::Int(password[..offset].chars().count())),
+                ],
+                None => Vec::new(),
+            },
+            Rule::NotContainUserInfo(check, _) => match context.and_then(|context| check.find(password, context)) {
+                Some(found) => vec![("contained_in", ParamValue::Int(usize::from(found == Containment::ContainedIn)))],
+                None => Vec::new(),
+            },
+            Rule::NotEmailOrUrl(_) => address::detect(password).map(|shape| vec![("shape", ParamValue::Name(shape))]).unwrap_or_default(),
+            Rule::SingleScriptOnly(_) => vec![("scripts", ParamValue::List(scripts::scripts(password)))],
+            Rule::Custom(rule, _) => rule.violation_params(password),
+            Rule::NotBreached(breaches, _) => match breaches.lookup(password) {
+                BreachLookup::Breached(count) => vec![("count", ParamValue::Int(usize::try_from(count).unwrap_or(usize::MAX)))],
+                BreachLookup::Unavailable(errors) => vec![("unavailable", ParamValue::Int(errors.len()))],
+                BreachLookup::Clean => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Characters of `password` the failure is about, reported in
+    /// [`Violation::span`]; `None` for rules about the whole password.
+    fn span(&self, password: &str, special: &SpecialCharPolicy) -> Option<Range<usize>> {
+        let chars_before = |offset: usize| password[..offset].chars().count();
+        match self {
+            Rule::ForbidUnsafeChars(_) => find_unsafe_char(password).map(|(offset, _)| chars_before(offset)).map(|i| i..i + 1),
+            Rule::KeyboardWalk(walk, _) => walk.find(password).map(|found| {
+                let start = chars_before(found.as_ptr() as usize - password.as_ptr() as usize);
+                start..start + found.chars().count()
+            }),
+            Rule::MaxClassRun(len, _) => class_run_over(password, *len, special),
+            Rule::NoPhonePatterns(_) => phone::phone_like_span(password),
+            _ => None,
+        }
+    }
+
+    /// The requirement as listed by [`PasswordChecker::describe`].
+    pub(crate) fn description(&self) -> String {
+        match self {
+            Rule::MinLength(len, _) => format!("At least {} characters", len),
+            Rule::MaxLength(len, _) => format!("At most {} characters", len),
+            Rule::MaxBytes(len, _) => format!("At most {} bytes", len),
+            Rule::RecommendedLength(len, _) => format!("At least {} characters recommended", len),
+            Rule::RequireUpperLower(_) => "Both uppercase and lowercase letters".to_string(),
+            Rule::RequireNumber(_) => "At least one number".to_string(),
+            Rule::RequireSpecialChar(SpecialCharPolicy::Chars(chars), _) => format!("At least one of the characters `{}`", chars),
+            Rule::RequireSpecialChar(SpecialCharPolicy::UnicodeCategories, _) => "At least one punctuation mark or symbol".to_string(),
+            Rule::RequireSpecialChar(..) => "At least one special character".to_string(),
+            Rule::Blacklist(..) => "Not a commonly used password".to_string(),
+            Rule::MustMatch(pattern, _) => format!("Matches the pattern `{}`", pattern.as_str()),
+            Rule::MustNotMatch(pattern, _) => format!("Does not match the pattern `{}`", pattern.as_str()),
+            Rule::KeyboardWalk(walk, _) => format!("No runs of {} or more adjacent keyboard keys", walk.min_length),
+            Rule::MinEntropy(bits, _, _) => format!("At least {} bits of estimated strength", bits),
+            Rule::ForbidUnsafeChars(_) => "No NUL characters or Unicode non-characters".to_string(),
+            Rule::NotSimilarToPrevious(Similarity::MaxRatio(ratio), _) => {
+                format!("At most {}% similar to the previous password", ratio * 100.0)
+            }
+            Rule::NotSimilarToPrevious(Similarity::MinEdits(edits), _) => {
+                format!("At least {} changes from the previous password", edits)
+            }
+            Rule::NotContainUserInfo(..) => "Does not contain your name, username or email address".to_string(),
+            Rule::ServiceNames(..) => "Does not contain the name of this service".to_string(),
+            Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, .. } => format!(
+                "At least {} characters, or {} characters with {} bits of estimated entropy",
+                min_len_fallback, min_len_strong, min_entropy_bits
+            ),
+            Rule::MaxClassRun(len, _) => format!("At most {} letters, digits or special characters in a row", len),
+            Rule::MinWords(n, _, _) => format!("At least {} words", n),
+            Rule::NotAllNumeric(_) => "Not only digits".to_string(),
+            Rule::NotAllAlphabetic(_) => "Not only letters".to_string(),
+            Rule::DigitsOnly(_) => "Only digits".to_string(),
+            Rule::NotSequentialDigits(_) => "Not consecutive digits such as 1234".to_string(),
+            Rule::NotRepeatedChar(_) => "Not one character repeated".to_string(),
+            Rule::NotPalindrome(_) => "Not the same backwards".to_string(),
+            Rule::NoPhonePatterns(_) => "No phone numbers or other runs of 7 or more digits".to_string(),
+            Rule::NotContainPhoneNumber(_) => "Does not contain your phone number".to_string(),
+            Rule::NotContainDateOfBirth(_) => "Does not contain your date of birth".to_string(),
+            Rule::NotEmailOrUrl(_) => "Not an email or web address".to_string(),
+            Rule::SingleScriptOnly(_) => "Letters from a single alphabet".to_string(),
+            Rule::Custom(rule, _) => format!("Passes the `{}` check", rule.code()),
+            Rule::NotBreached(..) => "Not found in known data breaches".to_string(),
+        }
+    }
+
+    /// Stable machine-readable identifier used in [`Violation::code`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            Rule::MinLength(..) => "min_length",
+            Rule::MaxLength(..) => "max_length",
+            Rule::MaxBytes(..) => "max_bytes",
+            Rule::RecommendedLength(..) => "recommended_length",
+            Rule::RequireUpperLower(_) => "require_upper_lower",
+            Rule::RequireNumber(_) => "require_number",
+            Rule::RequireSpecialChar(..) => "require_special_char",
+            Rule::Blacklist(..) => "blacklist",
+            Rule::MustMatch(..) => "must_match",
+            Rule::MustNotMatch(..) => "must_not_match",
+            Rule::KeyboardWalk(..) => "keyboard_walk",
+            Rule::MinEntropy(..) => "min_entropy",
+            Rule::ForbidUnsafeChars(_) => "forbid_unsafe_chars",
+            Rule::NotSimilarToPrevious(..) => "not_similar_to_previous",
+            Rule::NotContainUserInfo(..) => "not_contain_user_info",
+            Rule::ServiceNames(..) => "service_names",
+            Rule::LengthOrEntropy { .. } => "length_or_entropy",
+            Rule::MaxClassRun(..) => "max_class_run",
+            Rule::MinWords(..) => "min_words",
+            Rule::NotAllNumeric(_) => "not_all_numeric",
+            Rule::NotAllAlphabetic(_) => "not_all_alphabetic",
+            Rule::DigitsOnly(_) => "digits_only",
+            Rule::NotSequentialDigits(_) => "not_sequential_digits",
+            Rule::NotRepeatedChar(_) => "not_repeated_char",
+            Rule::NotPalindrome(_) => "not_palindrome",
+            Rule::NoPhonePatterns(_) => "no_phone_patterns",
+            Rule::NotContainPhoneNumber(_) => "not_contain_phone_number",
+            Rule::NotContainDateOfBirth(_) => "not_contain_date_of_birth",
+            Rule::NotEmailOrUrl(_) => "not_email_or_url",
+            Rule::SingleScriptOnly(_) => "single_script_only",
+            Rule::Custom(rule, _) => rule.code(),
+            Rule::NotBreached(..) => "not_breached",
+        }
+    }
+
+    /// Converts the rule into one that owns its custom message.
+    pub fn into_owned(self) -> Rule<'static> {
+        fn own(msg: Option<Cow<'_, str>>) -> Option<Cow<'static, str>> {
+            msg.map(|m| Cow::Owned(m.into_owned()))
+        }
+        match self {
+            Rule::MinLength(len, msg) => Rule::MinLength(len, own(msg)),
+            Rule::MaxLength(len, msg) => Rule::MaxLength(len, own(msg)),
+            Rule::MaxBytes(len, msg) => Rule::MaxBytes(len, own(msg)),
+            Rule::RecommendedLength(len, msg) => Rule::RecommendedLength(len, own(msg)),
+            Rule::RequireUpperLower(msg) => Rule::RequireUpperLower(own(msg)),
+            Rule::RequireNumber(msg) => Rule::RequireNumber(own(msg)),
+            Rule::RequireSpecialChar(policy, msg) => Rule::RequireSpecialChar(policy, own(msg)),
+            Rule::Blacklist(words, msg) => Rule::Blacklist(words, own(msg)),
+            Rule::MustMatch(pattern, msg) => Rule::MustMatch(pattern, own(msg)),
+            Rule::MustNotMatch(pattern, msg) => Rule::MustNotMatch(pattern, own(msg)),
+            Rule::KeyboardWalk(walk, msg) => Rule::KeyboardWalk(walk, own(msg)),
+            Rule::MinEntropy(bits, estimator, msg) => Rule::MinEntropy(bits, estimator, own(msg)),
+            Rule::ForbidUnsafeChars(msg) => Rule::ForbidUnsafeChars(own(msg)),
+            Rule::NotSimilarToPrevious(similarity, msg) => Rule::NotSimilarToPrevious(similarity, own(msg)),
+            Rule::NotContainUserInfo(check, msg) => Rule::NotContainUserInfo(check, own(msg)),
+            Rule::ServiceNames(names, msg) => Rule::ServiceNames(names, own(msg)),
+            Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, msg } => {
+                Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, msg: own(msg) }
+            }
+            Rule::MaxClassRun(len, msg) => Rule::MaxClassRun(len, own(msg)),
+            Rule::MinWords(n, separators, msg) => Rule::MinWords(n, separators, own(msg)),
+            Rule::NotAllNumeric(msg) => Rule::NotAllNumeric(own(msg)),
+            Rule::NotAllAlphabetic(msg) => Rule::NotAllAlphabetic(own(msg)),
+            Rule::DigitsOnly(msg) => Rule::DigitsOnly(own(msg)),
+            Rule::NotSequentialDigits(msg) => Rule::NotSequentialDigits(own(msg)),
+            Rule::NotRepeatedChar(msg) => Rule::NotRepeatedChar(own(msg)),
+            Rule::NotPalindrome(msg) => Rule::NotPalindrome(own(msg)),
+            Rule::NoPhonePatterns(msg) => Rule::NoPhonePatterns(own(msg)),
+            Rule::NotContainPhoneNumber(msg) => Rule::NotContainPhoneNumber(own(msg)),
+            Rule::NotContainDateOfBirth(msg) => Rule::NotContainDateOfBirth(own(msg)),
+            Rule::NotEmailOrUrl(msg) => Rule::NotEmailOrUrl(own(msg)),
+            Rule::SingleScriptOnly(msg) => Rule::SingleScriptOnly(own(msg)),
+            Rule::Custom(rule, msg) => Rule::Custom(rule, own(msg)),
+            Rule::NotBreached(breaches, msg) => Rule::NotBreached(breaches, own(msg)),
+        }
+    }
 }
 
 /// PasswordChecker holds the rules and validates passwords.
+///
+/// # Concurrency
+///
+/// `PasswordChecker` is `Send + Sync`, and validation only needs `&self`, so
+/// one checker can be shared across threads behind an `Arc`. This is a
+/// guarantee of the API: shared data such as dictionaries is immutable
+/// after construction, and lazily built state such as compiled patterns
+/// uses thread-safe one-time initialization.
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct PasswordChecker<'a> {
     rules: Vec<Rule<'a>>,
+    groups: Vec<group::RuleGroup<'a>>,
+    name: Option<Cow<'a, str>>,
+    fail_fast: bool,
+    dry_run: bool,
+    keys_only: bool,
+    formatter: format::Formatter,
+    catalogs: Vec<Catalog>,
+    estimator: Estimator,
+    score_weights: Option<ScoreWeights>,
+    meter_spec: Option<MeterSpec<'a>>,
+    max_input_bytes: Option<usize>,
+    length_exemption: Option<LengthExemption>,
+    record_fingerprint: bool,
+    timing: bool,
+    #[cfg(feature = "confusables")]
+    confusables: bool,
+    observers: observer::Observers,
 }
 
 impl<'a> PasswordChecker<'a> {
-    /// Creates a new empty PasswordChecker.
+    /// Creates a new empty PasswordChecker, as does `Default`. It accepts
+    /// every password until rules are added; for a reasonable policy
+    /// without choosing rules, start from [`recommended`](Self::recommended).
     pub fn new() -> Self {
-        PasswordChecker { rules: vec![] }
+        PasswordChecker::default()
+    }
+
+    /// A policy along the lines of NIST SP 800-63B: at least 12
+    /// characters, at most 128, no NUL or non-characters, not only digits,
+    /// and not in [`Dictionary::builtin`] (empty unless a `dict-*` feature
+    /// is enabled). Add rules to it as to any checker.
+    ///
+    /// ```
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let checker = PasswordChecker::recommended();
+    /// assert!(checker.check("correct horse battery").is_ok());
+    /// assert_eq!(checker.check("904417283365").unwrap_err().codes().collect::<Vec<_>>(), ["not_all_numeric"]);
+    /// ```
+    pub fn recommended() -> Self {
+        PasswordChecker::new()
+            .min_length(12, None)
+            .max_length(128, None)
+            .forbid_unsafe_chars(None)
+            .not_all_numeric(None)
+            .blacklist(Dictionary::builtin(), None)
+    }
+
+    /// A checker for numeric PINs: 4 to 8 digits, not consecutive digits
+    /// such as `1234` or `9876`, not one digit repeated, and not in
+    /// [`Dictionary::common_pins`]. Add
+    /// [`not_palindrome`](Self::not_palindrome) to also reject PINs such as
+    /// `1221`, or other rules as for any checker.
+    ///
+    /// ```
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let checker = PasswordChecker::pin();
+    /// assert!(checker.check("7291").is_ok());
+    /// assert_eq!(checker.check("9876").unwrap_err().codes().collect::<Vec<_>>(), ["not_sequential_digits"]);
+    /// ```
+    pub fn pin() -> Self {
+        PasswordChecker::new()
+            .min_length(4, None)
+            .max_length(8, None)
+            .digits_only(None)
+            .not_sequential_digits(None)
+            .not_repeated_char(None)
+            .blacklist(Dictionary::common_pins(), None)
+    }
+
+    /// Creates a checker with `rules`, evaluated in order, as if each had
+    /// been added with its builder method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if special-character rules have different sets, as
+    /// [`require_special_char_with`](Self::require_special_char_with) does.
+    ///
+    /// ```
+    /// use passcheck::{PasswordChecker, Rule};
+    ///
+    /// let checker = PasswordChecker::from_rules(vec![Rule::MinLength(12, None), Rule::RequireNumber(None)]);
+    /// assert_eq!(checker, PasswordChecker::new().min_length(12, None).require_number(None));
+    /// ```
+    #[track_caller]
+    pub fn from_rules(rules: Vec<Rule<'a>>) -> Self {
+        classes::assert_one_special_set(&rules);
+        PasswordChecker { rules, ..PasswordChecker::default() }
     }
 
     /// Adds a minimum length rule with an optional custom message.
     ///
     /// # Arguments
     ///
-    /// * `len` - Minimum required length.
+    /// * `len` - Minimum required length, in characters (Unicode scalar
+    ///   values).
     /// * `msg` - Optional custom error message.
     pub fn min_length(mut self, len: usize, msg: Option<&'a str>) -> Self {
-        self.rules.push(Rule::MinLength(len, msg));
+        self.rules.push(Rule::MinLength(len, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a maximum length rule with an optional custom message.
+    ///
+    /// Length is measured the same way as for [`min_length`](Self::min_length).
+    pub fn max_length(mut self, len: usize, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::MaxLength(len, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords longer than `len` bytes in UTF-8.
+    ///
+    /// Unlike [`max_length`](Self::max_length), which counts characters,
+    /// this counts bytes, which is what password hashes with an input
+    /// limit truncate.
+    pub fn max_bytes(mut self, len: usize, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::MaxBytes(len, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a 72-byte limit for passwords hashed with bcrypt, which ignores
+    /// everything past the 72nd byte: without it, two long passwords that
+    /// share a prefix would hash identically.
+    pub fn for_bcrypt(self) -> Self {
+        self.max_bytes(BCRYPT_MAX_BYTES, Some(BCRYPT_MESSAGE))
+    }
+
+    /// Adds a rule rejecting passwords with an embedded NUL, which C-backed
+    /// hashing libraries treat as the end of the password, or a Unicode
+    /// non-character such as U+FFFE.
+    pub fn forbid_unsafe_chars(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::ForbidUnsafeChars(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords too similar to the previous one,
+    /// measured by [`edit_distance`]. It needs the previous password, so it
+    /// is only checked by [`check_with_context`](Self::check_with_context).
+    ///
+    /// The distance costs O(n · m) for passwords of n and m characters, so
+    /// it is skipped when their lengths alone tell them apart, and pairs
+    /// longer than 256 characters only count as similar when equal.
+    pub fn not_similar_to_previous(mut self, similarity: Similarity, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotSimilarToPrevious(similarity, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords that contain the username, name or
+    /// email address of the [`UserContext`], ignoring case and diacritics. Only checked by
+    /// [`check_with_context`](Self::check_with_context).
+    pub fn not_contain_user_info(mut self, check: UserInfoCheck, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotContainUserInfo(check, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting runs of seven or more digits, such as the
+    /// phone number in `Dave07911123456!`. A single space or dash between
+    /// two digits does not end a run, so `0791 112-3456` counts as eleven
+    /// digits; shorter runs like a four-digit PIN pass.
+    pub fn no_phone_patterns(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NoPhonePatterns(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords that contain the user's
+    /// [phone number](UserContext::phone): any seven consecutive digits of
+    /// it, so `+44 7911 123456` matches `Dave07911123456!`. Only checked by
+    /// [`check_with_context`](Self::check_with_context).
+    pub fn not_contain_phone_number(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotContainPhoneNumber(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords that contain the user's [date of
+    /// birth](UserContext::date_of_birth) as `YYYYMMDD`, `DDMMYYYY`,
+    /// `MMDDYYYY`, `DDMMYY`, `MMDDYY`, `DDMM` or `MMDD`, ignoring `/`, `.`,
+    /// `-` or spaces between the digits. The year alone only counts as a
+    /// digit run of its own or next to the month or day, so other years
+    /// pass. Only checked by [`check_with_context`](Self::check_with_context).
+    pub fn not_contain_date_of_birth(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotContainDateOfBirth(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords that contain one of `names`, the
+    /// service or application the password is for, as in `MyAcme2024!`.
+    ///
+    /// Names and passwords are compared case-insensitively without spaces
+    /// or punctuation, also with leetspeak undone, and names of six or more
+    /// characters also match with one typo.
+    ///
+    /// The typo search costs O(n · k²) for n password and k name
+    /// characters, so passwords longer than 256 characters only match
+    /// names exactly.
+    pub fn with_service_names<I, S>(mut self, names: I, msg: Option<&'a str>) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rules.push(Rule::ServiceNames(names.into_iter().map(Into::into).collect(), msg.map(Cow::Borrowed)));
         self
     }
 
     /// Adds a rule requiring both uppercase and lowercase letters.
     pub fn require_upper_lower(mut self, msg: Option<&'a str>) -> Self {
-        self.rules.push(Rule::RequireUpperLower(msg));
+        self.rules.push(Rule::RequireUpperLower(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords that are an email address
+    /// (`local@domain.tld`) or start with `http://`, `https://` or `www.`,
+    /// ignoring case and surrounding whitespace. Passwords that merely
+    /// contain `@` or `.`, like `p@ssword.123`, pass. The violation's
+    /// `shape` param is `email` or `url`.
+    pub fn not_email_or_url(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotEmailOrUrl(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords whose letters come from more than
+    /// one Unicode script, such as a Cyrillic `а` among Latin letters.
+    /// Digits, punctuation, symbols and combining marks (the Common and
+    /// Inherited scripts) go with any script, and so do Han with Hiragana
+    /// and Katakana, Hangul or Bopomofo. The violation's `scripts` param
+    /// lists the scripts found, in order of appearance.
+    pub fn single_script_only(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::SingleScriptOnly(msg.map(Cow::Borrowed)));
         self
     }
 
     /// Adds a rule requiring at least one digit.
     pub fn require_number(mut self, msg: Option<&'a str>) -> Self {
-        self.rules.push(Rule::RequireNumber(msg));
+        self.rules.push(Rule::RequireNumber(msg.map(Cow::Borrowed)));
         self
     }
 
-    /// Adds a rule requiring at least one special character.
+    /// Adds a rule requiring at least one special character, of the
+    /// checker's [special set](CharClasses::special): the builtin one
+    /// unless set with [`require_special_char_with`](Self::require_special_char_with).
     pub fn require_special_char(mut self, msg: Option<&'a str>) -> Self {
-        self.rules.push(Rule::RequireSpecialChar(msg));
+        let policy = self.char_classes().special;
+        self.rules.push(Rule::RequireSpecialChar(policy, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Like [`require_special_char`](Self::require_special_char), with
+    /// `policy` deciding which characters are special. The policy becomes
+    /// the checker's special set, so special-character rules added after
+    /// count the same characters, as does [`generate`](Self::generate).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checker already has a special-character rule with a
+    /// different set: a checker has one special set, as policy files do.
+    #[track_caller]
+    pub fn require_special_char_with(mut self, policy: SpecialCharPolicy, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::RequireSpecialChar(policy, msg.map(Cow::Borrowed)));
+        classes::assert_one_special_set(&self.rules);
+        self
+    }
+
+    /// Adds a rule rejecting passwords made only of digits, in any script,
+    /// for policies without composition rules.
+    pub fn not_all_numeric(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotAllNumeric(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords made only of letters, in any script.
+    pub fn not_all_alphabetic(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotAllAlphabetic(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule requiring ASCII digits only, for PINs. The empty
+    /// password passes; combine with [`min_length`](Self::min_length).
+    pub fn digits_only(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::DigitsOnly(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords of three or more ASCII digits that
+    /// count up or down by one, such as `1234` or `9876`. Sequences do not
+    /// wrap around, so `8901` passes.
+    pub fn not_sequential_digits(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotSequentialDigits(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords of two or more copies of one
+    /// character, such as `1111` or `aaaaaaaa`.
+    pub fn not_repeated_char(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotRepeatedChar(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords of two or more characters that read
+    /// the same backwards, such as `1221` or `12321`.
+    pub fn not_palindrome(mut self, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotPalindrome(msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords found in `words`.
+    ///
+    /// Matching is exact unless `words` is a [`Dictionary`] built with
+    /// another [`MatchMode`]. Pass a [`Dictionary`] to share one list between
+    /// checkers without copying it.
+    pub fn blacklist(mut self, words: impl Into<Dictionary>, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::Blacklist(words.into(), msg.map(Cow::Borrowed)));
         self
     }
 
+    /// Adds a rule defined by the application. Its violations carry the
+    /// rule's [code](CustomRule::code) and [params](CustomRule::params) like
+    /// those of built-in rules, so catalogs and per-call messages apply.
+    ///
+    /// Custom rules cannot be saved to or loaded from a [`PolicyConfig`].
+    pub fn custom(mut self, rule: CustomRule, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::Custom(rule, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords that `breaches` reports as
+    /// compromised, or could not check when it fails closed. Violations
+    /// have a `count` param when a provider found the password and
+    /// `unavailable`, the number of providers that could not answer,
+    /// otherwise.
+    ///
+    /// Providers are asked during validation, so one backed by a network
+    /// service makes every check wait for it. Like custom rules, these
+    /// rules cannot be loaded from a [`PolicyConfig`].
+    pub fn not_breached(mut self, breaches: Breaches, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::NotBreached(breaches, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule requiring `pattern` to match somewhere in the password.
+    ///
+    /// See [`pattern`] for the supported syntax. The pattern is compiled
+    /// on first use and the result shared by clones of the checker.
+    /// Matching takes O(pattern size · n) for n characters.
+    pub fn must_match(mut self, pattern: Pattern, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::MustMatch(pattern, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting passwords in which `pattern` matches.
+    pub fn must_not_match(mut self, pattern: Pattern, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::MustNotMatch(pattern, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting keyboard walks such as `qwer` or `1qaz`.
+    ///
+    /// [`KeyboardWalk::default`] checks every built-in layout, in one
+    /// pass over the password per layout.
+    pub fn keyboard_walk(mut self, walk: KeyboardWalk, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::KeyboardWalk(walk, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule rejecting more than `len` consecutive characters of the
+    /// same class: letters of either case, digits, or special characters of
+    /// the checker's [special set](Self::char_classes). Whitespace and other
+    /// characters end a run.
+    pub fn max_class_run(mut self, len: usize, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::MaxClassRun(len, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule requiring at least `n` words of two or more characters,
+    /// split at whitespace, `-`, `_` and `.`, as in `correct-horse-battery-staple`.
+    pub fn min_words(self, n: usize, msg: Option<&'a str>) -> Self {
+        self.min_words_with(n, DEFAULT_WORD_SEPARATORS, msg)
+    }
+
+    /// Like [`min_words`](Self::min_words), splitting words at whitespace
+    /// and the characters of `separators` instead.
+    pub fn min_words_with(mut self, n: usize, separators: &str, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::MinWords(n, separators.to_string(), msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule requiring at least `bits` of strength as measured by
+    /// `estimator`.
+    pub fn min_entropy(mut self, bits: f64, estimator: Estimator, msg: Option<&'a str>) -> Self {
+        self.rules.push(Rule::MinEntropy(bits, estimator, msg.map(Cow::Borrowed)));
+        self
+    }
+
+    /// Adds a rule accepting passwords of `min_len_strong` characters with at
+    /// least `min_entropy_bits` of [`entropy`], or of `min_len_fallback`
+    /// characters regardless of entropy.
+    pub fn length_or_entropy(
+        mut self,
+        min_len_strong: usize,
+        min_entropy_bits: f64,
+        min_len_fallback: usize,
+        msg: Option<&'a str>,
+    ) -> Self {
+        let msg = msg.map(Cow::Borrowed);
+        self.rules.push(Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, msg });
+        self
+    }
+
+    /// Selects the estimator behind [`score`](Self::score). Defaults to
+    /// [`Estimator::Pool`].
+    pub fn estimator(mut self, estimator: Estimator) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Switches [`score`](Self::score) from estimated bits to a weighted sum
+    /// of length, character classes, uniqueness, pattern penalties and a
+    /// dictionary penalty. Start from [`ScoreWeights::default()`].
+    pub fn score_weights(mut self, weights: ScoreWeights) -> Self {
+        self.score_weights = Some(weights);
+        self
+    }
+
+    /// Replaces the milestones of [`meter`](Self::meter).
+    pub fn meter_spec(mut self, spec: MeterSpec<'a>) -> Self {
+        self.meter_spec = Some(spec);
+        self
+    }
+
+    /// Names the policy, e.g. after the tenant it belongs to.
+    ///
+    /// The name is reported to observers in [`ValidationEvent::policy`].
+    pub fn named(mut self, name: &'a str) -> Self {
+        self.name = Some(Cow::Borrowed(name));
+        self
+    }
+
+    /// Stops at the first failed rule instead of reporting all of them.
+    pub fn fail_fast(mut self, enabled: bool) -> Self {
+        self.fail_fast = enabled;
+        self
+    }
+
+    /// Evaluates the rules without enforcing them, for rolling out a
+    /// stricter policy: [`check`](Self::check) and
+    /// [`validate`](Self::validate) accept every password, while observers
+    /// still see the failed rules and
+    /// [`validate_staged`](Self::validate_staged) returns the violations.
+    /// Inputs over [`max_input_bytes`](Self::max_input_bytes) are still
+    /// rejected.
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Rejects passwords longer than `bytes` with a single
+    /// `input_too_large` violation before any rule runs, so that an
+    /// oversized request cannot tie up the checker.
+    /// [`DEFAULT_MAX_INPUT_BYTES`] applies until this is set; use
+    /// `usize::MAX` to turn the guard off.
+    ///
+    /// Unlike [`max_bytes`](Self::max_bytes), this is a safeguard rather
+    /// than part of the policy: it is not a rule and is not described.
+    pub fn max_input_bytes(mut self, bytes: usize) -> Self {
+        self.max_input_bytes = Some(bytes);
+        self
+    }
+
+    /// Skips rendering messages: violations keep their code and
+    /// [params](Violation::params) but carry an empty message, for clients
+    /// that show their own copy.
+    pub fn keys_only(mut self, enabled: bool) -> Self {
+        self.keys_only = enabled;
+        self
+    }
+
+    /// Renders messages of rules without a custom message with
+    /// `formatter` instead of [`DefaultFormatter`].
+    pub fn with_formatter(mut self, formatter: impl MessageFormatter + 'static) -> Self {
+        self.formatter = format::Formatter(Some(Arc::new(formatter)));
+        self
+    }
+
+    /// Also checks blacklist and `must_not_match` rules against the
+    /// [`skeleton`] of the password, so `"p\u{430}ssword"` (with a
+    /// Cyrillic `а`) is caught by a blacklist containing `"password"`.
+    #[cfg(feature = "confusables")]
+    pub fn match_confusables(mut self, enabled: bool) -> Self {
+        self.confusables = enabled;
+        self
+    }
+
+    /// Registers a callback invoked once after every validation, pass or fail.
+    ///
+    /// Any number of observers can be registered; they are called in
+    /// registration order on the validating thread, so keep them cheap.
+    pub fn with_observer(mut self, observer: impl Fn(&ValidationEvent<'_>) + Send + Sync + 'static) -> Self {
+        self.observers.0.push(Arc::new(observer));
+        self
+    }
+
+    /// Times each rule of every observed validation, reported in
+    /// [`ValidationEvent::timing`], to find the rules that make a policy
+    /// slow. Without it, or without observers, no rule is timed.
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use passcheck::PasswordChecker;
+    ///
+    /// let slowest = Arc::new(Mutex::new(None));
+    /// let sink = Arc::clone(&slowest);
+    /// let checker = PasswordChecker::new()
+    ///     .min_length(12, None)
+    ///     .require_number(None)
+    ///     .with_timing(true)
+    ///     .with_observer(move |event| *sink.lock().unwrap() = event.timing.as_ref().and_then(|t| t.slowest()).map(|rule| rule.code));
+    /// let _ = checker.check("correct horse battery");
+    /// assert!(slowest.lock().unwrap().is_some());
+    /// ```
+    pub fn with_timing(mut self, enabled: bool) -> Self {
+        self.timing = enabled;
+        self
+    }
+
+    /// Returns the name set with [`named`](Self::named).
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the configured rules in evaluation order.
+    pub fn rules(&self) -> &[Rule<'a>] {
+        &self.rules
+    }
+
+    /// The number of rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether the checker has no rules, and so accepts every password.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The character classes the rules use and [`generate`](Self::generate)
+    /// draws from.
+    pub fn char_classes(&self) -> CharClasses {
+        CharClasses::of(&self.rules)
+    }
+
+    /// Returns a human-readable description of each requirement, in rule
+    /// order. Rules waived by a [length exemption](Self::length_exemption)
+    /// end with `, or use a passphrase of N+ characters`.
+    pub fn describe(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .map(|rule| match self.waived_at(rule) {
+                Some(threshold) => format!("{}, or use a passphrase of {}+ characters", rule.description(), threshold),
+                None => rule.description(),
+            })
+            .collect()
+    }
+
+    /// Serializes [`describe`](Self::describe) with each rule's code, e.g.
+    /// `{"schema":1,"requirements":[{"code":"min_length","description":"At least 8 characters"}]}`.
+    /// `schema` is [`Violations::SCHEMA`]. Rules waived by a
+    /// [length exemption](Self::length_exemption) have a `waived_at_length`
+    /// field, the length in characters from which they are waived.
+    pub fn requirements_json(&self) -> String {
+        let requirements = self
+            .rules
+            .iter()
+            .zip(self.describe())
+            .map(|(rule, description)| {
+                let mut fields = vec![
+                    ("code".to_string(), json::Value::String(rule.code().to_string())),
+                    ("description".to_string(), json::Value::String(description)),
+                ];
+                if let Some(threshold) = self.waived_at(rule) {
+                    fields.push(("waived_at_length".to_string(), json::Value::Number(threshold as f64)));
+                }
+                json::Value::Object(fields)
+            })
+            .collect();
+        json::Value::Object(vec![
+            ("schema".to_string(), json::Value::Number(Violations::SCHEMA.into())),
+            ("requirements".to_string(), json::Value::Array(requirements)),
+        ])
+        .to_string()
+    }
+
+    /// Rates the strength of `password` from 0 to 100.
+    ///
+    /// By default the score is independent of the configured rules: one
+    /// point per bit of strength as measured by the
+    /// [`estimator`](Self::estimator), capped at 100. With
+    /// [`score_weights`](Self::score_weights) it is the weighted sum of the
+    /// password's factors, where the dictionary penalty applies to the
+    /// checker's blacklists and [`Dictionary::builtin`].
+    pub fn score(&self, password: &str) -> u8 {
+        let Some(weights) = &self.score_weights else {
+            return strength::score(password, self.estimator);
+        };
+        let builtin = Dictionary::builtin();
+        strength::Factors::of(password, self.blacklists().chain([&builtin])).score(weights)
+    }
+
+    /// Rates `password` on zxcvbn's 0–4 scale, for strength meters built
+    /// around it.
+    ///
+    /// The bucket comes from [`Analysis::guesses_log10`]:
+    ///
+    /// | score | guesses      |
+    /// |-------|--------------|
+    /// | 0     | < 10^3       |
+    /// | 1     | < 10^6       |
+    /// | 2     | < 10^8       |
+    /// | 3     | < 10^10      |
+    /// | 4     | ≥ 10^10      |
+    pub fn score_zxcvbn_scale(&self, password: &str) -> u8 {
+        strength::zxcvbn_bucket(self.analyze(password).guesses_log10)
+    }
+
+    /// Estimates how many guesses `password` takes to find.
+    ///
+    /// The estimate starts from the [`estimator`](Self::estimator)'s bits
+    /// and is lowered for keyboard walks, repeats and sequences, each
+    /// listed in [`Analysis::patterns`] with its contribution. A password in
+    /// one of the checker's blacklists or [`Dictionary::builtin`] takes at
+    /// most as many guesses as that list has entries.
+    pub fn analyze(&self, password: &str) -> Analysis {
+        let builtin = Dictionary::builtin();
+        let analysis = analysis::analyze(password, self.estimator, self.blacklists().chain([&builtin]), self.score(password));
+        Analysis { fingerprint: self.recorded_fingerprint(), ..analysis }
+    }
+
+    /// Evaluates the milestones of a progressive strength meter.
+    ///
+    /// Unlike validation, milestones may go beyond the policy. Without a
+    /// [`meter_spec`](Self::meter_spec), the meter has five segments: the
+    /// policy's minimum length (8 without one), four characters more (at
+    /// least 12), three character classes, no dictionary hit, and 70 bits
+    /// from the [`estimator`](Self::estimator).
+    pub fn meter(&self, password: &str) -> Meter {
+        let builtin = Dictionary::builtin();
+        let listed = self.blacklists().chain([&builtin]).any(|words| words.contains(password));
+        match &self.meter_spec {
+            Some(spec) => spec.evaluate(password, self.estimator, listed),
+            None => {
+                let min_length = self.rules.iter().find_map(|rule| match rule {
+                    Rule::MinLength(len, _) => Some(*len),
+                    _ => None,
+                });
+                MeterSpec::derived(min_length).evaluate(password, self.estimator, listed)
+            }
+        }
+    }
+
+    fn blacklists(&self) -> impl Iterator<Item = &Dictionary> {
+        self.rules.iter().filter_map(|rule| match rule {
+            Rule::Blacklist(words, _) => Some(words),
+            _ => None,
+        })
+    }
+
     /// Validates the given password against all configured rules.
     ///
     /// Returns:
     /// - `Ok(())` if all rules pass.
     /// - `Err(Vec<String>)` with all error messages if validation fails.
     pub fn validate(&self, password: &str) -> Result<(), Vec<String>> {
+        self.check(password).map_err(Violations::into_messages)
+    }
+
+    /// Like [`validate`](Self::validate), also applying the rules that
+    /// compare the password with what `context` knows about the user.
+    pub fn validate_with_context(&self, password: &str, context: &UserContext<'_>) -> Result<(), Vec<String>> {
+        self.check_with_context(password, context).map_err(Violations::into_messages)
+    }
+
+    /// Validates the given password, returning structured violations.
+    ///
+    /// Same rules and messages as [`validate`](Self::validate), with each
+    /// failure carrying its rule [`code`](Rule::code).
+    ///
+    /// A rule needing a [`UserContext`] cannot be applied here, and is
+    /// reported as a `context_required` violation naming the rule's code
+    /// in its `rule` param, even under [`dry_run`](Self::dry_run); see
+    /// [`requires_context`](Self::requires_context).
+    ///
+    /// ```
+    /// use passcheck::{ParamValue, PasswordChecker, UserContext, UserInfoCheck};
+    ///
+    /// let checker = PasswordChecker::new().not_contain_user_info(UserInfoCheck::new(), None);
+    /// let violations = checker.check("correct horse").unwrap_err();
+    /// assert_eq!(violations[0].code, "context_required");
+    /// assert_eq!(violations[0].param("rule"), Some(&ParamValue::Name("not_contain_user_info")));
+    /// assert!(checker.check_with_context("correct horse", &UserContext::new().username("jsmith")).is_ok());
+    /// ```
+    pub fn check(&self, password: &str) -> Result<(), Violations> {
+        self.check_in(password, None, None)
+    }
+
+    /// Whether a rule compares passwords with a [`UserContext`], so that
+    /// passwords must be validated with
+    /// [`check_with_context`](Self::check_with_context) or
+    /// [`validate_with_context`](Self::validate_with_context).
+    pub fn requires_context(&self) -> bool {
+        self.rules.iter().any(Rule::needs_context)
+    }
+
+    /// Like [`check`](Self::check), also applying the rules that compare the
+    /// password with what `context` knows about the user, such as
+    /// [`not_similar_to_previous`](Self::not_similar_to_previous).
+    pub fn check_with_context(&self, password: &str, context: &UserContext<'_>) -> Result<(), Violations> {
+        self.check_in(password, Some(context), None)
+    }
+
+    /// Evaluates the rules one at a time, in order, as the iterator is
+    /// advanced: `check_iter(pw).find(RuleOutcome::failed)` stops at the
+    /// first failure, and the violations of all outcomes are those of
+    /// [`check`](Self::check), so a rule needing a [`UserContext`] fails
+    /// with a `context_required` violation. Ignores
+    /// [`fail_fast`](Self::fail_fast), [`dry_run`](Self::dry_run) and
+    /// [`max_input_bytes`](Self::max_input_bytes), and does not notify
+    /// observers.
+    pub fn check_iter<'s>(&'s self, password: &'s str) -> impl Iterator<Item = RuleOutcome<'s>> + 's {
+        let fails = self.failure_test(password, None);
+        self.outcomes(password, None, None, move |_, rule| !rule.needs_context() && fails(rule)).map(|outcome| {
+            if outcome.rule.needs_context() { RuleOutcome { violation: Some(self.context_required(outcome.rule, None)), ..outcome } } else { outcome }
+        })
+    }
+
+    /// The outcome of each rule, with `fails(i, rule)` deciding whether the
+    /// `i`th rule fails.
+    fn outcomes<'s>(
+        &'s self,
+        password: &'s str,
+        context: Option<&'s UserContext<'s>>,
+        options: Option<&'s ValidateOptions<'s>>,
+        fails: impl Fn(usize, &Rule<'_>) -> bool + 's,
+    ) -> impl Iterator<Item = RuleOutcome<'s>> + 's {
+        let special = classes::special_of(&self.rules);
+        self.rules.iter().enumerate().map(move |(i, rule)| {
+            let violation =
+                fails(i, rule).then(|| self.violation(i, rule, rule.params(password, context, special), rule.span(password, special), options));
+            RuleOutcome { rule, violation }
+        })
+    }
+
+    /// The violation of the `i`th rule, with its message rendered.
+    fn violation(
+        &self,
+        i: usize,
+        rule: &Rule<'_>,
+        params: Vec<(&'static str, ParamValue)>,
+        span: Option<Range<usize>>,
+        options: Option<&ValidateOptions<'_>>,
+    ) -> Violation {
+        let group = self.group_of(i);
+        let message = match group {
+            _ if self.keys_only => String::new(),
+            Some((_, message)) => message.to_string(),
+            None => self.render(rule, &params, options),
+        };
+        Violation { code: rule.code(), message, params, span, group: group.map(|(index, _)| index) }
+    }
+
+    /// Like [`check`](Self::check), also returning the violations a
+    /// [`dry_run`](Self::dry_run) checker let through. Without dry run, the
+    /// result is that of `check` and nothing is staged.
+    pub fn validate_staged(&self, password: &str) -> (Result<(), Violations>, StagedViolations) {
+        let result = self.evaluate(password, None, None);
+        match result {
+            Err(violations) if self.dry_run => self.unstaged(violations),
+            result => (result, StagedViolations::default()),
+        }
+    }
+
+    fn check_in(&self, password: &str, context: Option<&UserContext<'_>>, options: Option<&ValidateOptions<'_>>) -> Result<(), Violations> {
+        match self.evaluate(password, context, options) {
+            Err(violations) if self.dry_run => self.unstaged(violations).0,
+            result => result,
+        }
+    }
+
+    /// [`check`](Self::check) with `fails(i, rule)` deciding whether the
+    /// `i`th rule fails, for callers that already know some results.
+    fn check_using(&self, password: &str, fails: impl Fn(usize, &Rule<'_>) -> bool) -> Result<(), Violations> {
+        match self.evaluate_with(password, None, None, |i, rule| !self.waives(rule, password) && fails(i, rule)) {
+            Err(violations) if self.dry_run => self.unstaged(violations).0,
+            result => result,
+        }
+    }
+
+    /// Splits the violations of a dry run into the ones that still block,
+    /// those of the input guard, and the staged rest.
+    fn unstaged(&self, violations: Violations) -> (Result<(), Violations>, StagedViolations) {
+        let (blocking, staged): (Vec<_>, Vec<_>) = violations.0.into_iter().partition(|v| v.code == "input_too_large" || v.code == "context_required");
+        let result = if blocking.is_empty() { Ok(()) } else { Err(Violations(blocking)) };
+        (result, StagedViolations(Violations(staged)))
+    }
+
+    fn evaluate(&self, password: &str, context: Option<&UserContext<'_>>, options: Option<&ValidateOptions<'_>>) -> Result<(), Violations> {
+        let fails = self.failure_test(password, context);
+        self.evaluate_with(password, context, options, |_, rule| fails(rule))
+    }
+
+    fn evaluate_with(
+        &self,
+        password: &str,
+        context: Option<&UserContext<'_>>,
+        options: Option<&ValidateOptions<'_>>,
+        fails: impl Fn(usize, &Rule<'_>) -> bool,
+    ) -> Result<(), Violations> {
         let mut errors = Vec::new();
+        let started = (!self.observers.0.is_empty()).then(Instant::now);
+        let timings = (started.is_some() && self.timing).then(|| RefCell::new(Vec::with_capacity(self.rules.len())));
+        let fails = |i: usize, rule: &Rule<'_>| match &timings {
+            Some(timings) => {
+                let started = Instant::now();
+                let failed = fails(i, rule);
+                timings.borrow_mut().push(RuleTiming { index: i, code: rule.code(), duration: started.elapsed() });
+                failed
+            }
+            None => fails(i, rule),
+        };
+        let max_input_bytes = self.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+        if password.len() > max_input_bytes {
+            errors.push(self.input_too_large(password.len(), options));
+        } else {
+            // Rules needing a context report that rather than passing.
+            let violations = self.outcomes(password, context, options, &fails).filter_map(|outcome| match context {
+                None if outcome.rule.needs_context() => Some(self.context_required(outcome.rule, options)),
+                _ => outcome.violation,
+            });
+            for violation in violations {
+                errors.push(violation);
+                if self.fail_fast {
+                    break;
+                }
+            }
+        }
+
+        if let Some(started) = started {
+            let duration = started.elapsed();
+            let event = ValidationEvent {
+                policy: self.name(),
+                valid: errors.is_empty(),
+                failed: errors.iter().map(|v| v.code).collect(),
+                dry_run: self.dry_run,
+                duration,
+                fingerprint: self.recorded_fingerprint(),
+                timing: timings.map(|timings| Timing { rules: timings.into_inner(), total: duration }),
+            };
+            for observer in &self.observers.0 {
+                observer(&event);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Violations(errors))
+        }
+    }
+
+    /// The violation of inputs over [`max_input_bytes`](Self::max_input_bytes),
+    /// `actual` bytes long.
+    fn input_too_large(&self, actual: usize, options: Option<&ValidateOptions<'_>>) -> Violation {
+        let max_input_bytes = self.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+        let params = vec![("max", ParamValue::Int(max_input_bytes)), ("actual", ParamValue::Int(actual))];
+        let message = if self.keys_only {
+            String::new()
+        } else {
+            self.render_code("input_too_large", &params, options)
+                .unwrap_or_else(|| format!("Password is too long to check; use at most {} bytes.", max_input_bytes))
+        };
+        Violation { code: "input_too_large", message, params, span: None, group: None }
+    }
+
+    /// The violation standing in for `rule` when no [`UserContext`] was
+    /// given.
+    fn context_required(&self, rule: &Rule<'_>, options: Option<&ValidateOptions<'_>>) -> Violation {
+        let params = vec![("rule", ParamValue::Name(rule.code()))];
+        let message = if self.keys_only {
+            String::new()
+        } else {
+            self.render_code("context_required", &params, options)
+                .unwrap_or_else(|| "Password cannot be checked without the user's details; validate it with a UserContext.".to_string())
+        };
+        Violation { code: "context_required", message, params, span: None, group: None }
+    }
+
+    /// The message for a violation of `rule`, as described in
+    /// [`check_with_options`](Self::check_with_options).
+    fn render(&self, rule: &Rule<'_>, params: &[(&'static str, ParamValue)], options: Option<&ValidateOptions<'_>>) -> String {
+        self.render_code(rule.code(), params, options)
+            .unwrap_or_else(|| rule.custom_message().map_or_else(|| self.formatter.get().format(rule, params), str::to_string))
+    }
+
+    /// The per-call or localized message for `code`, if `options` has one.
+    fn render_code(&self, code: &str, params: &[(&'static str, ParamValue)], options: Option<&ValidateOptions<'_>>) -> Option<String> {
+        let options = options?;
+        if let Some(message) = options.messages.get(code) {
+            return Some(message.clone());
+        }
+        options.locale.and_then(|locale| self.catalog(locale)).and_then(|c| c.render(code, params))
+    }
 
-        // Allowed special characters for validation.
-        const SPECIAL_CHARS: [char; 30] = [
-            '!', '@', '#', '$', '%', '^', '&', '*', '(', ')',
-            '-', '_', '=', '+', '[', ']', '{', '}', '\\', '|',
-            ';', ':', '\'', '"', ',', '.', '<', '>', '/', '?',
-        ];
+    /// Rules `password` breaks, in order, without fail-fast or observers.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn failed_rules<'s>(&'s self, password: &'s str) -> impl Iterator<Item = &'s Rule<'a>> + 's {
+        let fails = self.failure_test(password, None);
+        self.rules.iter().filter(move |rule| fails(rule))
+    }
 
-        for rule in &self.rules {
-            match rule {
-                Rule::MinLength(len, maybe_msg) => {
-                    if password.len() < *len {
-                        let msg = maybe_msg.unwrap_or_else(|| {
-                            Box::leak(format!("Password must be at least {} characters long.", len).into_boxed_str())
-                        });
-                        errors.push(msg.to_string());
-                    }
-                }
-                Rule::RequireUpperLower(maybe_msg) => {
-                    if !password.chars().any(|c| c.is_ascii_uppercase()) ||
-                       !password.chars().any(|c| c.is_ascii_lowercase()) {
-                        let msg = maybe_msg.unwrap_or("Password must include both uppercase and lowercase letters.");
-                        errors.push(msg.to_string());
-                    }
-                }
-                Rule::RequireNumber(maybe_msg) => {
-                    if !password.chars().any(|c| c.is_ascii_digit()) {
-                        let msg = maybe_msg.unwrap_or("Password must include at least one number.");
-                        errors.push(msg.to_string());
-                    }
-                }
-                Rule::RequireSpecialChar(maybe_msg) => {
-                    if !password.chars().any(|c| SPECIAL_CHARS.contains(&c)) {
-                        let msg = maybe_msg.unwrap_or("Password must include at least one special character.");
-                        errors.push(msg.to_string());
-                    }
-                }
+    /// Whether a rule fails for `password`, with what every rule shares,
+    /// such as the confusable skeleton, computed once up front. Rules a
+    /// [length exemption](Self::length_exemption) waives pass.
+    fn failure_test<'s>(&'s self, password: &'s str, context: Option<&'s UserContext<'s>>) -> impl Fn(&Rule<'_>) -> bool + 's {
+        #[cfg(feature = "confusables")]
+        let skeleton = self.confusables.then(|| skeleton(password)).filter(|s| s != password);
+        let prepared = Prepared::new(password, classes::special_of(&self.rules));
+        move |rule| {
+            if self.waives(rule, password) {
+                return false;
             }
+            #[cfg(feature = "confusables")]
+            let failed = prepared.fails(rule, context) || skeleton.as_deref().is_some_and(|s| rule.fails_skeleton(s));
+            #[cfg(not(feature = "confusables"))]
+            let failed = prepared.fails(rule, context);
+            failed
         }
+    }
+}
 
-        if errors.is_empty() {
-            Ok(())
-        } else {
-            Err(errors)
-        }
+impl<'a> FromIterator<Rule<'a>> for PasswordChecker<'a> {
+    fn from_iter<I: IntoIterator<Item = Rule<'a>>>(rules: I) -> Self {
+        PasswordChecker::from_rules(rules.into_iter().collect())
+    }
+}
+
+/// Appends rules after the existing ones, panicking if they bring a
+/// second special set, as
+/// [`require_special_char_with`](PasswordChecker::require_special_char_with)
+/// does. Rules only add violations, so a password the checker rejects is
+/// still rejected afterwards; property tests check this with
+/// `testing::monotonicity_counterexample`.
+impl<'a> Extend<Rule<'a>> for PasswordChecker<'a> {
+    fn extend<I: IntoIterator<Item = Rule<'a>>>(&mut self, rules: I) {
+        self.rules.extend(rules);
+        classes::assert_one_special_set(&self.rules);
     }
 }
 
+// Compile-time check of the documented thread-safety guarantee.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<PasswordChecker<'static>>();
+    assert_send_sync::<Rule<'static>>();
+    assert_send_sync::<Dictionary>();
+    assert_send_sync::<Pattern>();
+    assert_send_sync::<CustomRule>();
+    assert_send_sync::<KeyboardWalk>();
+    assert_send_sync::<Violations>();
+};
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,7 +1763,7 @@ mod tests {
         let checker = PasswordChecker::new().require_upper_lower(None);
         let result = checker.validate("alllowercase");
         assert!(result.is_err());
-        assert!(result.unwrap_err().iter().any(|e| e.contains("uppercase and lowercase")));
+        assert!(result.unwrap_err().iter().any(|e| e.contains("at least one uppercase letter")));
     }
 
     #[test]
@@ -149,6 +1781,136 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn special_chars_by_unicode_category() {
+        let checker = PasswordChecker::new().require_special_char_with(SpecialCharPolicy::UnicodeCategories, None);
+        for password in ["contraseña¡", "pass\u{2014}word", "price€", "¥en", "abc@"] {
+            assert!(checker.check(password).is_ok(), "{}", password);
+        }
+        for password in ["cafe\u{301}", "a\u{200d}b", "plain"] {
+            assert!(checker.check(password).is_err(), "{}", password);
+        }
+        assert!(PasswordChecker::new().require_special_char(None).check("contraseña¡").is_err());
+    }
+
+    #[test]
+    fn special_chars_from_an_explicit_list() {
+        let checker = PasswordChecker::new().require_special_char_with(SpecialCharPolicy::Chars("!@#$".into()), None);
+        assert!(checker.check("hunter2!").is_ok());
+        assert_eq!(checker.validate("hunter2%").unwrap_err(), ["Password must include at least one of these characters: !@#$"]);
+        assert_eq!(checker.describe(), ["At least one of the characters `!@#$`"]);
+    }
+
+    #[test]
+    fn class_runs_up_to_the_limit_pass() {
+        let checker = PasswordChecker::new().max_class_run(4, None);
+        for password in ["abcd", "ABcd1234!@#$", "abc", "12", "!?", "Aaaa1!"] {
+            assert!(checker.check(password).is_ok(), "{}", password);
+        }
+        for password in ["abcde", "Aaaaaaaaaaaa1!", "ab12345", "x!@#$%"] {
+            assert!(checker.check(password).is_err(), "{}", password);
+        }
+    }
+
+    #[test]
+    fn class_transitions_reset_the_run() {
+        let checker = PasswordChecker::new().max_class_run(3, None);
+        assert!(checker.check("abc123!@#def456").is_ok());
+        assert!(checker.check("abc def 123").is_ok());
+        assert!(checker.check("ab\u{e9}\u{df}").is_err());
+        let violations = checker.check("Aaaaaaaaaaaa1!").unwrap_err();
+        assert_eq!(violations[0].params, [("max", ParamValue::Int(3)), ("actual", ParamValue::Int(12))]);
+        assert_eq!(violations[0].message, "Password must not have more than 3 letters, digits or special characters in a row.");
+    }
+
+    #[test]
+    fn single_class_passwords_are_rejected() {
+        let checker = PasswordChecker::new().not_all_numeric(None).not_all_alphabetic(None);
+        assert_eq!(checker.check("19851985").unwrap_err().codes().collect::<Vec<_>>(), ["not_all_numeric"]);
+        assert_eq!(checker.check("\u{661}\u{669}\u{668}\u{665}").unwrap_err().codes().collect::<Vec<_>>(), ["not_all_numeric"]);
+        assert_eq!(checker.check("Sunshine").unwrap_err().codes().collect::<Vec<_>>(), ["not_all_alphabetic"]);
+        assert_eq!(checker.check("пароль").unwrap_err().codes().collect::<Vec<_>>(), ["not_all_alphabetic"]);
+        for password in ["1985198a", "Sunshine!", "Sunshine1", "1985 1985", ""] {
+            assert!(checker.check(password).is_ok(), "{}", password);
+        }
+    }
+
+    #[test]
+    fn single_class_rules_on_top_of_a_length_only_policy() {
+        // NIST SP 800-63B: a length floor and a blocklist, no composition rules.
+        let checker = PasswordChecker::new()
+            .min_length(8, None)
+            .blacklist(["password", "12345678"], None)
+            .not_all_numeric(None)
+            .not_all_alphabetic(None);
+        assert_eq!(checker.check("12345678").unwrap_err().codes().collect::<Vec<_>>(), ["blacklist", "not_all_numeric"]);
+        assert_eq!(checker.check("20242024").unwrap_err().codes().collect::<Vec<_>>(), ["not_all_numeric"]);
+        assert_eq!(checker.check("1234").unwrap_err().codes().collect::<Vec<_>>(), ["min_length", "not_all_numeric"]);
+        assert_eq!(checker.check("correcthorse").unwrap_err().codes().collect::<Vec<_>>(), ["not_all_alphabetic"]);
+        assert!(checker.check("correct horse").is_ok());
+    }
+
+    #[test]
+    fn phone_numbers_are_rejected() {
+        let checker = PasswordChecker::new().no_phone_patterns(None);
+        for password in ["Dave07911123456!", "call 0791 112-3456", "x1234567", "19851985"] {
+            assert_eq!(checker.check(password).unwrap_err().codes().collect::<Vec<_>>(), ["no_phone_patterns"], "{}", password);
+        }
+        for password in ["Dave1234!", "Summer 2024-06", "123456 and 123456", "12 34 56 x"] {
+            assert!(checker.check(password).is_ok(), "{}", password);
+        }
+        assert_eq!(checker.check("Dave07911123456!").unwrap_err()[0].params, [("digits", ParamValue::Int(11))]);
+    }
+
+    #[test]
+    fn the_users_phone_number_is_rejected_in_any_format() {
+        let checker = PasswordChecker::new().not_contain_phone_number(None);
+        let context = UserContext::new().phone("+44 7911 123456");
+        for password in ["Dave07911123456!", "7911-123-456x", "Dave1123456"] {
+            assert_eq!(checker.check_with_context(password, &context).unwrap_err().codes().collect::<Vec<_>>(), ["not_contain_phone_number"]);
+        }
+        assert!(checker.check_with_context("Dave1234!", &context).is_ok());
+        assert!(checker.check_with_context("Dave07911123456!", &UserContext::new()).is_ok());
+        assert_eq!(checker.check("Dave07911123456!").unwrap_err().codes().collect::<Vec<_>>(), ["context_required"]);
+        let context = UserContext::new().phone("07911123456");
+        assert!(checker.check_with_context("my +44 7911 123456", &context).is_err());
+    }
+
+    #[test]
+    fn context_rules_need_a_context() {
+        let checker = PasswordChecker::new().min_length(8, None).not_contain_user_info(UserInfoCheck::new(), None).not_palindrome(None);
+        assert!(checker.requires_context());
+        let context = UserContext::new().username("jsmith");
+        assert_eq!(checker.validate("abc").unwrap_err(), [
+            "Password must be at least 8 characters long.",
+            "Password cannot be checked without the user's details; validate it with a UserContext.",
+        ]);
+        let violations = checker.check("correct horse").unwrap_err();
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["context_required"]);
+        assert_eq!(violations[0].params, [("rule", ParamValue::Name("not_contain_user_info"))]);
+        assert_eq!(Violation::from_json(&violations[0].to_json()).unwrap(), violations[0]);
+        assert!(checker.validate_with_context("correct horse", &context).is_ok());
+        assert_eq!(checker.validate_with_context("jsmith2024", &context).unwrap_err().len(), 1);
+        // Dry run lets policy failures through, but not a missing context.
+        assert!(checker.clone().dry_run(true).check("correct horse").is_err());
+
+        let plain = PasswordChecker::new().min_length(8, None).not_palindrome(None);
+        assert!(!plain.requires_context());
+        assert!(plain.validate("correct horse").is_ok());
+        assert_eq!(plain.check("abc").unwrap_err().codes().collect::<Vec<_>>(), ["min_length"]);
+    }
+
+    #[test]
+    fn dates_of_birth_are_rejected() {
+        let checker = PasswordChecker::new().not_contain_date_of_birth(None);
+        let context = UserContext::new().date_of_birth(1985, 3, 15);
+        assert_eq!(checker.check_with_context("Anna15/03/1985", &context).unwrap_err().codes().collect::<Vec<_>>(), ["not_contain_date_of_birth"]);
+        assert!(checker.check_with_context("Anna1503!", &context).is_err());
+        assert!(checker.check_with_context("Summer2024!", &context).is_ok());
+        assert!(checker.check_with_context("Anna15/03/1985", &UserContext::new()).is_ok());
+        assert_eq!(checker.check("Anna15/03/1985").unwrap_err().codes().collect::<Vec<_>>(), ["context_required"]);
+    }
+
     #[test]
     fn custom_error_messages_work() {
         let checker = PasswordChecker::new()
@@ -166,4 +1928,817 @@ mod tests {
         assert!(errors.contains(&"Must include a number.".to_string()));
         assert!(errors.contains(&"Must include a special character.".to_string()));
     }
+
+    #[test]
+    fn describe_lists_requirements() {
+        let checker = PasswordChecker::new()
+            .min_length(12, Some("custom"))
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None);
+
+        assert_eq!(checker.describe(), vec![
+            "At least 12 characters",
+            "Both uppercase and lowercase letters",
+            "At least one number",
+            "At least one special character",
+        ]);
+    }
+
+    #[test]
+    fn requirements_json_shape_is_stable() {
+        let checker = PasswordChecker::new().min_length(8, None).require_number(None);
+        assert_eq!(
+            checker.requirements_json(),
+            r#"{"schema":1,"requirements":[{"code":"min_length","description":"At least 8 characters"},{"code":"require_number","description":"At least one number"}]}"#
+        );
+    }
+
+    #[test]
+    fn check_reports_codes() {
+        let checker = PasswordChecker::new()
+            .min_length(8, None)
+            .require_number(Some("Need a digit."))
+            .require_special_char(None);
+
+        let violations = checker.check("abc!").unwrap_err();
+        assert_eq!(violations.codes().collect::<Vec<_>>(), vec!["min_length", "require_number"]);
+        assert_eq!(violations[1].message, "Need a digit.");
+        assert_eq!(violations.into_messages(), checker.validate("abc!").unwrap_err());
+    }
+
+    #[test]
+    fn check_iter_is_lazy() {
+        let pattern = Pattern::new("\\d{4}").unwrap();
+        let checker = PasswordChecker::new().min_length(8, None).must_not_match(pattern.clone(), None).require_number(None);
+        let first = checker.check_iter("abc").find(RuleOutcome::failed).unwrap();
+        assert_eq!(first.rule.code(), "min_length");
+        assert_eq!(pattern.compilations(), 0, "later rules were evaluated");
+        let outcomes: Vec<_> = checker.check_iter("abc").collect();
+        assert_eq!(pattern.compilations(), 1);
+        assert_eq!(outcomes.iter().map(RuleOutcome::failed).collect::<Vec<_>>(), [true, false, true]);
+        assert_eq!(outcomes.into_iter().filter_map(|o| o.violation).collect::<Vec<_>>(), *checker.check("abc").unwrap_err());
+    }
+
+    #[test]
+    fn check_iter_reports_rules_needing_context() {
+        let checker = PasswordChecker::new().min_length(4, None).not_contain_user_info(UserInfoCheck::new(), None);
+        let outcomes: Vec<_> = checker.check_iter("alice123").collect();
+        assert_eq!(outcomes.iter().map(RuleOutcome::failed).collect::<Vec<_>>(), [false, true]);
+        assert_eq!(outcomes[1].rule.code(), "not_contain_user_info");
+        assert_eq!(outcomes.into_iter().filter_map(|o| o.violation).collect::<Vec<_>>(), *checker.check("alice123").unwrap_err());
+    }
+
+    #[test]
+    fn password_too_long() {
+        let checker = PasswordChecker::new().max_length(8, None);
+        assert!(checker.validate("exactly8").is_ok());
+        let errors = checker.validate("ninechars").unwrap_err();
+        assert_eq!(errors, vec!["Password must be at most 8 characters long.".to_string()]);
+        // The messages and descriptions say characters, and mean them.
+        assert!(checker.validate("éééééé").is_ok());
+        let errors = PasswordChecker::new().max_length(5, None).check("éééééé").unwrap_err();
+        assert_eq!(errors[0].message, "Password must be at most 5 characters long.");
+        assert_eq!(errors[0].param("actual"), Some(&ParamValue::Int(6)));
+        assert_eq!(checker.describe(), ["At most 8 characters"]);
+    }
+
+    #[test]
+    fn fail_fast_stops_at_first_failure() {
+        let checker = PasswordChecker::new().min_length(8, None).require_number(None).fail_fast(true);
+        let errors = checker.validate("abc").unwrap_err();
+        assert_eq!(errors, vec!["Password must be at least 8 characters long.".to_string()]);
+    }
+
+    /// Every rule that does more than a linear scan.
+    fn expensive_policy() -> PasswordChecker<'static> {
+        PasswordChecker::new()
+            .min_length(12, None)
+            .blacklist(Dictionary::new(["password", "letmein"]).fuzzy(2), None)
+            .must_not_match(Pattern::new("(a|b)*c{2,}(x|y)+$").unwrap(), None)
+            .keyboard_walk(KeyboardWalk::new(), None)
+            .min_entropy(40.0, Estimator::default(), None)
+            .with_service_names(["Widgetify", "acme portal"], None)
+            .not_similar_to_previous(Similarity::MaxRatio(0.8), None)
+    }
+
+    #[test]
+    fn oversized_inputs_fail_before_the_rules() {
+        let checker = expensive_policy();
+        let huge = "ab1!".repeat(1 << 20);
+        let started = Instant::now();
+        let violations = checker.check_with_context(&huge, &UserContext::new().previous_password(&huge)).unwrap_err();
+        assert!(started.elapsed() < std::time::Duration::from_secs(1), "{:?}", started.elapsed());
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["input_too_large"]);
+        assert_eq!(violations[0].params, [("max", ParamValue::Int(DEFAULT_MAX_INPUT_BYTES)), ("actual", ParamValue::Int(4 << 20))]);
+        assert_eq!(violations[0].message, "Password is too long to check; use at most 1048576 bytes.");
+
+        let checker = checker.max_input_bytes(64);
+        assert_eq!(checker.check(&"x".repeat(65)).unwrap_err().codes().collect::<Vec<_>>(), ["input_too_large"]);
+        assert!(checker.clone().keys_only(true).check(&huge).unwrap_err()[0].message.is_empty());
+        let options = ValidateOptions::new().message("input_too_large", "Too long.");
+        assert_eq!(checker.check_with_options(&huge, &options).unwrap_err().into_messages(), ["Too long."]);
+    }
+
+    #[test]
+    fn input_guard_leaves_normal_passwords_alone() {
+        let guarded = expensive_policy();
+        let unguarded = expensive_policy().max_input_bytes(usize::MAX);
+        let context = UserContext::new().previous_password("Widgetify2023!");
+        for password in ["short", "Widgetify2024!", "p4sswerd", "qwertyuiop12", "correct horse battery staple", &"z".repeat(1000)] {
+            assert_eq!(guarded.check_with_context(password, &context), unguarded.check_with_context(password, &context));
+        }
+        let at_limit = "qwerty".repeat(10);
+        assert_eq!(guarded.clone().max_input_bytes(60).check(&at_limit), unguarded.check(&at_limit));
+        assert_ne!(guarded.max_input_bytes(59).check(&at_limit), unguarded.check(&at_limit));
+    }
+
+    #[test]
+    fn every_rule_has_documented_keys_and_params() {
+        let context = UserContext::new().previous_password("abcdefgh1").username("alexander").phone("+1 555 010 9999").date_of_birth(1990, 7, 4);
+        let cases: [(PasswordChecker<'_>, &str, &[&str]); 29] = [
+            (PasswordChecker::new().min_length(8, None), "abc", &["min", "actual"]),
+            (PasswordChecker::new().max_length(2, None), "abc", &["max", "actual"]),
+            (PasswordChecker::new().max_bytes(2, None), "abc", &["max", "actual"]),
+            (PasswordChecker::new().require_upper_lower(None), "abc", &["missing"]),
+            (PasswordChecker::new().require_number(None), "abc", &[]),
+            (PasswordChecker::new().require_special_char(None), "abc", &[]),
+            (PasswordChecker::new().blacklist(["abc"], None), "abc", &[]),
+            (PasswordChecker::new().must_match(Pattern::new("^\\d+$").unwrap(), None), "abc", &[]),
+            (PasswordChecker::new().must_not_match(Pattern::new("b").unwrap(), None), "abc", &[]),
+            (PasswordChecker::new().keyboard_walk(KeyboardWalk::new(), None), "qwerty", &[]),
+            (PasswordChecker::new().min_entropy(100.0, Estimator::Pool, None), "abc", &["min", "actual"]),
+            (PasswordChecker::new().forbid_unsafe_chars(None), "ab\0", &["codepoint", "offset", "position"]),
+            (PasswordChecker::new().not_similar_to_previous(Similarity::MaxRatio(0.5), None), "abcdefgh2", &[]),
+            (PasswordChecker::new().not_contain_user_info(UserInfoCheck::new(), None), "alexander1", &["contained_in"]),
+            (PasswordChecker::new().with_service_names(["acme"], None), "acme123", &[]),
+            (PasswordChecker::new().length_or_entropy(20, 200.0, 30, None), "abc", &[]),
+            (PasswordChecker::new().max_class_run(2, None), "abc", &["max", "actual"]),
+            (PasswordChecker::new().not_all_numeric(None), "123", &[]),
+            (PasswordChecker::new().not_all_alphabetic(None), "abc", &[]),
+            (PasswordChecker::new().no_phone_patterns(None), "a1234567", &["digits"]),
+            (PasswordChecker::new().not_contain_phone_number(None), "5550109999", &[]),
+            (PasswordChecker::new().not_contain_date_of_birth(None), "July1990", &[]),
+            (PasswordChecker::new().single_script_only(None), "shalomשלום", &["scripts"]),
+            (PasswordChecker::new().min_words(3, None), "correct horse", &["min", "actual"]),
+            (PasswordChecker::new().not_email_or_url(None), "me@example.com", &["shape"]),
+            (PasswordChecker::new().digits_only(None), "12a4", &[]),
+            (PasswordChecker::new().not_sequential_digits(None), "1234", &[]),
+            (PasswordChecker::new().not_repeated_char(None), "1111", &[]),
+            (PasswordChecker::new().not_palindrome(None), "1221", &[]),
+        ];
+        for (checker, password, params) in cases {
+            let code = checker.rules().last().unwrap().code();
+            assert!(violation::CODES.contains(&code));
+            let rendered = checker.check_with_context(password, &context).unwrap_err();
+            let keyed = checker.clone().keys_only(true).check_with_context(password, &context).unwrap_err();
+            assert_eq!(keyed.keys().collect::<Vec<_>>(), rendered.keys().collect::<Vec<_>>(), "{}", code);
+            let key = keyed.keys().next().unwrap();
+            assert_eq!(key.key, code);
+            assert_eq!(key.params.iter().map(|(name, _)| *name).collect::<Vec<_>>(), params, "{}", code);
+            assert!(keyed.iter().all(|v| v.message.is_empty()));
+            assert!(!rendered[0].message.is_empty());
+        }
+    }
+
+    #[test]
+    fn custom_rules_report_like_built_in_ones() {
+        let rule = CustomRule::new("no_company_name", |pw| !pw.to_lowercase().contains("acme"))
+            .unwrap()
+            .params(|pw| vec![("position", ParamValue::Int(pw.to_lowercase().find("acme").unwrap_or(0)))]);
+        let checker = PasswordChecker::new().min_length(12, None).custom(rule.clone(), None);
+        assert!(checker.check("correct horse battery").is_ok());
+        let violations = checker.check("MyAcme2024!").unwrap_err();
+        assert_eq!(
+            violations.to_json(),
+            r#"{"schema":1,"valid":false,"violations":[{"code":"min_length","message":"Password must be at least 12 characters long.","params":{"min":12,"actual":11}},{"code":"no_company_name","message":"Password does not meet the `no_company_name` requirement.","params":{"position":2}}]}"#
+        );
+        assert_eq!(checker.describe()[1], "Passes the `no_company_name` check");
+
+        let options = ValidateOptions::new().message("no_company_name", "Leave the company name out.");
+        assert_eq!(checker.check_with_options("MyAcme2024!", &options).unwrap_err()[1].message, "Leave the company name out.");
+        let keyed = checker.clone().keys_only(true).check("MyAcme2024!").unwrap_err();
+        assert_eq!(keyed.keys().nth(1).unwrap().key, "no_company_name");
+        let own = PasswordChecker::new().custom(rule, Some("No company names."));
+        assert_eq!(own.check("acme").unwrap_err()[0].message, "No company names.");
+    }
+
+    #[test]
+    fn mixed_script_passwords() {
+        // Hebrew and Arabic are written right to left, but every rule sees
+        // characters in logical order, as typed.
+        let (hebrew, arabic) = ("שלום Shalom1", "كلمة2024");
+        let checker = PasswordChecker::new().min_length(12, None).max_class_run(6, None);
+        // Lengths count characters, though each Hebrew or Arabic letter
+        // takes two bytes.
+        assert!(checker.check(hebrew).is_ok());
+        assert_eq!(checker.check(arabic).unwrap_err()[0].params, [("min", ParamValue::Int(12)), ("actual", ParamValue::Int(8))]);
+        assert_eq!(checker.check("שלום").unwrap_err().codes().collect::<Vec<_>>(), ["min_length"]);
+        assert!(checker.check("שלוםשלום!שלוםשלום").unwrap_err().codes().eq(["max_class_run"]));
+
+        // Hebrew and Arabic letters have no case and are not ASCII digits.
+        let classes = PasswordChecker::new().require_upper_lower(None).require_number(None).not_all_alphabetic(None);
+        assert!(classes.check(hebrew).is_ok());
+        assert_eq!(classes.check(arabic).unwrap_err()[0].param("missing"), Some(&ParamValue::List(vec!["uppercase", "lowercase"])));
+        assert_eq!(classes.check("كلمة٣٤٥").unwrap_err().codes().collect::<Vec<_>>(), ["require_upper_lower", "require_number"]);
+        assert!(classes.check("שלום").unwrap_err().codes().any(|code| code == "not_all_alphabetic"));
+
+        assert!(PasswordChecker::new().keyboard_walk(KeyboardWalk::new(), None).check("שלום1234").is_err());
+        let sequence = PasswordChecker::new().analyze("אבגד!");
+        assert!(sequence.patterns.iter().any(|p| p.kind == WeakPattern::Sequence && p.matched == "אבגד"));
+
+        let unsafe_char = PasswordChecker::new().forbid_unsafe_chars(None).check("שלום\0").unwrap_err();
+        assert_eq!(unsafe_char[0].param("offset"), Some(&ParamValue::Int(8)));
+        assert_eq!(unsafe_char[0].param("position"), Some(&ParamValue::Int(4)));
+        assert_eq!(unsafe_char[0].message, "Password contains U+0000 as character 5, which cannot be stored safely.");
+
+        let single = PasswordChecker::new().single_script_only(None);
+        assert!(single.check(arabic).is_ok());
+        assert!(single.check("שלום 1234!").is_ok());
+        let violations = single.check(hebrew).unwrap_err();
+        assert_eq!(violations[0].param("scripts"), Some(&ParamValue::List(vec!["hebrew", "latin"])));
+        assert_eq!(violations[0].message, "Password must not mix letters from different alphabets.");
+        assert_eq!(Violations::from_json(&violations.to_json()).unwrap(), violations);
+    }
+
+    #[test]
+    fn located_violations_have_character_spans() {
+        let span = |checker: PasswordChecker<'_>, password: &str| checker.check(password).unwrap_err()[0].span.clone();
+        // `é` and `ש` are two bytes each, so character and byte indices differ.
+        let unsafe_chars = || PasswordChecker::new().forbid_unsafe_chars(None);
+        assert_eq!(span(unsafe_chars(), "ab\0"), Some(2..3));
+        assert_eq!(span(unsafe_chars(), "éé\0x"), Some(2..3));
+        let walk = || PasswordChecker::new().keyboard_walk(KeyboardWalk::new(), None);
+        assert_eq!(span(walk(), "qwerty1!"), Some(0..6));
+        assert_eq!(span(walk(), "שלוםasdfg!"), Some(4..9));
+        let run = || PasswordChecker::new().max_class_run(3, None);
+        assert_eq!(span(run(), "Aaaaa1!"), Some(0..5));
+        assert_eq!(span(run(), "é1!1234x"), Some(3..7));
+        let phone = || PasswordChecker::new().no_phone_patterns(None);
+        assert_eq!(span(phone(), "Dave07911123456!"), Some(4..15));
+        assert_eq!(span(phone(), "ééé 0791-112 3456"), Some(4..17));
+
+        let global = PasswordChecker::new().min_length(12, None).require_number(None).blacklist(["abc"], None);
+        assert!(global.check("abc").unwrap_err().iter().all(|v| v.span.is_none()));
+    }
+
+    #[test]
+    fn checkers_collect_and_extend_rules() {
+        let built = PasswordChecker::new()
+            .min_length(12, Some("Twelve or more"))
+            .require_upper_lower(None)
+            .blacklist(["password1"], None)
+            .max_class_run(4, None);
+        let rules = vec![
+            Rule::MinLength(12, Some(Cow::Borrowed("Twelve or more"))),
+            Rule::RequireUpperLower(None),
+            Rule::Blacklist(Dictionary::new(["password1"]), None),
+            Rule::MaxClassRun(4, None),
+        ];
+        assert_eq!(PasswordChecker::from_rules(rules.clone()), built);
+        let collected: PasswordChecker<'_> = rules.iter().cloned().collect();
+        assert_eq!(collected, built);
+
+        let mut extended = PasswordChecker::new().min_length(12, Some("Twelve or more")).fail_fast(true);
+        extended.extend(rules[1..].iter().cloned());
+        assert_eq!(extended.rules(), built.rules());
+        assert!(extended.fail_fast);
+        assert_eq!(extended.check("Aaaaaaaaaaaa").unwrap_err().codes().collect::<Vec<_>>(), ["max_class_run"]);
+    }
+
+    #[test]
+    fn min_words_counts_passphrase_words() {
+        let checker = PasswordChecker::new().min_words(4, None);
+        for password in ["correct horse battery staple", "correct-horse-battery-staple", "tr0ub4dor_and.42 more"] {
+            assert!(checker.check(password).is_ok(), "{}", password);
+        }
+        let violations = checker.check("correcthorsebatterystaple").unwrap_err();
+        assert_eq!(violations[0].params, [("min", ParamValue::Int(4)), ("actual", ParamValue::Int(1))]);
+        assert_eq!(violations[0].message, "Password must have at least 4 words.");
+        // Single characters are not words.
+        assert_eq!(checker.check("a b c d e f horse").unwrap_err()[0].param("actual"), Some(&ParamValue::Int(1)));
+        let plus = PasswordChecker::new().min_words_with(4, "+", None);
+        assert!(plus.check("correct+horse+battery+staple").is_ok());
+        assert!(plus.check("correct-horse-battery-staple").is_err());
+
+        // Either twelve characters with three classes or a passphrase.
+        let complex = PasswordChecker::new().min_length(12, None).require_upper_lower(None).require_number(None);
+        let passphrase = PasswordChecker::new().min_words(4, None);
+        let policies = [("complex", &complex), ("passphrase", &passphrase)];
+        for password in ["Tr0ub4dor&3xyz", "correct horse battery staple"] {
+            assert!(evaluate_all(&policies, password).passes_any(), "{}", password);
+        }
+        assert!(!evaluate_all(&policies, "correct horse").passes_any());
+    }
+
+    #[test]
+    fn pin_rules() {
+        let digits = PasswordChecker::new().digits_only(None);
+        assert!(digits.check("0472").is_ok() && digits.check("").is_ok());
+        for pin in ["12a4", "12 34", "١٢٣٤", "１２３４"] {
+            assert_eq!(digits.validate(pin), Err(vec!["Password must consist only of digits.".to_string()]), "{}", pin);
+        }
+        let sequences = PasswordChecker::new().not_sequential_digits(None);
+        for pin in ["1234", "9876", "456789", "210"] {
+            assert!(sequences.check(pin).is_err(), "{}", pin);
+        }
+        for pin in ["1235", "8901", "12", "2468"] {
+            assert!(sequences.check(pin).is_ok(), "{}", pin);
+        }
+        let repeats = PasswordChecker::new().not_repeated_char(None);
+        assert!(repeats.check("1111").is_err() && repeats.check("aaaaaaaa").is_err());
+        assert!(repeats.check("1112").is_ok() && repeats.check("7").is_ok());
+        let palindromes = PasswordChecker::new().not_palindrome(Some("Not the same both ways"));
+        assert_eq!(palindromes.validate("12321"), Err(vec!["Not the same both ways".to_string()]));
+        assert!(palindromes.check("1231").is_ok());
+    }
+
+    #[test]
+    fn pin_preset_defaults() {
+        let checker = PasswordChecker::pin();
+        assert_eq!(
+            checker.describe(),
+            ["At least 4 characters", "At most 8 characters", "Only digits", "Not consecutive digits such as 1234", "Not one character repeated", "Not a commonly used password"]
+        );
+        for pin in ["7291", "04729", "83920174"] {
+            assert!(checker.check(pin).is_ok(), "{}", pin);
+        }
+        for (pin, codes) in [
+            ("729", &["min_length"][..]),
+            ("729104836", &["max_length"]),
+            ("72a1", &["digits_only"]),
+            ("2345", &["not_sequential_digits"]),
+            ("1234", &["not_sequential_digits", "blacklist"]),
+            ("0000", &["not_repeated_char", "blacklist"]),
+            ("2580", &["blacklist"]),
+            ("1987", &["blacklist"]),
+        ] {
+            assert_eq!(checker.check(pin).unwrap_err().codes().collect::<Vec<_>>(), codes, "{}", pin);
+        }
+        // Palindromes are opt-in, and violations share the password shape.
+        assert!(checker.check("7227").is_ok());
+        let violations = checker.not_palindrome(None).check("7227").unwrap_err();
+        assert_eq!(Violations::from_json(&violations.to_json()).unwrap(), violations);
+        assert_eq!(violations[0].code, "not_palindrome");
+    }
+
+    #[test]
+    fn email_addresses_and_urls_are_rejected() {
+        let checker = PasswordChecker::new().not_email_or_url(None);
+        for (password, shape, message) in [
+            ("alice@example.com", "email", "Password must not be an email address."),
+            ("https://example.com/account/login", "url", "Password must not be a web address."),
+            ("www.example.com", "url", "Password must not be a web address."),
+            ("www.", "url", "Password must not be a web address."),
+        ] {
+            let violations = checker.check(password).unwrap_err();
+            assert_eq!(violations[0].param("shape"), Some(&ParamValue::Name(shape)), "{}", password);
+            assert_eq!(violations[0].message, message);
+            assert_eq!(Violations::from_json(&violations.to_json()).unwrap(), violations);
+        }
+        for password in ["p@ssword.123", "correct@horse", "Tr0ub4dor&3.www"] {
+            assert!(checker.check(password).is_ok(), "{}", password);
+        }
+        let custom = PasswordChecker::new().not_email_or_url(Some("That looks like an address."));
+        assert_eq!(custom.validate("bob@example.org"), Err(vec!["That looks like an address.".to_string()]));
+    }
+
+    #[test]
+    fn upper_lower_reports_the_missing_case() {
+        let checker = PasswordChecker::new().require_upper_lower(None);
+        for (password, missing, message) in [
+            ("lowercase1", &["uppercase"][..], "Password must include at least one uppercase letter."),
+            ("UPPERCASE1", &["lowercase"], "Password must include at least one lowercase letter."),
+            ("12345678", &["uppercase", "lowercase"], "Password must include both uppercase and lowercase letters."),
+        ] {
+            let violations = checker.check(password).unwrap_err();
+            assert_eq!(violations[0].param("missing"), Some(&ParamValue::List(missing.to_vec())), "{}", password);
+            assert_eq!(violations[0].message, message);
+        }
+        assert!(checker.check("MixedCase").is_ok());
+
+        let custom = PasswordChecker::new().require_upper_lower(Some("Mix the case."));
+        for password in ["lowercase1", "UPPERCASE1", "12345678"] {
+            let violations = custom.check(password).unwrap_err();
+            assert_eq!(violations[0].message, "Mix the case.");
+            assert!(violations[0].param("missing").is_some());
+        }
+    }
+
+    #[test]
+    fn keys_only_leaves_rendering_alone_when_off() {
+        let checker = PasswordChecker::new().min_length(12, Some("Longer, please"));
+        let rendered = checker.check("hunter2").unwrap_err();
+        assert_eq!(rendered.iter().map(|v| v.message.as_str()).collect::<Vec<_>>(), ["Longer, please"]);
+        let keyed = checker.keys_only(true).check("hunter2").unwrap_err();
+        assert_eq!(keyed.keys().next().unwrap().to_json(), r#"{"key":"min_length","params":{"min":12,"actual":7}}"#);
+        assert_eq!(keyed[0].message, "");
+    }
+
+    #[test]
+    fn observers_see_every_validation() {
+        use std::sync::Mutex;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let calls = Arc::new(Mutex::new(0));
+        let (seen, counted) = (Arc::clone(&events), Arc::clone(&calls));
+        let checker = PasswordChecker::new()
+            .named("tenant-a")
+            .min_length(8, None)
+            .require_number(None)
+            .with_observer(move |e| seen.lock().unwrap().push((e.policy.map(String::from), e.valid, e.failed.clone())))
+            .with_observer(move |_| *counted.lock().unwrap() += 1);
+
+        assert!(checker.validate("abcdefg1").is_ok());
+        assert!(checker.check("abc").is_err());
+        let fast = checker.clone().fail_fast(true);
+        assert!(fast.validate("abc").is_err());
+
+        let tenant = Some("tenant-a".to_string());
+        assert_eq!(*events.lock().unwrap(), vec![
+            (tenant.clone(), true, vec![]),
+            (tenant.clone(), false, vec!["min_length", "require_number"]),
+            (tenant, false, vec!["min_length"]),
+        ]);
+        assert_eq!(*calls.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn dry_runs_report_without_rejecting() {
+        use std::sync::Mutex;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&events);
+        let enforced = PasswordChecker::new()
+            .min_length(12, None)
+            .require_number(None)
+            .with_observer(move |e| seen.lock().unwrap().push((e.dry_run, e.valid, e.failed.clone())));
+        let staged = enforced.clone().dry_run(true);
+
+        for password in ["abc", "abcdefgh", "abcdefghijk1"] {
+            assert!(staged.validate(password).is_ok(), "{}", password);
+            assert!(staged.check_with_context(password, &UserContext::new()).is_ok());
+        }
+        let (result, violations) = staged.validate_staged("abc");
+        assert!(result.is_ok());
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["min_length", "require_number"]);
+        assert_eq!(*violations, enforced.check("abc").unwrap_err());
+        assert_eq!(events.lock().unwrap()[0], (true, false, vec!["min_length", "require_number"]));
+
+        let restored = staged.dry_run(false);
+        assert_eq!(restored.check("abc"), enforced.check("abc"));
+        let (result, violations) = restored.validate_staged("abc");
+        assert_eq!(result.unwrap_err().len(), 2);
+        assert!(violations.is_empty());
+        assert!(!events.lock().unwrap().last().unwrap().0);
+
+        // The input guard is a safeguard, not a rule being rolled out.
+        let guarded = PasswordChecker::new().min_length(12, None).max_input_bytes(4).dry_run(true);
+        let (result, violations) = guarded.validate_staged("abcdef");
+        assert_eq!(result.unwrap_err().codes().collect::<Vec<_>>(), ["input_too_large"]);
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn timing_is_opt_in() {
+        use std::sync::Mutex;
+
+        let timings = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&timings);
+        let untimed = PasswordChecker::new()
+            .min_length(8, None)
+            .blacklist(Dictionary::new((0..2_000).map(|i| format!("word{}", i))).fuzzy(1), None)
+            .require_number(None)
+            .with_observer(move |e| sink.lock().unwrap().push((e.timing.clone(), e.duration)));
+        let _ = untimed.check("correct horse");
+        assert_eq!(timings.lock().unwrap()[0].0, None);
+
+        let timed = untimed.with_timing(true);
+        let _ = timed.check("correct horse");
+        let _ = timed.clone().fail_fast(true).check("abc");
+        let events = timings.lock().unwrap();
+        let (timing, duration) = &events[1];
+        let timing = timing.as_ref().unwrap();
+        let codes: Vec<_> = timing.rules.iter().map(|rule| (rule.index, rule.code)).collect();
+        assert_eq!(codes, [(0, "min_length"), (1, "blacklist"), (2, "require_number")]);
+        assert_eq!(timing.total, *duration);
+        assert!(timing.rules.iter().map(|rule| rule.duration).sum::<std::time::Duration>() <= timing.total);
+        assert_eq!(timing.slowest().unwrap().code, "blacklist");
+        assert_eq!(events[2].0.as_ref().unwrap().rules.len(), 1);
+    }
+
+    #[test]
+    fn observer_events_never_contain_the_password() {
+        use std::sync::Mutex;
+
+        let dumps = Arc::new(Mutex::new(String::new()));
+        let sink = Arc::clone(&dumps);
+        let checker = PasswordChecker::new()
+            .min_length(64, None)
+            .with_observer(move |e| sink.lock().unwrap().push_str(&format!("{:?}", e)));
+        let _ = checker.validate("hunter2-secret");
+        assert!(!dumps.lock().unwrap().contains("hunter2"));
+    }
+
+    #[test]
+    fn blacklist_rejects_listed_passwords() {
+        let checker = PasswordChecker::new().blacklist(["password", "letmein"], None);
+        assert_eq!(checker.validate("letmein").unwrap_err(), vec!["Password is too common.".to_string()]);
+        assert!(checker.validate("Letmein").is_ok());
+    }
+
+    #[test]
+    fn pattern_rules() {
+        let checker = PasswordChecker::new()
+            .must_match(Pattern::new("^[A-Za-z]").unwrap(), Some("Start with a letter"))
+            .must_not_match(Pattern::new("aa|bb|11").unwrap(), None);
+        assert!(checker.validate("x1b2").is_ok());
+        assert_eq!(checker.check("1aa").unwrap_err().codes().collect::<Vec<_>>(), ["must_match", "must_not_match"]);
+    }
+
+    #[test]
+    fn bcrypt_limit_counts_bytes() {
+        let checker = PasswordChecker::new().for_bcrypt();
+        assert!(checker.validate(&"a".repeat(72)).is_ok());
+        let err = checker.check(&"a".repeat(73)).unwrap_err();
+        assert_eq!(err[0].code, "max_bytes");
+        assert_eq!(err[0].param("actual"), Some(&ParamValue::Int(73)));
+        assert_eq!(err[0].param("max"), Some(&ParamValue::Int(72)));
+
+        // 70 characters, 4 of them two bytes long.
+        let accented = format!("{}éééé", "a".repeat(66));
+        assert_eq!(accented.chars().count(), 70);
+        let err = checker.check(&accented).unwrap_err();
+        assert_eq!(err[0].param("actual"), Some(&ParamValue::Int(74)));
+        assert_eq!(err[0].message, BCRYPT_MESSAGE);
+        assert_eq!(PasswordChecker::new().max_bytes(4, None).validate("ééé"), Err(vec!["Password must be at most 4 bytes long.".to_string()]));
+    }
+
+    #[test]
+    fn length_rules_count_characters_and_max_bytes_bytes() {
+        // Six characters in twelve bytes.
+        let password = "éééééé";
+        assert!(PasswordChecker::new().min_length(6, None).max_length(6, None).check(password).is_ok());
+        assert_eq!(PasswordChecker::new().min_length(7, None).check(password).unwrap_err()[0].param("actual"), Some(&ParamValue::Int(6)));
+        let err = PasswordChecker::new().max_length(8, None).max_bytes(8, None).check(password).unwrap_err();
+        assert_eq!(err.codes().collect::<Vec<_>>(), ["max_bytes"]);
+        assert_eq!(err[0].param("actual"), Some(&ParamValue::Int(12)));
+        let limits = PasswordChecker::new().max_length(8, None).max_bytes(10, None);
+        let mut session = ValidationSession::new(&limits);
+        session.push_str(password);
+        assert_eq!(session.check().unwrap_err().codes().collect::<Vec<_>>(), ["max_bytes"]);
+        session.pop(1);
+        assert!(session.check().is_ok());
+    }
+
+    #[test]
+    fn unsafe_chars_report_their_position() {
+        let checker = PasswordChecker::new().forbid_unsafe_chars(None);
+        for (password, offset) in [("\0secret", 0), ("sec\0ret", 3), ("secret\0", 6)] {
+            let err = checker.check(password).unwrap_err();
+            assert_eq!(err[0].param("offset"), Some(&ParamValue::Int(offset)));
+            assert_eq!(err[0].param("codepoint"), Some(&ParamValue::Int(0)));
+            assert_eq!(err[0].param("position"), Some(&ParamValue::Int(offset)));
+            assert_eq!(err[0].message, format!("Password contains U+0000 as character {}, which cannot be stored safely.", offset + 1));
+        }
+        let err = checker.check("pässword\u{FFFE}").unwrap_err();
+        assert_eq!((err[0].param("offset"), err[0].param("position")), (Some(&ParamValue::Int(9)), Some(&ParamValue::Int(8))));
+        assert_eq!(err[0].message, "Password contains U+FFFE as character 9, which cannot be stored safely.");
+        for c in ['\u{FDD0}', '\u{FDEF}', '\u{1FFFF}', '\u{10FFFE}'] {
+            assert!(checker.validate(&format!("a{}", c)).is_err(), "{:?}", c);
+        }
+        for password in ["pässword", "\u{FDCF}\u{FFFD}\u{1FFFD}", "tab\tand space"] {
+            assert!(checker.validate(password).is_ok(), "{:?}", password);
+        }
+        let custom = PasswordChecker::new().forbid_unsafe_chars(Some("Invalid character"));
+        assert_eq!(custom.validate("a\0"), Err(vec!["Invalid character".to_string()]));
+    }
+
+    #[test]
+    fn similarity_needs_the_previous_password() {
+        let checker = PasswordChecker::new().min_length(8, None).not_similar_to_previous(Similarity::MinEdits(3), None);
+        let context = UserContext::new().previous_password("Pa55word!");
+        assert_eq!(
+            checker.check_with_context("Pa55owrd!", &context).unwrap_err().codes().collect::<Vec<_>>(),
+            ["not_similar_to_previous"]
+        );
+        assert!(checker.check_with_context("Pa55word!xyz", &context).is_ok());
+        assert_eq!(checker.check("Pa55owrd!").unwrap_err().codes().collect::<Vec<_>>(), ["context_required"]);
+        assert!(checker.check_with_context("Pa55owrd!", &UserContext::new()).is_ok());
+        let ratio = PasswordChecker::new().not_similar_to_previous(Similarity::MaxRatio(0.5), None);
+        assert!(ratio.check_with_context("Pa55word!", &UserContext::new().previous_password("pa55word?")).is_err());
+        assert!(ratio.check_with_context("correct horse", &context).is_ok());
+    }
+
+    #[test]
+    fn user_info_messages_name_the_direction() {
+        let checker = PasswordChecker::new().not_contain_user_info(UserInfoCheck::new().bidirectional(true), None);
+        let context = UserContext::new().username("alexander.hamilton");
+        let message = |password| checker.check_with_context(password, &context).unwrap_err()[0].message.clone();
+        assert_eq!(message("Hamilton1804!"), "Password must not contain your name, username or email address.");
+        assert_eq!(message("xander.ham"), "Password must not be part of your name, username or email address.");
+    }
+
+    #[test]
+    fn length_or_entropy_accepts_either_way() {
+        let checker = PasswordChecker::new().length_or_entropy(10, 60.0, 16, None);
+        // Short and weak, short but strong, long but weak, long and strong.
+        assert!(checker.validate("aaaaaaaaaa").is_err());
+        assert!(checker.validate("k$3Q!x9zR2").is_ok());
+        assert!(checker.validate("aaaaaaaaaaaaaaaa").is_ok());
+        assert!(checker.validate("k$3Q!x9zR2m#7Lp@").is_ok());
+        // Strong, but shorter than even the strong minimum.
+        assert!(checker.validate("k$3Q!x9z").is_err());
+        let message = checker.validate("aaaaaaaaaa").unwrap_err().remove(0);
+        assert!(message.contains("at least 16 characters"), "{}", message);
+        assert!(message.contains("at least 10 characters if it is random"), "{}", message);
+        assert_eq!(checker.check("short").unwrap_err().codes().collect::<Vec<_>>(), ["length_or_entropy"]);
+    }
+
+    #[test]
+    fn score_weights_apply_blacklists() {
+        let checker = PasswordChecker::new().blacklist(["Tr0ub4d&3"], None).score_weights(ScoreWeights::default());
+        let unlisted = PasswordChecker::new().score_weights(ScoreWeights::default());
+        assert!(checker.score("Tr0ub4d&3") + 40 < unlisted.score("Tr0ub4d&3"));
+        assert_eq!(PasswordChecker::new().score("abcd"), 18);
+    }
+
+    #[test]
+    fn zxcvbn_scale_buckets_canonical_examples() {
+        let checker = PasswordChecker::new().blacklist(Dictionary::builtin_with(["password"]), None);
+        assert_eq!(checker.score_zxcvbn_scale("password"), 0);
+        assert_eq!(checker.score_zxcvbn_scale("xq"), 0);
+        assert_eq!(checker.score_zxcvbn_scale("abc"), 0);
+        assert_eq!(checker.score_zxcvbn_scale("xqj"), 1);
+        assert_eq!(checker.score_zxcvbn_scale("xq7j2"), 2);
+        assert_eq!(checker.score_zxcvbn_scale("correct horse battery staple"), 4);
+        for password in ["", "a", "Tr0ub4dor&3", &"x".repeat(1000), &"aB3!".repeat(500)] {
+            assert!(checker.score_zxcvbn_scale(password) <= 4);
+        }
+    }
+
+    #[test]
+    fn meter_reports_milestones() {
+        let checker = PasswordChecker::new().blacklist(["Password123!"], None);
+        let reached = |password: &str| -> Vec<bool> {
+            checker.meter(password).segments.iter().map(|s| s.reached).collect()
+        };
+        assert_eq!(reached("abc"), [false, false, false, true, false]);
+        assert_eq!(reached("Password123!"), [true, true, true, false, true]);
+        assert_eq!(reached("Tr0ub4dor&3"), [true, false, true, true, true]);
+        assert_eq!(reached("Correct horse battery staple"), [true, true, true, true, true]);
+
+        let labels: Vec<_> = PasswordChecker::new().min_length(10, None).meter("").segments.into_iter().map(|s| s.label).collect();
+        assert_eq!(labels[..2], ["At least 10 characters", "At least 14 characters"]);
+    }
+
+    #[test]
+    fn custom_meter_spec_overrides_default() {
+        let spec = MeterSpec::new().milestone("Long", Milestone::MinLength(20)).milestone("Random", Milestone::MinEntropy(40.0));
+        let meter = PasswordChecker::new().meter_spec(spec).meter("Tr0ub4dor&3");
+        assert_eq!(meter, Meter {
+            segments: vec![
+                Segment { label: "Long".to_string(), reached: false },
+                Segment { label: "Random".to_string(), reached: true },
+            ],
+        });
+        assert_eq!(meter.reached(), 1);
+    }
+
+    #[test]
+    fn adding_a_rule_never_accepts_a_rejected_password() {
+        use crate::arbitrary::{CharClass, Seeded};
+        type Addition = fn(PasswordChecker<'static>) -> PasswordChecker<'static>;
+        // Builder methods, some adding several rules, a group, a rule only
+        // warning or one needing a context.
+        const ADDITIONS: [Addition; 6] = [
+            |c| c.for_bcrypt(),
+            |c| c.recommended_length(16, None),
+            |c| c.min_words(2, None),
+            |c| c.group("Mix it up.", |g| g.require_number(None).require_upper_lower(None)),
+            |c| c.not_contain_user_info(UserInfoCheck::new(), None),
+            |c| c.require_special_char_with(SpecialCharPolicy::UnicodeCategories, None),
+        ];
+        let specials = [SpecialCharPolicy::Builtin, SpecialCharPolicy::UnicodeCategories, SpecialCharPolicy::Chars("#%".into())];
+        let mut rng = Seeded::new(0x2545_F491_4F6C_DD1D);
+        for _ in 0..2_000 {
+            let special = specials[rng.below(specials.len())].clone();
+            let rule = |rng: &mut Seeded| match rng.rule() {
+                Rule::RequireSpecialChar(_, msg) => Rule::RequireSpecialChar(special.clone(), msg),
+                rule => rule,
+            };
+            let rules: Vec<_> = (0..rng.below(4)).map(|_| rule(&mut rng)).collect();
+            let mut checker = match rng.below(4) {
+                0 => PasswordChecker::new().group("Pick a stronger password.", |mut g| {
+                    g.extend(rules);
+                    g
+                }),
+                _ => PasswordChecker::from_rules(rules),
+            };
+            // The relaxing modifiers are exempt, but rules added under
+            // them must still only reject more.
+            if rng.below(2) == 0 {
+                checker = checker.fail_fast(true);
+            }
+            if rng.below(3) == 0 {
+                checker = checker.length_exemption(16);
+            }
+            let extended = if rng.below(2) == 0 {
+                let mut extended = checker.clone();
+                extended.extend([rule(&mut rng)]);
+                extended
+            } else {
+                let add = ADDITIONS[rng.below(ADDITIONS.len())];
+                // A second special set is refused with a panic.
+                let mut rules = checker.rules.clone();
+                rules.extend(add(PasswordChecker::new()).rules);
+                if classes::special_conflict(&rules).is_some() {
+                    continue;
+                }
+                add(checker.clone())
+            };
+            let password = rng.password(0..24, &CharClass::ALL);
+            let before = checker.check(&password).err().map(|v| v.len()).unwrap_or(0);
+            let after = extended.check(&password).err().map(|v| v.len()).unwrap_or(0);
+            assert!(after >= before, "{:?} {:?}", extended, password);
+        }
+    }
+
+    #[test]
+    fn min_entropy_uses_its_estimator() {
+        let checker = PasswordChecker::new().min_entropy(40.0, Estimator::Pool, None);
+        assert!(checker.validate("Monkey123!").is_ok());
+        assert!(checker.validate("monkey").is_err());
+        assert_eq!(checker.describe(), vec!["At least 40 bits of estimated strength".to_string()]);
+        #[cfg(feature = "markov")]
+        {
+            let checker = PasswordChecker::new().min_entropy(60.0, Estimator::Markov, None).estimator(Estimator::Markov);
+            assert!(checker.validate("Monkey123!").is_err());
+            assert!(checker.validate("r8#Kq2!vPz").is_ok());
+            assert!(checker.score("Monkey123!") < PasswordChecker::new().score("Monkey123!"));
+        }
+    }
+
+    #[cfg(feature = "confusables")]
+    #[test]
+    fn confusable_lookalikes_are_caught() {
+        let checker = PasswordChecker::new()
+            .blacklist(["password", "qwerty"], None)
+            .must_not_match(Pattern::new("admin").unwrap(), None);
+        let aware = checker.clone().match_confusables(true);
+        for evasion in ["p\u{0430}ssword", "p\u{03B1}ssw\u{03BF}rd", "ｑｗｅｒｔｙ", "my\u{0430}dmin1"] {
+            assert!(checker.validate(evasion).is_ok(), "{}", evasion);
+            assert!(aware.validate(evasion).is_err(), "{}", evasion);
+        }
+        for legitimate in ["пароль", "κωδικός", "密码", "Пароль-2024!"] {
+            assert!(aware.validate(legitimate).is_ok(), "{}", legitimate);
+        }
+    }
+
+    #[test]
+    fn shared_checker_gives_serial_results_across_threads() {
+        let words: Vec<String> = (0..1000).map(|i| format!("common{}", i)).collect();
+        let checker = Arc::new(
+            PasswordChecker::new()
+                .min_length(8, None)
+                .require_number(None)
+                .blacklist(Dictionary::new(words), None),
+        );
+        let inputs: Vec<String> = (0..2000).map(|i| format!("common{}", i * 7 % 1500)).collect();
+        let serial: Vec<_> = inputs.iter().map(|pw| checker.check(pw)).collect();
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let checker = Arc::clone(&checker);
+                let inputs = inputs.clone();
+                std::thread::spawn(move || inputs.iter().map(|pw| checker.check(pw)).collect::<Vec<_>>())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), serial);
+        }
+    }
+
+    #[test]
+    fn debug_output_is_bounded_by_the_configuration() {
+        let tenant = "Passwords for the Example Corporation staff portal must follow the 2026 security policy.";
+        let checker = |words: usize| {
+            PasswordChecker::new()
+                .min_length(12, Some(tenant))
+                .blacklist(Dictionary::new((0..words).map(|i| format!("common{}", i))), None)
+                .with_service_names((0..words).map(|i| format!("service{}", i)), None)
+                .custom(CustomRule::new("no_acme", |pw| !pw.contains("acme")).unwrap(), None)
+                .keyboard_walk(KeyboardWalk::new().layouts([keyboard::KeyboardLayout::custom("pad", ('a'..).take(words / 100).map(|c| (c, "a")))]), None)
+                .with_catalog(Catalog::new("de").message("min_length", "Zu kurz."))
+        };
+        let (small, large) = (format!("{:?}", checker(10_000)), format!("{:?}", checker(100_000)));
+        assert!(small.contains("Blacklist(Dictionary(entries: 10000), None)"), "{}", small);
+        assert!(small.contains("ServiceNames(names: 10000, None)"), "{}", small);
+        assert!(small.contains(r#"Custom(CustomRule { code: "no_acme", .. }, None)"#), "{}", small);
+        assert!(small.contains(r#"MinLength(12, Some("Passwords for the Example Corporation st…"))"#), "{}", small);
+        assert!(small.contains(r#"CustomLayout { name: "pad", keys: 100 }"#), "{}", small);
+        assert!(small.contains(r#"Catalog { locale: "de", messages: 1 }"#), "{}", small);
+        assert!(large.contains("entries: 100000") && large.len() <= small.len() + 8, "{}", large);
+        assert!(large.len() < 2_000, "{}", large);
+    }
 }
@@ -0,0 +1,213 @@
+//! Bounds on the size of policies loaded from untrusted configs.
+
+use crate::config::{LimitExceeded, migrate_root};
+use crate::json::{self, Value};
+use crate::{ConfigError, PasswordChecker, PolicyConfig};
+
+/// Limits on what a loaded policy may contain, enforced by
+/// [`PolicyConfig::from_json_with_limits`] and
+/// [`PasswordChecker::from_config_with_limits`] before any pattern is
+/// compiled or blacklist built. `None` leaves a field unlimited;
+/// [`PolicyLimits::NONE`] loads anything [`PolicyConfig::from_json`] does.
+///
+/// The defaults are generous for one organization's policy but bound what
+/// a hostile one costs a shared service. Limit the size of the JSON
+/// document itself before parsing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolicyLimits {
+    /// Rules in the policy. Default 256.
+    pub max_rules: Option<usize>,
+    /// `must_match` and `must_not_match` rules together. Default 64.
+    pub max_patterns: Option<usize>,
+    /// Bytes in each pattern's source. Default 1,024.
+    pub max_pattern_len: Option<usize>,
+    /// Words in all blacklists together. Default 1,000,000.
+    pub max_blacklist_entries: Option<usize>,
+    /// Bytes of all blacklists together: the words, or the size of a
+    /// sorted `file`. Default 64 MiB.
+    pub max_blacklist_bytes: Option<usize>,
+    /// Names in all `service_names` rules together, the strings passwords
+    /// must not contain. Default 10,000.
+    pub max_service_names: Option<usize>,
+}
+
+impl PolicyLimits {
+    /// No limit at all.
+    pub const NONE: PolicyLimits = PolicyLimits {
+        max_rules: None,
+        max_patterns: None,
+        max_pattern_len: None,
+        max_blacklist_entries: None,
+        max_blacklist_bytes: None,
+        max_service_names: None,
+    };
+
+    /// Every limit `items`, the rules of a migrated document, go over.
+    fn exceeded(&self, items: &[Value]) -> Vec<LimitExceeded> {
+        let mut exceeded = Vec::new();
+        let mut check = |limit: &'static str, max: Option<usize>, path: String, found: usize| {
+            if let Some(max) = max.filter(|max| found > *max) {
+                exceeded.push(LimitExceeded { limit, path, max, found });
+            }
+        };
+        let (mut patterns, mut entries, mut bytes, mut names) = (0, 0, 0, 0);
+        for (i, item) in items.iter().enumerate() {
+            let strings = |key| match item.get(key) {
+                Some(Value::Array(items)) => items.iter().filter_map(|item| if let Value::String(s) = item { Some(s.len()) } else { None }).collect(),
+                _ => Vec::new(),
+            };
+            match item.get("rule") {
+                Some(Value::String(rule)) if rule == "must_match" || rule == "must_not_match" => {
+                    patterns += 1;
+                    if let Some(Value::String(pattern)) = item.get("pattern") {
+                        check("max_pattern_len", self.max_pattern_len, format!("rules[{}].pattern", i), pattern.len());
+                    }
+                }
+                Some(Value::String(rule)) if rule == "blacklist" => {
+                    let words = strings("words");
+                    entries += words.len();
+                    bytes += words.iter().sum::<usize>();
+                    // A missing file is reported when the rule is parsed.
+                    if let Some(Value::String(file)) = item.get("file") &&
+                        let Ok(metadata) = std::fs::metadata(file)
+                    {
+                        bytes += usize::try_from(metadata.len()).unwrap_or(usize::MAX);
+                    }
+                }
+                Some(Value::String(rule)) if rule == "service_names" => names += strings("names").len(),
+                _ => {}
+            }
+        }
+        check("max_rules", self.max_rules, "rules".to_string(), items.len());
+        check("max_patterns", self.max_patterns, "rules".to_string(), patterns);
+        check("max_blacklist_entries", self.max_blacklist_entries, "rules".to_string(), entries);
+        check("max_blacklist_bytes", self.max_blacklist_bytes, "rules".to_string(), bytes);
+        check("max_service_names", self.max_service_names, "rules".to_string(), names);
+        exceeded
+    }
+}
+
+impl Default for PolicyLimits {
+    fn default() -> Self {
+        PolicyLimits {
+            max_rules: Some(256),
+            max_patterns: Some(64),
+            max_pattern_len: Some(1024),
+            max_blacklist_entries: Some(1_000_000),
+            max_blacklist_bytes: Some(64 << 20),
+            max_service_names: Some(10_000),
+        }
+    }
+}
+
+impl PolicyConfig {
+    /// Like [`from_json`](Self::from_json), rejecting policies over
+    /// `limits` with [`ConfigError::LimitsExceeded`], which lists every
+    /// limit exceeded.
+    pub fn from_json_with_limits(input: &str, limits: &PolicyLimits) -> Result<Self, ConfigError> {
+        let document = migrate_root(&json::parse(input)?)?;
+        let exceeded = limits.exceeded(&document.rules);
+        if !exceeded.is_empty() {
+            return Err(ConfigError::LimitsExceeded(exceeded));
+        }
+        Self::from_document(&document)
+    }
+}
+
+impl PasswordChecker<'static> {
+    /// Parses a JSON policy within `limits` and builds a checker from it,
+    /// for policies uploaded by tenants of a shared service.
+    ///
+    /// ```
+    /// use passcheck::{ConfigError, PasswordChecker, PolicyLimits};
+    ///
+    /// let limits = PolicyLimits { max_rules: Some(2), ..PolicyLimits::default() };
+    /// let policy = r#"{"rules": [{"rule": "min_length", "length": 12}, {"rule": "require_number"}, {"rule": "not_palindrome"}]}"#;
+    /// let Err(ConfigError::LimitsExceeded(exceeded)) = PasswordChecker::from_config_with_limits(policy, &limits) else { panic!() };
+    /// assert_eq!((exceeded[0].limit, exceeded[0].found), ("max_rules", 3));
+    /// assert!(PasswordChecker::from_config_with_limits(policy, &PolicyLimits::NONE).is_ok());
+    /// ```
+    pub fn from_config_with_limits(input: &str, limits: &PolicyLimits) -> Result<Self, ConfigError> {
+        PolicyConfig::from_json_with_limits(input, limits).map(Self::from_config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(rules: &[String]) -> String {
+        format!(r#"{{"rules": [{}]}}"#, rules.join(","))
+    }
+
+    fn exceeded(input: &str, limits: &PolicyLimits) -> Vec<(&'static str, String, usize)> {
+        match PolicyConfig::from_json_with_limits(input, limits) {
+            Ok(_) => Vec::new(),
+            Err(ConfigError::LimitsExceeded(exceeded)) => exceeded.into_iter().map(|e| (e.limit, e.path, e.found)).collect(),
+            Err(other) => panic!("{}", other),
+        }
+    }
+
+    fn words(n: usize, len: usize) -> String {
+        let words: Vec<String> = (0..n).map(|i| format!(r#""{:0len$}""#, i, len = len)).collect();
+        format!(r#"{{"rule": "blacklist", "words": [{}]}}"#, words.join(","))
+    }
+
+    #[test]
+    fn each_limit_holds_at_its_boundary() {
+        let limits = PolicyLimits {
+            max_rules: Some(3),
+            max_patterns: Some(2),
+            max_pattern_len: Some(8),
+            max_blacklist_entries: Some(4),
+            max_blacklist_bytes: Some(24),
+            max_service_names: Some(2),
+        };
+        let number = r#"{"rule": "require_number"}"#.to_string();
+        let pattern = |len: usize| format!(r#"{{"rule": "must_not_match", "pattern": "{}"}}"#, "a".repeat(len));
+        let services = |n: usize| format!(r#"{{"rule": "service_names", "names": [{}]}}"#, vec![r#""acme""#; n].join(","));
+        let row = |limit: &'static str, path: &str, found: usize| vec![(limit, path.to_string(), found)];
+        let cases = [
+            (vec![number.clone(); 3], vec![number.clone(); 4], row("max_rules", "rules", 4)),
+            (vec![pattern(1), pattern(1)], vec![pattern(1), pattern(1), pattern(1)], row("max_patterns", "rules", 3)),
+            (vec![pattern(8)], vec![number.clone(), pattern(9)], row("max_pattern_len", "rules[1].pattern", 9)),
+            (vec![words(2, 1), words(2, 1)], vec![words(2, 1), words(3, 1)], row("max_blacklist_entries", "rules", 5)),
+            (vec![words(4, 6)], vec![words(3, 6), words(1, 7)], row("max_blacklist_bytes", "rules", 25)),
+            (vec![services(2)], vec![services(1), services(2)], row("max_service_names", "rules", 3)),
+        ];
+        for (at, over, expected) in cases {
+            assert_eq!(exceeded(&rules(&at), &limits), [], "{:?}", at);
+            assert_eq!(exceeded(&rules(&over), &limits), expected);
+            assert!(PolicyConfig::from_json_with_limits(&rules(&over), &PolicyLimits::NONE).is_ok());
+        }
+    }
+
+    #[test]
+    fn every_exceeded_limit_is_reported() {
+        let mut policy: Vec<String> = (0..300).map(|_| r#"{"rule": "must_match", "pattern": "[a-z]"}"#.to_string()).collect();
+        policy.push(format!(r#"{{"rule": "must_not_match", "pattern": "{}"}}"#, "x".repeat(2000)));
+        policy.push(words(1_000_001, 1));
+        let err = PasswordChecker::from_config_with_limits(&rules(&policy), &PolicyLimits::default()).unwrap_err();
+        let ConfigError::LimitsExceeded(exceeded) = &err else { panic!("{}", err) };
+        let limits: Vec<_> = exceeded.iter().map(|e| e.limit).collect();
+        assert_eq!(limits, ["max_pattern_len", "max_rules", "max_patterns", "max_blacklist_entries"]);
+        assert!(err.to_string().starts_with("policy exceeds its limits: `rules[300].pattern` has 2000, over max_pattern_len of 1024; `rules` has 302"));
+    }
+
+    #[test]
+    fn sorted_files_count_their_size() {
+        let dir = std::env::temp_dir();
+        let (words, path) = (dir.join(format!("passcheck-limits-{}.txt", std::process::id())), dir.join(format!("passcheck-limits-{}.sorted", std::process::id())));
+        std::fs::write(&words, "apple\nbanana\ncherry\n").unwrap();
+        crate::dictionary::prepare(&words, &path).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len() as usize;
+        let file = format!(r#"{{"rules": [{{"rule": "blacklist", "file": {:?}}}]}}"#, path.to_str().unwrap());
+        let tight = PolicyLimits { max_blacklist_bytes: Some(size - 1), ..PolicyLimits::NONE };
+        assert_eq!(exceeded(&file, &tight), [("max_blacklist_bytes", "rules".to_string(), size)]);
+        assert!(exceeded(&file, &PolicyLimits { max_blacklist_bytes: Some(size), ..PolicyLimits::NONE }).is_empty());
+        std::fs::remove_file(&words).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // Documents that fail to parse report that instead.
+        assert!(matches!(PolicyConfig::from_json_with_limits("[", &tight), Err(ConfigError::Syntax { .. })));
+    }
+}
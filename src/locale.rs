@@ -0,0 +1,159 @@
+//! Messages in other languages, chosen per validation.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{ParamValue, PasswordChecker, Violations};
+
+/// Messages for one locale, keyed by rule [`code`](crate::Rule::code).
+///
+/// A template may refer to the violation's [params](crate::Violation::params)
+/// as `{min}`, `{actual}` and so on. Rules the catalog does not cover keep
+/// their usual message.
+#[derive(Clone, PartialEq, Default)]
+pub struct Catalog {
+    locale: String,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// Creates an empty catalog for `locale`, e.g. `"de"` or `"pt-BR"`.
+    pub fn new(locale: impl Into<String>) -> Self {
+        Catalog { locale: locale.into(), messages: HashMap::new() }
+    }
+
+    /// Sets the message template for rules with `code`.
+    pub fn message(mut self, code: impl Into<String>, template: impl Into<String>) -> Self {
+        self.messages.insert(code.into(), template.into());
+        self
+    }
+
+    /// Returns the locale this catalog is for.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub(crate) fn render(&self, code: &str, params: &[(&'static str, ParamValue)]) -> Option<String> {
+        let template = self.messages.get(code)?;
+        let mut out = template.clone();
+        for (name, value) in params {
+            out = out.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        Some(out)
+    }
+}
+
+/// Counts the messages rather than listing them.
+impl fmt::Debug for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Catalog").field("locale", &self.locale).field("messages", &self.messages.len()).finish()
+    }
+}
+
+/// Per-call settings for [`PasswordChecker::check_with_options`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidateOptions<'o> {
+    /// Locale of the [`Catalog`] to render messages with. `None`, or a
+    /// locale without a catalog, keeps the default messages.
+    pub locale: Option<&'o str>,
+    /// Messages replacing those of the rules with these codes, for this
+    /// call only.
+    pub messages: HashMap<&'o str, String>,
+}
+
+impl<'o> ValidateOptions<'o> {
+    /// Options changing nothing.
+    pub fn new() -> Self {
+        ValidateOptions::default()
+    }
+
+    /// Sets [`locale`](Self::locale).
+    pub fn locale(mut self, locale: &'o str) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+
+    /// Replaces the message of rules with `code`.
+    pub fn message(mut self, code: &'o str, message: impl Into<String>) -> Self {
+        self.messages.insert(code, message.into());
+        self
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// Installs a catalog used by [`validate_localized`](Self::validate_localized)
+    /// and [`check_with_options`](Self::check_with_options). A later
+    /// catalog for the same locale replaces the earlier one.
+    pub fn with_catalog(mut self, catalog: Catalog) -> Self {
+        self.catalogs.retain(|c| !c.locale.eq_ignore_ascii_case(&catalog.locale));
+        self.catalogs.push(catalog);
+        self
+    }
+
+    /// Like [`check`](Self::check), with messages from the catalog for
+    /// `locale`.
+    pub fn validate_localized(&self, password: &str, locale: &str) -> Result<(), Violations> {
+        self.check_with_options(password, &ValidateOptions::new().locale(locale))
+    }
+
+    /// Like [`check`](Self::check), resolving each message from, in order:
+    /// the `options` overrides, the catalog for `options.locale`, the rule's
+    /// custom message and the [formatter](Self::with_formatter). Which rules
+    /// fail does not depend on `options`.
+    pub fn check_with_options(&self, password: &str, options: &ValidateOptions<'_>) -> Result<(), Violations> {
+        self.check_in(password, None, Some(options))
+    }
+
+    /// The catalog for `locale`, falling back from a regional variant like
+    /// `de-CH` to its language.
+    pub(crate) fn catalog(&self, locale: &str) -> Option<&Catalog> {
+        let find = |locale: &str| self.catalogs.iter().find(|c| c.locale.eq_ignore_ascii_case(locale));
+        find(locale).or_else(|| find(locale.split(['-', '_']).next()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> PasswordChecker<'static> {
+        PasswordChecker::new()
+            .min_length(12, None)
+            .require_number(Some("Add a digit."))
+            .with_catalog(
+                Catalog::new("de")
+                    .message("min_length", "Das Passwort muss mindestens {min} Zeichen lang sein (jetzt {actual}).")
+                    .message("require_number", "Das Passwort muss eine Ziffer enthalten."),
+            )
+            .with_catalog(Catalog::new("fr").message("min_length", "Le mot de passe doit contenir au moins {min} caractères."))
+    }
+
+    fn messages(result: Result<(), Violations>) -> Vec<String> {
+        result.unwrap_err().into_messages()
+    }
+
+    #[test]
+    fn locales_render_the_same_violations() {
+        let checker = checker();
+        assert_eq!(
+            messages(checker.validate_localized("short", "de")),
+            ["Das Passwort muss mindestens 12 Zeichen lang sein (jetzt 5).", "Das Passwort muss eine Ziffer enthalten."]
+        );
+        assert_eq!(messages(checker.validate_localized("short", "fr-CA")), ["Le mot de passe doit contenir au moins 12 caractères.", "Add a digit."]);
+        assert_eq!(checker.validate_localized("short", "de").unwrap_err().codes().collect::<Vec<_>>(), ["min_length", "require_number"]);
+        assert_eq!(checker.validate_localized("long enough 1", "de"), Ok(()));
+    }
+
+    #[test]
+    fn unknown_locales_fall_back() {
+        let checker = checker();
+        assert_eq!(messages(checker.validate_localized("short", "ja")), messages(checker.check("short")));
+        assert_eq!(messages(checker.check("short")), ["Password must be at least 12 characters long.", "Add a digit."]);
+    }
+
+    #[test]
+    fn overrides_win_over_catalogs() {
+        let options = ValidateOptions::new().locale("de").message("min_length", "Zu kurz.");
+        assert_eq!(messages(checker().check_with_options("short", &options)), ["Zu kurz.", "Das Passwort muss eine Ziffer enthalten."]);
+    }
+}
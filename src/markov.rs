@@ -0,0 +1,114 @@
+//! Character-bigram model of human-chosen passwords.
+//!
+//! Letters are case-folded and all symbols share one class, so the model
+//! has 38 states: `a`–`z`, `0`–`9`, symbol, and a boundary state for the
+//! start and end of a password. The transition costs in
+//! `data/markov.bin` are trained on the word lists in `data/` by
+//! [`train`]; run the tests with `PASSCHECK_REGENERATE_MODEL=1` to rebuild
+//! the table after changing them.
+
+use crate::SPECIAL_CHARS;
+
+const CLASSES: usize = 38;
+const SYMBOL: usize = 36;
+const BOUNDARY: usize = 37;
+
+/// Transition costs in sixteenths of a bit, indexed `[from * CLASSES + to]`.
+static MODEL: &[u8; CLASSES * CLASSES] = include_bytes!("../data/markov.bin");
+
+/// Class of `c` and the bits it costs beyond its class, e.g. for picking
+/// one symbol out of many or for using uppercase.
+fn class(c: char) -> (usize, f64) {
+    match c {
+        'a'..='z' => (c as usize - 'a' as usize, 0.0),
+        'A'..='Z' => (c as usize - 'A' as usize, 1.0),
+        '0'..='9' => (26 + c as usize - '0' as usize, 0.0),
+        _ if SPECIAL_CHARS.contains(&c) => (SYMBOL, (SPECIAL_CHARS.len() as f64).log2()),
+        _ => (SYMBOL, 100f64.log2()),
+    }
+}
+
+/// Estimates how many guesses an attacker who models human passwords needs
+/// to find `password`, as log10 of the guess count.
+///
+/// Unlike the pool-size [`entropy`](crate::entropy), common human patterns
+/// such as a capitalized word followed by digits cost few guesses:
+///
+/// ```
+/// assert!(passcheck::guessability("Monkey123!") < passcheck::guessability("r8#Kq2!vPz"));
+/// ```
+pub fn guessability(password: &str) -> f64 {
+    if password.is_empty() {
+        return 0.0;
+    }
+    let mut bits = 0.0;
+    let mut prev = BOUNDARY;
+    for c in password.chars() {
+        let (next, extra) = class(c);
+        bits += f64::from(MODEL[prev * CLASSES + next]) / 16.0 + extra;
+        prev = next;
+    }
+    bits += f64::from(MODEL[prev * CLASSES + BOUNDARY]) / 16.0;
+    bits * 2f64.log10()
+}
+
+/// Builds the transition table from a corpus, one word per line, with
+/// add-half smoothing so unseen transitions stay possible.
+#[cfg(test)]
+fn train<'w>(words: impl IntoIterator<Item = &'w str>) -> Vec<u8> {
+    let mut counts = vec![0u32; CLASSES * CLASSES];
+    for word in words {
+        let mut prev = BOUNDARY;
+        for c in word.chars() {
+            let next = class(c).0;
+            counts[prev * CLASSES + next] += 1;
+            prev = next;
+        }
+        counts[prev * CLASSES + BOUNDARY] += 1;
+    }
+    counts
+        .chunks(CLASSES)
+        .flat_map(|row| {
+            let total = row.iter().sum::<u32>() as f64 + 0.5 * CLASSES as f64;
+            row.iter().map(move |&n| (-((f64::from(n) + 0.5) / total).log2() * 16.0).round().min(255.0) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CORPUS: [&str; 4] = [
+        include_str!("../data/common-en.txt"),
+        include_str!("../data/common-de.txt"),
+        include_str!("../data/common-es.txt"),
+        include_str!("../data/markov-words.txt"),
+    ];
+
+    #[test]
+    fn model_matches_corpus() {
+        let model = train(CORPUS.iter().flat_map(|list| list.lines()).filter(|w| !w.is_empty()));
+        if std::env::var_os("PASSCHECK_REGENERATE_MODEL").is_some() {
+            std::fs::write(concat!(env!("CARGO_MANIFEST_DIR"), "/data/markov.bin"), &model).unwrap();
+        } else {
+            assert!(model == MODEL[..], "data/markov.bin is stale; rerun with PASSCHECK_REGENERATE_MODEL=1");
+        }
+    }
+
+    #[test]
+    fn human_passwords_need_fewer_guesses_than_random_ones() {
+        let pairs = [
+            ("Monkey123!", "r8#Kq2!vPz"),
+            ("password1", "x7kq2vbzm"),
+            ("iloveyou2024", "q9vb3xk7wtz2"),
+            ("Summer2024", "hZ4qv9xkTb"),
+            ("football", "qzxkvbwj"),
+        ];
+        for (human, random) in pairs {
+            let (human, random) = (guessability(human), guessability(random));
+            assert!(human + 3.0 < random, "{} vs {}", human, random);
+        }
+        assert_eq!(guessability(""), 0.0);
+    }
+}
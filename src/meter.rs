@@ -0,0 +1,110 @@
+//! Milestone-based strength meters.
+
+use std::borrow::Cow;
+
+use crate::strength;
+use crate::Estimator;
+#[cfg(doc)]
+use crate::Dictionary;
+
+/// A goal a password can reach on a [`Meter`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Milestone {
+    /// At least this many characters.
+    MinLength(usize),
+    /// At least this many character classes (lowercase, uppercase, digits,
+    /// special characters, anything else).
+    MinClasses(usize),
+    /// Not in any of the checker's blacklists or [`Dictionary::builtin`].
+    NotInDictionary,
+    /// At least this many bits from the checker's estimator.
+    MinEntropy(f64),
+}
+
+/// The milestones a [`Meter`] shows, in display order.
+///
+/// ```
+/// use passcheck::{MeterSpec, Milestone, PasswordChecker};
+///
+/// let spec = MeterSpec::new()
+///     .milestone("10+ characters", Milestone::MinLength(10))
+///     .milestone("Mixed characters", Milestone::MinClasses(3));
+/// let meter = PasswordChecker::new().meter_spec(spec).meter("Tr0ub4dor&3");
+/// assert_eq!(meter.reached(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MeterSpec<'a> {
+    milestones: Vec<(Cow<'a, str>, Milestone)>,
+}
+
+impl<'a> MeterSpec<'a> {
+    /// Creates a spec without milestones.
+    pub fn new() -> Self {
+        MeterSpec::default()
+    }
+
+    /// Adds a milestone shown as `label`.
+    pub fn milestone(mut self, label: &'a str, milestone: Milestone) -> Self {
+        self.milestones.push((Cow::Borrowed(label), milestone));
+        self
+    }
+
+    /// The built-in five segments: the policy's minimum length (8 without
+    /// one), four characters more (at least 12), three character classes,
+    /// no dictionary hit, and 70 bits of estimated strength.
+    pub(crate) fn derived(min_length: Option<usize>) -> MeterSpec<'static> {
+        let short = min_length.unwrap_or(8);
+        let long = (short + 4).max(12);
+        let length = |n: usize| Cow::Owned(format!("At least {} characters", n));
+        MeterSpec {
+            milestones: vec![
+                (length(short), Milestone::MinLength(short)),
+                (length(long), Milestone::MinLength(long)),
+                (Cow::Borrowed("Three kinds of characters"), Milestone::MinClasses(3)),
+                (Cow::Borrowed("Not a common password"), Milestone::NotInDictionary),
+                (Cow::Borrowed("Hard to guess"), Milestone::MinEntropy(70.0)),
+            ],
+        }
+    }
+
+    /// Evaluates the milestones; `listed` tells whether the password is in
+    /// a dictionary.
+    pub(crate) fn evaluate(&self, password: &str, estimator: Estimator, listed: bool) -> Meter {
+        let chars: Vec<char> = password.chars().collect();
+        let segments = self
+            .milestones
+            .iter()
+            .map(|(label, milestone)| Segment {
+                label: label.to_string(),
+                reached: match milestone {
+                    Milestone::MinLength(len) => chars.len() >= *len,
+                    Milestone::MinClasses(classes) => strength::classes(&chars) >= *classes,
+                    Milestone::NotInDictionary => !listed,
+                    Milestone::MinEntropy(bits) => estimator.bits(password) >= *bits,
+                },
+            })
+            .collect();
+        Meter { segments }
+    }
+}
+
+/// Milestones a password reached, returned by
+/// [`PasswordChecker::meter`](crate::PasswordChecker::meter).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meter {
+    pub segments: Vec<Segment>,
+}
+
+impl Meter {
+    /// Number of milestones reached.
+    pub fn reached(&self) -> usize {
+        self.segments.iter().filter(|s| s.reached).count()
+    }
+}
+
+/// One milestone on a [`Meter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub label: String,
+    pub reached: bool,
+}
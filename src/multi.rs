@@ -0,0 +1,98 @@
+//! Running several policies over one password, e.g. during a migration.
+
+use crate::{PasswordPolicy, Violations};
+
+/// Outcome of [`evaluate_all`], per policy in the order given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiResult<'n> {
+    pub outcomes: Vec<(&'n str, Result<(), Violations>)>,
+}
+
+impl<'n> MultiResult<'n> {
+    /// Whether at least one policy accepts the password.
+    pub fn passes_any(&self) -> bool {
+        self.outcomes.iter().any(|(_, outcome)| outcome.is_ok())
+    }
+
+    /// Whether every policy accepts the password. True when there are none.
+    pub fn passes_all(&self) -> bool {
+        self.outcomes.iter().all(|(_, outcome)| outcome.is_ok())
+    }
+
+    /// The violations of the policy named `name`, `None` if it passed or
+    /// there is no such policy.
+    pub fn failures_for(&self, name: &str) -> Option<&Violations> {
+        self.outcomes.iter().find(|(n, _)| *n == name).and_then(|(_, outcome)| outcome.as_ref().err())
+    }
+
+    /// The failed policy with the most violations, the first one on ties.
+    pub fn strictest_failure(&self) -> Option<(&'n str, &Violations)> {
+        self.outcomes
+            .iter()
+            .filter_map(|(name, outcome)| Some((*name, outcome.as_ref().err()?)))
+            .reduce(|best, next| if next.1.len() > best.1.len() { next } else { best })
+    }
+}
+
+/// Checks `password` against every named policy. Policies of different
+/// types mix as `&dyn PasswordPolicy`.
+pub fn evaluate_all<'n, P: PasswordPolicy + ?Sized>(policies: &[(&'n str, &P)], password: &str) -> MultiResult<'n> {
+    MultiResult { outcomes: policies.iter().map(|(name, policy)| (*name, policy.check(password))).collect() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PasswordChecker, Violation};
+
+    /// A hand-rolled policy rejecting one password.
+    struct NotAdmin;
+
+    impl PasswordPolicy for NotAdmin {
+        fn check(&self, password: &str) -> Result<(), Violations> {
+            if password != "admin" {
+                return Ok(());
+            }
+            Err(Violations::from_iter([Violation { code: "not_admin", message: "Not admin.".to_string(), params: Vec::new(), span: None, group: None }]))
+        }
+
+        fn describe(&self) -> Vec<String> {
+            vec!["Not admin".to_string()]
+        }
+    }
+
+    #[test]
+    fn captures_every_outcome() {
+        let old = PasswordChecker::new().min_length(8, None);
+        let new = PasswordChecker::new().min_length(12, None).require_special_char(None);
+        let result = evaluate_all(&[("old", &old), ("new", &new)], "summer2024");
+
+        assert!(result.passes_any());
+        assert!(!result.passes_all());
+        assert_eq!(result.failures_for("old"), None);
+        assert_eq!(result.failures_for("new").unwrap().codes().collect::<Vec<_>>(), ["min_length", "require_special_char"]);
+        assert_eq!(result.failures_for("missing"), None);
+        assert_eq!(result.strictest_failure().map(|(name, _)| name), Some("new"));
+        assert_eq!(result.outcomes.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["old", "new"]);
+    }
+
+    #[test]
+    fn mixes_checkers_with_other_policies() {
+        let checker = PasswordChecker::new().min_length(6, None);
+        let policies: [(&str, &dyn PasswordPolicy); 2] = [("length", &checker), ("custom", &NotAdmin)];
+        let result = evaluate_all(&policies, "admin");
+        assert_eq!(result.failures_for("length").unwrap().codes().collect::<Vec<_>>(), ["min_length"]);
+        assert_eq!(result.failures_for("custom").unwrap().codes().collect::<Vec<_>>(), ["not_admin"]);
+        assert!(evaluate_all(&policies, "administrator").passes_all());
+        assert_eq!(evaluate_all(&[("custom", &NotAdmin)], "admin").strictest_failure().unwrap().1[0].message, "Not admin.");
+        assert_eq!(policies.map(|(_, policy)| policy.describe()), [vec!["At least 6 characters".to_string()], vec!["Not admin".to_string()]]);
+    }
+
+    #[test]
+    fn no_policies_pass_all_but_not_any() {
+        let result = evaluate_all::<PasswordChecker<'_>>(&[], "anything");
+        assert!(result.passes_all());
+        assert!(!result.passes_any());
+        assert_eq!(result.strictest_failure(), None);
+    }
+}
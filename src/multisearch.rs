@@ -0,0 +1,139 @@
+//! Searching a string for any of many substrings at once.
+
+/// Needle sets up to this size are searched one needle at a time. Larger
+/// ones go through an Aho-Corasick automaton, which scans the haystack
+/// once however many needles there are, but costs more to build.
+const AUTOMATON_MIN_NEEDLES: usize = 16;
+
+/// A set of substrings to look for, from [`Substrings::new`].
+#[derive(Debug, Clone)]
+pub(crate) enum Substrings {
+    Few(Vec<String>),
+    Many(Automaton),
+}
+
+impl Substrings {
+    pub(crate) fn new(needles: Vec<String>) -> Self {
+        if needles.len() < AUTOMATON_MIN_NEEDLES { Substrings::Few(needles) } else { Substrings::Many(Automaton::new(&needles)) }
+    }
+
+    /// Whether `haystack` contains one of the needles.
+    pub(crate) fn found_in(&self, haystack: &str) -> bool {
+        match self {
+            Substrings::Few(needles) => needles.iter().any(|needle| haystack.contains(needle.as_str())),
+            Substrings::Many(automaton) => automaton.found_in(haystack.as_bytes()),
+        }
+    }
+}
+
+/// An Aho-Corasick automaton over bytes. Matching UTF-8 bytes finds the
+/// same substrings as matching characters.
+#[derive(Debug, Clone)]
+pub(crate) struct Automaton {
+    /// Transitions of each state, sorted by byte; state 0 is the root.
+    next: Vec<Vec<(u8, usize)>>,
+    /// The state for the longest proper suffix of each state's string that
+    /// is also a prefix of a needle.
+    fail: Vec<usize>,
+    /// Whether a needle ends at each state or at one of its fail states.
+    accepting: Vec<bool>,
+}
+
+impl Automaton {
+    fn new(needles: &[String]) -> Self {
+        let mut automaton = Automaton { next: vec![Vec::new()], fail: vec![0], accepting: vec![false] };
+        for needle in needles {
+            let mut state = 0;
+            for &byte in needle.as_bytes() {
+                state = match automaton.step(state, byte) {
+                    Some(next) => next,
+                    None => automaton.add(state, byte),
+                };
+            }
+            automaton.accepting[state] = true;
+        }
+        // Breadth first, so fail states are final before they are used.
+        let mut queue: std::collections::VecDeque<usize> = automaton.next[0].iter().map(|&(_, child)| child).collect();
+        while let Some(state) = queue.pop_front() {
+            for (byte, child) in automaton.next[state].clone() {
+                let mut fail = automaton.fail[state];
+                let target = loop {
+                    match automaton.step(fail, byte) {
+                        Some(target) => break target,
+                        None if fail == 0 => break 0,
+                        None => fail = automaton.fail[fail],
+                    }
+                };
+                automaton.fail[child] = target;
+                automaton.accepting[child] |= automaton.accepting[target];
+                queue.push_back(child);
+            }
+        }
+        automaton
+    }
+
+    fn step(&self, state: usize, byte: u8) -> Option<usize> {
+        let edges = &self.next[state];
+        edges.binary_search_by_key(&byte, |&(b, _)| b).ok().map(|i| edges[i].1)
+    }
+
+    fn add(&mut self, state: usize, byte: u8) -> usize {
+        let child = self.next.len();
+        self.next.push(Vec::new());
+        self.fail.push(0);
+        self.accepting.push(false);
+        let edges = &mut self.next[state];
+        let at = edges.partition_point(|&(b, _)| b < byte);
+        edges.insert(at, (byte, child));
+        child
+    }
+
+    fn found_in(&self, haystack: &[u8]) -> bool {
+        if self.accepting[0] {
+            return true;
+        }
+        let mut state = 0;
+        for &byte in haystack {
+            state = loop {
+                match self.step(state, byte) {
+                    Some(next) => break next,
+                    None if state == 0 => break 0,
+                    None => state = self.fail[state],
+                }
+            };
+            if self.accepting[state] {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn automaton_matches_naive_search() {
+        let words = ["he", "she", "his", "hers", "ushe", "é", "ßtr", "abcab", "bca", "aab", "c", "ab"];
+        let haystacks = ["", "ushers", "ahishe", "xyz", "aaab", "cé", "straße", "Straßtr", "bcbcb", "ABC", "abca"];
+        for n in 1..=words.len() {
+            let needles: Vec<String> = words[..n].iter().map(|w| w.to_string()).collect();
+            let automaton = Automaton::new(&needles);
+            for haystack in haystacks {
+                let naive = needles.iter().any(|needle| haystack.contains(needle.as_str()));
+                assert_eq!(automaton.found_in(haystack.as_bytes()), naive, "{:?} in {:?}", needles, haystack);
+            }
+        }
+        assert!(Automaton::new(&[String::new()]).found_in(b""));
+    }
+
+    #[test]
+    fn large_sets_use_the_automaton() {
+        let needles: Vec<String> = (0..40).map(|i| format!("tok{}en", i)).collect();
+        let substrings = Substrings::new(needles.clone());
+        assert!(matches!(substrings, Substrings::Many(_)));
+        assert!(substrings.found_in("xxtok39enxx") && !substrings.found_in("tok40en"));
+        assert!(matches!(Substrings::new(needles[..3].to_vec()), Substrings::Few(_)));
+    }
+}
@@ -0,0 +1,81 @@
+//! Callbacks notified after every validation, e.g. to feed metrics.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::PolicyFingerprint;
+
+/// Summary of one completed validation passed to observers.
+///
+/// Never contains the password or anything derived from it beyond which
+/// rules failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationEvent<'e> {
+    /// Name set with [`PasswordChecker::named`](crate::PasswordChecker::named).
+    pub policy: Option<&'e str>,
+    /// Whether every evaluated rule passed.
+    pub valid: bool,
+    /// Codes of the failed rules, in rule order.
+    pub failed: Vec<&'static str>,
+    /// Whether the checker was in [dry run](crate::PasswordChecker::dry_run)
+    /// mode, so the failures did not reject the password.
+    pub dry_run: bool,
+    /// Time spent evaluating the rules.
+    pub duration: Duration,
+    /// The checker's fingerprint, if it
+    /// [records it](crate::PasswordChecker::record_fingerprint).
+    pub fingerprint: Option<PolicyFingerprint>,
+    /// How long each rule took, if the checker
+    /// [times them](crate::PasswordChecker::with_timing).
+    pub timing: Option<Timing>,
+}
+
+/// Per-rule evaluation times of one validation, measured with a monotonic
+/// clock.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Timing {
+    /// The rules evaluated, in order; with
+    /// [fail-fast](crate::PasswordChecker::fail_fast), those up to the
+    /// first failure.
+    pub rules: Vec<RuleTiming>,
+    /// The whole validation, including rendering messages, the same as
+    /// [`ValidationEvent::duration`].
+    pub total: Duration,
+}
+
+impl Timing {
+    /// The rule that took longest, if any rule was evaluated.
+    pub fn slowest(&self) -> Option<&RuleTiming> {
+        self.rules.iter().max_by_key(|rule| rule.duration)
+    }
+}
+
+/// How long deciding one rule took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleTiming {
+    /// Position of the rule in [`rules`](crate::PasswordChecker::rules).
+    pub index: usize,
+    pub code: &'static str,
+    pub duration: Duration,
+}
+
+pub(crate) type Observer = Arc<dyn Fn(&ValidationEvent<'_>) + Send + Sync>;
+
+/// Registered observers, called in registration order.
+#[derive(Clone, Default)]
+pub(crate) struct Observers(pub(crate) Vec<Observer>);
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Observers({})", self.0.len())
+    }
+}
+
+// Observers never change which passwords a checker accepts, so checkers
+// compare equal regardless of the observers attached.
+impl PartialEq for Observers {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,176 @@
+//! Diceware-style passphrase generation.
+
+use std::collections::HashSet;
+
+use crate::generate::OsRng;
+
+/// Words to build passphrases from.
+///
+/// Passphrase strength rests on every word being equally likely, so the
+/// list is deduplicated on construction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wordlist {
+    words: Vec<String>,
+}
+
+impl Wordlist {
+    /// Builds a list from `words`, skipping empty entries and duplicates.
+    pub fn new<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut seen = HashSet::new();
+        let mut list = Vec::new();
+        for word in words {
+            let word = word.into();
+            if !word.is_empty() && seen.insert(word.clone()) {
+                list.push(word);
+            }
+        }
+        Wordlist { words: list }
+    }
+
+    /// Parses one word per line. Diceware lists such as the EFF large
+    /// wordlist, formatted `11111<TAB>abacus`, keep only the word.
+    pub fn parse(text: &str) -> Self {
+        Wordlist::new(text.lines().filter_map(|line| line.split_whitespace().last()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Theoretical entropy in bits of a passphrase of `words` words drawn
+    /// from this list: `words × log2(len)`, plus log2(`words`) for
+    /// capitalizing one of them and log2(10) for an appended digit.
+    ///
+    /// The adjustments assume the list's words are lowercase.
+    pub fn entropy(&self, words: usize, options: PassphraseOptions) -> f64 {
+        if words == 0 || self.is_empty() {
+            return 0.0;
+        }
+        let mut bits = words as f64 * (self.len() as f64).log2();
+        if options.capitalize_one {
+            bits += (words as f64).log2();
+        }
+        if options.append_digit {
+            bits += 10f64.log2();
+        }
+        bits
+    }
+}
+
+/// Extras that help a passphrase pass composition rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PassphraseOptions {
+    /// Capitalize the first letter of one randomly chosen word.
+    pub capitalize_one: bool,
+    /// Append a random digit to the last word.
+    pub append_digit: bool,
+}
+
+/// Generates a passphrase of `words` words chosen uniformly from
+/// `wordlist` with the operating system's CSPRNG, joined by `separator`.
+///
+/// # Panics
+///
+/// Panics if `wordlist` is empty or the random number generator fails.
+pub fn generate_passphrase(words: usize, separator: char, wordlist: &Wordlist) -> String {
+    generate_passphrase_with(words, separator, wordlist, PassphraseOptions::default())
+}
+
+/// Like [`generate_passphrase`], with [`PassphraseOptions`] applied.
+///
+/// ```
+/// use passcheck::{PassphraseOptions, Wordlist, generate_passphrase_with};
+///
+/// let list = Wordlist::parse("11111\tabacus\n11112\tabdomen\n11113\tabdominal\n");
+/// let options = PassphraseOptions { capitalize_one: true, append_digit: true };
+/// let phrase = generate_passphrase_with(4, '-', &list, options);
+/// assert_eq!(phrase.split('-').count(), 4);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `wordlist` is empty or the random number generator fails.
+pub fn generate_passphrase_with(words: usize, separator: char, wordlist: &Wordlist, options: PassphraseOptions) -> String {
+    assert!(!wordlist.is_empty(), "cannot generate a passphrase from an empty wordlist");
+    let mut random = {
+        let mut rng = OsRng::new();
+        move |n: usize| rng.below(n).expect("operating-system random number generator failed")
+    };
+    let mut chosen: Vec<String> = (0..words).map(|_| wordlist.words[random(wordlist.len())].clone()).collect();
+    if options.capitalize_one && words > 0 {
+        let word = &mut chosen[random(words)];
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            *word = first.to_uppercase().chain(chars).collect();
+        }
+    }
+    if options.append_digit && words > 0 {
+        let digit = char::from(b'0' + random(10) as u8);
+        chosen[words - 1].push(digit);
+    }
+    chosen.join(separator.encode_utf8(&mut [0; 4]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordChecker;
+
+    fn list() -> Wordlist {
+        let words = include_str!("../data/markov-words.txt").lines();
+        Wordlist::new(words.filter(|w| w.chars().all(|c| c.is_ascii_lowercase())))
+    }
+
+    #[test]
+    fn entropy_math() {
+        let list = Wordlist::new((0..7776).map(|i| format!("w{}", i)));
+        let plain = PassphraseOptions::default();
+        assert!((list.entropy(6, plain) - 6.0 * 7776f64.log2()).abs() < 1e-9);
+        assert!((list.entropy(6, plain) - 77.55).abs() < 0.01);
+        let extras = PassphraseOptions { capitalize_one: true, append_digit: true };
+        assert!((list.entropy(4, extras) - (4.0 * 7776f64.log2() + 2.0 + 10f64.log2())).abs() < 1e-9);
+        assert_eq!(Wordlist::new(["a", "a", "b", ""]).entropy(3, plain), 3.0);
+        assert_eq!(list.entropy(0, extras), 0.0);
+    }
+
+    #[test]
+    fn parses_diceware_lists() {
+        let list = Wordlist::parse("11111\tabacus\n11112\tabdomen\n\nplain\n");
+        assert_eq!(list.words, ["abacus", "abdomen", "plain"]);
+    }
+
+    #[test]
+    fn separators_join_the_words() {
+        let list = list();
+        for separator in ['-', ' ', '.', '·'] {
+            let phrase = generate_passphrase(5, separator, &list);
+            let parts: Vec<&str> = phrase.split(separator).collect();
+            assert_eq!(parts.len(), 5, "{}", phrase);
+            assert!(parts.iter().all(|word| list.words.iter().any(|w| w == word)), "{}", phrase);
+        }
+        assert_eq!(generate_passphrase(0, '-', &list), "");
+    }
+
+    #[test]
+    fn extras_pass_composition_rules() {
+        let list = list();
+        let checker = PasswordChecker::new()
+            .min_length(15, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .blacklist(["password"], None);
+        let options = PassphraseOptions { capitalize_one: true, append_digit: true };
+        for _ in 0..200 {
+            let phrase = generate_passphrase_with(5, '-', &list, options);
+            assert!(checker.validate(&phrase).is_ok(), "{}", phrase);
+        }
+    }
+}
@@ -0,0 +1,705 @@
+//! Regular-expression rules without external dependencies.
+//!
+//! Supported syntax: literals, `.`, classes (`[a-z]`, `[^0-9]`), the escapes
+//! `\d \D \w \W \s \S \n \r \t` and escaped metacharacters, anchors `^` and
+//! `$`, groups `( )` and `(?: )`, alternation `|`, and the quantifiers
+//! `* + ? {n} {n,} {n,m}` (a trailing `?` for laziness is accepted and has no
+//! effect, since rules only ask whether a match exists). There are no
+//! backreferences or lookarounds.
+//!
+//! Matching uses a Pike VM, so time is bounded by pattern size × input
+//! length and no pattern can backtrack exponentially.
+//!
+//! Syntax is checked when the [`Pattern`] is created, but the matching
+//! program is compiled on first use and then cached. Clones share the
+//! cache, and concurrent first uses compile exactly once.
+
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+/// Largest count accepted in `{n,m}`.
+const MAX_REPEAT: u32 = 1000;
+/// Largest compiled program accepted, in instructions.
+const MAX_PROGRAM: usize = 20_000;
+
+/// Error returned for a malformed pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+    /// Character index in the pattern where the problem was found.
+    pub position: usize,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pattern at character {}: {}", self.position, self.reason)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// A parsed regular expression used by the `must_match` and
+/// `must_not_match` rules.
+#[derive(Clone)]
+pub struct Pattern {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    source: String,
+    ast: Node,
+    program: OnceLock<Program>,
+    #[cfg(test)]
+    compilations: std::sync::atomic::AtomicUsize,
+}
+
+impl Pattern {
+    /// Parses `source`, reporting syntax errors immediately.
+    pub fn new(source: &str) -> Result<Self, PatternError> {
+        let chars: Vec<char> = source.chars().collect();
+        let mut parser = Parser { chars: &chars, pos: 0 };
+        let ast = parser.alternation()?;
+        if parser.pos < chars.len() {
+            return Err(parser.error("unmatched `)`"));
+        }
+        if ast.program_size() > MAX_PROGRAM {
+            return Err(PatternError { position: 0, reason: "pattern is too large" });
+        }
+        Ok(Pattern {
+            inner: Arc::new(Inner {
+                source: source.to_string(),
+                ast,
+                program: OnceLock::new(),
+                #[cfg(test)]
+                compilations: Default::default(),
+            }),
+        })
+    }
+
+    /// The pattern as written.
+    pub fn as_str(&self) -> &str {
+        &self.inner.source
+    }
+
+    /// Returns whether the pattern matches anywhere in `text`.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.program().is_match(text)
+    }
+
+    /// A short string the pattern matches: the first branch of every
+    /// alternation and the fewest repetitions. Anchors are left out, so the
+    /// example only matches anchored patterns on its own.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn example(&self) -> Option<String> {
+        let mut out = String::new();
+        self.inner.ast.example(&mut out).then_some(out)
+    }
+
+    /// How often the pattern was compiled, which is at most once.
+    #[cfg(test)]
+    pub(crate) fn compilations(&self) -> usize {
+        self.inner.compilations.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn program(&self) -> &Program {
+        self.inner.program.get_or_init(|| {
+            #[cfg(test)]
+            self.inner.compilations.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Program::compile(&self.inner.ast)
+        })
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.source == other.inner.source
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pattern({:?})", self.inner.source)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat { node: Box<Node>, min: u32, max: Option<u32> },
+}
+
+impl Node {
+    #[cfg(feature = "test-util")]
+    fn example(&self, out: &mut String) -> bool {
+        match self {
+            Node::Empty | Node::Start | Node::End => true,
+            Node::Char(c) => {
+                out.push(*c);
+                true
+            }
+            Node::Any => {
+                out.push('a');
+                true
+            }
+            Node::Class(class) => {
+                let pick = if class.negated {
+                    ['a', 'A', '0', '!', ' ', '~'].into_iter().find(|&c| !class.ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c)))
+                } else {
+                    class.ranges.first().map(|&(lo, _)| lo)
+                };
+                pick.map(|c| out.push(c)).is_some()
+            }
+            Node::Concat(nodes) => nodes.iter().all(|node| node.example(out)),
+            Node::Alt(nodes) => {
+                let len = out.len();
+                nodes.iter().any(|node| {
+                    out.truncate(len);
+                    node.example(out)
+                })
+            }
+            Node::Repeat { node, min, .. } => (0..*min).all(|_| node.example(out)),
+        }
+    }
+
+    /// Upper bound on the instructions `Program::compile` emits.
+    fn program_size(&self) -> usize {
+        match self {
+            Node::Empty => 0,
+            Node::Char(_) | Node::Any | Node::Class(_) | Node::Start | Node::End => 1,
+            Node::Concat(nodes) => nodes.iter().map(Node::program_size).fold(0, usize::saturating_add),
+            Node::Alt(nodes) => nodes.iter().map(|n| n.program_size().saturating_add(2)).fold(0, usize::saturating_add),
+            Node::Repeat { node, min, max } => {
+                let copies = max.unwrap_or(min + 1) as usize;
+                node.program_size().saturating_add(2).saturating_mul(copies.max(1))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Class {
+    ranges: Vec<(char, char)>,
+    negated: bool,
+}
+
+const DIGIT: &[(char, char)] = &[('0', '9')];
+const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')];
+const SPACE: &[(char, char)] = &[('\t', '\r'), (' ', ' ')];
+
+struct Parser<'p> {
+    chars: &'p [char],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: &'static str) -> PatternError {
+        PatternError { position: self.pos, reason }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn alternation(&mut self) -> Result<Node, PatternError> {
+        let mut branches = vec![self.concat()?];
+        while self.eat('|') {
+            branches.push(self.concat()?);
+        }
+        Ok(if branches.len() == 1 { branches.pop().unwrap_or(Node::Empty) } else { Node::Alt(branches) })
+    }
+
+    fn concat(&mut self) -> Result<Node, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap_or(Node::Empty),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, PatternError> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                let bounds = self.bounds()?;
+                self.pos -= 1;
+                bounds
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        self.eat('?');
+        if matches!(self.peek(), Some('*' | '+' | '?' | '{')) {
+            return Err(self.error("nothing to repeat"));
+        }
+        if matches!(atom, Node::Start | Node::End) {
+            return Err(self.error("cannot repeat an anchor"));
+        }
+        Ok(Node::Repeat { node: Box::new(atom), min, max })
+    }
+
+    /// Parses `n}`, `n,}` or `n,m}` after `{`, leaving `pos` past the `}`.
+    fn bounds(&mut self) -> Result<(u32, Option<u32>), PatternError> {
+        let min = self.number()?.ok_or_else(|| self.error("expected a number in `{}`"))?;
+        let max = if self.eat(',') { self.number()? } else { Some(min) };
+        if !self.eat('}') {
+            return Err(self.error("expected `}`"));
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err(self.error("invalid repetition range"));
+        }
+        Ok((min, max))
+    }
+
+    fn number(&mut self) -> Result<Option<u32>, PatternError> {
+        let start = self.pos;
+        let mut value: u32 = 0;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            value = value.saturating_mul(10).saturating_add(d);
+            self.pos += 1;
+        }
+        if value > MAX_REPEAT {
+            return Err(PatternError { position: start, reason: "repetition count is too large" });
+        }
+        Ok((self.pos > start).then_some(value))
+    }
+
+    fn atom(&mut self) -> Result<Node, PatternError> {
+        let Some(c) = self.peek() else {
+            return Err(self.error("unexpected end of pattern"));
+        };
+        self.pos += 1;
+        Ok(match c {
+            '(' => {
+                if self.eat('?') && !self.eat(':') {
+                    return Err(self.error("unsupported group syntax"));
+                }
+                let inner = self.alternation()?;
+                if !self.eat(')') {
+                    return Err(self.error("unclosed group"));
+                }
+                inner
+            }
+            '[' => Node::Class(self.class()?),
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => self.escape()?,
+            '*' | '+' | '?' | '{' => {
+                self.pos -= 1;
+                return Err(self.error("nothing to repeat"));
+            }
+            c => Node::Char(c),
+        })
+    }
+
+    fn escape(&mut self) -> Result<Node, PatternError> {
+        let Some(c) = self.peek() else {
+            return Err(self.error("trailing backslash"));
+        };
+        self.pos += 1;
+        let class = |ranges: &[(char, char)], negated| Node::Class(Class { ranges: ranges.to_vec(), negated });
+        Ok(match c {
+            'd' => class(DIGIT, false),
+            'D' => class(DIGIT, true),
+            'w' => class(WORD, false),
+            'W' => class(WORD, true),
+            's' => class(SPACE, false),
+            'S' => class(SPACE, true),
+            'n' => Node::Char('\n'),
+            'r' => Node::Char('\r'),
+            't' => Node::Char('\t'),
+            c if !c.is_alphanumeric() => Node::Char(c),
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("unknown escape"));
+            }
+        })
+    }
+
+    fn class(&mut self) -> Result<Class, PatternError> {
+        let negated = self.eat('^');
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error("unclosed character class"));
+            };
+            self.pos += 1;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let start = if c == '\\' {
+                let escaped = self.peek().ok_or_else(|| self.error("trailing backslash"))?;
+                self.pos += 1;
+                match escaped {
+                    'd' => {
+                        ranges.extend_from_slice(DIGIT);
+                        continue;
+                    }
+                    'w' => {
+                        ranges.extend_from_slice(WORD);
+                        continue;
+                    }
+                    's' => {
+                        ranges.extend_from_slice(SPACE);
+                        continue;
+                    }
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    c if !c.is_alphanumeric() => c,
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.error("unsupported escape in class"));
+                    }
+                }
+            } else {
+                c
+            };
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&c| c != ']') {
+                self.pos += 1;
+                let mut end = self.chars[self.pos];
+                self.pos += 1;
+                if end == '\\' {
+                    end = self.peek().ok_or_else(|| self.error("trailing backslash"))?;
+                    self.pos += 1;
+                }
+                if end < start {
+                    return Err(self.error("invalid class range"));
+                }
+                ranges.push((start, end));
+            } else {
+                ranges.push((start, start));
+            }
+        }
+        Ok(Class { ranges, negated })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(usize),
+    Start,
+    End,
+    Split(usize, usize),
+    Jmp(usize),
+    Match,
+}
+
+/// Class with merged ranges and an ASCII lookup table.
+struct CompiledClass {
+    ascii: [bool; 128],
+    ranges: Vec<(char, char)>,
+    negated: bool,
+}
+
+impl CompiledClass {
+    fn new(class: &Class) -> Self {
+        let mut ranges = class.ranges.clone();
+        ranges.sort_unstable();
+        let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+        for (lo, hi) in ranges {
+            match merged.last_mut() {
+                Some(last) if (lo as u32) <= (last.1 as u32).saturating_add(1) => last.1 = last.1.max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        let mut ascii = [false; 128];
+        for (i, slot) in ascii.iter_mut().enumerate() {
+            let c = i as u8 as char;
+            *slot = merged.iter().any(|&(lo, hi)| lo <= c && c <= hi);
+        }
+        CompiledClass { ascii, ranges: merged, negated: class.negated }
+    }
+
+    fn matches(&self, c: char) -> bool {
+        let hit = if c.is_ascii() {
+            self.ascii[c as usize]
+        } else {
+            self.ranges
+                .binary_search_by(|&(lo, hi)| {
+                    if hi < c {
+                        std::cmp::Ordering::Less
+                    } else if lo > c {
+                        std::cmp::Ordering::Greater
+                    } else {
+                        std::cmp::Ordering::Equal
+                    }
+                })
+                .is_ok()
+        };
+        hit != self.negated
+    }
+}
+
+struct Program {
+    insts: Vec<Inst>,
+    classes: Vec<CompiledClass>,
+}
+
+impl Program {
+    fn compile(ast: &Node) -> Self {
+        let mut program = Program { insts: Vec::new(), classes: Vec::new() };
+        program.emit(ast);
+        program.insts.push(Inst::Match);
+        program
+    }
+
+    fn emit(&mut self, node: &Node) {
+        match node {
+            Node::Empty => {}
+            Node::Char(c) => self.insts.push(Inst::Char(*c)),
+            Node::Any => self.insts.push(Inst::Any),
+            Node::Class(class) => {
+                self.classes.push(CompiledClass::new(class));
+                self.insts.push(Inst::Class(self.classes.len() - 1));
+            }
+            Node::Start => self.insts.push(Inst::Start),
+            Node::End => self.insts.push(Inst::End),
+            Node::Concat(nodes) => nodes.iter().for_each(|n| self.emit(n)),
+            Node::Alt(branches) => {
+                let mut jumps = Vec::new();
+                for (i, branch) in branches.iter().enumerate() {
+                    if i + 1 < branches.len() {
+                        let split = self.insts.len();
+                        self.insts.push(Inst::Split(split + 1, 0));
+                        self.emit(branch);
+                        jumps.push(self.insts.len());
+                        self.insts.push(Inst::Jmp(0));
+                        let next = self.insts.len();
+                        self.insts[split] = Inst::Split(split + 1, next);
+                    } else {
+                        self.emit(branch);
+                    }
+                }
+                let end = self.insts.len();
+                for j in jumps {
+                    self.insts[j] = Inst::Jmp(end);
+                }
+            }
+            Node::Repeat { node, min, max } => {
+                for _ in 0..*min {
+                    self.emit(node);
+                }
+                match max {
+                    None => {
+                        let split = self.insts.len();
+                        self.insts.push(Inst::Split(split + 1, 0));
+                        self.emit(node);
+                        self.insts.push(Inst::Jmp(split));
+                        let end = self.insts.len();
+                        self.insts[split] = Inst::Split(split + 1, end);
+                    }
+                    Some(max) => {
+                        let mut splits = Vec::new();
+                        for _ in *min..*max {
+                            splits.push(self.insts.len());
+                            self.insts.push(Inst::Split(0, 0));
+                            self.emit(node);
+                        }
+                        let end = self.insts.len();
+                        for s in splits {
+                            self.insts[s] = Inst::Split(s + 1, end);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        let chars: Vec<char> = text.chars().collect();
+        let n = self.insts.len();
+        let mut current = Threads::new(n);
+        let mut next = Threads::new(n);
+        let mut stack = Vec::new();
+        for pos in 0..=chars.len() {
+            // Unanchored search: a new thread starts at every position.
+            if self.add(&mut current, &mut stack, 0, pos, chars.len()) {
+                return true;
+            }
+            let Some(&c) = chars.get(pos) else {
+                break;
+            };
+            next.clear();
+            for i in 0..current.len {
+                let pc = current.dense[i];
+                let advances = match self.insts[pc] {
+                    Inst::Char(expected) => c == expected,
+                    Inst::Any => true,
+                    Inst::Class(idx) => self.classes[idx].matches(c),
+                    _ => false,
+                };
+                if advances && self.add(&mut next, &mut stack, pc + 1, pos + 1, chars.len()) {
+                    return true;
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+        }
+        false
+    }
+
+    /// Adds the thread at `pc` and everything reachable without consuming
+    /// input. Returns true as soon as `Match` is reachable.
+    fn add(&self, threads: &mut Threads, stack: &mut Vec<usize>, pc: usize, pos: usize, len: usize) -> bool {
+        stack.push(pc);
+        while let Some(pc) = stack.pop() {
+            if !threads.insert(pc) {
+                continue;
+            }
+            match self.insts[pc] {
+                Inst::Match => {
+                    stack.clear();
+                    return true;
+                }
+                Inst::Jmp(to) => stack.push(to),
+                Inst::Split(a, b) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                Inst::Start if pos == 0 => stack.push(pc + 1),
+                Inst::End if pos == len => stack.push(pc + 1),
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+/// Sparse set of instruction indices with O(1) clear.
+struct Threads {
+    dense: Vec<usize>,
+    sparse: Vec<usize>,
+    len: usize,
+}
+
+impl Threads {
+    fn new(size: usize) -> Self {
+        Threads { dense: vec![0; size], sparse: vec![0; size], len: 0 }
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    fn insert(&mut self, pc: usize) -> bool {
+        let i = self.sparse[pc];
+        if i < self.len && self.dense[i] == pc {
+            return false;
+        }
+        self.sparse[pc] = self.len;
+        self.dense[self.len] = pc;
+        self.len += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::Ordering;
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        Pattern::new(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn matching_semantics() {
+        let cases = [
+            ("abc", "xxabcxx", true),
+            ("^abc", "xabc", false),
+            ("abc$", "abcx", false),
+            ("^$", "", true),
+            ("a.c", "a-c", true),
+            ("colou?r", "color", true),
+            ("(ab)+c", "ababc", true),
+            ("(?:cat|dog)s", "hotdogs", true),
+            ("^(cat|dog)$", "bird", false),
+            ("[a-c]{3}", "xcabx", true),
+            ("[a-c]{3}", "xcax", false),
+            ("[^0-9]+", "123", false),
+            ("\\d{4,}", "pin 12345", true),
+            ("\\d{4,}", "pin 123", false),
+            ("^\\w+$", "under_score9", true),
+            ("\\s", "no-space", false),
+            ("\\.", "a.b", true),
+            ("[\\]x]", "]", true),
+            ("[-a]", "-", true),
+            ("é+", "caféé", true),
+            ("(a*)*b", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaac", false),
+            ("a{2}?", "a", false),
+            ("a+?b", "xaab", true),
+        ];
+        for (pattern, text, expected) in cases {
+            assert_eq!(matches(pattern, text), expected, "{:?} on {:?}", pattern, text);
+        }
+    }
+
+    #[test]
+    fn syntax_errors_are_reported_eagerly() {
+        let cases = [
+            ("(abc", "unclosed group"),
+            ("abc)", "unmatched `)`"),
+            ("[abc", "unclosed character class"),
+            ("*a", "nothing to repeat"),
+            ("a**", "nothing to repeat"),
+            ("a{3,1}", "invalid repetition range"),
+            ("a{5000}", "repetition count is too large"),
+            ("\\q", "unknown escape"),
+            ("[z-a]", "invalid class range"),
+            ("(?=a)", "unsupported group syntax"),
+            ("(a{1000}){1000}", "pattern is too large"),
+        ];
+        for (pattern, reason) in cases {
+            assert_eq!(Pattern::new(pattern).unwrap_err().reason, reason, "{:?}", pattern);
+        }
+    }
+
+    #[test]
+    fn compiled_once_under_concurrent_first_use() {
+        let pattern = Pattern::new("(?:spring|summer|autumn|winter)[0-9]{2,4}").unwrap();
+        assert!(pattern.inner.program.get().is_none());
+        let barrier = std::sync::Barrier::new(16);
+        std::thread::scope(|scope| {
+            for _ in 0..16 {
+                let (pattern, barrier) = (pattern.clone(), &barrier);
+                scope.spawn(move || {
+                    barrier.wait();
+                    assert!(pattern.is_match("xsummer2024"));
+                });
+            }
+        });
+        assert!(!pattern.is_match("winter"));
+        assert_eq!(pattern.inner.compilations.load(Ordering::SeqCst), 1);
+    }
+}
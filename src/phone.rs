@@ -0,0 +1,100 @@
+//! Phone numbers embedded in passwords.
+
+use std::ops::Range;
+
+/// Digit runs at least this long look like a phone number: the shortest
+/// subscriber numbers have seven digits, and E.164 numbers at most 15.
+pub(crate) const MIN_DIGITS: usize = 7;
+
+/// Characters ignored between two digits of a phone number.
+const SEPARATORS: [char; 2] = [' ', '-'];
+
+/// The digit runs of `password`, with a single one of `separators` between
+/// two digits removed, so `0791-123 456` is one run of ten digits.
+pub(crate) fn digit_runs(password: &str, separators: &[char]) -> Vec<String> {
+    digit_run_spans(password, separators).into_iter().map(|(_, run)| run).collect()
+}
+
+/// Like [`digit_runs`], with the character range each run covers in
+/// `password`, separators included.
+fn digit_run_spans(password: &str, separators: &[char]) -> Vec<(Range<usize>, String)> {
+    let chars: Vec<char> = password.chars().collect();
+    let mut runs: Vec<(Range<usize>, String)> = Vec::new();
+    let digit_at = |i: usize| chars.get(i).is_some_and(char::is_ascii_digit);
+    let mut open = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_digit() {
+            match runs.last_mut() {
+                Some((span, run)) if open => {
+                    span.end = i + 1;
+                    run.push(c);
+                }
+                _ => runs.push((i..i + 1, c.to_string())),
+            }
+            open = true;
+        } else if !(separators.contains(&c) && i > 0 && digit_at(i - 1) && digit_at(i + 1)) {
+            open = false;
+        }
+    }
+    runs
+}
+
+/// Length of the longest digit run in `password`, ignoring single spaces
+/// or dashes between digits.
+pub(crate) fn longest_digit_run(password: &str) -> usize {
+    digit_runs(password, &SEPARATORS).iter().map(String::len).max().unwrap_or(0)
+}
+
+/// Character range of the first digit run that looks like a phone number.
+pub(crate) fn phone_like_span(password: &str) -> Option<Range<usize>> {
+    digit_run_spans(password, &SEPARATORS).into_iter().find(|(_, run)| run.len() >= MIN_DIGITS).map(|(span, _)| span)
+}
+
+/// Whether `password` contains the digits of `phone`, however either is
+/// formatted.
+///
+/// Country codes and trunk prefixes differ between ways of writing the
+/// same number (`+44 7911 123456`, `07911 123456`), so any seven
+/// consecutive digits of `phone` in a digit run of the password count. A
+/// number with fewer digits only matches whole.
+pub(crate) fn contains_number(password: &str, phone: &str) -> bool {
+    let phone: String = phone.chars().filter(char::is_ascii_digit).collect();
+    if phone.is_empty() {
+        return false;
+    }
+    let window = phone.len().min(MIN_DIGITS);
+    digit_runs(password, &SEPARATORS).iter().any(|run| (0..=run.len().saturating_sub(window)).any(|i| {
+        run.get(i..i + window).is_some_and(|part| phone.contains(part))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separators_between_digits_are_removed() {
+        assert_eq!(digit_runs("Dave07911123456!", &SEPARATORS), ["07911123456"]);
+        assert_eq!(digit_runs("0791-112 3456 and 12--34", &SEPARATORS), ["07911123456", "12", "34"]);
+        assert_eq!(digit_runs("- 12 -", &SEPARATORS), ["12"]);
+        assert_eq!(longest_digit_run("abc"), 0);
+        let spans = digit_run_spans("é0791-112 3456 and 12--34", &SEPARATORS);
+        assert_eq!(spans.iter().map(|(span, _)| span.clone()).collect::<Vec<_>>(), [1..14, 19..21, 23..25]);
+        assert_eq!(phone_like_span("pw 12, 0791-112 3456"), Some(7..20));
+        assert_eq!(phone_like_span("pw 123456"), None);
+    }
+
+    #[test]
+    fn numbers_match_across_formats() {
+        for phone in ["+44 7911 123456", "07911 123456", "(0)7911-123-456", "447911123456"] {
+            for password in ["Dave07911123456!", "x7911 123 456x", "my+447911123456", "pw-1123456"] {
+                assert!(contains_number(password, phone), "{} {}", password, phone);
+            }
+            for password in ["Dave1234!", "791112x3456", "Summer2024", "0123456789"] {
+                assert!(!contains_number(password, phone), "{} {}", password, phone);
+            }
+        }
+        assert!(contains_number("call 911 now", "911"));
+        assert!(!contains_number("anything", ""));
+    }
+}
@@ -0,0 +1,64 @@
+//! Numeric PINs.
+
+/// PINs chosen far more often than chance predicts, from published
+/// analyses of leaked PIN sets: repeated and paired digits, keypad
+/// columns such as `2580`, and words spelled on the keypad.
+const COMMON_PINS: &[&str] = &[
+    "0000", "1111", "1122", "1212", "1234", "1313", "2222", "2580", "3333", "4321", "4444", "5555", "5683", "6666", "6969", "7777",
+    "8888", "9999", "0852", "1004", "1010", "1590", "0258", "7410", "1478", "3698", "9630", "000000", "111111", "112233", "121212",
+    "123123", "123321", "123456", "147258", "159753", "258369", "654321", "666666", "696969", "11223344", "12341234", "12345678",
+    "87654321",
+];
+
+/// Four-digit years that may be a birth year, also common as PINs.
+const YEARS: std::ops::RangeInclusive<u16> = 1940..=2039;
+
+/// The entries of [`Dictionary::common_pins`](crate::Dictionary::common_pins).
+pub(crate) fn common_pins() -> impl Iterator<Item = String> {
+    COMMON_PINS.iter().map(|pin| pin.to_string()).chain(YEARS.map(|year| year.to_string()))
+}
+
+/// Whether `password` is three or more ASCII digits counting up or down
+/// by one, such as `1234` or `9876`.
+pub(crate) fn is_sequential(password: &str) -> bool {
+    let digits = password.as_bytes();
+    let steps = || digits.windows(2).map(|pair| i16::from(pair[1]) - i16::from(pair[0]));
+    digits.len() >= 3 && digits.iter().all(u8::is_ascii_digit) && (steps().all(|step| step == 1) || steps().all(|step| step == -1))
+}
+
+/// Whether `password` is two or more copies of one character, such as
+/// `1111`.
+pub(crate) fn is_one_repeated(password: &str) -> bool {
+    let mut chars = password.chars();
+    chars.next().is_some_and(|first| chars.clone().next().is_some() && chars.all(|c| c == first))
+}
+
+/// Whether `password` has two or more characters and reads the same
+/// backwards, such as `1221` or `12321`.
+pub(crate) fn is_palindrome(password: &str) -> bool {
+    password.chars().nth(1).is_some() && password.chars().eq(password.chars().rev())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequences_count_up_or_down() {
+        for pin in ["123", "1234", "9876", "3456789", "0123456789"] {
+            assert!(is_sequential(pin), "{}", pin);
+        }
+        for pin in ["12", "1235", "1243", "8901", "abcd", "1111", "١٢٣٤"] {
+            assert!(!is_sequential(pin), "{}", pin);
+        }
+    }
+
+    #[test]
+    fn repeats_and_palindromes() {
+        assert!(is_one_repeated("1111") && is_one_repeated("zz"));
+        assert!(!is_one_repeated("1") && !is_one_repeated("") && !is_one_repeated("1112"));
+        assert!(is_palindrome("1221") && is_palindrome("12321") && is_palindrome("77"));
+        assert!(!is_palindrome("7") && !is_palindrome("") && !is_palindrome("1234"));
+        assert_eq!(common_pins().filter(|pin| pin == "1984" || pin == "2580").count(), 2);
+    }
+}
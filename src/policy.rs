@@ -0,0 +1,50 @@
+//! The interface shared by password policies.
+
+use crate::{PasswordChecker, Violations};
+
+/// A password policy: what [`evaluate_all`](crate::evaluate_all) and
+/// other code generic over policies needs to check passwords and list
+/// requirements.
+///
+/// [`PasswordChecker`] implements it. Applications implement it for
+/// hand-rolled policies so they plug in where a checker does, reporting
+/// [`Violation`](crate::Violation)s of the same shape.
+///
+/// ```
+/// use passcheck::{PasswordPolicy, Violation, Violations};
+///
+/// struct NotAdmin;
+///
+/// impl PasswordPolicy for NotAdmin {
+///     fn check(&self, password: &str) -> Result<(), Violations> {
+///         if !password.eq_ignore_ascii_case("admin") {
+///             return Ok(());
+///         }
+///         let message = "Password must not be `admin`.".to_string();
+///         Err(Violations::from_iter([Violation { code: "not_admin", message, params: Vec::new(), span: None, group: None }]))
+///     }
+///
+///     fn describe(&self) -> Vec<String> {
+///         vec!["Not `admin`".to_string()]
+///     }
+/// }
+///
+/// assert!(NotAdmin.check("ADMIN").is_err());
+/// ```
+pub trait PasswordPolicy {
+    /// Checks `password`, returning every requirement it breaks.
+    fn check(&self, password: &str) -> Result<(), Violations>;
+
+    /// A human-readable description of each requirement.
+    fn describe(&self) -> Vec<String>;
+}
+
+impl PasswordPolicy for PasswordChecker<'_> {
+    fn check(&self, password: &str) -> Result<(), Violations> {
+        PasswordChecker::check(self, password)
+    }
+
+    fn describe(&self) -> Vec<String> {
+        PasswordChecker::describe(self)
+    }
+}
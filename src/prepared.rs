@@ -0,0 +1,104 @@
+//! A password normalized once per validation for the rules matching
+//! substrings.
+
+use std::borrow::Cow;
+use std::cell::OnceCell;
+
+use crate::dictionary::MatchMode;
+use crate::stats::longest_class_run;
+use crate::{Rule, SpecialCharPolicy, UserContext, accents, casefold, service};
+
+/// The forms of one password that blacklists, service names and user
+/// information are matched in, each computed when a rule first needs it
+/// and then shared by every rule of the validation.
+pub(crate) struct Prepared<'p> {
+    password: &'p str,
+    /// The checker's special set, which class runs go by.
+    special: &'p SpecialCharPolicy,
+    ascii_lowercase: OnceCell<Cow<'p, str>>,
+    folded: OnceCell<String>,
+    without_accents: OnceCell<String>,
+    service_forms: OnceCell<[String; 2]>,
+}
+
+impl<'p> Prepared<'p> {
+    pub(crate) fn new(password: &'p str, special: &'p SpecialCharPolicy) -> Self {
+        Prepared {
+            password,
+            special,
+            ascii_lowercase: OnceCell::new(),
+            folded: OnceCell::new(),
+            without_accents: OnceCell::new(),
+            service_forms: OnceCell::new(),
+        }
+    }
+
+    /// The password as [normalized](MatchMode::normalize) for `mode`.
+    fn normalized(&self, mode: MatchMode) -> &str {
+        match mode {
+            MatchMode::Exact => self.password,
+            MatchMode::AsciiCaseInsensitive => self.ascii_lowercase.get_or_init(|| MatchMode::AsciiCaseInsensitive.normalize(self.password)),
+            MatchMode::UnicodeCaseFold => self.folded(),
+        }
+    }
+
+    fn folded(&self) -> &str {
+        self.folded.get_or_init(|| casefold::fold(self.password))
+    }
+
+    /// Whether `rule` fails, as [`Rule::fails_in`] decides, with class runs
+    /// going by the checker's special set.
+    pub(crate) fn fails(&self, rule: &Rule<'_>, context: Option<&UserContext<'_>>) -> bool {
+        match (rule, context) {
+            (Rule::Blacklist(words, _), _) => words.matches_normalized(self.normalized(words.mode())),
+            (Rule::ServiceNames(names, _), _) => {
+                service::contains_service_name_in(self.service_forms.get_or_init(|| service::folded_forms(self.folded())), names)
+            }
+            (Rule::NotContainUserInfo(check, _), Some(context)) => {
+                check.find_normalized(self.without_accents.get_or_init(|| accents::strip(self.folded())), context).is_some()
+            }
+            (Rule::MaxClassRun(len, _), _) => longest_class_run(self.password, self.special) > *len,
+            _ => rule.fails_in(self.password, context),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dictionary, PasswordChecker, UserInfoCheck};
+
+    #[test]
+    fn shared_forms_give_the_same_failures() {
+        let checker = PasswordChecker::new()
+            .blacklist(["Password1", "straße"], None)
+            .blacklist(Dictionary::with_mode(["letmein", "Qwerty"], MatchMode::AsciiCaseInsensitive), None)
+            .blacklist(Dictionary::with_mode(["STRASSE", "çava"], MatchMode::UnicodeCaseFold), None)
+            .blacklist(Dictionary::new(["dragon"]).fuzzy(1), None)
+            .with_service_names(["acme", "Widgetify"], None)
+            .with_service_names((0..40).map(|i| format!("service{}", i)), None)
+            .not_contain_user_info(UserInfoCheck::new(), None)
+            .not_contain_user_info(UserInfoCheck::new().email_domain(false).bidirectional(true), None);
+        let fields = ["alexander.hamilton", "José García", "j.smith+shop@example-corp.com", "Ana", "w.l.k@x.io"];
+        let contexts: Vec<UserContext<'_>> = (0..fields.len())
+            .map(|i| UserContext::new().username(fields[i]).full_name(fields[(i + 1) % fields.len()]).email(fields[(i + 2) % fields.len()]))
+            .chain([UserContext::new()])
+            .collect();
+        let passwords = [
+            "", "Password1", "password1", "STRASSE", "Straße", "LETMEIN", "ÇaVa", "drag0n", "dragons", "MyAcme2024!", "W1dget1fy",
+            "service39", "xservice12y", "Hamilton99", "xander.ham", "JoseGarcia", "GARCÍA!", "smith-shop", "corp2024", "ana",
+            "Ἀθῆναι", "İstanbul", "correct horse battery",
+        ];
+        for context in &contexts {
+            for password in passwords {
+                let prepared = Prepared::new(password, &SpecialCharPolicy::Builtin);
+                for rule in checker.rules() {
+                    assert_eq!(prepared.fails(rule, Some(context)), rule.fails_in(password, Some(context)), "{:?} {:?} {:?}", rule, password, context);
+                }
+                let separate: Vec<_> = checker.rules().iter().filter(|rule| rule.fails_in(password, Some(context))).map(Rule::code).collect();
+                let combined = checker.check_with_context(password, context).err().map(|v| v.codes().collect::<Vec<_>>()).unwrap_or_default();
+                assert_eq!(combined, separate, "{:?}", password);
+            }
+        }
+    }
+}
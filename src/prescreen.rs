@@ -0,0 +1,199 @@
+//! Length and character-class checks on a password read as a stream.
+
+use crate::{DEFAULT_MAX_INPUT_BYTES, ParamValue, PasswordChecker, Rule, Violations, find_unsafe_char};
+
+/// The result of [`PasswordChecker::prescreen_stream`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prescreen {
+    /// The rules decided from the stream reject the password, with their
+    /// violations as [`check`](PasswordChecker::check) reports them, or
+    /// the stream is over [`max_input_bytes`](PasswordChecker::max_input_bytes).
+    Rejected(Violations),
+    /// Every rule was decided from the stream, and none fails.
+    Accepted,
+    /// No rule decided from the stream fails, and the rules with these
+    /// codes need the whole password; validate it in full.
+    NeedsFullValidation { undetermined: Vec<&'static str> },
+}
+
+/// What the streaming rules need to know about the characters read.
+struct Seen {
+    bytes: usize,
+    chars: usize,
+    upper: bool,
+    lower: bool,
+    digit: bool,
+    all_numeric: bool,
+    all_alphabetic: bool,
+    all_ascii_digits: bool,
+    /// Byte offset, character position and value of the first unsafe
+    /// character.
+    unsafe_char: Option<(usize, usize, char)>,
+    /// Per rule, whether a special character of its policy was read.
+    special: Vec<bool>,
+}
+
+impl PasswordChecker<'_> {
+    /// Checks a password available only as characters to read, consuming
+    /// at most [`max_input_bytes`](Self::max_input_bytes) of them: a longer
+    /// stream is rejected as `input_too_large` as soon as the limit is
+    /// passed, with `actual` the bytes read so far.
+    ///
+    /// Only rules decidable from one pass are evaluated: the length
+    /// limits, character-class requirements, unsafe characters and
+    /// [`not_all_numeric`](Self::not_all_numeric),
+    /// [`not_all_alphabetic`](Self::not_all_alphabetic) and
+    /// [`digits_only`](Self::digits_only). Other rules are reported as
+    /// undetermined. Lengths are in bytes, as for
+    /// [`min_length`](Self::min_length). Fail-fast, dry run and observers
+    /// do not apply.
+    ///
+    /// ```
+    /// use passcheck::{PasswordChecker, Prescreen};
+    ///
+    /// let checker = PasswordChecker::new().max_input_bytes(64).min_length(8, None).require_number(None).blacklist(["password1"], None);
+    /// let huge = std::iter::repeat('a');
+    /// assert!(matches!(checker.prescreen_stream(huge), Prescreen::Rejected(v) if v[0].code == "input_too_large"));
+    /// assert!(matches!(checker.prescreen_stream("sunflower".chars()), Prescreen::Rejected(v) if v[0].code == "require_number"));
+    /// assert_eq!(checker.prescreen_stream("password1".chars()), Prescreen::NeedsFullValidation { undetermined: vec!["blacklist"] });
+    /// ```
+    pub fn prescreen_stream(&self, chars: impl Iterator<Item = char>) -> Prescreen {
+        let max_input_bytes = self.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+        let mut seen = Seen {
+            bytes: 0,
+            chars: 0,
+            upper: false,
+            lower: false,
+            digit: false,
+            all_numeric: true,
+            all_alphabetic: true,
+            all_ascii_digits: true,
+            unsafe_char: None,
+            special: vec![false; self.rules.len()],
+        };
+        for c in chars {
+            if seen.bytes + c.len_utf8() > max_input_bytes {
+                return Prescreen::Rejected(Violations(vec![self.input_too_large(seen.bytes + c.len_utf8(), None)]));
+            }
+            seen.upper |= c.is_ascii_uppercase();
+            seen.lower |= c.is_ascii_lowercase();
+            seen.digit |= c.is_ascii_digit();
+            seen.all_numeric &= c.is_numeric();
+            seen.all_alphabetic &= c.is_alphabetic();
+            seen.all_ascii_digits &= c.is_ascii_digit();
+            if seen.unsafe_char.is_none() && find_unsafe_char(c.encode_utf8(&mut [0; 4])).is_some() {
+                seen.unsafe_char = Some((seen.bytes, seen.chars, c));
+            }
+            for (special, rule) in seen.special.iter_mut().zip(&self.rules) {
+                if let Rule::RequireSpecialChar(policy, _) = rule {
+                    *special |= policy.contains(c);
+                }
+            }
+            seen.bytes += c.len_utf8();
+            seen.chars += 1;
+        }
+
+        let mut violations = Vec::new();
+        let mut undetermined = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let length = |bound: &'static str, len: usize| vec![(bound, ParamValue::Int(len)), ("actual", ParamValue::Int(seen.bytes))];
+            let (failed, params) = match rule {
+                Rule::MinLength(len, _) => (seen.bytes < *len, length("min", *len)),
+                Rule::MaxLength(len, _) | Rule::MaxBytes(len, _) => (seen.bytes > *len, length("max", *len)),
+                Rule::RecommendedLength(..) => (false, Vec::new()),
+                Rule::RequireUpperLower(_) => {
+                    let missing = [("uppercase", seen.upper), ("lowercase", seen.lower)].into_iter().filter(|(_, seen)| !seen).map(|(case, _)| case);
+                    (!(seen.upper && seen.lower), vec![("missing", ParamValue::List(missing.collect()))])
+                }
+                Rule::RequireNumber(_) => (!seen.digit, Vec::new()),
+                Rule::RequireSpecialChar(..) => (!seen.special[i], Vec::new()),
+                Rule::ForbidUnsafeChars(_) => match seen.unsafe_char {
+                    Some((offset, position, c)) => (true, vec![
+                        ("codepoint", ParamValue::Int(c as usize)),
+                        ("offset", ParamValue::Int(offset)),
+                        ("position", ParamValue::Int(position)),
+                    ]),
+                    None => (false, Vec::new()),
+                },
+                Rule::NotAllNumeric(_) => (seen.bytes > 0 && seen.all_numeric, Vec::new()),
+                Rule::NotAllAlphabetic(_) => (seen.bytes > 0 && seen.all_alphabetic, Vec::new()),
+                Rule::DigitsOnly(_) => (!seen.all_ascii_digits, Vec::new()),
+                _ => {
+                    undetermined.push(rule.code());
+                    continue;
+                }
+            };
+            if failed {
+                let span = seen.unsafe_char.filter(|_| matches!(rule, Rule::ForbidUnsafeChars(_))).map(|(_, position, _)| position..position + 1);
+                violations.push(self.violation(i, rule, params, span, None));
+            }
+        }
+        match (violations.is_empty(), undetermined.is_empty()) {
+            (false, _) => Prescreen::Rejected(Violations(violations)),
+            (true, true) => Prescreen::Accepted,
+            (true, false) => Prescreen::NeedsFullValidation { undetermined },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SpecialCharPolicy;
+    use std::cell::Cell;
+
+    fn streaming() -> PasswordChecker<'static> {
+        PasswordChecker::new()
+            .max_input_bytes(256)
+            .min_length(8, Some("Use 8 characters."))
+            .max_length(32, None)
+            .for_bcrypt()
+            .length_bands(6, 12, None, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char_with(SpecialCharPolicy::UnicodeCategories, None)
+            .forbid_unsafe_chars(None)
+            .not_all_numeric(None)
+            .not_all_alphabetic(None)
+    }
+
+    #[test]
+    fn stops_reading_at_the_input_limit() {
+        let read = Cell::new(0);
+        let endless = std::iter::repeat_with(|| {
+            read.set(read.get() + 1);
+            'é'
+        });
+        let Prescreen::Rejected(violations) = streaming().prescreen_stream(endless) else { panic!("accepted an endless stream") };
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["input_too_large"]);
+        assert_eq!(violations[0].params, [("max", ParamValue::Int(256)), ("actual", ParamValue::Int(258))]);
+        assert_eq!(read.get(), 129);
+    }
+
+    #[test]
+    fn agrees_with_full_validation_for_short_inputs() {
+        let checker = streaming();
+        let digits = PasswordChecker::new().digits_only(None).min_length(4, None);
+        for password in [
+            "", "short", "sunflower", "Sunflower", "Sunflower9", "Sunflower9!", "Sunflower9€", "12345678", "Ünïcödé9!",
+            "Sun\u{0}flower9!", "Sunflower9!\u{FFFF}x", "Aa1!Aa1!Aa1!Aa1!Aa1!Aa1!Aa1!Aa1!Aa1!", "٣٤٥٦٧٨٩٠", "abc",
+        ] {
+            for checker in [&checker, &digits] {
+                let expected = match checker.check(password) {
+                    Ok(()) => Prescreen::Accepted,
+                    Err(violations) => Prescreen::Rejected(violations),
+                };
+                assert_eq!(checker.prescreen_stream(password.chars()), expected, "{:?}", password);
+            }
+        }
+    }
+
+    #[test]
+    fn whole_string_rules_are_undetermined() {
+        let checker = streaming().blacklist(["Sunflower9!"], None).not_palindrome(None).keys_only(true);
+        assert_eq!(checker.prescreen_stream("Sunflower9!".chars()), Prescreen::NeedsFullValidation { undetermined: vec!["blacklist", "not_palindrome"] });
+        let Prescreen::Rejected(violations) = checker.prescreen_stream("sunflower".chars()) else { panic!("not rejected") };
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["require_upper_lower", "require_number", "require_special_char", "not_all_alphabetic"]);
+        assert!(violations.iter().all(|v| v.message.is_empty()));
+    }
+}
@@ -0,0 +1,99 @@
+//! Counting met requirements, for progress indicators.
+
+use crate::{DEFAULT_MAX_INPUT_BYTES, PasswordChecker, RuleMeta};
+
+/// How many of a checker's requirements a password meets, from
+/// [`PasswordChecker::progress`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Progress {
+    pub satisfied: usize,
+    /// Number of requirements counted. It depends only on the checker, so
+    /// it stays the same while the user types.
+    pub total: usize,
+    /// The requirements not met yet, in rule order.
+    pub remaining: Vec<RuleMeta>,
+}
+
+impl Progress {
+    /// Share of requirements met, from 0 to 1; 1 without requirements.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 { 1.0 } else { self.satisfied as f64 / self.total as f64 }
+    }
+
+    /// Whether every requirement is met.
+    pub fn is_complete(&self) -> bool {
+        self.remaining.is_empty()
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// Counts the requirements `password` meets, e.g. for "3 of 6
+    /// requirements met".
+    ///
+    /// Rules that compare the password with a [`UserContext`](crate::UserContext),
+    /// such as [`not_similar_to_previous`](Self::not_similar_to_previous),
+    /// cannot be judged from the password alone and are left out of
+    /// `total`, so that it does not change with what is known about the
+    /// user. Inputs over [`max_input_bytes`](Self::max_input_bytes) meet
+    /// none. The rules run once each, as in [`check`](Self::check).
+    ///
+    /// Growing a password only ever meets more of the length, class and
+    /// word-count rules; blacklists and pattern rules can flip either way.
+    pub fn progress(&self, password: &str) -> Progress {
+        let rules = self.rules.iter().filter(|rule| !rule.needs_context());
+        let oversized = password.len() > self.max_input_bytes.unwrap_or(DEFAULT_MAX_INPUT_BYTES);
+        let fails = self.failure_test(password, None);
+        let mut progress = Progress::default();
+        for rule in rules {
+            progress.total += 1;
+            if oversized || fails(rule) {
+                progress.remaining.push(RuleMeta::of(rule));
+            } else {
+                progress.satisfied += 1;
+            }
+        }
+        progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Similarity;
+
+    #[test]
+    fn typing_only_adds_met_requirements() {
+        let checker = PasswordChecker::new()
+            .min_length(10, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None)
+            .min_words(2, None)
+            .not_similar_to_previous(Similarity::MinEdits(3), None);
+        let typed = "Blue horse 42!";
+        let mut last = 0;
+        for end in (0..=typed.len()).filter(|&i| typed.is_char_boundary(i)) {
+            let progress = checker.progress(&typed[..end]);
+            assert_eq!(progress.total, 5, "{:?}", &typed[..end]);
+            assert!(progress.satisfied >= last, "{:?}", &typed[..end]);
+            assert_eq!(progress.satisfied + progress.remaining.len(), progress.total);
+            last = progress.satisfied;
+        }
+        assert!(checker.progress(typed).is_complete());
+
+        let partial = checker.progress("blue");
+        assert_eq!(partial.satisfied, 0);
+        assert_eq!(partial.remaining.iter().map(|meta| meta.rule).collect::<Vec<_>>(), ["min_length", "require_upper_lower", "require_number", "require_special_char", "min_words"]);
+        assert_eq!(partial.remaining[0].params, r#"{"length":10}"#);
+        assert_eq!(checker.progress("Blue").satisfied, 1);
+        assert!((checker.progress("Blue horse").fraction() - 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn oversized_and_empty_policies() {
+        let checker = PasswordChecker::new().min_length(4, None).max_input_bytes(8);
+        assert_eq!(checker.progress("long enough").satisfied, 0);
+        let empty = PasswordChecker::new().progress("anything");
+        assert_eq!((empty.total, empty.fraction(), empty.is_complete()), (0, 1.0, true));
+    }
+}
@@ -0,0 +1,154 @@
+//! Policies looked up by name, e.g. per tenant or per role.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::config::invalid;
+use crate::json::{self, Value};
+use crate::{ConfigError, PasswordChecker, PolicyConfig, Violations};
+
+/// Error returned by [`PolicyRegistry::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    /// No policy is registered under this name.
+    UnknownPolicy(String),
+    /// The password breaks the policy.
+    Invalid(Violations),
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownPolicy(name) => write!(f, "no policy named `{}`", name),
+            RegistryError::Invalid(violations) => write!(f, "invalid password: {}", violations),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RegistryError::Invalid(violations) => Some(violations),
+            RegistryError::UnknownPolicy(_) => None,
+        }
+    }
+}
+
+/// Checkers by name. `Send + Sync`, so one registry can be shared behind an
+/// `Arc`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PolicyRegistry {
+    policies: BTreeMap<String, PasswordChecker<'static>>,
+}
+
+impl PolicyRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        PolicyRegistry::default()
+    }
+
+    /// Registers `checker` as `name`, returning the checker it replaces.
+    pub fn insert(&mut self, name: impl Into<String>, checker: PasswordChecker<'static>) -> Option<PasswordChecker<'static>> {
+        self.policies.insert(name.into(), checker)
+    }
+
+    /// Returns the checker registered as `name`.
+    pub fn get(&self, name: &str) -> Option<&PasswordChecker<'static>> {
+        self.policies.get(name)
+    }
+
+    /// Names of the registered policies, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.policies.keys().map(String::as_str)
+    }
+
+    /// Checks `password` against the policy registered as `name`.
+    pub fn validate(&self, name: &str, password: &str) -> Result<(), RegistryError> {
+        let checker = self.get(name).ok_or_else(|| RegistryError::UnknownPolicy(name.to_string()))?;
+        checker.check(password).map_err(RegistryError::Invalid)
+    }
+
+    /// Loads a registry from a JSON object mapping names to policies in the
+    /// format of [`PolicyConfig::from_json`], e.g.
+    /// `{"admin": {"rules": [...]}, "user": {"rules": [...]}}`. Each checker
+    /// is [named](PasswordChecker::named) after its key.
+    pub fn from_json(input: &str) -> Result<Self, ConfigError> {
+        let root = json::parse(input)?;
+        let Value::Object(fields) = &root else {
+            return Err(invalid("", format!("expected object, found {}", root.type_name())));
+        };
+        let mut registry = PolicyRegistry::new();
+        for (name, policy) in fields {
+            let config = PolicyConfig::from_value(policy).map_err(|err| err.prefixed(name))?;
+            let mut checker = PasswordChecker::from_config(config);
+            checker.name = Some(Cow::Owned(name.clone()));
+            if registry.insert(name.clone(), checker).is_some() {
+                return Err(invalid(name, "duplicate policy name".to_string()));
+            }
+        }
+        Ok(registry)
+    }
+}
+
+// Compile-time check of the documented thread-safety guarantee.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<PolicyRegistry>();
+};
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    const POLICIES: &str = r#"{
+        "admin": { "rules": [{ "rule": "min_length", "length": 16 }, { "rule": "require_special_char" }] },
+        "user": { "version": 1, "rules": [{ "rule": "min_length", "length": 10 }] },
+        "service": { "rules": [{ "rule": "min_length", "length": 32 }] }
+    }"#;
+
+    #[test]
+    fn dispatches_by_name() {
+        let registry = PolicyRegistry::from_json(POLICIES).unwrap();
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["admin", "service", "user"]);
+        assert_eq!(registry.validate("user", "correct horse"), Ok(()));
+        let Err(RegistryError::Invalid(violations)) = registry.validate("admin", "correct horse") else { panic!() };
+        assert_eq!(violations.codes().collect::<Vec<_>>(), ["min_length", "require_special_char"]);
+        assert_eq!(registry.get("service").map(|c| c.rules()[0].code()), Some("min_length"));
+        assert_eq!(registry.get("admin").and_then(|c| c.name()), Some("admin"));
+    }
+
+    #[test]
+    fn unknown_names_are_distinct_errors() {
+        let mut registry = PolicyRegistry::new();
+        assert_eq!(registry.validate("admin", "pw"), Err(RegistryError::UnknownPolicy("admin".into())));
+        assert_eq!(RegistryError::UnknownPolicy("admin".into()).to_string(), "no policy named `admin`");
+        assert!(registry.insert("admin", PasswordChecker::new().min_length(4, None)).is_none());
+        assert_eq!(registry.validate("admin", "long enough"), Ok(()));
+    }
+
+    #[test]
+    fn errors_point_into_the_named_policy() {
+        let err = PolicyRegistry::from_json(r#"{"admin": {"rules": [{"rule": "min_length"}]}}"#).unwrap_err();
+        assert_eq!(err, invalid("admin.rules[0].length", "missing field".to_string()));
+        let err = PolicyRegistry::from_json(r#"{"a": {"allow_empty": true}, "a": {"allow_empty": true}}"#).unwrap_err();
+        assert_eq!(err, invalid("a", "duplicate policy name".to_string()));
+    }
+
+    #[test]
+    fn shared_across_threads() {
+        let registry = Arc::new(PolicyRegistry::from_json(POLICIES).unwrap());
+        let handles: Vec<_> = ["admin", "user", "service", "user"]
+            .into_iter()
+            .map(|name| {
+                let registry = Arc::clone(&registry);
+                thread::spawn(move || registry.validate(name, "a fairly long passphrase!").is_ok())
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results, [true, true, false, true]);
+    }
+}
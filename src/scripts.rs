@@ -0,0 +1,344 @@
+//! Unicode scripts, for rules about mixing writing systems.
+//!
+//! Generated from the Unicode 14.0.0 character database. Only the Script
+//! property is kept, as merged ranges; Common (digits, punctuation,
+//! symbols), Inherited (combining marks) and Unknown are left out, so
+//! those characters belong to no script.
+
+/// Scripts customarily written together, which count as one.
+const COMBINATIONS: &[&[&str]] = &[&["han", "hiragana", "katakana"], &["han", "hangul"], &["han", "bopomofo"]];
+
+/// The script of `c`, as a lowercase Unicode script name such as
+/// `"latin"` or `"old_italic"`; `None` for Common and Inherited characters.
+pub(crate) fn script(c: char) -> Option<&'static str> {
+    SCRIPTS
+        .binary_search_by(|&(start, end, _)| {
+            if end < c {
+                std::cmp::Ordering::Less
+            } else if start > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .ok()
+        .map(|i| SCRIPTS[i].2)
+}
+
+/// The scripts used in `password`, in order of first appearance.
+pub(crate) fn scripts(password: &str) -> Vec<&'static str> {
+    let mut scripts = Vec::new();
+    for script in password.chars().filter_map(script) {
+        if !scripts.contains(&script) {
+            scripts.push(script);
+        }
+    }
+    scripts
+}
+
+/// Whether `scripts` are one script, or scripts written together such as
+/// Han, Hiragana and Katakana in Japanese.
+pub(crate) fn is_single_script(scripts: &[&str]) -> bool {
+    scripts.len() <= 1 || COMBINATIONS.iter().any(|combination| scripts.iter().all(|s| combination.contains(s)))
+}
+
+/// The script called `name`, for parsing params.
+pub(crate) fn name(name: &str) -> Option<&'static str> {
+    SCRIPTS.iter().map(|&(_, _, script)| script).find(|&script| script == name)
+}
+
+/// Inclusive ranges of code points with their script, sorted and disjoint.
+const SCRIPTS: &[(char, char, &str)] = &[
+    ('\u{41}', '\u{5a}', "latin"), ('\u{61}', '\u{7a}', "latin"), ('\u{aa}', '\u{aa}', "latin"), ('\u{ba}', '\u{ba}', "latin"),
+    ('\u{c0}', '\u{d6}', "latin"), ('\u{d8}', '\u{f6}', "latin"), ('\u{f8}', '\u{2b8}', "latin"), ('\u{2e0}', '\u{2e4}', "latin"),
+    ('\u{2ea}', '\u{2eb}', "bopomofo"), ('\u{370}', '\u{373}', "greek"), ('\u{375}', '\u{377}', "greek"),
+    ('\u{37a}', '\u{37d}', "greek"), ('\u{37f}', '\u{37f}', "greek"), ('\u{384}', '\u{384}', "greek"),
+    ('\u{386}', '\u{386}', "greek"), ('\u{388}', '\u{38a}', "greek"), ('\u{38c}', '\u{38c}', "greek"),
+    ('\u{38e}', '\u{3a1}', "greek"), ('\u{3a3}', '\u{3e1}', "greek"), ('\u{3e2}', '\u{3ef}', "coptic"),
+    ('\u{3f0}', '\u{3ff}', "greek"), ('\u{400}', '\u{484}', "cyrillic"), ('\u{487}', '\u{52f}', "cyrillic"),
+    ('\u{531}', '\u{556}', "armenian"), ('\u{559}', '\u{58a}', "armenian"), ('\u{58d}', '\u{58f}', "armenian"),
+    ('\u{591}', '\u{5c7}', "hebrew"), ('\u{5d0}', '\u{5ea}', "hebrew"), ('\u{5ef}', '\u{5f4}', "hebrew"),
+    ('\u{600}', '\u{604}', "arabic"), ('\u{606}', '\u{60b}', "arabic"), ('\u{60d}', '\u{61a}', "arabic"),
+    ('\u{61c}', '\u{61e}', "arabic"), ('\u{620}', '\u{63f}', "arabic"), ('\u{641}', '\u{64a}', "arabic"),
+    ('\u{656}', '\u{66f}', "arabic"), ('\u{671}', '\u{6dc}', "arabic"), ('\u{6de}', '\u{6ff}', "arabic"),
+    ('\u{700}', '\u{70d}', "syriac"), ('\u{70f}', '\u{74a}', "syriac"), ('\u{74d}', '\u{74f}', "syriac"),
+    ('\u{750}', '\u{77f}', "arabic"), ('\u{780}', '\u{7b1}', "thaana"), ('\u{7c0}', '\u{7fa}', "nko"),
+    ('\u{7fd}', '\u{7ff}', "nko"), ('\u{800}', '\u{82d}', "samaritan"), ('\u{830}', '\u{83e}', "samaritan"),
+    ('\u{840}', '\u{85b}', "mandaic"), ('\u{85e}', '\u{85e}', "mandaic"), ('\u{860}', '\u{86a}', "syriac"),
+    ('\u{870}', '\u{88e}', "arabic"), ('\u{890}', '\u{891}', "arabic"), ('\u{898}', '\u{8e1}', "arabic"),
+    ('\u{8e3}', '\u{8ff}', "arabic"), ('\u{900}', '\u{950}', "devanagari"), ('\u{955}', '\u{963}', "devanagari"),
+    ('\u{966}', '\u{97f}', "devanagari"), ('\u{980}', '\u{983}', "bengali"), ('\u{985}', '\u{98c}', "bengali"),
+    ('\u{98f}', '\u{990}', "bengali"), ('\u{993}', '\u{9a8}', "bengali"), ('\u{9aa}', '\u{9b0}', "bengali"),
+    ('\u{9b2}', '\u{9b2}', "bengali"), ('\u{9b6}', '\u{9b9}', "bengali"), ('\u{9bc}', '\u{9c4}', "bengali"),
+    ('\u{9c7}', '\u{9c8}', "bengali"), ('\u{9cb}', '\u{9ce}', "bengali"), ('\u{9d7}', '\u{9d7}', "bengali"),
+    ('\u{9dc}', '\u{9dd}', "bengali"), ('\u{9df}', '\u{9e3}', "bengali"), ('\u{9e6}', '\u{9fe}', "bengali"),
+    ('\u{a01}', '\u{a03}', "gurmukhi"), ('\u{a05}', '\u{a0a}', "gurmukhi"), ('\u{a0f}', '\u{a10}', "gurmukhi"),
+    ('\u{a13}', '\u{a28}', "gurmukhi"), ('\u{a2a}', '\u{a30}', "gurmukhi"), ('\u{a32}', '\u{a33}', "gurmukhi"),
+    ('\u{a35}', '\u{a36}', "gurmukhi"), ('\u{a38}', '\u{a39}', "gurmukhi"), ('\u{a3c}', '\u{a3c}', "gurmukhi"),
+    ('\u{a3e}', '\u{a42}', "gurmukhi"), ('\u{a47}', '\u{a48}', "gurmukhi"), ('\u{a4b}', '\u{a4d}', "gurmukhi"),
+    ('\u{a51}', '\u{a51}', "gurmukhi"), ('\u{a59}', '\u{a5c}', "gurmukhi"), ('\u{a5e}', '\u{a5e}', "gurmukhi"),
+    ('\u{a66}', '\u{a76}', "gurmukhi"), ('\u{a81}', '\u{a83}', "gujarati"), ('\u{a85}', '\u{a8d}', "gujarati"),
+    ('\u{a8f}', '\u{a91}', "gujarati"), ('\u{a93}', '\u{aa8}', "gujarati"), ('\u{aaa}', '\u{ab0}', "gujarati"),
+    ('\u{ab2}', '\u{ab3}', "gujarati"), ('\u{ab5}', '\u{ab9}', "gujarati"), ('\u{abc}', '\u{ac5}', "gujarati"),
+    ('\u{ac7}', '\u{ac9}', "gujarati"), ('\u{acb}', '\u{acd}', "gujarati"), ('\u{ad0}', '\u{ad0}', "gujarati"),
+    ('\u{ae0}', '\u{ae3}', "gujarati"), ('\u{ae6}', '\u{af1}', "gujarati"), ('\u{af9}', '\u{aff}', "gujarati"),
+    ('\u{b01}', '\u{b03}', "oriya"), ('\u{b05}', '\u{b0c}', "oriya"), ('\u{b0f}', '\u{b10}', "oriya"),
+    ('\u{b13}', '\u{b28}', "oriya"), ('\u{b2a}', '\u{b30}', "oriya"), ('\u{b32}', '\u{b33}', "oriya"),
+    ('\u{b35}', '\u{b39}', "oriya"), ('\u{b3c}', '\u{b44}', "oriya"), ('\u{b47}', '\u{b48}', "oriya"),
+    ('\u{b4b}', '\u{b4d}', "oriya"), ('\u{b55}', '\u{b57}', "oriya"), ('\u{b5c}', '\u{b5d}', "oriya"),
+    ('\u{b5f}', '\u{b63}', "oriya"), ('\u{b66}', '\u{b77}', "oriya"), ('\u{b82}', '\u{b83}', "tamil"),
+    ('\u{b85}', '\u{b8a}', "tamil"), ('\u{b8e}', '\u{b90}', "tamil"), ('\u{b92}', '\u{b95}', "tamil"),
+    ('\u{b99}', '\u{b9a}', "tamil"), ('\u{b9c}', '\u{b9c}', "tamil"), ('\u{b9e}', '\u{b9f}', "tamil"),
+    ('\u{ba3}', '\u{ba4}', "tamil"), ('\u{ba8}', '\u{baa}', "tamil"), ('\u{bae}', '\u{bb9}', "tamil"),
+    ('\u{bbe}', '\u{bc2}', "tamil"), ('\u{bc6}', '\u{bc8}', "tamil"), ('\u{bca}', '\u{bcd}', "tamil"),
+    ('\u{bd0}', '\u{bd0}', "tamil"), ('\u{bd7}', '\u{bd7}', "tamil"), ('\u{be6}', '\u{bfa}', "tamil"),
+    ('\u{c00}', '\u{c0c}', "telugu"), ('\u{c0e}', '\u{c10}', "telugu"), ('\u{c12}', '\u{c28}', "telugu"),
+    ('\u{c2a}', '\u{c39}', "telugu"), ('\u{c3c}', '\u{c44}', "telugu"), ('\u{c46}', '\u{c48}', "telugu"),
+    ('\u{c4a}', '\u{c4d}', "telugu"), ('\u{c55}', '\u{c56}', "telugu"), ('\u{c58}', '\u{c5a}', "telugu"),
+    ('\u{c5d}', '\u{c5d}', "telugu"), ('\u{c60}', '\u{c63}', "telugu"), ('\u{c66}', '\u{c6f}', "telugu"),
+    ('\u{c77}', '\u{c7f}', "telugu"), ('\u{c80}', '\u{c8c}', "kannada"), ('\u{c8e}', '\u{c90}', "kannada"),
+    ('\u{c92}', '\u{ca8}', "kannada"), ('\u{caa}', '\u{cb3}', "kannada"), ('\u{cb5}', '\u{cb9}', "kannada"),
+    ('\u{cbc}', '\u{cc4}', "kannada"), ('\u{cc6}', '\u{cc8}', "kannada"), ('\u{cca}', '\u{ccd}', "kannada"),
+    ('\u{cd5}', '\u{cd6}', "kannada"), ('\u{cdd}', '\u{cde}', "kannada"), ('\u{ce0}', '\u{ce3}', "kannada"),
+    ('\u{ce6}', '\u{cef}', "kannada"), ('\u{cf1}', '\u{cf2}', "kannada"), ('\u{d00}', '\u{d0c}', "malayalam"),
+    ('\u{d0e}', '\u{d10}', "malayalam"), ('\u{d12}', '\u{d44}', "malayalam"), ('\u{d46}', '\u{d48}', "malayalam"),
+    ('\u{d4a}', '\u{d4f}', "malayalam"), ('\u{d54}', '\u{d63}', "malayalam"), ('\u{d66}', '\u{d7f}', "malayalam"),
+    ('\u{d81}', '\u{d83}', "sinhala"), ('\u{d85}', '\u{d96}', "sinhala"), ('\u{d9a}', '\u{db1}', "sinhala"),
+    ('\u{db3}', '\u{dbb}', "sinhala"), ('\u{dbd}', '\u{dbd}', "sinhala"), ('\u{dc0}', '\u{dc6}', "sinhala"),
+    ('\u{dca}', '\u{dca}', "sinhala"), ('\u{dcf}', '\u{dd4}', "sinhala"), ('\u{dd6}', '\u{dd6}', "sinhala"),
+    ('\u{dd8}', '\u{ddf}', "sinhala"), ('\u{de6}', '\u{def}', "sinhala"), ('\u{df2}', '\u{df4}', "sinhala"),
+    ('\u{e01}', '\u{e3a}', "thai"), ('\u{e40}', '\u{e5b}', "thai"), ('\u{e81}', '\u{e82}', "lao"), ('\u{e84}', '\u{e84}', "lao"),
+    ('\u{e86}', '\u{e8a}', "lao"), ('\u{e8c}', '\u{ea3}', "lao"), ('\u{ea5}', '\u{ea5}', "lao"), ('\u{ea7}', '\u{ebd}', "lao"),
+    ('\u{ec0}', '\u{ec4}', "lao"), ('\u{ec6}', '\u{ec6}', "lao"), ('\u{ec8}', '\u{ecd}', "lao"), ('\u{ed0}', '\u{ed9}', "lao"),
+    ('\u{edc}', '\u{edf}', "lao"), ('\u{f00}', '\u{f47}', "tibetan"), ('\u{f49}', '\u{f6c}', "tibetan"),
+    ('\u{f71}', '\u{f97}', "tibetan"), ('\u{f99}', '\u{fbc}', "tibetan"), ('\u{fbe}', '\u{fcc}', "tibetan"),
+    ('\u{fce}', '\u{fd4}', "tibetan"), ('\u{fd9}', '\u{fda}', "tibetan"), ('\u{1000}', '\u{109f}', "myanmar"),
+    ('\u{10a0}', '\u{10c5}', "georgian"), ('\u{10c7}', '\u{10c7}', "georgian"), ('\u{10cd}', '\u{10cd}', "georgian"),
+    ('\u{10d0}', '\u{10fa}', "georgian"), ('\u{10fc}', '\u{10ff}', "georgian"), ('\u{1100}', '\u{11ff}', "hangul"),
+    ('\u{1200}', '\u{1248}', "ethiopic"), ('\u{124a}', '\u{124d}', "ethiopic"), ('\u{1250}', '\u{1256}', "ethiopic"),
+    ('\u{1258}', '\u{1258}', "ethiopic"), ('\u{125a}', '\u{125d}', "ethiopic"), ('\u{1260}', '\u{1288}', "ethiopic"),
+    ('\u{128a}', '\u{128d}', "ethiopic"), ('\u{1290}', '\u{12b0}', "ethiopic"), ('\u{12b2}', '\u{12b5}', "ethiopic"),
+    ('\u{12b8}', '\u{12be}', "ethiopic"), ('\u{12c0}', '\u{12c0}', "ethiopic"), ('\u{12c2}', '\u{12c5}', "ethiopic"),
+    ('\u{12c8}', '\u{12d6}', "ethiopic"), ('\u{12d8}', '\u{1310}', "ethiopic"), ('\u{1312}', '\u{1315}', "ethiopic"),
+    ('\u{1318}', '\u{135a}', "ethiopic"), ('\u{135d}', '\u{137c}', "ethiopic"), ('\u{1380}', '\u{1399}', "ethiopic"),
+    ('\u{13a0}', '\u{13f5}', "cherokee"), ('\u{13f8}', '\u{13fd}', "cherokee"), ('\u{1400}', '\u{167f}', "canadian_aboriginal"),
+    ('\u{1680}', '\u{169c}', "ogham"), ('\u{16a0}', '\u{16ea}', "runic"), ('\u{16ee}', '\u{16f8}', "runic"),
+    ('\u{1700}', '\u{1715}', "tagalog"), ('\u{171f}', '\u{171f}', "tagalog"), ('\u{1720}', '\u{1734}', "hanunoo"),
+    ('\u{1740}', '\u{1753}', "buhid"), ('\u{1760}', '\u{176c}', "tagbanwa"), ('\u{176e}', '\u{1770}', "tagbanwa"),
+    ('\u{1772}', '\u{1773}', "tagbanwa"), ('\u{1780}', '\u{17dd}', "khmer"), ('\u{17e0}', '\u{17e9}', "khmer"),
+    ('\u{17f0}', '\u{17f9}', "khmer"), ('\u{1800}', '\u{1801}', "mongolian"), ('\u{1804}', '\u{1804}', "mongolian"),
+    ('\u{1806}', '\u{1819}', "mongolian"), ('\u{1820}', '\u{1878}', "mongolian"), ('\u{1880}', '\u{18aa}', "mongolian"),
+    ('\u{18b0}', '\u{18f5}', "canadian_aboriginal"), ('\u{1900}', '\u{191e}', "limbu"), ('\u{1920}', '\u{192b}', "limbu"),
+    ('\u{1930}', '\u{193b}', "limbu"), ('\u{1940}', '\u{1940}', "limbu"), ('\u{1944}', '\u{194f}', "limbu"),
+    ('\u{1950}', '\u{196d}', "tai_le"), ('\u{1970}', '\u{1974}', "tai_le"), ('\u{1980}', '\u{19ab}', "new_tai_lue"),
+    ('\u{19b0}', '\u{19c9}', "new_tai_lue"), ('\u{19d0}', '\u{19da}', "new_tai_lue"), ('\u{19de}', '\u{19df}', "new_tai_lue"),
+    ('\u{19e0}', '\u{19ff}', "khmer"), ('\u{1a00}', '\u{1a1b}', "buginese"), ('\u{1a1e}', '\u{1a1f}', "buginese"),
+    ('\u{1a20}', '\u{1a5e}', "tai_tham"), ('\u{1a60}', '\u{1a7c}', "tai_tham"), ('\u{1a7f}', '\u{1a89}', "tai_tham"),
+    ('\u{1a90}', '\u{1a99}', "tai_tham"), ('\u{1aa0}', '\u{1aad}', "tai_tham"), ('\u{1b00}', '\u{1b4c}', "balinese"),
+    ('\u{1b50}', '\u{1b7e}', "balinese"), ('\u{1b80}', '\u{1bbf}', "sundanese"), ('\u{1bc0}', '\u{1bf3}', "batak"),
+    ('\u{1bfc}', '\u{1bff}', "batak"), ('\u{1c00}', '\u{1c37}', "lepcha"), ('\u{1c3b}', '\u{1c49}', "lepcha"),
+    ('\u{1c4d}', '\u{1c4f}', "lepcha"), ('\u{1c50}', '\u{1c7f}', "ol_chiki"), ('\u{1c80}', '\u{1c88}', "cyrillic"),
+    ('\u{1c90}', '\u{1cba}', "georgian"), ('\u{1cbd}', '\u{1cbf}', "georgian"), ('\u{1cc0}', '\u{1cc7}', "sundanese"),
+    ('\u{1d00}', '\u{1d25}', "latin"), ('\u{1d26}', '\u{1d2a}', "greek"), ('\u{1d2b}', '\u{1d2b}', "cyrillic"),
+    ('\u{1d2c}', '\u{1d5c}', "latin"), ('\u{1d5d}', '\u{1d61}', "greek"), ('\u{1d62}', '\u{1d65}', "latin"),
+    ('\u{1d66}', '\u{1d6a}', "greek"), ('\u{1d6b}', '\u{1d77}', "latin"), ('\u{1d78}', '\u{1d78}', "cyrillic"),
+    ('\u{1d79}', '\u{1dbe}', "latin"), ('\u{1dbf}', '\u{1dbf}', "greek"), ('\u{1e00}', '\u{1eff}', "latin"),
+    ('\u{1f00}', '\u{1f15}', "greek"), ('\u{1f18}', '\u{1f1d}', "greek"), ('\u{1f20}', '\u{1f45}', "greek"),
+    ('\u{1f48}', '\u{1f4d}', "greek"), ('\u{1f50}', '\u{1f57}', "greek"), ('\u{1f59}', '\u{1f59}', "greek"),
+    ('\u{1f5b}', '\u{1f5b}', "greek"), ('\u{1f5d}', '\u{1f5d}', "greek"), ('\u{1f5f}', '\u{1f7d}', "greek"),
+    ('\u{1f80}', '\u{1fb4}', "greek"), ('\u{1fb6}', '\u{1fc4}', "greek"), ('\u{1fc6}', '\u{1fd3}', "greek"),
+    ('\u{1fd6}', '\u{1fdb}', "greek"), ('\u{1fdd}', '\u{1fef}', "greek"), ('\u{1ff2}', '\u{1ff4}', "greek"),
+    ('\u{1ff6}', '\u{1ffe}', "greek"), ('\u{2071}', '\u{2071}', "latin"), ('\u{207f}', '\u{207f}', "latin"),
+    ('\u{2090}', '\u{209c}', "latin"), ('\u{2126}', '\u{2126}', "greek"), ('\u{212a}', '\u{212b}', "latin"),
+    ('\u{2132}', '\u{2132}', "latin"), ('\u{214e}', '\u{214e}', "latin"), ('\u{2160}', '\u{2188}', "latin"),
+    ('\u{2800}', '\u{28ff}', "braille"), ('\u{2c00}', '\u{2c5f}', "glagolitic"), ('\u{2c60}', '\u{2c7f}', "latin"),
+    ('\u{2c80}', '\u{2cf3}', "coptic"), ('\u{2cf9}', '\u{2cff}', "coptic"), ('\u{2d00}', '\u{2d25}', "georgian"),
+    ('\u{2d27}', '\u{2d27}', "georgian"), ('\u{2d2d}', '\u{2d2d}', "georgian"), ('\u{2d30}', '\u{2d67}', "tifinagh"),
+    ('\u{2d6f}', '\u{2d70}', "tifinagh"), ('\u{2d7f}', '\u{2d7f}', "tifinagh"), ('\u{2d80}', '\u{2d96}', "ethiopic"),
+    ('\u{2da0}', '\u{2da6}', "ethiopic"), ('\u{2da8}', '\u{2dae}', "ethiopic"), ('\u{2db0}', '\u{2db6}', "ethiopic"),
+    ('\u{2db8}', '\u{2dbe}', "ethiopic"), ('\u{2dc0}', '\u{2dc6}', "ethiopic"), ('\u{2dc8}', '\u{2dce}', "ethiopic"),
+    ('\u{2dd0}', '\u{2dd6}', "ethiopic"), ('\u{2dd8}', '\u{2dde}', "ethiopic"), ('\u{2de0}', '\u{2dff}', "cyrillic"),
+    ('\u{2e80}', '\u{2e99}', "han"), ('\u{2e9b}', '\u{2ef3}', "han"), ('\u{2f00}', '\u{2fd5}', "han"),
+    ('\u{3005}', '\u{3005}', "han"), ('\u{3007}', '\u{3007}', "han"), ('\u{3021}', '\u{3029}', "han"),
+    ('\u{302e}', '\u{302f}', "hangul"), ('\u{3038}', '\u{303b}', "han"), ('\u{3041}', '\u{3096}', "hiragana"),
+    ('\u{309d}', '\u{309f}', "hiragana"), ('\u{30a1}', '\u{30fa}', "katakana"), ('\u{30fd}', '\u{30ff}', "katakana"),
+    ('\u{3105}', '\u{312f}', "bopomofo"), ('\u{3131}', '\u{318e}', "hangul"), ('\u{31a0}', '\u{31bf}', "bopomofo"),
+    ('\u{31f0}', '\u{31ff}', "katakana"), ('\u{3200}', '\u{321e}', "hangul"), ('\u{3260}', '\u{327e}', "hangul"),
+    ('\u{32d0}', '\u{32fe}', "katakana"), ('\u{3300}', '\u{3357}', "katakana"), ('\u{3400}', '\u{4dbf}', "han"),
+    ('\u{4e00}', '\u{9fff}', "han"), ('\u{a000}', '\u{a48c}', "yi"), ('\u{a490}', '\u{a4c6}', "yi"),
+    ('\u{a4d0}', '\u{a4ff}', "lisu"), ('\u{a500}', '\u{a62b}', "vai"), ('\u{a640}', '\u{a69f}', "cyrillic"),
+    ('\u{a6a0}', '\u{a6f7}', "bamum"), ('\u{a722}', '\u{a787}', "latin"), ('\u{a78b}', '\u{a7ca}', "latin"),
+    ('\u{a7d0}', '\u{a7d1}', "latin"), ('\u{a7d3}', '\u{a7d3}', "latin"), ('\u{a7d5}', '\u{a7d9}', "latin"),
+    ('\u{a7f2}', '\u{a7ff}', "latin"), ('\u{a800}', '\u{a82c}', "syloti_nagri"), ('\u{a840}', '\u{a877}', "phags_pa"),
+    ('\u{a880}', '\u{a8c5}', "saurashtra"), ('\u{a8ce}', '\u{a8d9}', "saurashtra"), ('\u{a8e0}', '\u{a8ff}', "devanagari"),
+    ('\u{a900}', '\u{a92d}', "kayah_li"), ('\u{a92f}', '\u{a92f}', "kayah_li"), ('\u{a930}', '\u{a953}', "rejang"),
+    ('\u{a95f}', '\u{a95f}', "rejang"), ('\u{a960}', '\u{a97c}', "hangul"), ('\u{a980}', '\u{a9cd}', "javanese"),
+    ('\u{a9d0}', '\u{a9d9}', "javanese"), ('\u{a9de}', '\u{a9df}', "javanese"), ('\u{a9e0}', '\u{a9fe}', "myanmar"),
+    ('\u{aa00}', '\u{aa36}', "cham"), ('\u{aa40}', '\u{aa4d}', "cham"), ('\u{aa50}', '\u{aa59}', "cham"),
+    ('\u{aa5c}', '\u{aa5f}', "cham"), ('\u{aa60}', '\u{aa7f}', "myanmar"), ('\u{aa80}', '\u{aac2}', "tai_viet"),
+    ('\u{aadb}', '\u{aadf}', "tai_viet"), ('\u{aae0}', '\u{aaf6}', "meetei_mayek"), ('\u{ab01}', '\u{ab06}', "ethiopic"),
+    ('\u{ab09}', '\u{ab0e}', "ethiopic"), ('\u{ab11}', '\u{ab16}', "ethiopic"), ('\u{ab20}', '\u{ab26}', "ethiopic"),
+    ('\u{ab28}', '\u{ab2e}', "ethiopic"), ('\u{ab30}', '\u{ab5a}', "latin"), ('\u{ab5c}', '\u{ab64}', "latin"),
+    ('\u{ab65}', '\u{ab65}', "greek"), ('\u{ab66}', '\u{ab69}', "latin"), ('\u{ab70}', '\u{abbf}', "cherokee"),
+    ('\u{abc0}', '\u{abed}', "meetei_mayek"), ('\u{abf0}', '\u{abf9}', "meetei_mayek"), ('\u{ac00}', '\u{d7a3}', "hangul"),
+    ('\u{d7b0}', '\u{d7c6}', "hangul"), ('\u{d7cb}', '\u{d7fb}', "hangul"), ('\u{f900}', '\u{fa6d}', "han"),
+    ('\u{fa70}', '\u{fad9}', "han"), ('\u{fb00}', '\u{fb06}', "latin"), ('\u{fb13}', '\u{fb17}', "armenian"),
+    ('\u{fb1d}', '\u{fb36}', "hebrew"), ('\u{fb38}', '\u{fb3c}', "hebrew"), ('\u{fb3e}', '\u{fb3e}', "hebrew"),
+    ('\u{fb40}', '\u{fb41}', "hebrew"), ('\u{fb43}', '\u{fb44}', "hebrew"), ('\u{fb46}', '\u{fb4f}', "hebrew"),
+    ('\u{fb50}', '\u{fbc2}', "arabic"), ('\u{fbd3}', '\u{fd3d}', "arabic"), ('\u{fd40}', '\u{fd8f}', "arabic"),
+    ('\u{fd92}', '\u{fdc7}', "arabic"), ('\u{fdcf}', '\u{fdcf}', "arabic"), ('\u{fdf0}', '\u{fdff}', "arabic"),
+    ('\u{fe2e}', '\u{fe2f}', "cyrillic"), ('\u{fe70}', '\u{fe74}', "arabic"), ('\u{fe76}', '\u{fefc}', "arabic"),
+    ('\u{ff21}', '\u{ff3a}', "latin"), ('\u{ff41}', '\u{ff5a}', "latin"), ('\u{ff66}', '\u{ff6f}', "katakana"),
+    ('\u{ff71}', '\u{ff9d}', "katakana"), ('\u{ffa0}', '\u{ffbe}', "hangul"), ('\u{ffc2}', '\u{ffc7}', "hangul"),
+    ('\u{ffca}', '\u{ffcf}', "hangul"), ('\u{ffd2}', '\u{ffd7}', "hangul"), ('\u{ffda}', '\u{ffdc}', "hangul"),
+    ('\u{10000}', '\u{1000b}', "linear_b"), ('\u{1000d}', '\u{10026}', "linear_b"), ('\u{10028}', '\u{1003a}', "linear_b"),
+    ('\u{1003c}', '\u{1003d}', "linear_b"), ('\u{1003f}', '\u{1004d}', "linear_b"), ('\u{10050}', '\u{1005d}', "linear_b"),
+    ('\u{10080}', '\u{100fa}', "linear_b"), ('\u{10140}', '\u{1018e}', "greek"), ('\u{101a0}', '\u{101a0}', "greek"),
+    ('\u{10280}', '\u{1029c}', "lycian"), ('\u{102a0}', '\u{102d0}', "carian"), ('\u{10300}', '\u{10323}', "old_italic"),
+    ('\u{1032d}', '\u{1032f}', "old_italic"), ('\u{10330}', '\u{1034a}', "gothic"), ('\u{10350}', '\u{1037a}', "old_permic"),
+    ('\u{10380}', '\u{1039d}', "ugaritic"), ('\u{1039f}', '\u{1039f}', "ugaritic"), ('\u{103a0}', '\u{103c3}', "old_persian"),
+    ('\u{103c8}', '\u{103d5}', "old_persian"), ('\u{10400}', '\u{1044f}', "deseret"), ('\u{10450}', '\u{1047f}', "shavian"),
+    ('\u{10480}', '\u{1049d}', "osmanya"), ('\u{104a0}', '\u{104a9}', "osmanya"), ('\u{104b0}', '\u{104d3}', "osage"),
+    ('\u{104d8}', '\u{104fb}', "osage"), ('\u{10500}', '\u{10527}', "elbasan"), ('\u{10530}', '\u{10563}', "caucasian_albanian"),
+    ('\u{1056f}', '\u{1056f}', "caucasian_albanian"), ('\u{10570}', '\u{1057a}', "vithkuqi"),
+    ('\u{1057c}', '\u{1058a}', "vithkuqi"), ('\u{1058c}', '\u{10592}', "vithkuqi"), ('\u{10594}', '\u{10595}', "vithkuqi"),
+    ('\u{10597}', '\u{105a1}', "vithkuqi"), ('\u{105a3}', '\u{105b1}', "vithkuqi"), ('\u{105b3}', '\u{105b9}', "vithkuqi"),
+    ('\u{105bb}', '\u{105bc}', "vithkuqi"), ('\u{10600}', '\u{10736}', "linear_a"), ('\u{10740}', '\u{10755}', "linear_a"),
+    ('\u{10760}', '\u{10767}', "linear_a"), ('\u{10780}', '\u{10785}', "latin"), ('\u{10787}', '\u{107b0}', "latin"),
+    ('\u{107b2}', '\u{107ba}', "latin"), ('\u{10800}', '\u{10805}', "cypriot"), ('\u{10808}', '\u{10808}', "cypriot"),
+    ('\u{1080a}', '\u{10835}', "cypriot"), ('\u{10837}', '\u{10838}', "cypriot"), ('\u{1083c}', '\u{1083c}', "cypriot"),
+    ('\u{1083f}', '\u{1083f}', "cypriot"), ('\u{10840}', '\u{10855}', "imperial_aramaic"),
+    ('\u{10857}', '\u{1085f}', "imperial_aramaic"), ('\u{10860}', '\u{1087f}', "palmyrene"),
+    ('\u{10880}', '\u{1089e}', "nabataean"), ('\u{108a7}', '\u{108af}', "nabataean"), ('\u{108e0}', '\u{108f2}', "hatran"),
+    ('\u{108f4}', '\u{108f5}', "hatran"), ('\u{108fb}', '\u{108ff}', "hatran"), ('\u{10900}', '\u{1091b}', "phoenician"),
+    ('\u{1091f}', '\u{1091f}', "phoenician"), ('\u{10920}', '\u{10939}', "lydian"), ('\u{1093f}', '\u{1093f}', "lydian"),
+    ('\u{10980}', '\u{1099f}', "meroitic_hieroglyphs"), ('\u{109a0}', '\u{109b7}', "meroitic_cursive"),
+    ('\u{109bc}', '\u{109cf}', "meroitic_cursive"), ('\u{109d2}', '\u{109ff}', "meroitic_cursive"),
+    ('\u{10a00}', '\u{10a03}', "kharoshthi"), ('\u{10a05}', '\u{10a06}', "kharoshthi"), ('\u{10a0c}', '\u{10a13}', "kharoshthi"),
+    ('\u{10a15}', '\u{10a17}', "kharoshthi"), ('\u{10a19}', '\u{10a35}', "kharoshthi"), ('\u{10a38}', '\u{10a3a}', "kharoshthi"),
+    ('\u{10a3f}', '\u{10a48}', "kharoshthi"), ('\u{10a50}', '\u{10a58}', "kharoshthi"),
+    ('\u{10a60}', '\u{10a7f}', "old_south_arabian"), ('\u{10a80}', '\u{10a9f}', "old_north_arabian"),
+    ('\u{10ac0}', '\u{10ae6}', "manichaean"), ('\u{10aeb}', '\u{10af6}', "manichaean"), ('\u{10b00}', '\u{10b35}', "avestan"),
+    ('\u{10b39}', '\u{10b3f}', "avestan"), ('\u{10b40}', '\u{10b55}', "inscriptional_parthian"),
+    ('\u{10b58}', '\u{10b5f}', "inscriptional_parthian"), ('\u{10b60}', '\u{10b72}', "inscriptional_pahlavi"),
+    ('\u{10b78}', '\u{10b7f}', "inscriptional_pahlavi"), ('\u{10b80}', '\u{10b91}', "psalter_pahlavi"),
+    ('\u{10b99}', '\u{10b9c}', "psalter_pahlavi"), ('\u{10ba9}', '\u{10baf}', "psalter_pahlavi"),
+    ('\u{10c00}', '\u{10c48}', "old_turkic"), ('\u{10c80}', '\u{10cb2}', "old_hungarian"),
+    ('\u{10cc0}', '\u{10cf2}', "old_hungarian"), ('\u{10cfa}', '\u{10cff}', "old_hungarian"),
+    ('\u{10d00}', '\u{10d27}', "hanifi_rohingya"), ('\u{10d30}', '\u{10d39}', "hanifi_rohingya"),
+    ('\u{10e60}', '\u{10e7e}', "arabic"), ('\u{10e80}', '\u{10ea9}', "yezidi"), ('\u{10eab}', '\u{10ead}', "yezidi"),
+    ('\u{10eb0}', '\u{10eb1}', "yezidi"), ('\u{10f00}', '\u{10f27}', "old_sogdian"), ('\u{10f30}', '\u{10f59}', "sogdian"),
+    ('\u{10f70}', '\u{10f89}', "old_uyghur"), ('\u{10fb0}', '\u{10fcb}', "chorasmian"), ('\u{10fe0}', '\u{10ff6}', "elymaic"),
+    ('\u{11000}', '\u{1104d}', "brahmi"), ('\u{11052}', '\u{11075}', "brahmi"), ('\u{1107f}', '\u{1107f}', "brahmi"),
+    ('\u{11080}', '\u{110c2}', "kaithi"), ('\u{110cd}', '\u{110cd}', "kaithi"), ('\u{110d0}', '\u{110e8}', "sora_sompeng"),
+    ('\u{110f0}', '\u{110f9}', "sora_sompeng"), ('\u{11100}', '\u{11134}', "chakma"), ('\u{11136}', '\u{11147}', "chakma"),
+    ('\u{11150}', '\u{11176}', "mahajani"), ('\u{11180}', '\u{111df}', "sharada"), ('\u{111e1}', '\u{111f4}', "sinhala"),
+    ('\u{11200}', '\u{11211}', "khojki"), ('\u{11213}', '\u{1123e}', "khojki"), ('\u{11280}', '\u{11286}', "multani"),
+    ('\u{11288}', '\u{11288}', "multani"), ('\u{1128a}', '\u{1128d}', "multani"), ('\u{1128f}', '\u{1129d}', "multani"),
+    ('\u{1129f}', '\u{112a9}', "multani"), ('\u{112b0}', '\u{112ea}', "khudawadi"), ('\u{112f0}', '\u{112f9}', "khudawadi"),
+    ('\u{11300}', '\u{11303}', "grantha"), ('\u{11305}', '\u{1130c}', "grantha"), ('\u{1130f}', '\u{11310}', "grantha"),
+    ('\u{11313}', '\u{11328}', "grantha"), ('\u{1132a}', '\u{11330}', "grantha"), ('\u{11332}', '\u{11333}', "grantha"),
+    ('\u{11335}', '\u{11339}', "grantha"), ('\u{1133c}', '\u{11344}', "grantha"), ('\u{11347}', '\u{11348}', "grantha"),
+    ('\u{1134b}', '\u{1134d}', "grantha"), ('\u{11350}', '\u{11350}', "grantha"), ('\u{11357}', '\u{11357}', "grantha"),
+    ('\u{1135d}', '\u{11363}', "grantha"), ('\u{11366}', '\u{1136c}', "grantha"), ('\u{11370}', '\u{11374}', "grantha"),
+    ('\u{11400}', '\u{1145b}', "newa"), ('\u{1145d}', '\u{11461}', "newa"), ('\u{11480}', '\u{114c7}', "tirhuta"),
+    ('\u{114d0}', '\u{114d9}', "tirhuta"), ('\u{11580}', '\u{115b5}', "siddham"), ('\u{115b8}', '\u{115dd}', "siddham"),
+    ('\u{11600}', '\u{11644}', "modi"), ('\u{11650}', '\u{11659}', "modi"), ('\u{11660}', '\u{1166c}', "mongolian"),
+    ('\u{11680}', '\u{116b9}', "takri"), ('\u{116c0}', '\u{116c9}', "takri"), ('\u{11700}', '\u{1171a}', "ahom"),
+    ('\u{1171d}', '\u{1172b}', "ahom"), ('\u{11730}', '\u{11746}', "ahom"), ('\u{11800}', '\u{1183b}', "dogra"),
+    ('\u{118a0}', '\u{118f2}', "warang_citi"), ('\u{118ff}', '\u{118ff}', "warang_citi"),
+    ('\u{11900}', '\u{11906}', "dives_akuru"), ('\u{11909}', '\u{11909}', "dives_akuru"),
+    ('\u{1190c}', '\u{11913}', "dives_akuru"), ('\u{11915}', '\u{11916}', "dives_akuru"),
+    ('\u{11918}', '\u{11935}', "dives_akuru"), ('\u{11937}', '\u{11938}', "dives_akuru"),
+    ('\u{1193b}', '\u{11946}', "dives_akuru"), ('\u{11950}', '\u{11959}', "dives_akuru"),
+    ('\u{119a0}', '\u{119a7}', "nandinagari"), ('\u{119aa}', '\u{119d7}', "nandinagari"),
+    ('\u{119da}', '\u{119e4}', "nandinagari"), ('\u{11a00}', '\u{11a47}', "zanabazar_square"),
+    ('\u{11a50}', '\u{11aa2}', "soyombo"), ('\u{11ab0}', '\u{11abf}', "canadian_aboriginal"),
+    ('\u{11ac0}', '\u{11af8}', "pau_cin_hau"), ('\u{11c00}', '\u{11c08}', "bhaiksuki"), ('\u{11c0a}', '\u{11c36}', "bhaiksuki"),
+    ('\u{11c38}', '\u{11c45}', "bhaiksuki"), ('\u{11c50}', '\u{11c6c}', "bhaiksuki"), ('\u{11c70}', '\u{11c8f}', "marchen"),
+    ('\u{11c92}', '\u{11ca7}', "marchen"), ('\u{11ca9}', '\u{11cb6}', "marchen"), ('\u{11d00}', '\u{11d06}', "masaram_gondi"),
+    ('\u{11d08}', '\u{11d09}', "masaram_gondi"), ('\u{11d0b}', '\u{11d36}', "masaram_gondi"),
+    ('\u{11d3a}', '\u{11d3a}', "masaram_gondi"), ('\u{11d3c}', '\u{11d3d}', "masaram_gondi"),
+    ('\u{11d3f}', '\u{11d47}', "masaram_gondi"), ('\u{11d50}', '\u{11d59}', "masaram_gondi"),
+    ('\u{11d60}', '\u{11d65}', "gunjala_gondi"), ('\u{11d67}', '\u{11d68}', "gunjala_gondi"),
+    ('\u{11d6a}', '\u{11d8e}', "gunjala_gondi"), ('\u{11d90}', '\u{11d91}', "gunjala_gondi"),
+    ('\u{11d93}', '\u{11d98}', "gunjala_gondi"), ('\u{11da0}', '\u{11da9}', "gunjala_gondi"),
+    ('\u{11ee0}', '\u{11ef8}', "makasar"), ('\u{11fb0}', '\u{11fb0}', "lisu"), ('\u{11fc0}', '\u{11ff1}', "tamil"),
+    ('\u{11fff}', '\u{11fff}', "tamil"), ('\u{12000}', '\u{12399}', "cuneiform"), ('\u{12400}', '\u{1246e}', "cuneiform"),
+    ('\u{12470}', '\u{12474}', "cuneiform"), ('\u{12480}', '\u{12543}', "cuneiform"), ('\u{12f90}', '\u{12ff2}', "cypro_minoan"),
+    ('\u{13000}', '\u{1342e}', "egyptian_hieroglyphs"), ('\u{13430}', '\u{13438}', "egyptian_hieroglyphs"),
+    ('\u{14400}', '\u{14646}', "anatolian_hieroglyphs"), ('\u{16800}', '\u{16a38}', "bamum"), ('\u{16a40}', '\u{16a5e}', "mro"),
+    ('\u{16a60}', '\u{16a69}', "mro"), ('\u{16a6e}', '\u{16a6f}', "mro"), ('\u{16a70}', '\u{16abe}', "tangsa"),
+    ('\u{16ac0}', '\u{16ac9}', "tangsa"), ('\u{16ad0}', '\u{16aed}', "bassa_vah"), ('\u{16af0}', '\u{16af5}', "bassa_vah"),
+    ('\u{16b00}', '\u{16b45}', "pahawh_hmong"), ('\u{16b50}', '\u{16b59}', "pahawh_hmong"),
+    ('\u{16b5b}', '\u{16b61}', "pahawh_hmong"), ('\u{16b63}', '\u{16b77}', "pahawh_hmong"),
+    ('\u{16b7d}', '\u{16b8f}', "pahawh_hmong"), ('\u{16e40}', '\u{16e9a}', "medefaidrin"), ('\u{16f00}', '\u{16f4a}', "miao"),
+    ('\u{16f4f}', '\u{16f87}', "miao"), ('\u{16f8f}', '\u{16f9f}', "miao"), ('\u{16fe0}', '\u{16fe0}', "tangut"),
+    ('\u{16fe1}', '\u{16fe1}', "nushu"), ('\u{16fe2}', '\u{16fe3}', "han"), ('\u{16fe4}', '\u{16fe4}', "khitan_small_script"),
+    ('\u{16ff0}', '\u{16ff1}', "han"), ('\u{17000}', '\u{187f7}', "tangut"), ('\u{18800}', '\u{18aff}', "tangut"),
+    ('\u{18b00}', '\u{18cd5}', "khitan_small_script"), ('\u{18d00}', '\u{18d08}', "tangut"),
+    ('\u{1aff0}', '\u{1aff3}', "katakana"), ('\u{1aff5}', '\u{1affb}', "katakana"), ('\u{1affd}', '\u{1affe}', "katakana"),
+    ('\u{1b000}', '\u{1b000}', "katakana"), ('\u{1b001}', '\u{1b11f}', "hiragana"), ('\u{1b120}', '\u{1b122}', "katakana"),
+    ('\u{1b150}', '\u{1b152}', "hiragana"), ('\u{1b164}', '\u{1b167}', "katakana"), ('\u{1b170}', '\u{1b2fb}', "nushu"),
+    ('\u{1bc00}', '\u{1bc6a}', "duployan"), ('\u{1bc70}', '\u{1bc7c}', "duployan"), ('\u{1bc80}', '\u{1bc88}', "duployan"),
+    ('\u{1bc90}', '\u{1bc99}', "duployan"), ('\u{1bc9c}', '\u{1bc9f}', "duployan"), ('\u{1d200}', '\u{1d245}', "greek"),
+    ('\u{1d800}', '\u{1da8b}', "signwriting"), ('\u{1da9b}', '\u{1da9f}', "signwriting"),
+    ('\u{1daa1}', '\u{1daaf}', "signwriting"), ('\u{1df00}', '\u{1df1e}', "latin"), ('\u{1e000}', '\u{1e006}', "glagolitic"),
+    ('\u{1e008}', '\u{1e018}', "glagolitic"), ('\u{1e01b}', '\u{1e021}', "glagolitic"), ('\u{1e023}', '\u{1e024}', "glagolitic"),
+    ('\u{1e026}', '\u{1e02a}', "glagolitic"), ('\u{1e100}', '\u{1e12c}', "nyiakeng_puachue_hmong"),
+    ('\u{1e130}', '\u{1e13d}', "nyiakeng_puachue_hmong"), ('\u{1e140}', '\u{1e149}', "nyiakeng_puachue_hmong"),
+    ('\u{1e14e}', '\u{1e14f}', "nyiakeng_puachue_hmong"), ('\u{1e290}', '\u{1e2ae}', "toto"), ('\u{1e2c0}', '\u{1e2f9}', "wancho"),
+    ('\u{1e2ff}', '\u{1e2ff}', "wancho"), ('\u{1e7e0}', '\u{1e7e6}', "ethiopic"), ('\u{1e7e8}', '\u{1e7eb}', "ethiopic"),
+    ('\u{1e7ed}', '\u{1e7ee}', "ethiopic"), ('\u{1e7f0}', '\u{1e7fe}', "ethiopic"), ('\u{1e800}', '\u{1e8c4}', "mende_kikakui"),
+    ('\u{1e8c7}', '\u{1e8d6}', "mende_kikakui"), ('\u{1e900}', '\u{1e94b}', "adlam"), ('\u{1e950}', '\u{1e959}', "adlam"),
+    ('\u{1e95e}', '\u{1e95f}', "adlam"), ('\u{1ee00}', '\u{1ee03}', "arabic"), ('\u{1ee05}', '\u{1ee1f}', "arabic"),
+    ('\u{1ee21}', '\u{1ee22}', "arabic"), ('\u{1ee24}', '\u{1ee24}', "arabic"), ('\u{1ee27}', '\u{1ee27}', "arabic"),
+    ('\u{1ee29}', '\u{1ee32}', "arabic"), ('\u{1ee34}', '\u{1ee37}', "arabic"), ('\u{1ee39}', '\u{1ee39}', "arabic"),
+    ('\u{1ee3b}', '\u{1ee3b}', "arabic"), ('\u{1ee42}', '\u{1ee42}', "arabic"), ('\u{1ee47}', '\u{1ee47}', "arabic"),
+    ('\u{1ee49}', '\u{1ee49}', "arabic"), ('\u{1ee4b}', '\u{1ee4b}', "arabic"), ('\u{1ee4d}', '\u{1ee4f}', "arabic"),
+    ('\u{1ee51}', '\u{1ee52}', "arabic"), ('\u{1ee54}', '\u{1ee54}', "arabic"), ('\u{1ee57}', '\u{1ee57}', "arabic"),
+    ('\u{1ee59}', '\u{1ee59}', "arabic"), ('\u{1ee5b}', '\u{1ee5b}', "arabic"), ('\u{1ee5d}', '\u{1ee5d}', "arabic"),
+    ('\u{1ee5f}', '\u{1ee5f}', "arabic"), ('\u{1ee61}', '\u{1ee62}', "arabic"), ('\u{1ee64}', '\u{1ee64}', "arabic"),
+    ('\u{1ee67}', '\u{1ee6a}', "arabic"), ('\u{1ee6c}', '\u{1ee72}', "arabic"), ('\u{1ee74}', '\u{1ee77}', "arabic"),
+    ('\u{1ee79}', '\u{1ee7c}', "arabic"), ('\u{1ee7e}', '\u{1ee7e}', "arabic"), ('\u{1ee80}', '\u{1ee89}', "arabic"),
+    ('\u{1ee8b}', '\u{1ee9b}', "arabic"), ('\u{1eea1}', '\u{1eea3}', "arabic"), ('\u{1eea5}', '\u{1eea9}', "arabic"),
+    ('\u{1eeab}', '\u{1eebb}', "arabic"), ('\u{1eef0}', '\u{1eef1}', "arabic"), ('\u{1f200}', '\u{1f200}', "hiragana"),
+    ('\u{20000}', '\u{2a6df}', "han"), ('\u{2a700}', '\u{2b738}', "han"), ('\u{2b740}', '\u{2b81d}', "han"),
+    ('\u{2b820}', '\u{2cea1}', "han"), ('\u{2ceb0}', '\u{2ebe0}', "han"), ('\u{2f800}', '\u{2fa1d}', "han"),
+    ('\u{30000}', '\u{3134a}', "han"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_and_inherited_characters_have_no_script() {
+        assert_eq!(script('a'), Some("latin"));
+        assert_eq!(script('é'), Some("latin"));
+        assert_eq!(script('ש'), Some("hebrew"));
+        assert_eq!(script('ب'), Some("arabic"));
+        assert_eq!(script('ж'), Some("cyrillic"));
+        assert_eq!(script('漢'), Some("han"));
+        // Arabic-Indic digits belong to Arabic, unlike ASCII ones.
+        assert_eq!(script('٣'), Some("arabic"));
+        for c in ['1', '!', ' ', '€', '\u{301}', '\u{200d}'] {
+            assert_eq!(script(c), None, "{:?}", c);
+        }
+        assert!(SCRIPTS.windows(2).all(|w| w[0].1 < w[1].0));
+    }
+
+    #[test]
+    fn scripts_written_together_count_as_one() {
+        assert_eq!(scripts("Shalom שלום 123"), ["latin", "hebrew"]);
+        assert!(is_single_script(&scripts("correct horse 123!")));
+        assert!(is_single_script(&scripts("كلمة٣٤٥")));
+        assert!(is_single_script(&scripts("كلمة2024")));
+        assert!(is_single_script(&scripts("パスワード漢字ひらがな")));
+        assert!(is_single_script(&scripts("비밀번호漢字")));
+        assert!(!is_single_script(&scripts("パスワード비밀")));
+        assert!(!is_single_script(&scripts("pаssword")));
+        assert_eq!(name("hebrew"), Some("hebrew"));
+        assert_eq!(name("klingon"), None);
+    }
+}
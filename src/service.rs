@@ -0,0 +1,85 @@
+//! Matching of service and application names in passwords.
+
+use crate::casefold;
+use crate::leet::unleet;
+use crate::similarity::edit_distance;
+
+/// Names at least this long also match with one typo.
+const FUZZY_MIN_CHARS: usize = 6;
+
+/// The forms of `s` that are compared: case-folded with everything but
+/// letters and digits removed, once as written and once with leetspeak
+/// undone first so that `@` and `$` survive as `a` and `s`.
+fn forms(s: &str) -> [String; 2] {
+    folded_forms(&casefold::fold(s))
+}
+
+/// [`forms`] of a string already case-folded.
+pub(crate) fn folded_forms(folded: &str) -> [String; 2] {
+    let keep = |c: &char| c.is_alphanumeric();
+    [folded.chars().filter(keep).collect(), folded.chars().map(unleet).filter(keep).collect()]
+}
+
+/// Passwords longer than this, in characters, are only searched for exact
+/// names: the one-edit search costs O(len(password) · len(name)²).
+const FUZZY_MAX_CHARS: usize = 256;
+
+/// Returns whether `password` contains one of `names`, or for names of six
+/// or more characters and passwords of at most 256 a variant one edit away.
+pub(crate) fn contains_service_name(password: &str, names: &[String]) -> bool {
+    contains_service_name_in(&forms(password), names)
+}
+
+/// Like [`contains_service_name`], for the [`forms`] of the password.
+pub(crate) fn contains_service_name_in([plain, unleeted]: &[String; 2], names: &[String]) -> bool {
+    names.iter().any(|name| {
+        let [name_plain, name_unleeted] = forms(name);
+        if name_plain.is_empty() {
+            return false;
+        }
+        if plain.contains(&name_plain) || unleeted.contains(&name_unleeted) {
+            return true;
+        }
+        let len = name_unleeted.chars().count();
+        len >= FUZZY_MIN_CHARS && unleeted.chars().count() <= FUZZY_MAX_CHARS && within_one_edit(unleeted, &name_unleeted, len)
+    })
+}
+
+/// Whether a substring of `haystack` of `len - 1` to `len + 1` characters
+/// is at most one edit from `name`.
+fn within_one_edit(haystack: &str, name: &str, len: usize) -> bool {
+    let chars: Vec<char> = haystack.chars().collect();
+    (len - 1..=len + 1).any(|window| {
+        chars.windows(window).any(|w| edit_distance(&w.iter().collect::<String>(), name) <= 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        ["acme", "acme portal", "Widgetify"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn matches_normalized_names() {
+        for password in ["MyAcme2024!", "ACME-PORTAL!1", "acmeportal99", "@cme2024", "W1dget1fy!", "Wdgetify2024", "Widgetfiy#1"] {
+            assert!(contains_service_name(password, &names()), "{}", password);
+        }
+        for password in ["correct horse battery", "academy2024", "widgets4all"] {
+            assert!(!contains_service_name(password, &names()), "{}", password);
+        }
+        // Short names must match exactly.
+        assert!(!contains_service_name("acne2024", &names()));
+        assert!(!contains_service_name("anything", &["  ".to_string()]));
+    }
+
+    #[test]
+    fn long_passwords_only_match_exact_names() {
+        let padding = "x".repeat(FUZZY_MAX_CHARS);
+        assert!(contains_service_name(&format!("{}Widgetify", padding), &names()));
+        assert!(!contains_service_name(&format!("{}Widgetfiy", padding), &names()));
+        assert!(contains_service_name(&format!("{}Widgetfiy", &padding[..100]), &names()));
+    }
+}
@@ -0,0 +1,241 @@
+//! Revalidation of a password as it is typed.
+
+use std::fmt;
+
+use crate::{PasswordChecker, Rule, Violations};
+
+/// A password being edited at its end, for live feedback: each
+/// [`check`](Self::check) gives the result of
+/// [`PasswordChecker::check`] on the current password.
+///
+/// Rules that only count characters, such as lengths and the composition
+/// rules, are decided from counts kept up to date by
+/// [`push_str`](Self::push_str) and [`pop`](Self::pop), so an edit of a
+/// long passphrase costs only the characters it touches. Rules that need
+/// the whole password, such as dictionaries and patterns, are evaluated
+/// again after each edit, or only every few characters with
+/// [`rescan_every`](Self::rescan_every).
+///
+/// ```
+/// use passcheck::{PasswordChecker, ValidationSession};
+///
+/// let checker = PasswordChecker::new().min_length(8, None).require_number(None);
+/// let mut session = ValidationSession::new(&checker);
+/// session.push_str("secret");
+/// assert_eq!(session.check().unwrap_err().codes().collect::<Vec<_>>(), ["min_length", "require_number"]);
+/// session.push_str("42");
+/// assert!(session.check().is_ok());
+/// session.pop(1);
+/// assert_eq!(session.check(), checker.check("secret4"));
+/// ```
+#[derive(Clone)]
+pub struct ValidationSession<'c, 'a> {
+    checker: &'c PasswordChecker<'a>,
+    password: String,
+    counts: Counts,
+    /// Characters matching each [`Rule::RequireSpecialChar`] rule, by rule
+    /// index; 0 for other rules.
+    special: Vec<usize>,
+    rescan_every: usize,
+    /// Characters pushed or popped since the last rescan.
+    pending: usize,
+    /// Whether each rule failed at the last rescan; only read for rules
+    /// needing the whole password.
+    scanned: Vec<bool>,
+}
+
+/// Characters of the password in the classes the counting rules test.
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    chars: usize,
+    uppercase: usize,
+    lowercase: usize,
+    digits: usize,
+    numeric: usize,
+    alphabetic: usize,
+}
+
+impl<'c, 'a> ValidationSession<'c, 'a> {
+    /// A session with an empty password.
+    pub fn new(checker: &'c PasswordChecker<'a>) -> Self {
+        let mut session = ValidationSession {
+            checker,
+            password: String::new(),
+            counts: Counts::default(),
+            special: vec![0; checker.rules.len()],
+            rescan_every: 1,
+            pending: 0,
+            scanned: Vec::new(),
+        };
+        session.rescan();
+        session
+    }
+
+    /// Evaluates the rules needing the whole password only once `chars`
+    /// characters were pushed or popped since they last were, and reuses
+    /// their results in between. Counting rules stay exact; the others lag
+    /// behind by up to `chars - 1` edited characters, so use the checker
+    /// for the final submission. The default of 1 matches the checker
+    /// exactly.
+    pub fn rescan_every(mut self, chars: usize) -> Self {
+        self.rescan_every = chars.max(1);
+        self
+    }
+
+    /// Appends `s` to the password.
+    pub fn push_str(&mut self, s: &str) {
+        for c in s.chars() {
+            self.count(c, true);
+        }
+        self.password.push_str(s);
+        self.edited(s.chars().count());
+    }
+
+    /// Removes the last `n` characters of the password, or all if it has
+    /// fewer.
+    pub fn pop(&mut self, n: usize) {
+        let mut popped = 0;
+        while popped < n && let Some(c) = self.password.pop() {
+            self.count(c, false);
+            popped += 1;
+        }
+        self.edited(popped);
+    }
+
+    /// The current password.
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    /// The result of [`PasswordChecker::check`] on the current password,
+    /// with violations, messages and observer notifications alike.
+    pub fn check(&self) -> Result<(), Violations> {
+        self.checker.check_using(&self.password, |i, rule| self.counted(i, rule).unwrap_or(self.scanned[i]))
+    }
+
+    /// Whether the `i`th rule fails, for rules decided by counts alone.
+    fn counted(&self, i: usize, rule: &Rule<'_>) -> Option<bool> {
+        let counts = &self.counts;
+        Some(match rule {
+            Rule::MinLength(len, _) => counts.chars < *len,
+            Rule::MaxLength(len, _) => counts.chars > *len,
+            Rule::MaxBytes(len, _) => self.password.len() > *len,
+            Rule::RequireUpperLower(_) => counts.uppercase == 0 || counts.lowercase == 0,
+            Rule::RequireNumber(_) => counts.digits == 0,
+            Rule::RequireSpecialChar(..) => self.special[i] == 0,
+            Rule::NotAllNumeric(_) => counts.chars > 0 && counts.numeric == counts.chars,
+            Rule::NotAllAlphabetic(_) => counts.chars > 0 && counts.alphabetic == counts.chars,
+            // Every other character has a byte that is not an ASCII digit.
+            Rule::DigitsOnly(_) => counts.digits != counts.chars,
+            _ => return None,
+        })
+    }
+
+    fn count(&mut self, c: char, added: bool) {
+        let update = |n: &mut usize, matches: bool| {
+            if matches {
+                if added { *n += 1 } else { *n -= 1 }
+            }
+        };
+        let counts = &mut self.counts;
+        update(&mut counts.chars, true);
+        update(&mut counts.uppercase, c.is_ascii_uppercase());
+        update(&mut counts.lowercase, c.is_ascii_lowercase());
+        update(&mut counts.digits, c.is_ascii_digit());
+        update(&mut counts.numeric, c.is_numeric());
+        update(&mut counts.alphabetic, c.is_alphabetic());
+        for (rule, n) in self.checker.rules.iter().zip(&mut self.special) {
+            if let Rule::RequireSpecialChar(policy, _) = rule {
+                update(n, policy.contains(c));
+            }
+        }
+    }
+
+    fn edited(&mut self, chars: usize) {
+        self.pending += chars;
+        if self.pending >= self.rescan_every {
+            self.rescan();
+        }
+    }
+
+    /// Evaluates the rules needing the whole password.
+    fn rescan(&mut self) {
+        let fails = self.checker.failure_test(&self.password, None);
+        let scanned = self.checker.rules.iter().enumerate().map(|(i, rule)| self.counted(i, rule).is_none() && fails(rule)).collect();
+        self.scanned = scanned;
+        self.pending = 0;
+    }
+}
+
+/// Leaves the password out.
+impl fmt::Debug for ValidationSession<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidationSession").field("chars", &self.counts.chars).field("rescan_every", &self.rescan_every).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrary::Seeded;
+    use crate::{Pattern, SpecialCharPolicy};
+
+    fn checkers() -> Vec<PasswordChecker<'static>> {
+        let policy = || {
+            PasswordChecker::new()
+                .min_length(8, None)
+                .max_length(24, None)
+                .require_upper_lower(None)
+                .require_number(None)
+                .require_special_char_with(SpecialCharPolicy::Chars("!#".into()), None)
+                .not_all_numeric(None)
+                .not_all_alphabetic(None)
+                .blacklist(["password1!", "aB1!"], None)
+                .must_not_match(Pattern::new("^\\s").unwrap(), None)
+                .keyboard_walk(crate::KeyboardWalk::new(), None)
+                .max_class_run(4, None)
+        };
+        vec![policy(), policy().fail_fast(true), policy().dry_run(true), PasswordChecker::pin().not_palindrome(None)]
+    }
+
+    #[test]
+    fn random_edits_match_full_checks() {
+        let pieces = ["a", "B", "1", "!", "#", "é", "٣", " ", "qwerty", "password", "1!", "aB1!", "ß", "\u{1d40f}", "0000"];
+        for checker in checkers() {
+            let mut session = ValidationSession::new(&checker);
+            let mut rng = Seeded::new(0x9e37_79b9_7f4a_7c15);
+            for _ in 0..2_000 {
+                let roll = rng.next_u64();
+                if roll.is_multiple_of(3) {
+                    session.pop((roll / 3 % 4) as usize);
+                } else {
+                    session.push_str(pieces[(roll / 3 % pieces.len() as u64) as usize]);
+                }
+                // Bounded so the sequence keeps crossing the length rules.
+                if session.password().chars().count() > 30 {
+                    session.pop(20);
+                }
+                assert_eq!(session.check(), checker.check(session.password()), "{:?}", session.password());
+            }
+        }
+    }
+
+    #[test]
+    fn whole_password_rules_can_lag() {
+        let checker = PasswordChecker::new().min_length(4, None).blacklist(["abcd"], None);
+        let mut session = ValidationSession::new(&checker).rescan_every(3);
+        session.push_str("abc");
+        session.push_str("d");
+        // The length is current, the blacklist was last evaluated on `abc`.
+        assert!(session.check().is_ok());
+        session.push_str("");
+        session.pop(1);
+        session.push_str("d");
+        assert_eq!(session.check().unwrap_err().codes().collect::<Vec<_>>(), ["blacklist"]);
+        session.pop(10);
+        assert_eq!(session.password(), "");
+        assert_eq!(session.check(), checker.check(""));
+        session.push_str("hunter2");
+        assert_eq!(format!("{:?}", session), "ValidationSession { chars: 7, rescan_every: 3, .. }");
+    }
+}
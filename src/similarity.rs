@@ -0,0 +1,204 @@
+//! Edit distance between passwords.
+
+use std::collections::HashMap;
+
+/// Minimum share of characters a new password must change, or the minimum
+/// number of edits, for it to count as different from the previous one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Similarity {
+    /// Rejects passwords more similar than this fraction, where similarity
+    /// is `1 - edit_distance / longer length`: `MaxRatio(0.8)` allows at
+    /// most 80% similar.
+    MaxRatio(f64),
+    /// Rejects passwords fewer than this many edits away.
+    MinEdits(usize),
+}
+
+/// Longest passwords, in characters, compared by edit distance, which
+/// takes O(len(a) · len(b)) time and memory. Longer pairs only count as
+/// too similar when they are equal.
+const MAX_EDIT_CHARS: usize = 256;
+
+impl Similarity {
+    /// Returns whether `a` and `b` are too similar.
+    pub(crate) fn too_similar(self, a: &str, b: &str) -> bool {
+        let (n, m) = (a.chars().count(), b.chars().count());
+        let too_close = |distance: usize| match self {
+            Similarity::MaxRatio(max) => ratio(distance, n.max(m)) > max,
+            Similarity::MinEdits(min) => distance < min,
+        };
+        // The distance is at least the difference in length, which often
+        // settles it without computing anything.
+        if !too_close(n.abs_diff(m)) {
+            return false;
+        }
+        if n.max(m) > MAX_EDIT_CHARS {
+            return a == b && too_close(0);
+        }
+        too_close(edit_distance(a, b))
+    }
+}
+
+/// Similarity from 0 (nothing in common) to 1 (equal) of two strings
+/// `distance` edits apart, the longer one of `longer` characters.
+fn ratio(distance: usize, longer: usize) -> f64 {
+    if longer == 0 { 1.0 } else { 1.0 - distance as f64 / longer as f64 }
+}
+
+/// Damerau–Levenshtein distance between `a` and `b`, in characters: the
+/// fewest insertions, deletions, substitutions and transpositions of
+/// adjacent characters turning one into the other.
+///
+/// ```
+/// assert_eq!(passcheck::edit_distance("Pa55owrd", "Pa55word"), 1);
+/// assert_eq!(passcheck::edit_distance("kitten", "sitting"), 3);
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (n, m) = (a.len(), b.len());
+    let infinity = n + m;
+    // Row `i + 1` holds the distances for the first `i` characters of `a`,
+    // with an extra sentinel row and column for transpositions.
+    let width = m + 2;
+    let mut d = vec![0; (n + 2) * width];
+    d[0] = infinity;
+    for i in 0..=n {
+        d[(i + 1) * width] = infinity;
+        d[(i + 1) * width + 1] = i;
+    }
+    for j in 0..=m {
+        d[j + 1] = infinity;
+        d[width + j + 1] = j;
+    }
+    // Last row in which each character of `a` was seen.
+    let mut last_row: HashMap<char, usize> = HashMap::new();
+    for i in 1..=n {
+        let mut last_match_col = 0;
+        for j in 1..=m {
+            let k = last_row.get(&b[j - 1]).copied().unwrap_or(0);
+            let l = last_match_col;
+            let cost = if a[i - 1] == b[j - 1] {
+                last_match_col = j;
+                0
+            } else {
+                1
+            };
+            let substitution = d[i * width + j] + cost;
+            let insertion = d[(i + 1) * width + j] + 1;
+            let deletion = d[i * width + j + 1] + 1;
+            let transposition = d[k * width + l] + (i - k - 1) + 1 + (j - l - 1);
+            d[(i + 1) * width + j + 1] = substitution.min(insertion).min(deletion).min(transposition);
+        }
+        last_row.insert(a[i - 1], i);
+    }
+    d[(n + 1) * width + m + 1]
+}
+
+/// Whether the Levenshtein distance between `a` and `b` is at most `max`.
+///
+/// Only the diagonal band of `2 * max + 1` cells per row is computed, and
+/// the comparison stops at the first row where every cell exceeds `max`,
+/// so it costs O(len(a) · max) at worst and usually much less. `rows` is
+/// scratch space, reused across calls to avoid allocating.
+pub(crate) fn within_distance(a: &[char], b: &[char], max: usize, rows: &mut [Vec<usize>; 2]) -> bool {
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+    let (m, over) = (b.len(), max + 1);
+    let [prev, row] = rows;
+    prev.clear();
+    prev.extend((0..=m).map(|j| if j <= max { j } else { over }));
+    row.clear();
+    row.resize(m + 1, over);
+    for i in 1..=a.len() {
+        let (lo, hi) = (i.saturating_sub(max), (i + max).min(m));
+        // Cells left of the band; those right of it were never written.
+        let mut best = if lo == 0 { i } else { over };
+        if lo == 0 {
+            row[0] = i;
+        } else {
+            row[lo - 1] = over;
+        }
+        for j in lo.max(1)..=hi {
+            let substitution = prev[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            row[j] = substitution.min(prev[j] + 1).min(row[j - 1] + 1).min(over);
+            best = best.min(row[j]);
+        }
+        if best > max {
+            return false;
+        }
+        std::mem::swap(prev, row);
+    }
+    prev[m] <= max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_distances() {
+        for (a, b, distance) in [
+            ("", "", 0),
+            ("", "abc", 3),
+            ("password", "password", 0),
+            ("Pa55owrd", "Pa55word", 1),
+            ("kitten", "sitting", 3),
+            ("ca", "abc", 2),
+            ("Summer2023!", "Summer2024!", 1),
+            ("Summer2024!", "summer2024!", 1),
+            ("héllo", "hello", 1),
+            ("日本語", "日語本", 1),
+            ("abcdef", "badcfe", 3),
+        ] {
+            assert_eq!(edit_distance(a, b), distance, "{:?} {:?}", a, b);
+            assert_eq!(edit_distance(b, a), distance, "{:?} {:?}", b, a);
+        }
+    }
+
+    #[test]
+    fn bounded_distance_agrees_with_levenshtein() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        let mut rows = Default::default();
+        for (a, b, distance) in [
+            ("", "", 0),
+            ("", "ab", 2),
+            ("password", "passw0rd", 1),
+            ("password", "pasword", 1),
+            ("password", "passwords", 1),
+            ("password", "p4ssw0rd", 2),
+            ("kitten", "sitting", 3),
+            ("abcdef", "badcfe", 4),
+            ("héllo", "hello", 1),
+        ] {
+            for max in 0..5 {
+                assert_eq!(within_distance(&chars(a), &chars(b), max, &mut rows), distance <= max, "{:?} {:?} {}", a, b, max);
+                assert_eq!(within_distance(&chars(b), &chars(a), max, &mut rows), distance <= max, "{:?} {:?} {}", b, a, max);
+            }
+        }
+    }
+
+    #[test]
+    fn thresholds_at_their_boundaries() {
+        // 2 edits over 10 characters: 80% similar.
+        let (old, new) = ("abcdefghij", "abcdefghXY");
+        assert!(!Similarity::MaxRatio(0.8).too_similar(old, new));
+        assert!(Similarity::MaxRatio(0.79).too_similar(old, new));
+        assert!(!Similarity::MinEdits(2).too_similar(old, new));
+        assert!(Similarity::MinEdits(3).too_similar(old, new));
+        assert!(Similarity::MaxRatio(0.99).too_similar("", ""));
+    }
+
+    #[test]
+    fn long_inputs_skip_the_distance() {
+        let long = "ab".repeat(1_000_000);
+        assert!(!Similarity::MaxRatio(0.8).too_similar(&long, "abababab"));
+        assert!(!Similarity::MinEdits(3).too_similar("abababab", &long));
+        // Over the cap, only equal passwords are too similar.
+        let (old, new) = ("x".repeat(300), format!("{}y", "x".repeat(299)));
+        assert!(Similarity::MinEdits(3).too_similar(&old, &old));
+        assert!(!Similarity::MinEdits(3).too_similar(&old, &new));
+        assert!(Similarity::MinEdits(3).too_similar(&old[..256], &new[..256]));
+    }
+}
@@ -0,0 +1,279 @@
+//! Policies built in constant expressions.
+
+use crate::{PasswordChecker, Rule, SpecialCharPolicy};
+
+/// The rules a [`PolicySpec`] can hold: those with only numeric
+/// parameters, reported with their default messages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SpecRule {
+    MinLength(usize),
+    MaxLength(usize),
+    MaxBytes(usize),
+    RecommendedLength(usize),
+    RequireUpperLower,
+    RequireNumber,
+    RequireSpecialChar,
+    ForbidUnsafeChars,
+    MaxClassRun(usize),
+    NotAllNumeric,
+    NotAllAlphabetic,
+    DigitsOnly,
+    NotSequentialDigits,
+    NotRepeatedChar,
+    NotPalindrome,
+    NoPhonePatterns,
+}
+
+/// A policy built with `const fn`s, so it can live in a `const` or
+/// `static` and become a checker with [`PasswordChecker::from_spec`].
+///
+/// It holds up to [`CAPACITY`](Self::CAPACITY) rules with only numeric
+/// parameters, in the order added, and uses their default messages. Rules
+/// with messages, character sets, dictionaries or patterns are added to
+/// the checker at runtime.
+///
+/// ```
+/// use passcheck::{PasswordChecker, PolicySpec};
+///
+/// static BASELINE: PolicySpec = PolicySpec::new().min_length(12).require_number().max_length(128);
+///
+/// let checker = PasswordChecker::from_spec(&BASELINE).blacklist(["password123456"], None);
+/// assert!(checker.check("correct horse 42").is_ok());
+/// assert_eq!(checker.check("password123456").unwrap_err().codes().collect::<Vec<_>>(), ["blacklist"]);
+/// ```
+///
+/// Custom messages are not `const`:
+///
+/// ```compile_fail
+/// use passcheck::PolicySpec;
+///
+/// const BASELINE: PolicySpec = PolicySpec::new().min_length(12, Some("Use 12 characters."));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolicySpec {
+    rules: [Option<SpecRule>; PolicySpec::CAPACITY],
+    len: usize,
+    fail_fast: bool,
+    max_input_bytes: Option<usize>,
+}
+
+impl PolicySpec {
+    /// How many rules a spec holds. Adding more fails to compile in a
+    /// constant, and panics at runtime.
+    pub const CAPACITY: usize = 16;
+
+    /// An empty spec.
+    pub const fn new() -> Self {
+        PolicySpec { rules: [None; PolicySpec::CAPACITY], len: 0, fail_fast: false, max_input_bytes: None }
+    }
+
+    const fn with(mut self, rule: SpecRule) -> Self {
+        assert!(self.len < PolicySpec::CAPACITY, "a PolicySpec holds at most 16 rules");
+        self.rules[self.len] = Some(rule);
+        self.len += 1;
+        self
+    }
+
+    /// As [`PasswordChecker::min_length`].
+    pub const fn min_length(self, len: usize) -> Self {
+        self.with(SpecRule::MinLength(len))
+    }
+
+    /// As [`PasswordChecker::max_length`].
+    pub const fn max_length(self, len: usize) -> Self {
+        self.with(SpecRule::MaxLength(len))
+    }
+
+    /// As [`PasswordChecker::max_bytes`].
+    pub const fn max_bytes(self, len: usize) -> Self {
+        self.with(SpecRule::MaxBytes(len))
+    }
+
+    /// As [`PasswordChecker::recommended_length`].
+    pub const fn recommended_length(self, len: usize) -> Self {
+        self.with(SpecRule::RecommendedLength(len))
+    }
+
+    /// As [`PasswordChecker::require_upper_lower`].
+    pub const fn require_upper_lower(self) -> Self {
+        self.with(SpecRule::RequireUpperLower)
+    }
+
+    /// As [`PasswordChecker::require_number`].
+    pub const fn require_number(self) -> Self {
+        self.with(SpecRule::RequireNumber)
+    }
+
+    /// As [`PasswordChecker::require_special_char`], with the built-in set.
+    pub const fn require_special_char(self) -> Self {
+        self.with(SpecRule::RequireSpecialChar)
+    }
+
+    /// As [`PasswordChecker::forbid_unsafe_chars`].
+    pub const fn forbid_unsafe_chars(self) -> Self {
+        self.with(SpecRule::ForbidUnsafeChars)
+    }
+
+    /// As [`PasswordChecker::max_class_run`].
+    pub const fn max_class_run(self, len: usize) -> Self {
+        self.with(SpecRule::MaxClassRun(len))
+    }
+
+    /// As [`PasswordChecker::not_all_numeric`].
+    pub const fn not_all_numeric(self) -> Self {
+        self.with(SpecRule::NotAllNumeric)
+    }
+
+    /// As [`PasswordChecker::not_all_alphabetic`].
+    pub const fn not_all_alphabetic(self) -> Self {
+        self.with(SpecRule::NotAllAlphabetic)
+    }
+
+    /// As [`PasswordChecker::digits_only`].
+    pub const fn digits_only(self) -> Self {
+        self.with(SpecRule::DigitsOnly)
+    }
+
+    /// As [`PasswordChecker::not_sequential_digits`].
+    pub const fn not_sequential_digits(self) -> Self {
+        self.with(SpecRule::NotSequentialDigits)
+    }
+
+    /// As [`PasswordChecker::not_repeated_char`].
+    pub const fn not_repeated_char(self) -> Self {
+        self.with(SpecRule::NotRepeatedChar)
+    }
+
+    /// As [`PasswordChecker::not_palindrome`].
+    pub const fn not_palindrome(self) -> Self {
+        self.with(SpecRule::NotPalindrome)
+    }
+
+    /// As [`PasswordChecker::no_phone_patterns`].
+    pub const fn no_phone_patterns(self) -> Self {
+        self.with(SpecRule::NoPhonePatterns)
+    }
+
+    /// As [`PasswordChecker::fail_fast`].
+    pub const fn fail_fast(mut self, enabled: bool) -> Self {
+        self.fail_fast = enabled;
+        self
+    }
+
+    /// As [`PasswordChecker::max_input_bytes`].
+    pub const fn max_input_bytes(mut self, bytes: usize) -> Self {
+        self.max_input_bytes = Some(bytes);
+        self
+    }
+
+    /// The number of rules added.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no rule was added.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Default for PolicySpec {
+    fn default() -> Self {
+        PolicySpec::new()
+    }
+}
+
+impl PasswordChecker<'_> {
+    /// A checker with the rules and settings of `spec`, to which more
+    /// rules can be added.
+    pub fn from_spec(spec: &PolicySpec) -> Self {
+        let rules = spec.rules[..spec.len].iter().flatten().map(|rule| match *rule {
+            SpecRule::MinLength(len) => Rule::MinLength(len, None),
+            SpecRule::MaxLength(len) => Rule::MaxLength(len, None),
+            SpecRule::MaxBytes(len) => Rule::MaxBytes(len, None),
+            SpecRule::RecommendedLength(len) => Rule::RecommendedLength(len, None),
+            SpecRule::RequireUpperLower => Rule::RequireUpperLower(None),
+            SpecRule::RequireNumber => Rule::RequireNumber(None),
+            SpecRule::RequireSpecialChar => Rule::RequireSpecialChar(SpecialCharPolicy::Builtin, None),
+            SpecRule::ForbidUnsafeChars => Rule::ForbidUnsafeChars(None),
+            SpecRule::MaxClassRun(len) => Rule::MaxClassRun(len, None),
+            SpecRule::NotAllNumeric => Rule::NotAllNumeric(None),
+            SpecRule::NotAllAlphabetic => Rule::NotAllAlphabetic(None),
+            SpecRule::DigitsOnly => Rule::DigitsOnly(None),
+            SpecRule::NotSequentialDigits => Rule::NotSequentialDigits(None),
+            SpecRule::NotRepeatedChar => Rule::NotRepeatedChar(None),
+            SpecRule::NotPalindrome => Rule::NotPalindrome(None),
+            SpecRule::NoPhonePatterns => Rule::NoPhonePatterns(None),
+        });
+        let checker = PasswordChecker::from_rules(rules.collect()).fail_fast(spec.fail_fast);
+        match spec.max_input_bytes {
+            Some(bytes) => checker.max_input_bytes(bytes),
+            None => checker,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static BASELINE: PolicySpec = PolicySpec::new().min_length(12).require_number().max_length(128).fail_fast(true);
+
+    #[test]
+    fn static_specs_become_checkers() {
+        let checker = PasswordChecker::from_spec(&BASELINE);
+        let built = PasswordChecker::new().min_length(12, None).require_number(None).max_length(128, None).fail_fast(true);
+        assert_eq!(checker, built);
+        assert!(checker.check("correct horse 42").is_ok());
+        assert_eq!(checker.check("short").unwrap_err().codes().collect::<Vec<_>>(), ["min_length"]);
+        assert_eq!(BASELINE.len(), 3);
+    }
+
+    #[test]
+    fn every_rule_matches_its_builder() {
+        const SPEC: PolicySpec = PolicySpec::new()
+            .min_length(4)
+            .max_length(8)
+            .max_bytes(72)
+            .recommended_length(6)
+            .require_upper_lower()
+            .require_number()
+            .require_special_char()
+            .forbid_unsafe_chars()
+            .max_class_run(3)
+            .not_all_numeric()
+            .not_all_alphabetic()
+            .digits_only()
+            .not_sequential_digits()
+            .not_repeated_char()
+            .not_palindrome()
+            .no_phone_patterns()
+            .max_input_bytes(1024);
+        let built = PasswordChecker::new()
+            .min_length(4, None)
+            .max_length(8, None)
+            .max_bytes(72, None)
+            .recommended_length(6, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None)
+            .forbid_unsafe_chars(None)
+            .max_class_run(3, None)
+            .not_all_numeric(None)
+            .not_all_alphabetic(None)
+            .digits_only(None)
+            .not_sequential_digits(None)
+            .not_repeated_char(None)
+            .not_palindrome(None)
+            .no_phone_patterns(None)
+            .max_input_bytes(1024);
+        assert_eq!(PasswordChecker::from_spec(&SPEC), built);
+        assert!(PasswordChecker::from_spec(&PolicySpec::new()).rules().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 16 rules")]
+    fn capacity_is_enforced() {
+        (0..=PolicySpec::CAPACITY).fold(PolicySpec::new(), |spec, _| spec.require_number());
+    }
+}
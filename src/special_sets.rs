@@ -0,0 +1,176 @@
+//! Named sets of special characters for
+//! [`require_special_char_with`](crate::PasswordChecker::require_special_char_with).
+//!
+//! A set becomes the checker's special set, so validation,
+//! [generation](crate::PasswordChecker::generate) and
+//! [`describe`](crate::PasswordChecker::describe) all use it:
+//!
+//! ```
+//! use passcheck::PasswordChecker;
+//! use passcheck::special_sets::{SHELL_SAFE, SpecialSet};
+//!
+//! let symbols = SHELL_SAFE.minus(&SpecialSet::new("%"));
+//! let checker = PasswordChecker::new().require_special_char_with(symbols.into(), None);
+//! assert!(checker.check("deploy=prod").is_ok());
+//! assert!(checker.check("deploy%prod").is_err());
+//! assert_eq!(checker.describe(), ["At least one of the characters `+,-./:=@_`"]);
+//! ```
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::SpecialCharPolicy;
+
+/// The special characters of [`SpecialCharPolicy::Builtin`].
+pub const DEFAULT: SpecialSet = SpecialSet(Cow::Borrowed("!@#$%^&*()-_=+[]{}\\|;:'\",.<>/?"));
+
+/// The password special characters listed by OWASP: every ASCII
+/// punctuation mark, and the space.
+pub const OWASP: SpecialSet = SpecialSet(Cow::Borrowed(" !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~"));
+
+/// Punctuation a POSIX shell leaves alone in an unquoted word: no quotes,
+/// expansions, globs, redirections, separators or history references.
+pub const SHELL_SAFE: SpecialSet = SpecialSet(Cow::Borrowed("%+,-./:=@_"));
+
+/// The punctuation RFC 3986 leaves unreserved, which URLs carry without
+/// percent-encoding.
+pub const URL_SAFE: SpecialSet = SpecialSet(Cow::Borrowed("-._~"));
+
+/// A set of special characters, kept in the order they were given.
+/// Converting one into a [`SpecialCharPolicy`] gives
+/// [`Builtin`](SpecialCharPolicy::Builtin) for the [`DEFAULT`]
+/// characters and [`Chars`](SpecialCharPolicy::Chars) otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpecialSet(Cow<'static, str>);
+
+impl SpecialSet {
+    /// The characters of `chars`, each once.
+    pub fn new(chars: &str) -> Self {
+        let mut set = String::new();
+        for c in chars.chars() {
+            if !set.contains(c) {
+                set.push(c);
+            }
+        }
+        SpecialSet(Cow::Owned(set))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.0.contains(c)
+    }
+
+    /// Number of characters.
+    pub fn len(&self) -> usize {
+        self.0.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The characters of `self`, then those of `other` not in `self`.
+    pub fn union(&self, other: &SpecialSet) -> SpecialSet {
+        SpecialSet::new(&format!("{}{}", self.0, other.0))
+    }
+
+    /// The characters of `self` not in `other`.
+    pub fn minus(&self, other: &SpecialSet) -> SpecialSet {
+        SpecialSet(Cow::Owned(self.0.chars().filter(|&c| !other.contains(c)).collect()))
+    }
+
+    /// Whether both sets hold the same characters, in any order.
+    fn same_chars(&self, other: &SpecialSet) -> bool {
+        self.len() == other.len() && self.0.chars().all(|c| other.contains(c))
+    }
+}
+
+impl fmt::Display for SpecialSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<SpecialSet> for SpecialCharPolicy {
+    fn from(set: SpecialSet) -> Self {
+        if set.same_chars(&DEFAULT) { SpecialCharPolicy::Builtin } else { SpecialCharPolicy::Chars(set.0.into_owned()) }
+    }
+}
+
+/// [`DEFAULT`] as an array, for the builtin special set.
+pub(crate) const fn default_chars<const N: usize>() -> [char; N] {
+    let Cow::Borrowed(chars) = DEFAULT.0 else { unreachable!() };
+    let bytes = chars.as_bytes();
+    assert!(bytes.len() == N);
+    let mut out = ['\0'; N];
+    let mut i = 0;
+    while i < N {
+        assert!(bytes[i].is_ascii());
+        out[i] = bytes[i] as char;
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordChecker;
+
+    #[test]
+    fn presets_have_their_exact_contents() {
+        assert_eq!(DEFAULT.as_str(), "!@#$%^&*()-_=+[]{}\\|;:'\",.<>/?");
+        assert_eq!(DEFAULT.len(), 30);
+        assert_eq!(crate::SPECIAL_CHARS.iter().collect::<String>(), DEFAULT.as_str());
+        let punctuation: String = (' '..='~').filter(|c| *c == ' ' || c.is_ascii_punctuation()).collect();
+        assert_eq!(OWASP.len(), 33);
+        assert!(OWASP.same_chars(&SpecialSet::new(&punctuation)));
+        assert_eq!(SHELL_SAFE.as_str(), "%+,-./:=@_");
+        assert_eq!(URL_SAFE.as_str(), "-._~");
+        for set in [&DEFAULT, &OWASP, &SHELL_SAFE, &URL_SAFE] {
+            assert_eq!(SpecialSet::new(set.as_str()), SpecialSet(Cow::Owned(set.to_string())), "{} repeats a character", set);
+        }
+        for hazard in ['\'', '"', '`', '$', '\\', '!', '*', '?', '[', '~', '&', '|', ';', '<', '(', '#', ' '] {
+            assert!(!SHELL_SAFE.contains(hazard), "{:?}", hazard);
+        }
+    }
+
+    #[test]
+    fn union_and_minus_keep_order_without_repeats() {
+        let set = SpecialSet::new("#!#%");
+        assert_eq!(set.as_str(), "#!%");
+        assert_eq!(set.union(&URL_SAFE).as_str(), "#!%-._~");
+        assert_eq!(URL_SAFE.union(&SpecialSet::new("~!")).as_str(), "-._~!");
+        assert_eq!(OWASP.minus(&DEFAULT).as_str(), " `~");
+        assert_eq!(URL_SAFE.minus(&URL_SAFE), SpecialSet::new(""));
+        assert!(URL_SAFE.minus(&URL_SAFE).is_empty());
+    }
+
+    #[test]
+    fn sets_become_the_checkers_special_set() {
+        assert_eq!(SpecialCharPolicy::from(DEFAULT), SpecialCharPolicy::Builtin);
+        assert_eq!(SpecialCharPolicy::from(OWASP.minus(&SpecialSet::new(" `~"))), SpecialCharPolicy::Builtin);
+        assert_eq!(SpecialCharPolicy::from(URL_SAFE), SpecialCharPolicy::Chars("-._~".into()));
+
+        let checker = PasswordChecker::new().require_special_char_with(URL_SAFE.into(), None);
+        assert!(checker.check("pass~word").is_ok() && checker.check("pass!word").is_err());
+        assert_eq!(checker.describe(), ["At least one of the characters `-._~`"]);
+        let owasp = PasswordChecker::new().require_special_char_with(OWASP.into(), None);
+        assert!(owasp.check("pass word").is_ok());
+        assert_eq!(owasp.describe(), ["At least one of the characters ` !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~`"]);
+    }
+
+    #[cfg(feature = "generate")]
+    #[test]
+    fn generated_passwords_draw_from_the_set() {
+        let checker = PasswordChecker::new().min_length(16, None).require_number(None).require_special_char_with(SHELL_SAFE.into(), None);
+        for _ in 0..20 {
+            let password = checker.generate(16).unwrap();
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric() || SHELL_SAFE.contains(c)), "{}", password);
+            assert!(password.chars().any(|c| SHELL_SAFE.contains(c)));
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Character composition of passwords.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+#[cfg(doc)]
+use crate::Rule;
+use crate::{SpecialCharPolicy, categories};
+
+/// How [`analyze_chars`] sorts characters into classes.
+///
+/// Classes go by the Unicode properties of each character as given,
+/// without normalizing it first. A combining mark, such as U+0301 in `e`
+/// followed by U+0301, belongs to the character before it, so `é` counts
+/// as one character whether it is written precomposed or not: a lowercase
+/// letter in Unicode mode, and `other` in ASCII mode, where marked letters
+/// and digits are not ASCII. Characters outside the Basic Multilingual
+/// Plane are classified like any other: the mathematical `𝐏` is uppercase
+/// in Unicode mode, and letters without case, such as Old Italic `𐌀` or
+/// Han, are `other` in both.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClassModes {
+    /// Count letters and digits of every script, such as `É` or `٣`, and
+    /// not only ASCII ones as the composition rules do.
+    pub unicode: bool,
+    /// Which characters count as special.
+    pub special: SpecialCharPolicy,
+}
+
+/// Counts describing what a password is made of, from [`analyze_chars`].
+///
+/// Every character, together with the combining marks following it,
+/// lands in exactly one of the six class counts: whitespace first, then
+/// uppercase, lowercase, digits and special characters as configured by
+/// the [`ClassModes`], and anything else, such as `ß` in ASCII mode or a
+/// control character, as `other`. Repeats, class runs and distinct
+/// characters count marked characters as one too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CharStats {
+    pub uppercase: usize,
+    pub lowercase: usize,
+    pub digits: usize,
+    pub special: usize,
+    pub other: usize,
+    pub whitespace: usize,
+    /// Length in UTF-8 bytes.
+    pub bytes: usize,
+    /// Length in Unicode scalar values.
+    pub chars: usize,
+    /// Longest run of one character repeated, e.g. 3 for `aaa`.
+    pub longest_repeat: usize,
+    /// Longest run of one class as counted by [`Rule::MaxClassRun`]:
+    /// letters of either case, ASCII digits, or special characters of the
+    /// [`ClassModes::special`] set.
+    pub longest_class_run: usize,
+    /// Number of distinct characters.
+    pub unique_chars: usize,
+}
+
+/// Counts the characters of `password` in one pass.
+///
+/// ```
+/// use passcheck::{ClassModes, analyze_chars};
+///
+/// let stats = analyze_chars("Tr0ub4dor&3", &ClassModes::default());
+/// assert_eq!((stats.uppercase, stats.lowercase, stats.digits, stats.special), (1, 6, 3, 1));
+/// ```
+pub fn analyze_chars(password: &str, modes: &ClassModes) -> CharStats {
+    let mut stats = CharStats { bytes: password.len(), ..CharStats::default() };
+    let mut seen = HashSet::new();
+    let mut repeat = 0;
+    let mut last = None;
+    for cluster in clusters(password) {
+        stats.chars += cluster.chars().count();
+        seen.insert(cluster);
+        let c = cluster.chars().next().unwrap_or_default();
+        // Only a single byte is an ASCII character without marks.
+        let ascii = cluster.len() == 1;
+        let count = if c.is_whitespace() {
+            &mut stats.whitespace
+        } else if if modes.unicode { c.is_uppercase() } else { ascii && c.is_ascii_uppercase() } {
+            &mut stats.uppercase
+        } else if if modes.unicode { c.is_lowercase() } else { ascii && c.is_ascii_lowercase() } {
+            &mut stats.lowercase
+        } else if if modes.unicode { c.is_numeric() } else { ascii && c.is_ascii_digit() } {
+            &mut stats.digits
+        } else if modes.special.contains(c) {
+            &mut stats.special
+        } else {
+            &mut stats.other
+        };
+        *count += 1;
+
+        repeat = if last == Some(cluster) { repeat + 1 } else { 1 };
+        stats.longest_repeat = stats.longest_repeat.max(repeat);
+        last = Some(cluster);
+    }
+    stats.longest_class_run = longest_class_run(password, &modes.special);
+    stats.unique_chars = seen.len();
+    stats
+}
+
+/// The class of `c` for [`Rule::MaxClassRun`]: letters, ASCII digits as
+/// counted by [`Rule::RequireNumber`], and the characters `special` counts.
+/// Anything else, such as whitespace or a symbol outside the set, belongs
+/// to no class.
+fn char_class(c: char, special: &SpecialCharPolicy) -> Option<u8> {
+    if c.is_alphabetic() {
+        Some(0)
+    } else if c.is_ascii_digit() {
+        Some(1)
+    } else if special.contains(c) {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// `password` split into characters, each with the combining marks that
+/// follow it. A mark at the start stands alone.
+fn clusters(password: &str) -> impl Iterator<Item = &str> + '_ {
+    let mut starts = password.char_indices().filter(|&(i, c)| i == 0 || !categories::is_mark(c)).map(|(i, _)| i).peekable();
+    std::iter::from_fn(move || {
+        let start = starts.next()?;
+        Some(&password[start..starts.peek().copied().unwrap_or(password.len())])
+    })
+}
+
+/// Length of the longest run of consecutive characters of one class, with
+/// `special` as the special set.
+pub(crate) fn longest_class_run(password: &str, special: &SpecialCharPolicy) -> usize {
+    class_runs(password, special).map(|(_, len)| len).max().unwrap_or(0)
+}
+
+/// Character range of the first run of one class longer than `max`.
+pub(crate) fn class_run_over(password: &str, max: usize, special: &SpecialCharPolicy) -> Option<Range<usize>> {
+    class_runs(password, special).find(|&(_, len)| len > max).map(|(span, _)| span)
+}
+
+/// The maximal runs of one class, in order, as character ranges with their
+/// length. Combining marks extend the run of the character they follow
+/// without adding to its length.
+fn class_runs(password: &str, special: &SpecialCharPolicy) -> impl Iterator<Item = (Range<usize>, usize)> {
+    let mut runs: Vec<(Option<u8>, Range<usize>, usize)> = Vec::new();
+    let mut end = 0;
+    for cluster in clusters(password) {
+        let start = end;
+        end += cluster.chars().count();
+        let class = cluster.chars().next().and_then(|c| char_class(c, special));
+        match runs.last_mut() {
+            Some((last, span, len)) if *last == class => {
+                span.end = end;
+                *len += 1;
+            }
+            _ => runs.push((class, start..end, 1)),
+        }
+    }
+    runs.into_iter().filter_map(|(class, span, len)| class.map(|_| (span, len)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParamValue, PasswordChecker};
+
+    #[test]
+    fn counts_every_field() {
+        let ascii = ClassModes::default();
+        assert_eq!(
+            analyze_chars("Tr0ub4dor&3", &ascii),
+            CharStats {
+                uppercase: 1,
+                lowercase: 6,
+                digits: 3,
+                special: 1,
+                other: 0,
+                whitespace: 0,
+                bytes: 11,
+                chars: 11,
+                longest_repeat: 1,
+                longest_class_run: 3,
+                unique_chars: 10,
+            }
+        );
+        assert_eq!(
+            analyze_chars("aaa  111!!", &ascii),
+            CharStats {
+                lowercase: 3,
+                digits: 3,
+                special: 2,
+                whitespace: 2,
+                bytes: 10,
+                chars: 10,
+                longest_repeat: 3,
+                longest_class_run: 3,
+                unique_chars: 4,
+                ..CharStats::default()
+            }
+        );
+        assert_eq!(analyze_chars("", &ascii), CharStats::default());
+    }
+
+    #[test]
+    fn class_runs_are_character_ranges() {
+        let builtin = &SpecialCharPolicy::Builtin;
+        assert_eq!(class_runs("ab12 !!x", builtin).map(|(span, _)| span).collect::<Vec<_>>(), [0..2, 2..4, 5..7, 7..8]);
+        assert_eq!(class_run_over("éé1Aaaa!", 3, builtin), Some(3..7));
+        assert_eq!(class_run_over("ab  cd", 2, builtin), None);
+        assert_eq!(longest_class_run("", builtin), 0);
+        // Hebrew points are marks, and belong to the letters.
+        assert_eq!(class_runs("\u{5e9}\u{5b8}\u{5c1}\u{5dc}\u{5d5}\u{5b9}\u{5dd}1", builtin).collect::<Vec<_>>(), [(0..7, 4), (7..8, 1)]);
+        assert_eq!(class_run_over("e\u{301}e\u{301}e\u{301}!", 2, builtin), Some(0..6));
+    }
+
+    #[test]
+    fn special_runs_follow_the_special_set() {
+        let narrowed = SpecialCharPolicy::Chars("!@".to_string());
+        assert_eq!(longest_class_run("€€€€€€", &narrowed), 0);
+        assert_eq!(longest_class_run("!@!@", &narrowed), 4);
+        assert_eq!(longest_class_run("!#!#", &narrowed), 1);
+        assert_eq!(longest_class_run("€€€€€€", &SpecialCharPolicy::UnicodeCategories), 6);
+        let modes = ClassModes { unicode: false, special: narrowed.clone() };
+        assert_eq!(analyze_chars("ab€€€€€€", &modes).longest_class_run, 2);
+
+        let checker = PasswordChecker::new().require_special_char_with(narrowed, None).max_class_run(3, None);
+        assert!(checker.check("€€€€€€!").is_ok());
+        let unicode = PasswordChecker::new().require_special_char_with(SpecialCharPolicy::UnicodeCategories, None).max_class_run(3, None);
+        assert_eq!(unicode.check("€€€€€€!").unwrap_err().codes().collect::<Vec<_>>(), ["max_class_run"]);
+        let violations = checker.check("Ab1!@!@").unwrap_err();
+        assert_eq!(violations[0].params, [("max", ParamValue::Int(3)), ("actual", ParamValue::Int(4))]);
+        assert_eq!(violations[0].span, Some(3..7));
+    }
+
+    #[test]
+    fn multi_byte_characters_follow_the_modes() {
+        // É is two bytes, ß two, ٣ (Arabic-Indic three) two and € three.
+        // Only the Unicode modes know what to make of them.
+        let password = "Éßß٣€x";
+        assert_eq!(
+            analyze_chars(password, &ClassModes::default()),
+            CharStats {
+                lowercase: 1,
+                other: 5,
+                bytes: 12,
+                chars: 6,
+                longest_repeat: 2,
+                longest_class_run: 3,
+                unique_chars: 5,
+                ..CharStats::default()
+            }
+        );
+        let unicode = ClassModes { unicode: true, special: SpecialCharPolicy::UnicodeCategories };
+        assert_eq!(
+            analyze_chars(password, &unicode),
+            CharStats {
+                uppercase: 1,
+                lowercase: 3,
+                digits: 1,
+                special: 1,
+                bytes: 12,
+                chars: 6,
+                longest_repeat: 2,
+                longest_class_run: 3,
+                unique_chars: 5,
+                ..CharStats::default()
+            }
+        );
+    }
+
+    /// The one class `password` is counted in.
+    fn class_of(password: &str, modes: &ClassModes) -> &'static str {
+        let stats = analyze_chars(password, modes);
+        let counts = [
+            ("uppercase", stats.uppercase),
+            ("lowercase", stats.lowercase),
+            ("digits", stats.digits),
+            ("special", stats.special),
+            ("other", stats.other),
+            ("whitespace", stats.whitespace),
+        ];
+        let classes: Vec<_> = counts.iter().filter(|(_, n)| *n > 0).collect();
+        assert!(matches!(classes[..], [(_, 1)]), "{:?}: {:?}", password, classes);
+        classes[0].0
+    }
+
+    #[test]
+    fn tricky_characters_by_mode() {
+        let ascii = ClassModes::default();
+        let unicode = ClassModes { unicode: true, special: SpecialCharPolicy::UnicodeCategories };
+        for (password, in_ascii, in_unicode) in [
+            ("A", "uppercase", "uppercase"),
+            ("z", "lowercase", "lowercase"),
+            ("7", "digits", "digits"),
+            ("!", "special", "special"),
+            ("\u{a0}", "whitespace", "whitespace"),
+            // é precomposed and as e followed by U+0301 COMBINING ACUTE ACCENT.
+            ("\u{e9}", "other", "lowercase"),
+            ("e\u{301}", "other", "lowercase"),
+            ("E\u{301}\u{323}", "other", "uppercase"),
+            ("a\u{20dd}", "other", "lowercase"),
+            ("!\u{301}", "special", "special"),
+            ("\u{df}", "other", "lowercase"),
+            ("\u{130}", "other", "uppercase"),
+            // ǅ is titlecase, neither upper- nor lowercase.
+            ("\u{1c5}", "other", "other"),
+            ("\u{2b0}", "other", "lowercase"),
+            ("\u{216b}", "other", "uppercase"),
+            ("\u{ff21}", "other", "uppercase"),
+            ("\u{ff13}", "other", "digits"),
+            ("\u{663}", "other", "digits"),
+            ("\u{bd}", "other", "digits"),
+            // Mathematical bold P, a and 3, outside the BMP.
+            ("\u{1d40f}", "other", "uppercase"),
+            ("\u{1d41a}", "other", "lowercase"),
+            ("\u{1d7d1}", "other", "digits"),
+            // Old Italic letter A and numeral one.
+            ("\u{10300}", "other", "other"),
+            ("\u{10320}", "other", "digits"),
+            ("\u{4e2d}", "other", "other"),
+            ("\u{915}\u{93f}", "other", "other"),
+            ("\u{20ac}", "other", "special"),
+            ("\u{1f600}", "other", "special"),
+            ("\u{2764}\u{fe0f}", "other", "special"),
+            ("\u{301}", "other", "other"),
+            ("\u{200d}", "other", "other"),
+            ("\u{0}", "other", "other"),
+        ] {
+            assert_eq!(class_of(password, &ascii), in_ascii, "{:?} in ASCII mode", password);
+            assert_eq!(class_of(password, &unicode), in_unicode, "{:?} in Unicode mode", password);
+        }
+    }
+
+    #[test]
+    fn marked_characters_count_once() {
+        let unicode = ClassModes { unicode: true, ..ClassModes::default() };
+        let composed = analyze_chars("\u{e9}\u{e9}\u{1d40f}assword1", &unicode);
+        let decomposed = analyze_chars("e\u{301}e\u{301}\u{1d40f}assword1", &unicode);
+        assert_eq!((composed.lowercase, composed.uppercase, composed.longest_repeat, composed.unique_chars), (9, 1, 2, 9));
+        assert_eq!(decomposed, CharStats { bytes: decomposed.bytes, chars: composed.chars + 2, ..composed });
+        assert_eq!(analyze_chars("e\u{301}", &ClassModes::default()).chars, 2);
+    }
+}
@@ -0,0 +1,392 @@
+//! Password strength estimation.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::{Dictionary, KeyboardWalk, SPECIAL_CHARS};
+
+// Pool size credited once any character outside the ASCII classes appears.
+const OTHER_POOL: u32 = 100;
+
+/// Estimates the entropy of `password` in bits.
+///
+/// Uses the classic pool-size model: the length in characters times log2 of
+/// the combined size of every character class that appears (lowercase 26,
+/// uppercase 26, digits 10, special characters 30, anything else 100).
+pub fn entropy(password: &str) -> f64 {
+    let (mut lower, mut upper, mut digit, mut special, mut other) = (false, false, false, false, false);
+    let mut len = 0usize;
+    for c in password.chars() {
+        len += 1;
+        if c.is_ascii_lowercase() {
+            lower = true;
+        } else if c.is_ascii_uppercase() {
+            upper = true;
+        } else if c.is_ascii_digit() {
+            digit = true;
+        } else if SPECIAL_CHARS.contains(&c) {
+            special = true;
+        } else {
+            other = true;
+        }
+    }
+    let pool = [(lower, 26), (upper, 26), (digit, 10), (special, SPECIAL_CHARS.len() as u32), (other, OTHER_POOL)]
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, size)| size)
+        .sum::<u32>();
+    if pool == 0 {
+        return 0.0;
+    }
+    len as f64 * f64::from(pool).log2()
+}
+
+/// Empirical Shannon entropy of the characters of `password`, in bits per
+/// character.
+///
+/// Unlike [`entropy`], this looks at how often each character actually
+/// occurs: a single repeated character has 0 bits per character and `n`
+/// distinct characters have log2(n).
+pub fn shannon_entropy_per_char(password: &str) -> f64 {
+    let mut counts = BTreeMap::new();
+    let mut len = 0usize;
+    for c in password.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+        len += 1;
+    }
+    if len == 0 {
+        return 0.0;
+    }
+    let len = len as f64;
+    counts.values().map(|&n| n as f64 / len).map(|p| -p * p.log2()).sum::<f64>().max(0.0)
+}
+
+/// Empirical Shannon entropy of `password` in bits: the
+/// [per-character entropy](shannon_entropy_per_char) times the length.
+///
+/// ```
+/// // The pool of `aaaaaaaB1!` spans four classes, but its characters barely vary.
+/// assert!(passcheck::shannon_entropy("aaaaaaaB1!") < passcheck::entropy("aaaaaaaB1!") / 3.0);
+/// ```
+pub fn shannon_entropy(password: &str) -> f64 {
+    shannon_entropy_per_char(password) * password.chars().count() as f64
+}
+
+/// How the strength of a password is estimated, in bits.
+///
+/// [`Pool`](Self::Pool) rewards using many character classes but ignores
+/// repetition; [`Shannon`](Self::Shannon) rewards variety but ignores which
+/// characters are used, so `abcdefgh` scores as well as `k$3Q!x9z`.
+/// Shannon entropy rarely exceeds the pool estimate, the exception being
+/// passwords with more distinct characters than their classes' pool sizes
+/// (over 100 distinct non-ASCII characters); [`Min`](Self::Min) takes the
+/// lower of the two and is the safest choice for a minimum-strength rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Estimator {
+    /// Pool-size [`entropy`]. Cheap, but credits human patterns such as
+    /// `Monkey123!` with every character class they use.
+    #[default]
+    Pool,
+    /// [`shannon_entropy`] of the password itself.
+    Shannon,
+    /// The lower of [`Pool`](Self::Pool) and [`Shannon`](Self::Shannon).
+    Min,
+    /// The character-bigram model behind [`guessability`](crate::guessability),
+    /// converted to bits.
+    #[cfg(feature = "markov")]
+    Markov,
+}
+
+impl Estimator {
+    /// Estimated strength of `password` in bits.
+    pub fn bits(self, password: &str) -> f64 {
+        match self {
+            Estimator::Pool => entropy(password),
+            Estimator::Shannon => shannon_entropy(password),
+            Estimator::Min => entropy(password).min(shannon_entropy(password)),
+            #[cfg(feature = "markov")]
+            Estimator::Markov => crate::markov::guessability(password) * 10f64.log2(),
+        }
+    }
+
+    /// Name used in policy files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Estimator::Pool => "pool",
+            Estimator::Shannon => "shannon",
+            Estimator::Min => "min",
+            #[cfg(feature = "markov")]
+            Estimator::Markov => "markov",
+        }
+    }
+
+    /// Parses a name returned by [`name`](Self::name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pool" => Some(Estimator::Pool),
+            "shannon" => Some(Estimator::Shannon),
+            "min" => Some(Estimator::Min),
+            #[cfg(feature = "markov")]
+            "markov" => Some(Estimator::Markov),
+            _ => None,
+        }
+    }
+}
+
+/// Weights of the factors behind a weighted
+/// [`score`](crate::PasswordChecker::score), in points per unit.
+///
+/// Negative weights are treated as zero, so a longer password never scores
+/// lower than a shorter one that is otherwise the same.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreWeights {
+    /// Points per character.
+    pub length: f64,
+    /// Points per character class used (lowercase, uppercase, digits,
+    /// special characters, anything else).
+    pub class_diversity: f64,
+    /// Points for a password of only distinct characters, scaled down by
+    /// the share of repeated ones.
+    pub uniqueness: f64,
+    /// Points deducted per character inside a keyboard walk, a run of one
+    /// repeated character, or a sequence such as `abc` or `321`.
+    pub pattern_penalty: f64,
+    /// Points deducted when the password is in a dictionary.
+    pub dictionary_penalty: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights { length: 4.0, class_diversity: 8.0, uniqueness: 20.0, pattern_penalty: 3.0, dictionary_penalty: 50.0 }
+    }
+}
+
+/// The measured properties of a password that [`ScoreWeights`] apply to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Factors {
+    pub(crate) length: usize,
+    pub(crate) classes: usize,
+    pub(crate) uniqueness: f64,
+    pub(crate) pattern_chars: usize,
+    pub(crate) in_dictionary: bool,
+}
+
+impl Factors {
+    /// Measures `password`, checking it against `dictionaries`.
+    pub(crate) fn of<'d>(password: &str, dictionaries: impl IntoIterator<Item = &'d Dictionary>) -> Self {
+        let chars: Vec<char> = password.chars().collect();
+        let mut distinct = chars.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        Factors {
+            length: chars.len(),
+            classes: classes(&chars),
+            uniqueness: if chars.is_empty() { 0.0 } else { distinct.len() as f64 / chars.len() as f64 },
+            pattern_chars: pattern_chars(password, &chars),
+            in_dictionary: dictionaries.into_iter().any(|d| d.contains(password)),
+        }
+    }
+
+    /// Combines the factors into a 0–100 score.
+    pub(crate) fn score(&self, weights: &ScoreWeights) -> u8 {
+        let w = |weight: f64| weight.max(0.0);
+        let points = w(weights.length) * self.length as f64 +
+            w(weights.class_diversity) * self.classes as f64 +
+            w(weights.uniqueness) * self.uniqueness -
+            w(weights.pattern_penalty) * self.pattern_chars as f64 -
+            if self.in_dictionary { w(weights.dictionary_penalty) } else { 0.0 };
+        points.clamp(0.0, 100.0) as u8
+    }
+}
+
+/// Kind of predictable pattern found inside a password.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeakPattern {
+    /// Adjacent keys on a keyboard, e.g. `qwer`.
+    KeyboardWalk,
+    /// One character repeated, e.g. `aaa`.
+    Repeat,
+    /// Characters stepping by one code point, e.g. `abc` or `321`.
+    Sequence,
+    /// The whole password is in a dictionary.
+    Dictionary,
+}
+
+/// Finds runs of three or more characters that repeat or step by one code
+/// point, and the first keyboard walk, as character ranges. Ranges of
+/// different patterns may overlap.
+pub(crate) fn find_patterns(password: &str, chars: &[char]) -> Vec<(WeakPattern, Range<usize>)> {
+    let mut found = Vec::new();
+    for (kind, step) in [(WeakPattern::Repeat, 0i64), (WeakPattern::Sequence, 1), (WeakPattern::Sequence, -1)] {
+        let mut start = 0;
+        for end in 1..=chars.len() {
+            let continues = end < chars.len() && chars[end] as i64 - chars[end - 1] as i64 == step;
+            if !continues {
+                if end - start >= 3 {
+                    found.push((kind, start..end));
+                }
+                start = end;
+            }
+        }
+    }
+    if let Some(walk) = KeyboardWalk::new().find(password) {
+        let start = password[..walk.as_ptr() as usize - password.as_ptr() as usize].chars().count();
+        found.push((WeakPattern::KeyboardWalk, start..start + walk.chars().count()));
+    }
+    found
+}
+
+/// Counts the character classes in `chars`: lowercase, uppercase, digits,
+/// special characters and anything else.
+pub(crate) fn classes(chars: &[char]) -> usize {
+    let has = |f: fn(&char) -> bool| chars.iter().any(f);
+    [
+        has(char::is_ascii_lowercase),
+        has(char::is_ascii_uppercase),
+        has(char::is_ascii_digit),
+        has(|c| SPECIAL_CHARS.contains(c)),
+        has(|c| !c.is_ascii_alphanumeric() && !SPECIAL_CHARS.contains(c)),
+    ]
+    .iter()
+    .filter(|&&present| present)
+    .count()
+}
+
+/// Counts the characters covered by a keyboard walk, or by a run of three
+/// or more characters that repeat or step by one code point.
+fn pattern_chars(password: &str, chars: &[char]) -> usize {
+    let mut covered = vec![false; chars.len()];
+    let mut walk = 0;
+    for (kind, range) in find_patterns(password, chars) {
+        match kind {
+            WeakPattern::KeyboardWalk => walk = range.len(),
+            _ => covered[range].iter_mut().for_each(|c| *c = true),
+        }
+    }
+    covered.iter().filter(|&&c| c).count().max(walk)
+}
+
+/// Upper bounds, in log10 guesses, of zxcvbn scores 0 to 3; anything at or
+/// above the last bound scores 4.
+pub(crate) const ZXCVBN_BOUNDS: [f64; 4] = [3.0, 6.0, 8.0, 10.0];
+
+/// Maps log10 guesses onto zxcvbn's 0–4 scale.
+pub(crate) fn zxcvbn_bucket(log10_guesses: f64) -> u8 {
+    ZXCVBN_BOUNDS.iter().take_while(|&&bound| log10_guesses >= bound).count() as u8
+}
+
+/// Maps the estimate onto 0–100, one point per bit.
+pub(crate) fn score(password: &str, estimator: Estimator) -> u8 {
+    estimator.bits(password).min(100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entropy_uses_pool_of_present_classes() {
+        assert_eq!(entropy(""), 0.0);
+        assert!((entropy("abcd") - 4.0 * 26f64.log2()).abs() < 1e-9);
+        assert!((entropy("aB3!") - 4.0 * 92f64.log2()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shannon_entropy_edge_cases() {
+        assert_eq!(shannon_entropy_per_char(""), 0.0);
+        assert_eq!(shannon_entropy(""), 0.0);
+        assert_eq!(shannon_entropy_per_char("aaaaaaaa"), 0.0);
+        assert_eq!(shannon_entropy("aaaaaaaa"), 0.0);
+        assert_eq!(shannon_entropy_per_char("abcd"), 2.0);
+        assert_eq!(shannon_entropy("abcd"), 8.0);
+        assert_eq!(shannon_entropy_per_char("ab!Z3xé9"), 3.0);
+        assert_eq!(shannon_entropy("ab!Z3xé9"), 24.0);
+        assert_eq!(shannon_entropy_per_char("aabb"), 1.0);
+    }
+
+    #[test]
+    fn min_estimator_takes_the_weaker_estimate() {
+        let repetitive = "aaaaaaaB1!";
+        assert!(Estimator::Shannon.bits(repetitive) < Estimator::Pool.bits(repetitive));
+        assert_eq!(Estimator::Min.bits(repetitive), Estimator::Shannon.bits(repetitive));
+        assert_eq!(Estimator::Min.bits("abababab"), Estimator::Shannon.bits("abababab"));
+        let ideographs: String = ('\u{4E00}'..'\u{4E78}').collect();
+        assert_eq!(Estimator::Min.bits(&ideographs), Estimator::Pool.bits(&ideographs));
+        for estimator in [Estimator::Pool, Estimator::Shannon, Estimator::Min] {
+            assert_eq!(Estimator::from_name(estimator.name()), Some(estimator));
+        }
+    }
+
+    #[test]
+    fn factors_measure_the_password() {
+        let factors = Factors::of("aaab1234Qwer", [&Dictionary::from(["password"])]);
+        assert_eq!(factors.length, 12);
+        assert_eq!(factors.classes, 3);
+        assert_eq!(factors.uniqueness, 10.0 / 12.0);
+        assert_eq!(factors.pattern_chars, 7);
+        assert!(!factors.in_dictionary);
+        assert!(Factors::of("password", [&Dictionary::from(["password"])]).in_dictionary);
+        assert_eq!(Factors::of("", []).score(&ScoreWeights::default()), 0);
+    }
+
+    #[test]
+    fn weighted_score_is_monotone_in_length() {
+        let mut rng = crate::arbitrary::Seeded::new(0x9E37_79B9_7F4A_7C15);
+        let mut next = move |bound: f64| rng.fraction() * bound;
+        for _ in 0..2_000 {
+            let weights = ScoreWeights {
+                length: next(40.0) - 10.0,
+                class_diversity: next(40.0) - 10.0,
+                uniqueness: next(60.0) - 10.0,
+                pattern_penalty: next(20.0) - 5.0,
+                dictionary_penalty: next(100.0) - 20.0,
+            };
+            let factors = Factors {
+                length: next(40.0) as usize,
+                classes: next(6.0) as usize,
+                uniqueness: next(1.0),
+                pattern_chars: next(10.0) as usize,
+                in_dictionary: next(1.0) < 0.5,
+            };
+            let longer = Factors { length: factors.length + 1 + next(10.0) as usize, ..factors };
+            assert!(longer.score(&weights) >= factors.score(&weights), "{:?} {:?}", weights, factors);
+        }
+    }
+
+    #[test]
+    fn reweighting_changes_the_ranking() {
+        let long_plain = Factors::of("correcthorsebatterystaple", []);
+        let short_mixed = Factors::of("Tr0ub4d&3", []);
+        let by_default = ScoreWeights::default();
+        let by_length = ScoreWeights { length: 4.0, class_diversity: 1.0, uniqueness: 5.0, ..by_default };
+        let by_diversity = ScoreWeights { length: 1.0, class_diversity: 15.0, uniqueness: 20.0, ..by_default };
+        assert!(long_plain.score(&by_length) > short_mixed.score(&by_length));
+        assert!(long_plain.score(&by_diversity) < short_mixed.score(&by_diversity));
+    }
+
+    #[test]
+    fn zxcvbn_buckets_follow_the_bounds() {
+        assert_eq!(zxcvbn_bucket(0.0), 0);
+        assert_eq!(zxcvbn_bucket(2.99), 0);
+        assert_eq!(zxcvbn_bucket(3.0), 1);
+        assert_eq!(zxcvbn_bucket(7.5), 2);
+        assert_eq!(zxcvbn_bucket(9.0), 3);
+        assert_eq!(zxcvbn_bucket(10.0), 4);
+        assert_eq!(zxcvbn_bucket(f64::INFINITY), 4);
+    }
+
+    #[test]
+    fn score_is_capped() {
+        assert_eq!(score("", Estimator::Pool), 0);
+        assert_eq!(score("abcd", Estimator::Pool), 18);
+        assert_eq!(score(&"aB3!".repeat(10), Estimator::Pool), 100);
+    }
+
+    #[cfg(feature = "markov")]
+    #[test]
+    fn markov_estimator_penalizes_human_patterns() {
+        assert_eq!(Estimator::Pool.bits("Monkey123!"), Estimator::Pool.bits("r8#Kq2!vPz"));
+        assert!(Estimator::Markov.bits("Monkey123!") + 20.0 < Estimator::Markov.bits("r8#Kq2!vPz"));
+        assert_eq!(Estimator::from_name("markov"), Some(Estimator::Markov));
+    }
+}
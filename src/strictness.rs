@@ -0,0 +1,307 @@
+//! Structural comparison of two policies.
+
+use std::collections::HashSet;
+
+use crate::dictionary::DictionarySource;
+use crate::{Estimator, KeyboardWalk, MatchMode, PasswordChecker, Rule, SpecialCharPolicy};
+
+/// Outcome of [`PasswordChecker::is_at_least_as_strict_as`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrictnessResult {
+    /// Every comparable requirement is at least as strict, and one is
+    /// stricter or new.
+    Stricter,
+    /// The policies require the same things.
+    Equal,
+    /// These requirements were relaxed.
+    Weaker(Vec<Relaxation>),
+    /// Requirements were relaxed, but the policy also gained rules whose
+    /// effect cannot be compared and might make up for it.
+    Incomparable,
+}
+
+/// One requirement the new policy relaxes compared to the old one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Relaxation {
+    MinLength { was: usize, now: usize },
+    MaxLength { was: Option<usize>, now: Option<usize> },
+    /// Fewer of the old blacklist entries are still rejected.
+    BlacklistEntries { missing: usize },
+    KeyboardWalkLength { was: usize, now: usize },
+    MinEntropy { estimator: Estimator, was: f64, now: f64 },
+    /// A rule of the old policy has no counterpart at least as strict,
+    /// identified by its [`code`](Rule::code).
+    RuleRemoved(&'static str),
+}
+
+impl PasswordChecker<'_> {
+    /// Compares this policy with `other`, e.g. an edited policy with the
+    /// one it replaces, requirement by requirement.
+    ///
+    /// Comparable requirements are the length limits, the required
+    /// character classes, blacklist entries, the run length of keyboard
+    /// walks with the same settings, entropy minimums with the same
+    /// estimator, `length_or_entropy` thresholds, and patterns and
+    /// context rules with the same settings. This does not decide whether
+    /// one set of rules implies the other in general: a pattern or entropy
+    /// rule without a counterpart only counts as an added requirement, so
+    /// the result is
+    /// [`Incomparable`](StrictnessResult::Incomparable) when such a rule
+    /// comes with a relaxation.
+    pub fn is_at_least_as_strict_as(&self, other: &PasswordChecker<'_>) -> StrictnessResult {
+        let mut cmp = Comparison::default();
+        cmp.lengths(self, other);
+        cmp.classes(self, other);
+        cmp.blacklists(self, other);
+        cmp.patterns(self, other);
+        cmp.keyboard_walks(self, other);
+        cmp.entropy(self, other);
+        cmp.length_or_entropy(self, other);
+        if !cmp.relaxations.is_empty() {
+            if cmp.opaque { StrictnessResult::Incomparable } else { StrictnessResult::Weaker(cmp.relaxations) }
+        } else if cmp.stricter || cmp.opaque {
+            StrictnessResult::Stricter
+        } else {
+            StrictnessResult::Equal
+        }
+    }
+}
+
+#[derive(Default)]
+struct Comparison {
+    stricter: bool,
+    /// Whether the new policy has rules outside the comparable subset.
+    opaque: bool,
+    relaxations: Vec<Relaxation>,
+}
+
+impl Comparison {
+    /// Records that `new` relates to `old` in `ordering`, where greater is
+    /// stricter.
+    fn order(&mut self, ordering: Option<std::cmp::Ordering>, relaxation: impl FnOnce() -> Relaxation) {
+        match ordering {
+            Some(std::cmp::Ordering::Greater) => self.stricter = true,
+            Some(std::cmp::Ordering::Less) => self.relaxations.push(relaxation()),
+            _ => {}
+        }
+    }
+
+    fn lengths(&mut self, new: &PasswordChecker<'_>, old: &PasswordChecker<'_>) {
+        let min = |c: &PasswordChecker<'_>| rules(c, |r| if let Rule::MinLength(n, _) = r { Some(*n) } else { None }).max().unwrap_or(0);
+        let (was, now) = (min(old), min(new));
+        self.order(now.partial_cmp(&was), || Relaxation::MinLength { was, now });
+
+        let max = |c: &PasswordChecker<'_>| rules(c, |r| if let Rule::MaxLength(n, _) | Rule::MaxBytes(n, _) = r { Some(*n) } else { None }).min();
+        let (was, now) = (max(old), max(new));
+        // A lower maximum, or a maximum where there was none, is stricter.
+        let ordering = match (now, was) {
+            (Some(now), Some(was)) => was.partial_cmp(&now),
+            (Some(_), None) => Some(std::cmp::Ordering::Greater),
+            (None, Some(_)) => Some(std::cmp::Ordering::Less),
+            (None, None) => None,
+        };
+        self.order(ordering, || Relaxation::MaxLength { was, now });
+    }
+
+    fn classes(&mut self, new: &PasswordChecker<'_>, old: &PasswordChecker<'_>) {
+        let codes = [
+            "require_upper_lower",
+            "require_number",
+            "require_special_char",
+            "forbid_unsafe_chars",
+            "not_all_numeric",
+            "not_all_alphabetic",
+            "digits_only",
+            "not_sequential_digits",
+            "not_repeated_char",
+            "not_palindrome",
+            "no_phone_patterns",
+            "not_contain_phone_number",
+            "not_contain_date_of_birth",
+            "single_script_only",
+            "not_email_or_url",
+        ];
+        for code in codes {
+            // Other special-character sets are compared as opaque rules.
+            let has = |c: &PasswordChecker<'_>| {
+                c.rules.iter().any(|r| r.code() == code && !matches!(r, Rule::RequireSpecialChar(policy, _) if *policy != SpecialCharPolicy::Builtin))
+            };
+            self.order(has(new).partial_cmp(&has(old)), || Relaxation::RuleRemoved(code));
+        }
+    }
+
+    fn blacklists(&mut self, new: &PasswordChecker<'_>, old: &PasswordChecker<'_>) {
+        let entries = |c: &PasswordChecker<'_>| -> HashSet<(MatchMode, String)> {
+            let mut entries = HashSet::new();
+            for rule in &c.rules {
+                if let Rule::Blacklist(words, _) = rule {
+                    match words.source() {
+                        DictionarySource::Memory(_) => entries.extend(words.iter().map(|w| (words.mode(), w.to_string()))),
+                        DictionarySource::SortedFile(file) => {
+                            entries.insert((words.mode(), format!("\0file:{}", file.path().display())));
+                        }
+                    }
+                }
+            }
+            entries
+        };
+        let (was, now) = (entries(old), entries(new));
+        let missing = was.difference(&now).count();
+        if missing > 0 {
+            self.relaxations.push(Relaxation::BlacklistEntries { missing });
+        }
+        if now.difference(&was).next().is_some() {
+            self.stricter = true;
+        }
+    }
+
+    fn patterns(&mut self, new: &PasswordChecker<'_>, old: &PasswordChecker<'_>) {
+        let sources = |c: &PasswordChecker<'_>| -> HashSet<(&'static str, String)> {
+            rules(c, |r| match r {
+                Rule::MustMatch(p, _) | Rule::MustNotMatch(p, _) => Some((r.code(), p.as_str().to_string())),
+                Rule::NotSimilarToPrevious(similarity, _) => Some((r.code(), format!("{:?}", similarity))),
+                Rule::NotContainUserInfo(check, _) => Some((r.code(), format!("{:?}", check))),
+                Rule::ServiceNames(names, _) => Some((r.code(), format!("{:?}", names))),
+                Rule::MaxClassRun(len, _) => Some((r.code(), len.to_string())),
+                Rule::MinWords(n, separators, _) => Some((r.code(), format!("{} {:?}", n, separators))),
+                // Only the code says anything about a closure.
+                Rule::Custom(..) => Some((r.code(), String::new())),
+                Rule::Blacklist(words, _) if words.fuzzy_distance() > 0 => Some((r.code(), format!("fuzzy {}", words.fuzzy_distance()))),
+                Rule::RequireSpecialChar(policy, _) if *policy != SpecialCharPolicy::Builtin => Some((r.code(), format!("{:?}", policy))),
+                _ => None,
+            })
+            .collect()
+        };
+        let (was, now) = (sources(old), sources(new));
+        for (code, _) in was.difference(&now) {
+            self.relaxations.push(Relaxation::RuleRemoved(code));
+        }
+        self.opaque |= now.difference(&was).next().is_some();
+    }
+
+    fn keyboard_walks(&mut self, new: &PasswordChecker<'_>, old: &PasswordChecker<'_>) {
+        let walks = |c: &PasswordChecker<'_>| -> Vec<KeyboardWalk> {
+            rules(c, |r| if let Rule::KeyboardWalk(w, _) = r { Some(w.clone()) } else { None }).collect()
+        };
+        let same_settings = |a: &KeyboardWalk, b: &KeyboardWalk| KeyboardWalk { min_length: b.min_length, ..a.clone() } == *b;
+        let (was, now) = (walks(old), walks(new));
+        for old_walk in &was {
+            // A shorter run length catches more walks.
+            match now.iter().filter(|w| same_settings(w, old_walk)).map(|w| w.min_length).min() {
+                Some(len) => self.order(old_walk.min_length.partial_cmp(&len), || Relaxation::KeyboardWalkLength {
+                    was: old_walk.min_length,
+                    now: len,
+                }),
+                None => self.relaxations.push(Relaxation::RuleRemoved("keyboard_walk")),
+            }
+        }
+        self.opaque |= now.iter().any(|w| !was.iter().any(|old_walk| same_settings(w, old_walk)));
+    }
+
+    fn entropy(&mut self, new: &PasswordChecker<'_>, old: &PasswordChecker<'_>) {
+        let minimums = |c: &PasswordChecker<'_>| -> Vec<(Estimator, f64)> {
+            rules(c, |r| if let Rule::MinEntropy(bits, e, _) = r { Some((*e, *bits)) } else { None }).collect()
+        };
+        let highest = |list: &[(Estimator, f64)], estimator| {
+            list.iter().filter(|(e, _)| *e == estimator).map(|&(_, bits)| bits).reduce(f64::max)
+        };
+        let (was, now) = (minimums(old), minimums(new));
+        for &(estimator, _) in &was {
+            let old_bits = highest(&was, estimator).unwrap_or_default();
+            match highest(&now, estimator) {
+                Some(bits) => {
+                    self.order(bits.partial_cmp(&old_bits), || Relaxation::MinEntropy { estimator, was: old_bits, now: bits })
+                }
+                None => self.relaxations.push(Relaxation::RuleRemoved("min_entropy")),
+            }
+        }
+        self.relaxations.dedup();
+        self.opaque |= now.iter().any(|(e, _)| highest(&was, *e).is_none());
+    }
+
+    fn length_or_entropy(&mut self, new: &PasswordChecker<'_>, old: &PasswordChecker<'_>) {
+        let first = |c: &PasswordChecker<'_>| {
+            c.rules.iter().find_map(|r| match r {
+                Rule::LengthOrEntropy { min_len_strong, min_entropy_bits, min_len_fallback, .. } => {
+                    Some((*min_len_strong as f64, *min_entropy_bits, *min_len_fallback as f64))
+                }
+                _ => None,
+            })
+        };
+        match (first(new), first(old)) {
+            (Some(now), Some(was)) => {
+                let parts = [(now.0, was.0), (now.1, was.1), (now.2, was.2)];
+                if parts.iter().all(|(n, w)| n >= w) {
+                    self.stricter |= parts.iter().any(|(n, w)| n > w);
+                } else {
+                    self.relaxations.push(Relaxation::RuleRemoved("length_or_entropy"));
+                    self.opaque |= parts.iter().any(|(n, w)| n > w);
+                }
+            }
+            (Some(_), None) => self.opaque = true,
+            (None, Some(_)) => self.relaxations.push(Relaxation::RuleRemoved("length_or_entropy")),
+            (None, None) => {}
+        }
+    }
+}
+
+fn rules<'c, T>(checker: &'c PasswordChecker<'_>, f: impl FnMut(&'c Rule<'_>) -> Option<T> + 'c) -> impl Iterator<Item = T> + 'c {
+    checker.rules.iter().filter_map(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pattern;
+
+    fn base() -> PasswordChecker<'static> {
+        PasswordChecker::new().min_length(8, None).require_number(None).blacklist(["password", "qwerty"], None)
+    }
+
+    #[test]
+    fn equal_and_stricter() {
+        assert_eq!(base().is_at_least_as_strict_as(&base()), StrictnessResult::Equal);
+        let longer = PasswordChecker::new().min_length(12, None).require_number(None).blacklist(["password", "qwerty"], None);
+        assert_eq!(longer.is_at_least_as_strict_as(&base()), StrictnessResult::Stricter);
+        let more = base().require_special_char(None).blacklist(["letmein"], None).max_length(64, None);
+        assert_eq!(more.is_at_least_as_strict_as(&base()), StrictnessResult::Stricter);
+        let pattern = base().must_not_match(Pattern::new("\\d{4}").unwrap(), None);
+        assert_eq!(pattern.is_at_least_as_strict_as(&base()), StrictnessResult::Stricter);
+    }
+
+    #[test]
+    fn weaker_lists_relaxations() {
+        let relaxed = PasswordChecker::new().min_length(6, None).blacklist(["password"], None);
+        assert_eq!(
+            relaxed.is_at_least_as_strict_as(&base()),
+            StrictnessResult::Weaker(vec![
+                Relaxation::MinLength { was: 8, now: 6 },
+                Relaxation::RuleRemoved("require_number"),
+                Relaxation::BlacklistEntries { missing: 1 },
+            ])
+        );
+        let old = PasswordChecker::new().keyboard_walk(KeyboardWalk::new().min_length(4), None).max_length(20, None);
+        let new = PasswordChecker::new().keyboard_walk(KeyboardWalk::new().min_length(6), None);
+        assert_eq!(
+            new.is_at_least_as_strict_as(&old),
+            StrictnessResult::Weaker(vec![
+                Relaxation::MaxLength { was: Some(20), now: None },
+                Relaxation::KeyboardWalkLength { was: 4, now: 6 },
+            ])
+        );
+        let old = PasswordChecker::new().min_entropy(60.0, Estimator::Pool, None);
+        let new = PasswordChecker::new().min_entropy(50.0, Estimator::Pool, None);
+        assert_eq!(
+            new.is_at_least_as_strict_as(&old),
+            StrictnessResult::Weaker(vec![Relaxation::MinEntropy { estimator: Estimator::Pool, was: 60.0, now: 50.0 }])
+        );
+    }
+
+    #[test]
+    fn different_rule_kinds_are_incomparable() {
+        let regex = PasswordChecker::new().must_match(Pattern::new("^\\S{10,}$").unwrap(), None);
+        let entropy = PasswordChecker::new().min_entropy(50.0, Estimator::Pool, None);
+        assert_eq!(regex.is_at_least_as_strict_as(&entropy), StrictnessResult::Incomparable);
+        assert_eq!(entropy.is_at_least_as_strict_as(&regex), StrictnessResult::Incomparable);
+    }
+}
@@ -0,0 +1,335 @@
+//! Example passwords for testing code built on a policy.
+//!
+//! [`satisfying`] finds a password that passes a checker and [`violating`]
+//! one that breaks exactly one kind of rule, so tests can assert on error
+//! codes without hand-crafting inputs that go stale as the policy changes.
+//!
+//! ```
+//! use passcheck::PasswordChecker;
+//! use passcheck::testing::violating;
+//!
+//! let checker = PasswordChecker::new().min_length(12, None).require_number(None);
+//! let password = violating("require_number", &checker).unwrap();
+//! assert_eq!(checker.check(&password).unwrap_err().codes().collect::<Vec<_>>(), ["require_number"]);
+//! ```
+//!
+//! [`monotonicity_counterexample`] searches for a password that appending a
+//! rule would let through, which must never happen.
+//!
+//! All three search a deterministic set of candidates: random mixes of
+//! character classes at lengths the policy cares about, low-variety
+//! strings, blacklisted words, keyboard walks and examples of the
+//! configured patterns. The same checker always yields the same password.
+
+use std::fmt;
+
+use crate::arbitrary::Seeded;
+use crate::dictionary::DictionarySource;
+use crate::{KeyboardLayout, PasswordChecker, Rule, SPECIAL_CHARS, SpecialCharPolicy};
+
+/// Error returned by [`satisfying`] and [`violating`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestingError {
+    /// The checker has no rule with this code.
+    UnknownRule(String),
+    /// No candidate breaks this rule while passing the others, usually
+    /// because the rules overlap, e.g. a minimum entropy that only long
+    /// passwords reach next to a minimum length.
+    NoSingleViolation(String),
+    /// No candidate passes every rule.
+    Unsatisfiable,
+}
+
+impl fmt::Display for TestingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestingError::UnknownRule(code) => write!(f, "the policy has no `{}` rule", code),
+            TestingError::NoSingleViolation(code) => {
+                write!(f, "no password breaks the `{}` rule without breaking another", code)
+            }
+            TestingError::Unsatisfiable => f.write_str("no password passes the policy"),
+        }
+    }
+}
+
+impl std::error::Error for TestingError {}
+
+/// Returns a password that passes `checker`.
+pub fn satisfying(checker: &PasswordChecker<'_>) -> Result<String, TestingError> {
+    candidates(checker)
+        .into_iter()
+        .find(|password| checker.failed_rules(password).next().is_none())
+        .ok_or(TestingError::Unsatisfiable)
+}
+
+/// Returns a password that breaks the rules with code `code`, such as
+/// `"min_length"`, and passes every other rule of `checker`.
+pub fn violating(code: &str, checker: &PasswordChecker<'_>) -> Result<String, TestingError> {
+    if !checker.rules.iter().any(|rule| rule.code() == code) {
+        return Err(TestingError::UnknownRule(code.to_string()));
+    }
+    candidates(checker)
+        .into_iter()
+        .find(|password| {
+            let mut failed = checker.failed_rules(password).peekable();
+            failed.peek().is_some() && failed.all(|rule| rule.code() == code)
+        })
+        .ok_or_else(|| TestingError::NoSingleViolation(code.to_string()))
+}
+
+/// Returns a password that `checker` rejects but accepts once `rule` is
+/// appended with [`Extend`], if any candidate is one.
+///
+/// Appending a rule must never let a password through: a rule can only
+/// add violations, and a rule bringing a second special set is refused
+/// with a panic rather than changing the earlier ones. Any password
+/// returned is therefore a bug. This crate's tests check the property on
+/// policies from [`arbitrary`](crate::arbitrary), with groups, [fail-fast](PasswordChecker::fail_fast),
+/// rules needing a [`UserContext`](crate::UserContext) and rules that
+/// only warn, such as
+/// [`recommended_length`](PasswordChecker::recommended_length). Modifiers
+/// that relax a checker are not rules and are exempt: turning on
+/// [`length_exemption`](PasswordChecker::length_exemption) or
+/// [`dry_run`](PasswordChecker::dry_run) accepts passwords that were
+/// rejected before, while rules appended to a checker with them set are
+/// still covered. Observers are not called.
+pub fn monotonicity_counterexample<'a>(checker: &PasswordChecker<'a>, rule: Rule<'a>) -> Option<String> {
+    monotonicity_counterexample_with(checker, |mut checker| {
+        checker.extend([rule]);
+        checker
+    })
+}
+
+/// Like [`monotonicity_counterexample`], with `add` adding rules with
+/// builder methods such as
+/// [`require_special_char_with`](PasswordChecker::require_special_char_with).
+/// `add` should only add rules; a relaxing modifier gives counterexamples
+/// by design.
+pub fn monotonicity_counterexample_with<'a>(checker: &PasswordChecker<'a>, add: impl FnOnce(PasswordChecker<'a>) -> PasswordChecker<'a>) -> Option<String> {
+    let base = PasswordChecker { observers: Default::default(), ..checker.clone() };
+    let extended = add(base.clone());
+    let mut passwords = candidates(&base);
+    passwords.extend(candidates(&extended));
+    passwords.extend(["abc€".to_string(), "pass word".to_string()]);
+    passwords.into_iter().find(|password| base.check(password).is_err() && extended.check(password).is_ok())
+}
+
+const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+/// Characters outside the ASCII classes, including whitespace.
+const OTHER: &str = " é";
+const SEEDS: u64 = 4;
+const MAX_LEN: usize = 512;
+const WALKS: [&str; 6] = ["qwertyuiop", "asdfghjkl", "zxcvbnm", "1234567890", "azertyuiop", "qwertzuiop"];
+
+fn candidates(checker: &PasswordChecker<'_>) -> Vec<String> {
+    let mut lengths = vec![0, 1, 2, 3, 4, 6, 8, 10, 12, 16, 20, 24, 32, 48, 64];
+    let mut words = Vec::new();
+    let mut inserts = Vec::new();
+    for rule in &checker.rules {
+        match rule {
+            Rule::MinLength(n, _) | Rule::MaxLength(n, _) | Rule::MaxBytes(n, _) | Rule::MaxClassRun(n, _) => {
+                lengths.extend([n.saturating_sub(1), *n, n + 1])
+            }
+            Rule::LengthOrEntropy { min_len_strong, min_len_fallback, .. } => {
+                for n in [*min_len_strong, *min_len_fallback] {
+                    lengths.extend([n.saturating_sub(1), n, n + 1]);
+                }
+            }
+            Rule::Blacklist(dictionary, _) => {
+                if let DictionarySource::Memory(_) = dictionary.source() {
+                    words.extend(dictionary.iter().map(str::to_string));
+                }
+            }
+            Rule::MustMatch(pattern, _) | Rule::MustNotMatch(pattern, _) => inserts.extend(pattern.example()),
+            Rule::ForbidUnsafeChars(_) => inserts.push("\0".to_string()),
+            Rule::SingleScriptOnly(_) => inserts.push("ж".to_string()),
+            Rule::NotEmailOrUrl(_) => inserts.push("www.".to_string()),
+            Rule::NotSequentialDigits(_) => inserts.push("34567".to_string()),
+            Rule::NotRepeatedChar(_) => inserts.push("77777".to_string()),
+            Rule::NotPalindrome(_) => inserts.push("13531".to_string()),
+            Rule::MinWords(n, _, _) => inserts.push(vec!["xy"; *n].join(" ")),
+            Rule::ServiceNames(names, _) => inserts.extend(names.iter().cloned()),
+            Rule::KeyboardWalk(walk, _) => {
+                lengths.extend([walk.min_length.saturating_sub(1), walk.min_length]);
+                for layout in &walk.layouts {
+                    inserts.extend(chain(layout, walk.min_length));
+                }
+                inserts.extend(WALKS.iter().map(|w| w.chars().take(walk.min_length.max(1)).collect::<String>()));
+            }
+            _ => {}
+        }
+    }
+    lengths.retain(|&n| n <= MAX_LEN);
+    lengths.sort_unstable();
+    lengths.dedup();
+
+    // An explicit special-character set replaces the builtin one.
+    let special = checker.rules.iter().find_map(|rule| match rule {
+        Rule::RequireSpecialChar(SpecialCharPolicy::Chars(chars), _) => Some(chars.clone()),
+        _ => None,
+    });
+    let special = special.unwrap_or_else(|| SPECIAL_CHARS.iter().collect());
+    let classes = [LOWER, UPPER, DIGITS, &special, OTHER];
+    let mut out = Vec::new();
+    for &len in &lengths {
+        for mask in 1..1u32 << classes.len() {
+            for seed in 0..SEEDS {
+                out.push(random(len, mask, &classes, seed));
+            }
+            // Low variety: one character of each class, repeated.
+            let unit: Vec<char> = classes.iter().enumerate().filter(|(i, _)| mask & 1 << i != 0).map(|(_, c)| c.chars().next().unwrap()).collect();
+            out.push(unit.iter().cycle().take(len).collect());
+        }
+    }
+    out.extend(words.iter().cloned());
+    out.extend(inserts.iter().cloned());
+    // The first four classes, so bases carry no whitespace.
+    let bases: Vec<String> = lengths.iter().flat_map(|&len| (0..SEEDS).map(move |seed| (len, seed))).map(|(len, seed)| random(len, 0b1111, &classes, seed)).collect();
+    for base in &bases {
+        for insert in words.iter().chain(&inserts) {
+            out.push(format!("{}{}", insert, base));
+            out.push(format!("{}{}", base, insert));
+        }
+    }
+    out
+}
+
+/// A `len`-character string over the classes in `mask`, with one of each
+/// when it fits, from a generator seeded by its inputs.
+fn random(len: usize, mask: u32, classes: &[&str], seed: u64) -> String {
+    let mut rng = Seeded::new(0x9E37_79B9_7F4A_7C15 ^ (len as u64) << 32 ^ u64::from(mask) << 8 ^ seed);
+    let mut next = move || rng.next_u64();
+    let chosen: Vec<Vec<char>> = classes.iter().enumerate().filter(|(i, _)| mask & 1 << i != 0).map(|(_, c)| c.chars().collect()).collect();
+    let all: Vec<char> = chosen.concat();
+    let mut chars: Vec<char> = (0..len).map(|_| all[next() as usize % all.len()]).collect();
+    if len >= chosen.len() {
+        for (i, class) in chosen.iter().enumerate() {
+            let pos = (next() as usize % (len / chosen.len())) * chosen.len() + i;
+            chars[pos] = class[next() as usize % class.len()];
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// A walk of `len` keys on `layout`, following the first unvisited
+/// neighbor from the first key that gets that far.
+fn chain(layout: &KeyboardLayout, len: usize) -> Option<String> {
+    let adjacency = layout.adjacency();
+    adjacency.iter().find_map(|&(start, _)| {
+        let mut walk = vec![start];
+        while walk.len() < len {
+            let last = *walk.last()?;
+            let (_, neighbors) = adjacency.iter().find(|&&(c, _)| c == last)?;
+            walk.push(neighbors.chars().find(|n| !walk.contains(n))?);
+        }
+        Some(walk.into_iter().collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dictionary, Estimator, KeyboardWalk, Pattern, UserInfoCheck};
+
+    fn assert_breaks_only(checker: &PasswordChecker<'_>, code: &str) {
+        let password = violating(code, checker).unwrap_or_else(|err| panic!("{}", err));
+        let codes: Vec<_> = checker.failed_rules(&password).map(Rule::code).collect();
+        assert_eq!(codes, [code], "{:?}", password);
+    }
+
+    #[test]
+    fn breaks_each_rule_alone() {
+        let checker = PasswordChecker::new()
+            .min_length(10, None)
+            .max_length(24, None)
+            .require_upper_lower(None)
+            .require_number(None)
+            .require_special_char(None)
+            .blacklist(["Password123!", "letmein"], None)
+            .must_match(Pattern::new("^\\S+$").unwrap(), None)
+            .must_not_match(Pattern::new("(?:19|20)\\d{2}").unwrap(), None)
+            .keyboard_walk(KeyboardWalk::new(), None)
+            .forbid_unsafe_chars(None);
+        for rule in &checker.rules {
+            assert_breaks_only(&checker, rule.code());
+        }
+        assert!(checker.validate(&satisfying(&checker).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn breaks_strength_rules_alone() {
+        let checker = PasswordChecker::new().min_length(8, None).min_entropy(20.0, Estimator::Shannon, None);
+        assert_breaks_only(&checker, "min_entropy");
+        let checker = PasswordChecker::new().require_number(None).length_or_entropy(10, 60.0, 16, None);
+        assert_breaks_only(&checker, "length_or_entropy");
+        assert_breaks_only(&checker, "require_number");
+        let checker = PasswordChecker::new().min_length(10, None).require_upper_lower(None).max_class_run(6, None);
+        assert_breaks_only(&checker, "max_class_run");
+    }
+
+    #[test]
+    fn reports_impossible_requests() {
+        // Seven characters carry at most log2(7) bits each.
+        let checker = PasswordChecker::new().min_length(8, None).min_entropy(20.0, Estimator::Shannon, None);
+        assert_eq!(violating("min_length", &checker), Err(TestingError::NoSingleViolation("min_length".into())));
+        assert_eq!(violating("require_number", &checker), Err(TestingError::UnknownRule("require_number".into())));
+        let contradiction = PasswordChecker::new().min_length(10, None).max_length(5, None);
+        assert_eq!(satisfying(&contradiction), Err(TestingError::Unsatisfiable));
+    }
+
+    #[test]
+    #[should_panic(expected = "one special set")]
+    fn widening_the_special_set_is_refused() {
+        // Rewriting the builtin rule to Unicode categories used to accept
+        // "abc€", which the builtin set rejects.
+        let base = PasswordChecker::new().require_special_char(None);
+        assert!(base.check("abc€").is_err());
+        let _ = monotonicity_counterexample_with(&base, |c| c.require_special_char_with(SpecialCharPolicy::UnicodeCategories, None));
+    }
+
+    #[test]
+    fn adding_the_same_special_set_is_monotonic() {
+        let base = PasswordChecker::new().require_special_char_with(SpecialCharPolicy::UnicodeCategories, None).min_length(4, None);
+        assert!(base.check("abc€").is_ok() && base.check("abcd").is_err());
+        assert_eq!(monotonicity_counterexample_with(&base, |c| c.require_special_char(None)), None);
+        assert_eq!(monotonicity_counterexample_with(&base, |c| c.require_special_char_with(SpecialCharPolicy::UnicodeCategories, None)), None);
+    }
+
+    #[test]
+    fn relaxing_modifiers_are_exempt_but_keep_appends_monotonic() {
+        let base = PasswordChecker::new().min_length(12, None).require_number(None);
+        let passphrase = "sunflowers in the field";
+        // The modifiers themselves accept what was rejected.
+        assert!(base.check(passphrase).is_err());
+        assert!(base.clone().length_exemption(20).check(passphrase).is_ok());
+        assert!(base.clone().dry_run(true).check("short").is_ok());
+        // Rules appended under them only ever reject more.
+        let exempt = base.clone().length_exemption(20);
+        assert_eq!(monotonicity_counterexample(&exempt, Rule::RequireSpecialChar(SpecialCharPolicy::Builtin, None)), None);
+        let mut blacklisted = exempt.clone();
+        blacklisted.extend([Rule::Blacklist(Dictionary::from([passphrase]), None)]);
+        assert!(blacklisted.check(passphrase).is_err());
+        assert_eq!(monotonicity_counterexample(&base.clone().dry_run(true), Rule::MinLength(16, None)), None);
+    }
+
+    #[test]
+    fn tricky_constructs_stay_monotonic() {
+        let base = PasswordChecker::new().min_length(8, None);
+        // Members of a group, and rules after one, add violations.
+        let grouped = base.clone().group("Mix it up.", |g| g.require_number(None).require_upper_lower(None));
+        assert_eq!(monotonicity_counterexample(&grouped, Rule::RequireNumber(None)), None);
+        // Fail-fast only stops reporting.
+        assert_eq!(monotonicity_counterexample(&base.clone().fail_fast(true), Rule::RequireNumber(None)), None);
+        // Warnings never fail a check.
+        assert_eq!(monotonicity_counterexample(&base, Rule::RecommendedLength(16, None)), None);
+        // A rule needing a context fails without one rather than passing.
+        let with_context = base.clone().not_contain_user_info(UserInfoCheck::new(), None);
+        assert!(with_context.check("sunflowers").is_err());
+        assert_eq!(monotonicity_counterexample(&base, Rule::NotContainUserInfo(UserInfoCheck::new(), None)), None);
+        // Either half of length_or_entropy passing still leaves the others.
+        let either = PasswordChecker::new().require_number(None);
+        assert_eq!(monotonicity_counterexample(&either, Rule::LengthOrEntropy { min_len_strong: 8, min_entropy_bits: 30.0, min_len_fallback: 12, msg: None }), None);
+    }
+}
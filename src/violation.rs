@@ -0,0 +1,543 @@
+//! Structured validation failures.
+//!
+//! Violations serialize to a stable JSON shape, versioned by the top-level
+//! `schema` field of a report ([`Violations::SCHEMA`]):
+//!
+//! ```json
+//! {
+//!   "schema": 1,
+//!   "valid": false,
+//!   "violations": [
+//!     { "code": "min_length", "message": "Password must be at least 8 characters long.", "params": { "min": 8, "actual": 5 } },
+//!     { "code": "require_number", "message": "Password must include at least one number.", "params": {} }
+//!   ]
+//! }
+//! ```
+//!
+//! `code` is a rule code, `message` a string and `params` an object, all
+//! always present. Violations with a [`span`](Violation::span) also have
+//! `"span": {"start": 2, "end": 6}`, in characters: the indices, never
+//! the characters themselves, and members of a
+//! [rule group](crate::PasswordChecker::group) have `"group": 0`. Params are non-negative integers except the
+//! `min_entropy` bits, which may be fractional, `missing` and `scripts`,
+//! arrays of strings, and `shape`, `rule` and `field`, strings. Positions count characters
+//! from 0 in logical order, the order they were typed in, which for
+//! right-to-left text differs from the order they are displayed in.
+//! Fields are only ever added under the same schema version; any other
+//! change bumps it.
+//!
+//! For clients that own their copy, [`Violations::keys`] drops the
+//! messages and [`PasswordChecker::keys_only`](crate::PasswordChecker::keys_only)
+//! skips rendering them. Keys are the codes below; rules not listed have
+//! no params. [Custom rules](crate::CustomRule) report their own code and
+//! params.
+//!
+//! | key | params |
+//! |-----|--------|
+//! | `min_length`, `recommended_length` | `min`, `actual` |
+//! | `require_upper_lower` | `missing` (`["uppercase"]`, `["lowercase"]` or both) |
+//! | `max_length`, `max_bytes` | `max`, `actual` |
+//! | `max_class_run` | `max`, `actual` (longest run) |
+//! | `min_words` | `min`, `actual` (words) |
+//! | `min_entropy` | `min`, `actual` (bits) |
+//! | `forbid_unsafe_chars` | `codepoint`, `offset` (byte), `position` (character) |
+//! | `not_contain_user_info` | `contained_in` (1 if the password is part of the user's info, 0 if it contains it) |
+//! | `no_phone_patterns` | `digits` (longest run) |
+//! | `not_email_or_url` | `shape` (`"email"` or `"url"`) |
+//! | `single_script_only` | `scripts` (lowercase Unicode script names, such as `["latin", "cyrillic"]`) |
+//! | `not_in_history` | `history` (entries kept) |
+//! | `not_breached` | `count` (times seen), or `unavailable` (providers that could not answer) |
+//! | `input_too_large` | `max`, `actual` (bytes) |
+//! | `context_required` | `rule` (code of the rule needing a [`UserContext`](crate::UserContext)) |
+//! | `field_missing` | `field` (`"password"` or `"confirmation"`), from [`validate_form`](crate::forms::validate_form) |
+
+use std::fmt;
+use std::ops::{Deref, Range};
+
+use crate::config::{ConfigError, invalid};
+use crate::json::{self, Value};
+use crate::{Rule, scripts};
+
+/// Codes [`Violation::from_json`] accepts: every rule's plus the ones
+/// added outside rules.
+pub(crate) const CODES: &[&str] = &[
+    "min_length",
+    "max_length",
+    "max_bytes",
+    "recommended_length",
+    "require_upper_lower",
+    "require_number",
+    "require_special_char",
+    "blacklist",
+    "must_match",
+    "must_not_match",
+    "keyboard_walk",
+    "min_entropy",
+    "forbid_unsafe_chars",
+    "not_similar_to_previous",
+    "not_contain_user_info",
+    "service_names",
+    "length_or_entropy",
+    "max_class_run",
+    "min_words",
+    "not_all_numeric",
+    "not_all_alphabetic",
+    "digits_only",
+    "not_sequential_digits",
+    "not_repeated_char",
+    "not_palindrome",
+    "no_phone_patterns",
+    "not_contain_phone_number",
+    "not_contain_date_of_birth",
+    "single_script_only",
+    "not_breached",
+    "not_email_or_url",
+    "not_in_history",
+    "input_too_large",
+    "context_required",
+    "field_missing",
+    "confirmation_mismatch",
+];
+
+/// Parameter names [`Violation::from_json`] accepts.
+const PARAMS: &[&str] = &["min", "max", "actual", "codepoint", "offset", "contained_in", "history", "digits", "missing", "position", "scripts", "shape", "rule", "count", "unavailable", "field"];
+
+/// Strings [`Violation::from_json`] accepts as names and in list params,
+/// besides script names and codes.
+const NAMES: &[&str] = &["uppercase", "lowercase", "email", "url", "password", "confirmation"];
+
+/// A single failed rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Stable machine-readable identifier of the rule, e.g. `"min_length"`.
+    pub code: &'static str,
+    /// The custom message if one was configured, otherwise the default one.
+    pub message: String,
+    /// Values behind the failure, e.g. `("max", 72)` and `("actual", 80)`
+    /// for a `max_bytes` rule. Empty for rules without any.
+    pub params: Vec<(&'static str, ParamValue)>,
+    /// The part of the password the failure is about, such as a keyboard
+    /// walk, as indices of characters (not bytes) in logical order. `None`
+    /// for rules about the password as a whole, like `min_length`.
+    pub span: Option<Range<usize>>,
+    /// The index of the [rule group](crate::PasswordChecker::group) whose
+    /// message this is, in the order groups were added.
+    pub group: Option<usize>,
+}
+
+/// The result of one rule, as yielded by
+/// [`PasswordChecker::check_iter`](crate::PasswordChecker::check_iter).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleOutcome<'s> {
+    pub rule: &'s Rule<'s>,
+    /// The violation if the rule failed.
+    pub violation: Option<Violation>,
+}
+
+impl RuleOutcome<'_> {
+    /// Whether the rule failed.
+    pub fn failed(&self) -> bool {
+        self.violation.is_some()
+    }
+}
+
+/// A violation without its message, as yielded by [`Violations::keys`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViolationKey<'v> {
+    /// The violation's [`code`](Violation::code).
+    pub key: &'static str,
+    pub params: &'v [(&'static str, ParamValue)],
+    /// The violation's [`span`](Violation::span).
+    pub span: Option<&'v Range<usize>>,
+}
+
+impl ViolationKey<'_> {
+    /// Serializes the key, e.g. `{"key":"min_length","params":{"min":12,"actual":7}}`.
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![("key".to_string(), Value::String(self.key.to_string())), ("params".to_string(), params_value(self.params))];
+        fields.extend(self.span.map(span_field));
+        Value::Object(fields).to_string()
+    }
+}
+
+/// A value in [`Violation::params`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Int(usize),
+    Float(f64),
+    /// Names, such as the letter cases a `require_upper_lower` violation
+    /// is missing.
+    List(Vec<&'static str>),
+    /// A name, such as the shape a `not_email_or_url` violation detected.
+    Name(&'static str),
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamValue::Int(n) => write!(f, "{}", n),
+            ParamValue::Float(x) => write!(f, "{}", x),
+            ParamValue::List(items) => f.write_str(&items.join(", ")),
+            ParamValue::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+impl Violation {
+    /// Looks up a parameter by name.
+    pub fn param(&self, name: &str) -> Option<&ParamValue> {
+        self.params.iter().find(|(k, _)| *k == name).map(|(_, v)| v)
+    }
+
+    /// Serializes the violation, e.g.
+    /// `{"code":"max_bytes","message":"...","params":{"max":72,"actual":80}}`.
+    pub fn to_json(&self) -> String {
+        self.to_value().to_string()
+    }
+
+    /// Parses a violation written by [`to_json`](Self::to_json). Codes and
+    /// params this version does not know are rejected, and so are the codes
+    /// of [custom rules](crate::CustomRule).
+    pub fn from_json(input: &str) -> Result<Self, ConfigError> {
+        json::parse(input).map_err(ConfigError::from).and_then(|value| Self::from_value(&value, "violation")).map_err(in_document)
+    }
+
+    fn to_value(&self) -> Value {
+        let mut fields = vec![
+            ("code".to_string(), Value::String(self.code.to_string())),
+            ("message".to_string(), Value::String(self.message.clone())),
+            ("params".to_string(), params_value(&self.params)),
+        ];
+        fields.extend(self.span.as_ref().map(span_field));
+        fields.extend(self.group.map(|group| ("group".to_string(), Value::Number(group as f64))));
+        Value::Object(fields)
+    }
+
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConfigError> {
+        let Value::Object(fields) = value else {
+            return Err(invalid(path, format!("expected object, found {}", value.type_name())));
+        };
+        if let Some((key, _)) = fields.iter().find(|(key, _)| !["code", "message", "params", "span", "group"].contains(&key.as_str())) {
+            return Err(invalid(&format!("{}.{}", path, key), "unknown field".to_string()));
+        }
+        let code = string_field(value, path, "code")?;
+        let code = *CODES.iter().find(|c| **c == code).ok_or_else(|| invalid(&format!("{}.code", path), format!("unknown code `{}`", code)))?;
+        let message = string_field(value, path, "message")?.to_string();
+        let params_path = format!("{}.params", path);
+        let params = match value.get("params") {
+            Some(Value::Object(params)) => params
+                .iter()
+                .map(|(name, value)| {
+                    let path = format!("{}.{}", params_path, name);
+                    let name = *PARAMS.iter().find(|p| **p == name).ok_or_else(|| invalid(&path, "unknown param".to_string()))?;
+                    let value = match value {
+                        Value::Number(x) if code == "min_entropy" || x.fract() != 0.0 => ParamValue::Float(*x),
+                        Value::Number(n) if *n >= 0.0 && *n <= u32::MAX as f64 => ParamValue::Int(*n as usize),
+                        Value::Number(n) => return Err(invalid(&path, format!("expected non-negative integer, found {}", n))),
+                        Value::Array(items) => ParamValue::List(
+                            items
+                                .iter()
+                                .map(|item| match item {
+                                    Value::String(s) => known_name(s, &path),
+                                    other => Err(invalid(&path, format!("expected string, found {}", other.type_name()))),
+                                })
+                                .collect::<Result<_, _>>()?,
+                        ),
+                        Value::String(s) => ParamValue::Name(known_name(s, &path)?),
+                        other => return Err(invalid(&path, format!("expected number, found {}", other.type_name()))),
+                    };
+                    Ok((name, value))
+                })
+                .collect::<Result<_, _>>()?,
+            Some(other) => return Err(invalid(&params_path, format!("expected object, found {}", other.type_name()))),
+            None => return Err(invalid(&params_path, "missing field".to_string())),
+        };
+        let span = match value.get("span") {
+            None => None,
+            Some(span) => {
+                let path = format!("{}.span", path);
+                let bound = |key| match span.get(key) {
+                    Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 && *n <= u32::MAX as f64 => Ok(*n as usize),
+                    Some(other) => Err(invalid(&format!("{}.{}", path, key), format!("expected non-negative integer, found {}", other))),
+                    None => Err(invalid(&format!("{}.{}", path, key), "missing field".to_string())),
+                };
+                let Value::Object(fields) = span else {
+                    return Err(invalid(&path, format!("expected object, found {}", span.type_name())));
+                };
+                if let Some((key, _)) = fields.iter().find(|(key, _)| key != "start" && key != "end") {
+                    return Err(invalid(&format!("{}.{}", path, key), "unknown field".to_string()));
+                }
+                Some(bound("start")?..bound("end")?)
+            }
+        };
+        let group = match value.get("group") {
+            None => None,
+            Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 && *n <= u32::MAX as f64 => Some(*n as usize),
+            Some(other) => return Err(invalid(&format!("{}.group", path), format!("expected non-negative integer, found {}", other))),
+        };
+        Ok(Violation { code, message, params, span, group })
+    }
+}
+
+fn known_name(name: &str, path: &str) -> Result<&'static str, ConfigError> {
+    NAMES.iter().chain(CODES).find(|n| **n == name).copied().or_else(|| scripts::name(name)).ok_or_else(|| invalid(path, format!("unknown value `{}`", name)))
+}
+
+/// The `span` field of a serialized violation, e.g. `{"start":2,"end":6}`.
+fn span_field(span: &Range<usize>) -> (String, Value) {
+    let bound = |n: usize| Value::Number(n as f64);
+    ("span".to_string(), Value::Object(vec![("start".to_string(), bound(span.start)), ("end".to_string(), bound(span.end))]))
+}
+
+fn params_value(params: &[(&'static str, ParamValue)]) -> Value {
+    let params = params
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                ParamValue::Int(n) => Value::Number(*n as f64),
+                ParamValue::Float(x) => Value::Number(*x),
+                ParamValue::List(items) => Value::Array(items.iter().map(|item| Value::String(item.to_string())).collect()),
+                ParamValue::Name(name) => Value::String(name.to_string()),
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+    Value::Object(params)
+}
+
+/// Reports an error from the policy helpers as one in a violation document.
+fn in_document(err: ConfigError) -> ConfigError {
+    match err {
+        ConfigError::Syntax { offset, reason } => ConfigError::InvalidViolation { path: String::new(), reason: format!("{} at byte {}", reason, offset) },
+        ConfigError::InvalidField { path, reason } => ConfigError::InvalidViolation { path, reason },
+        other => other,
+    }
+}
+
+fn string_field<'v>(value: &'v Value, path: &str, key: &str) -> Result<&'v str, ConfigError> {
+    match value.get(key) {
+        Some(Value::String(s)) => Ok(s),
+        Some(other) => Err(invalid(&format!("{}.{}", path, key), format!("expected string, found {}", other.type_name()))),
+        None => Err(invalid(&format!("{}.{}", path, key), "missing field".to_string())),
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// All violations produced by one validation, in rule order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Violations(pub(crate) Vec<Violation>);
+
+impl Violations {
+    /// Version of the report format written by [`to_json`](Self::to_json).
+    pub const SCHEMA: u32 = 1;
+
+    /// Codes of the failed rules.
+    pub fn codes(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.iter().map(|v| v.code)
+    }
+
+    /// The violations without their messages, in rule order.
+    pub fn keys(&self) -> impl Iterator<Item = ViolationKey<'_>> + '_ {
+        self.0.iter().map(|v| ViolationKey { key: v.code, params: &v.params, span: v.span.as_ref() })
+    }
+
+    /// Returns the messages, as produced by [`PasswordChecker::validate`](crate::PasswordChecker::validate).
+    /// The members of a [rule group](crate::PasswordChecker::group) share
+    /// one message, listed once.
+    pub fn into_messages(self) -> Vec<String> {
+        let shown = self.shown();
+        self.0.into_iter().zip(shown).filter(|(_, shown)| *shown).map(|(v, _)| v.message).collect()
+    }
+
+    /// Whether each violation's message is shown, rather than being that of
+    /// a group shown before.
+    fn shown(&self) -> Vec<bool> {
+        self.0.iter().enumerate().map(|(i, v)| v.group.is_none_or(|group| !self.0[..i].iter().any(|w| w.group == Some(group)))).collect()
+    }
+
+    /// Serializes the violations as a report in the format described in
+    /// the [module docs](self). A passing check is reported as
+    /// `Violations::default().to_json()`, with `"valid":true`.
+    pub fn to_json(&self) -> String {
+        Value::Object(vec![
+            ("schema".to_string(), Value::Number(Self::SCHEMA.into())),
+            ("valid".to_string(), Value::Bool(self.0.is_empty())),
+            ("violations".to_string(), Value::Array(self.0.iter().map(Violation::to_value).collect())),
+        ])
+        .to_string()
+    }
+
+    /// Parses a report written by [`to_json`](Self::to_json), rejecting
+    /// newer schema versions.
+    pub fn from_json(input: &str) -> Result<Self, ConfigError> {
+        Self::parse(input).map_err(in_document)
+    }
+
+    fn parse(input: &str) -> Result<Self, ConfigError> {
+        let root = json::parse(input)?;
+        let Value::Object(fields) = &root else {
+            return Err(invalid("", format!("expected object, found {}", root.type_name())));
+        };
+        if let Some((key, _)) = fields.iter().find(|(key, _)| !["schema", "valid", "violations"].contains(&key.as_str())) {
+            return Err(invalid(key, "unknown field".to_string()));
+        }
+        match root.get("schema") {
+            Some(Value::Number(n)) if *n == f64::from(Self::SCHEMA) => {}
+            Some(Value::Number(n)) => return Err(invalid("schema", format!("unsupported schema version {}, expected {}", n, Self::SCHEMA))),
+            Some(other) => return Err(invalid("schema", format!("expected number, found {}", other.type_name()))),
+            None => return Err(invalid("schema", "missing field".to_string())),
+        }
+        let violations = match root.get("violations") {
+            Some(Value::Array(items)) => {
+                items.iter().enumerate().map(|(i, item)| Violation::from_value(item, &format!("violations[{}]", i))).collect::<Result<_, _>>()?
+            }
+            Some(other) => return Err(invalid("violations", format!("expected array, found {}", other.type_name()))),
+            None => return Err(invalid("violations", "missing field".to_string())),
+        };
+        Ok(Violations(violations))
+    }
+}
+
+impl Deref for Violations {
+    type Target = [Violation];
+
+    fn deref(&self) -> &[Violation] {
+        &self.0
+    }
+}
+
+/// Violations a [dry run](crate::PasswordChecker::dry_run) did not enforce,
+/// from [`validate_staged`](crate::PasswordChecker::validate_staged).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StagedViolations(pub Violations);
+
+impl Deref for StagedViolations {
+    type Target = Violations;
+
+    fn deref(&self) -> &Violations {
+        &self.0
+    }
+}
+
+impl IntoIterator for Violations {
+    type Item = Violation;
+    type IntoIter = std::vec::IntoIter<Violation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// For [`PasswordPolicy`](crate::PasswordPolicy) implementations
+/// reporting their own violations.
+impl FromIterator<Violation> for Violations {
+    fn from_iter<I: IntoIterator<Item = Violation>>(violations: I) -> Self {
+        Violations(violations.into_iter().collect())
+    }
+}
+
+impl<'v> IntoIterator for &'v Violations {
+    type Item = &'v Violation;
+    type IntoIter = std::slice::Iter<'v, Violation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl fmt::Display for Violations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, v) in self.0.iter().zip(self.shown()).filter(|(_, shown)| *shown).map(|(v, _)| v).enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(&v.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Violations {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PasswordChecker;
+
+    fn sample() -> Violations {
+        let checker = PasswordChecker::new().min_length(8, None).require_number(Some("Add a \"digit\"")).max_bytes(4, None);
+        checker.check("short").unwrap_err()
+    }
+
+    #[test]
+    fn report_shape_is_stable() {
+        assert_eq!(
+            sample().to_json(),
+            concat!(
+                r#"{"schema":1,"valid":false,"violations":["#,
+                r#"{"code":"min_length","message":"Password must be at least 8 characters long.","params":{"min":8,"actual":5}},"#,
+                r#"{"code":"require_number","message":"Add a \"digit\"","params":{}},"#,
+                r#"{"code":"max_bytes","message":"Password must be at most 4 bytes long.","params":{"max":4,"actual":5}}]}"#,
+            )
+        );
+        assert_eq!(Violations::default().to_json(), r#"{"schema":1,"valid":true,"violations":[]}"#);
+    }
+
+    #[test]
+    fn reports_round_trip() {
+        let violations = sample();
+        assert_eq!(Violations::from_json(&violations.to_json()).unwrap(), violations);
+        let entropy = PasswordChecker::new().min_entropy(40.0, crate::Estimator::Pool, None).check("short").unwrap_err();
+        assert!(matches!(entropy[0].param("min"), Some(ParamValue::Float(_))));
+        let case = PasswordChecker::new().require_upper_lower(None).check("lower").unwrap_err();
+        assert!(case[0].to_json().ends_with(r#""params":{"missing":["uppercase"]}}"#), "{}", case[0].to_json());
+        for violation in violations.iter().chain(&entropy).chain(&case) {
+            assert_eq!(Violation::from_json(&violation.to_json()).as_ref(), Ok(violation));
+        }
+        assert_eq!(Violations::from_json(&Violations::default().to_json()), Ok(Violations::default()));
+    }
+
+    #[test]
+    fn spans_serialize_as_indices_only() {
+        let walk = PasswordChecker::new().keyboard_walk(crate::KeyboardWalk::new(), None).check("ééqwerty!").unwrap_err();
+        let json = walk.to_json();
+        assert!(json.ends_with(r#""params":{},"span":{"start":2,"end":8}}]}"#), "{}", json);
+        assert!(!json.contains("qwerty"));
+        assert_eq!(walk.keys().next().unwrap().to_json(), r#"{"key":"keyboard_walk","params":{},"span":{"start":2,"end":8}}"#);
+        assert_eq!(Violations::from_json(&json).unwrap(), walk);
+        assert_eq!(
+            Violation::from_json(r#"{"code":"keyboard_walk","message":"","params":{},"span":{"start":2}}"#).unwrap_err().to_string(),
+            "invalid violation document field `violation.span.end`: missing field"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_shapes() {
+        let err = |input: &str| Violations::from_json(input).unwrap_err().to_string();
+        assert_eq!(err("[]"), "invalid violation document: expected object, found array");
+        assert_eq!(err("{"), "invalid violation document: expected object key at byte 1");
+        assert!(matches!(Violation::from_json("null"), Err(ConfigError::InvalidViolation { path, .. }) if path == "violation"));
+        assert_eq!(err(r#"{"schema":2,"violations":[]}"#), "invalid violation document field `schema`: unsupported schema version 2, expected 1");
+        assert_eq!(
+            err(r#"{"schema":1,"violations":[{"code":"min_strength","message":"","params":{}}]}"#),
+            "invalid violation document field `violations[0].code`: unknown code `min_strength`"
+        );
+        assert_eq!(
+            err(r#"{"schema":1,"violations":[{"code":"min_length","message":"","params":{"min":-1}}]}"#),
+            "invalid violation document field `violations[0].params.min`: expected non-negative integer, found -1"
+        );
+        assert_eq!(
+            err(r#"{"schema":1,"violations":[{"code":"require_upper_lower","message":"","params":{"missing":["digits"]}}]}"#),
+            "invalid violation document field `violations[0].params.missing`: unknown value `digits`"
+        );
+        assert_eq!(
+            Violation::from_json(r#"{"code":"min_length","message":""}"#).unwrap_err().to_string(),
+            "invalid violation document field `violation.params`: missing field"
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//! Words of passphrases.
+
+/// Separators of [`Rule::MinWords`](crate::Rule::MinWords) besides
+/// whitespace.
+pub const DEFAULT_WORD_SEPARATORS: &str = "-_.";
+
+/// Number of words of two or more characters in `password`, split at
+/// whitespace and `separators`.
+pub(crate) fn count_words(password: &str, separators: &str) -> usize {
+    password.split(|c: char| c.is_whitespace() || separators.contains(c)).filter(|word| word.chars().nth(1).is_some()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_are_split_at_separators() {
+        assert_eq!(count_words("correct horse battery staple", DEFAULT_WORD_SEPARATORS), 4);
+        assert_eq!(count_words("correct-horse_battery.staple", DEFAULT_WORD_SEPARATORS), 4);
+        assert_eq!(count_words("  a b cd -- ef  ", DEFAULT_WORD_SEPARATORS), 2);
+        assert_eq!(count_words("über+straße+zürich", "+"), 3);
+        assert_eq!(count_words("correct-horse", ""), 1);
+        assert_eq!(count_words("", DEFAULT_WORD_SEPARATORS), 0);
+    }
+}
@@ -0,0 +1,171 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const POLICY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/policy.json");
+
+fn passcheck(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_passcheck"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Usage errors exit before reading stdin, so a broken pipe is expected there.
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn validate_with_policy_file() {
+    let ok = passcheck(&["validate", "--policy", POLICY], "longenough1\n");
+    assert_eq!(ok.status.code(), Some(0));
+    assert_eq!(stdout(&ok), "");
+
+    let bad = passcheck(&["validate", "--policy", POLICY], "short\n");
+    assert_eq!(bad.status.code(), Some(1));
+    assert_eq!(stdout(&bad), "Password must be at least 10 characters long.\nAdd at least one digit.\n");
+}
+
+#[test]
+fn validate_with_rules_string() {
+    let bad = passcheck(&["validate", "--rules", "min:12 digit special"], "abcdefghijkl1");
+    assert_eq!(bad.status.code(), Some(1));
+    assert_eq!(stdout(&bad), "Password must include at least one special character.\n");
+
+    let ok = passcheck(&["validate", "--rules", "min:12 digit special"], "abcdefghijkl1!");
+    assert_eq!(ok.status.code(), Some(0));
+}
+
+#[test]
+fn stdin_only_first_line_without_terminator_is_used() {
+    let ok = passcheck(&["validate", "--rules", "min:4"], "abcd\r\nignored\n");
+    assert_eq!(ok.status.code(), Some(0));
+
+    // A trailing space is part of the password and must not be trimmed.
+    let bad = passcheck(&["validate", "--rules", "min:5"], "abc \n");
+    assert_eq!(bad.status.code(), Some(1));
+
+    let empty = passcheck(&["validate", "--rules", "min:1"], "");
+    assert_eq!(empty.status.code(), Some(1));
+}
+
+#[test]
+fn describe_prints_requirements() {
+    let out = passcheck(&["describe", "--policy", POLICY], "");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(stdout(&out), "At least 10 characters\nAt least one number\n");
+}
+
+#[test]
+fn score_prints_strength() {
+    let out = passcheck(&["score"], "abcd\n");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(stdout(&out), "18\n");
+}
+
+#[test]
+fn usage_and_config_errors_exit_with_two() {
+    assert_eq!(passcheck(&[], "").status.code(), Some(2));
+    assert_eq!(passcheck(&["validate"], "x").status.code(), Some(2));
+    assert_eq!(passcheck(&["frobnicate"], "x").status.code(), Some(2));
+    assert_eq!(passcheck(&["validate", "--rules", "bogus"], "x").status.code(), Some(2));
+    assert_eq!(passcheck(&["validate", "--policy", "/nonexistent.json"], "x").status.code(), Some(2));
+    assert_eq!(passcheck(&["validate", "--rules", "min:1", "--policy", POLICY], "x").status.code(), Some(2));
+}
+
+#[test]
+fn password_is_never_echoed() {
+    let out = passcheck(&["validate", "--rules", "min:64"], "hunter2-secret\n");
+    assert!(!stdout(&out).contains("hunter2"));
+    assert!(!String::from_utf8_lossy(&out.stderr).contains("hunter2"));
+}
+
+const PASSWORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/passwords.txt");
+/// Its second line is Latin-1, not UTF-8.
+const LATIN1: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/passwords-latin1.txt");
+
+#[test]
+fn audit_json_reports_lines_and_summary() {
+    let out = passcheck(&["audit", "--policy", POLICY, "--input", PASSWORDS, "--format", "json"], "");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        stdout(&out),
+        concat!(
+            r#"{"results":["#,
+            r#"{"line":1,"valid":true,"violations":[]},"#,
+            r#"{"line":2,"valid":false,"violations":["min_length","require_number"]},"#,
+            r#"{"line":3,"valid":false,"violations":["require_number"]},"#,
+            r#"{"line":4,"valid":false,"violations":["min_length"]},"#,
+            r#"{"line":5,"valid":true,"violations":[]}"#,
+            r#"],"summary":{"total":5,"valid":2,"invalid":3,"violations":{"min_length":2,"require_number":2}}}"#,
+            "\n"
+        )
+    );
+}
+
+#[test]
+fn audit_csv_quotes_multi_code_fields() {
+    let out = passcheck(&["audit", "--rules", "min:10 digit", "--input", PASSWORDS, "--format", "csv"], "");
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        stdout(&out),
+        "line,valid,violations\n\
+         1,true,\n\
+         2,false,\"min_length,require_number\"\n\
+         3,false,require_number\n\
+         4,false,min_length\n\
+         5,true,\n\
+         \n\
+         code,count\n\
+         total,5\n\
+         valid,2\n\
+         invalid,3\n\
+         min_length,2\n\
+         require_number,2\n"
+    );
+}
+
+#[test]
+fn audit_reports_invalid_utf8_lines_and_continues() {
+    let json = passcheck(&["audit", "--policy", POLICY, "--input", LATIN1, "--format", "json"], "");
+    assert_eq!(json.status.code(), Some(0));
+    assert_eq!(
+        stdout(&json),
+        concat!(
+            r#"{"results":["#,
+            r#"{"line":1,"valid":true,"violations":[]},"#,
+            r#"{"line":2,"valid":false,"violations":["invalid_utf8"]},"#,
+            r#"{"line":3,"valid":false,"violations":["min_length"]}"#,
+            r#"],"summary":{"total":3,"valid":1,"invalid":2,"violations":{"invalid_utf8":1,"min_length":1}}}"#,
+            "\n"
+        )
+    );
+    let csv = passcheck(&["audit", "--policy", POLICY, "--input", LATIN1, "--format", "csv"], "");
+    assert!(stdout(&csv).starts_with("line,valid,violations\n1,true,\n2,false,invalid_utf8\n3,false,min_length\n\ncode,count\ntotal,3\n"));
+    assert!(!String::from_utf8_lossy(&csv.stderr).contains("ngerpasswort"));
+}
+
+#[test]
+fn audit_never_echoes_candidates() {
+    let candidates = std::fs::read_to_string(PASSWORDS).unwrap();
+    for format in ["json", "csv"] {
+        let out = passcheck(&["audit", "--policy", POLICY, "--input", PASSWORDS, "--format", format], "");
+        let text = stdout(&out) + &String::from_utf8_lossy(&out.stderr);
+        for candidate in candidates.lines() {
+            assert!(!text.contains(candidate.trim_end()), "{} output leaked {:?}", format, candidate);
+        }
+    }
+}
+
+#[test]
+fn audit_argument_errors() {
+    assert_eq!(passcheck(&["audit", "--policy", POLICY], "").status.code(), Some(2));
+    assert_eq!(passcheck(&["audit", "--policy", POLICY, "--input", PASSWORDS, "--format", "xml"], "").status.code(), Some(2));
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "derive")]
+
+use std::collections::HashMap;
+
+use passcheck::{PasswordChecker, PasswordPolicy};
+
+#[derive(PasswordPolicy)]
+struct Signup {
+    #[allow(dead_code)]
+    username: String,
+    #[password(min_length = 12, require_number, require_special, max_length = 128)]
+    password: String,
+}
+
+#[derive(PasswordPolicy)]
+pub struct Reset<'a> {
+    pub extra: HashMap<String, Vec<u8>>,
+    /// Doc comments and other attributes are left alone.
+    #[password(require_upper_lower, min_length = 1_0)]
+    pub(crate) new_password: &'a str,
+}
+
+#[test]
+fn generated_policy_matches_builder() {
+    let expected = PasswordChecker::new()
+        .min_length(12, None)
+        .require_number(None)
+        .require_special_char(None)
+        .max_length(128, None);
+    assert_eq!(Signup::password_policy(), expected);
+
+    let expected = PasswordChecker::new().require_upper_lower(None).min_length(10, None);
+    assert_eq!(Reset::password_policy(), expected);
+}
+
+#[test]
+fn validate_password_checks_the_marked_field() {
+    for candidate in ["short", "longenoughbutplain", "longenough1!", &"a1!".repeat(50)] {
+        let form = Signup { username: "correct horse 1!".into(), password: candidate.into() };
+        let expected = Signup::password_policy().check(candidate);
+        assert_eq!(form.validate_password(), expected, "{:?}", candidate);
+    }
+
+    // Twelve characters in 24 bytes meet `min_length = 12`.
+    let accented = Signup { username: String::new(), password: "éééééééééé1!".into() };
+    assert_eq!(accented.validate_password(), Ok(()));
+
+    let form = Reset { extra: HashMap::new(), new_password: "lowercase only" };
+    let codes: Vec<_> = form.validate_password().unwrap_err().codes().collect();
+    assert_eq!(codes, vec!["require_upper_lower"]);
+}
@@ -0,0 +1,18 @@
+//! `from_env` changes the process environment, which is only sound while
+//! no other thread reads it, so it gets a test binary of its own with a
+//! single test.
+
+use passcheck::{ConfigError, PasswordChecker};
+
+#[test]
+fn reads_the_process_environment() {
+    let prefix = "PASSCHECK_ENV_TEST";
+    // SAFETY: this is the only test in the binary, so no other thread
+    // reads the environment.
+    unsafe { std::env::set_var("PASSCHECK_ENV_TEST_MIN_LENGTH", "10") };
+    let checker = PasswordChecker::from_env(prefix);
+    // SAFETY: as above.
+    unsafe { std::env::remove_var("PASSCHECK_ENV_TEST_MIN_LENGTH") };
+    assert_eq!(checker, Ok(PasswordChecker::new().min_length(10, None)));
+    assert_eq!(PasswordChecker::from_env(prefix), Err(ConfigError::NoVariables { prefix: prefix.to_string() }));
+}